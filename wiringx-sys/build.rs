@@ -1,10 +1,25 @@
 use std::env;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 const WIRINGX: &str = "duo-wiringx-1.0.3";
+const SHIM: &str = "shim";
+
+/// The platform families, named after their directory and feature, with the SoCs they need.
+const FAMILIES: [(&str, &[&str]); 8] = [
+    ("hardkernel", &["amlogic", "samsung"]),
+    ("lemaker", &["allwinner"]),
+    ("linksprite", &["allwinner"]),
+    ("milkv", &["sophgo"]),
+    ("radxa", &["rockchip"]),
+    ("raspberrypi", &["broadcom"]),
+    ("solidrun", &["nxp"]),
+    ("xunlong", &["allwinner"]),
+];
 
 fn main() {
     println!("cargo:rerun-if-changed={}", WIRINGX);
+    println!("cargo:rerun-if-changed={}", SHIM);
 
     let include_dirs = [
         "",
@@ -28,34 +43,45 @@ fn main() {
     ]
     .map(|path| WIRINGX.to_string() + "/src/" + path);
 
-    let source_files = [
-        "*.c",
-        "platform/*.c",
-        "platform/linksprite/*.c",
-        "platform/lemaker/*.c",
-        "platform/solidrun/*.c",
-        "platform/raspberrypi/*.c",
-        "platform/hardkernel/*.c",
-        "platform/xunlong/*.c",
-        "platform/radxa/*.c",
-        "platform/milkv/*.c",
-        "soc/*.c",
-        "soc/allwinner/*.c",
-        "soc/nxp/*.c",
-        "soc/broadcom/*.c",
-        "soc/amlogic/*.c",
-        "soc/samsung/*.c",
-        "soc/rockchip/*.c",
-        "soc/sophgo/*.c",
-    ]
-    .map(|path| WIRINGX.to_string() + "/src/" + path);
+    let mut source_files = ["*.c", "platform/*.c", "soc/*.c"]
+        .map(|path| WIRINGX.to_string() + "/src/" + path)
+        .to_vec();
+    let mut socs = Vec::new();
 
+    // Only the platforms of the enabled features and their SoCs are compiled,
+    // the others are registered empty by the shim.
     let mut build = cc::Build::new();
-    build.files(source_files.iter().flat_map(|pattern| {
-        glob::glob(pattern)
-            .expect("Failed to read glob pattern")
-            .map(|entry| entry.unwrap())
-    }));
+    for (family, family_socs) in FAMILIES {
+        if env::var_os(format!("CARGO_FEATURE_{}", family.to_uppercase())).is_none() {
+            continue;
+        }
+
+        source_files.push(format!("{WIRINGX}/src/platform/{family}/*.c"));
+        build.define(&format!("WIRINGX_RS_{}", family.to_uppercase()), None);
+
+        for soc in family_socs {
+            if !socs.contains(soc) {
+                socs.push(soc);
+                source_files.push(format!("{WIRINGX}/src/soc/{soc}/*.c"));
+                build.define(&format!("WIRINGX_RS_{}", soc.to_uppercase()), None);
+            }
+        }
+    }
+
+    // With the library loaded at runtime only the shim is compiled, and the functions are declared by hand.
+    let dlopen = env::var_os("CARGO_FEATURE_DLOPEN").is_some();
+
+    if dlopen {
+        build.define("WIRINGX_RS_DLOPEN", None);
+    } else {
+        build.files(source_files.iter().flat_map(|pattern| {
+            glob::glob(pattern)
+                .expect("Failed to read glob pattern")
+                .map(|entry| entry.unwrap())
+        }));
+        build.file(SHIM.to_string() + "/platforms.c");
+    }
+    build.file(SHIM.to_string() + "/log.c");
 
     for dir in include_dirs {
         build.include(dir);
@@ -70,8 +96,14 @@ fn main() {
 
     build.compile("wiringx");
 
-    let bindings = bindgen::Builder::default()
+    let mut builder = bindgen::Builder::default()
         .header(WIRINGX.to_string() + "/src/wiringx.h")
+        .header(SHIM.to_string() + "/log.h");
+    if dlopen {
+        builder = builder.blocklist_function(".*").blocklist_var("_wiringXLog");
+    }
+
+    let bindings = builder
         .generate()
         .expect("Unable to generate bindings");
 
@@ -79,4 +111,193 @@ fn main() {
     bindings
         .write_to_file(out_path.join("bindings.rs"))
         .expect("Couldn't write bindings!");
+    generate_platforms(&out_path);
+}
+
+/// Writes the platforms registered by the vendored sources as `PLATFORMS`, with the pins of their `map` and `irq`
+/// tables and the PWM channels of their SoC, so new boards show up without editing the bindings.
+fn generate_platforms(out_path: &Path) {
+    let platform_sources = read_sources(&format!("{WIRINGX}/src/platform/*/*.c"));
+    let soc_sources = read_sources(&format!("{WIRINGX}/src/soc/*/*.c"));
+
+    let mut platforms = Vec::new();
+    for (file, source) in &platform_sources {
+        let family = file
+            .parent()
+            .and_then(Path::file_name)
+            .unwrap()
+            .to_string_lossy()
+            .into_owned();
+
+        let Some(name) = string_arguments(source, "platform_register(").next() else {
+            continue;
+        };
+        let aliases: Vec<&str> = string_arguments(source, "platform_add_alias(").collect();
+
+        if !FAMILIES.iter().any(|(known, _)| *known == family) {
+            println!("cargo:warning=The platform {name} of the unknown family {family} is not compiled in");
+        }
+
+        // Some families set their platforms up in a shared file without a platform, like `milkv/common.c`.
+        let setup: String = platform_sources
+            .iter()
+            .filter(|(other, other_source)| {
+                other == file
+                    || (other.parent() == file.parent()
+                        && string_arguments(other_source, "platform_register(")
+                            .next()
+                            .is_none())
+            })
+            .map(|(_, other_source)| other_source.as_str())
+            .collect();
+
+        let map = int_array(source, "map").unwrap_or_default();
+        let gpio_pins: Vec<usize> = (0..map.len()).filter(|&pin| map[pin] >= 0).collect();
+        // Platforms without an ISR function can not wait for edges on any pin.
+        let isr_pins: Vec<usize> = if !assigns(&setup, "isr") {
+            Vec::new()
+        } else if let Some(irq) = int_array(source, "irq") {
+            gpio_pins
+                .iter()
+                .copied()
+                .filter(|&pin| irq.get(pin).is_some_and(|&irq| irq >= 0))
+                .collect()
+        } else {
+            gpio_pins.clone()
+        };
+
+        // The PWM channels of the pins are in the tables of the SoC, and only used if the platform passes PWM on.
+        let soc = call_strings(source, "soc_get(");
+        let mut pwm_channels: Vec<(i32, u32)> = soc_sources
+            .iter()
+            .find(|(_, soc_source)| call_strings(soc_source, "soc_register(") == soc)
+            .filter(|_| assigns(&setup, "pwmSetPeriod"))
+            .and_then(|(_, soc_source)| pair_array(soc_source, "pin_pwm"))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(pin, channel)| (pin, channel as u32))
+            .collect();
+        pwm_channels.sort_unstable();
+        let pwm_pins: Vec<i32> = pwm_channels.iter().map(|&(pin, _)| pin).collect();
+
+        platforms.push(format!(
+            "    PlatformSource {{ name: {name:?}, aliases: &{aliases:?}, family: {family:?}, \
+             gpio_pins: &{gpio_pins:?}, isr_pins: &{isr_pins:?}, pwm_pins: &{pwm_pins:?}, \
+             pwm_channels: &{pwm_channels:?} }},\n"
+        ));
+    }
+
+    let code = format!(
+        "pub const PLATFORMS: [PlatformSource; {}] = [\n{}];\n",
+        platforms.len(),
+        platforms.concat()
+    );
+    fs::write(out_path.join("platforms.rs"), code).expect("Couldn't write platforms!");
+}
+
+/// Returns the paths and the sources without comments of the files matching the pattern, sorted by path.
+fn read_sources(pattern: &str) -> Vec<(PathBuf, String)> {
+    let mut files: Vec<PathBuf> = glob::glob(pattern)
+        .expect("Failed to read glob pattern")
+        .map(|entry| entry.unwrap())
+        .collect();
+    files.sort();
+
+    files
+        .into_iter()
+        .map(|file| {
+            let source = strip_comments(&fs::read_to_string(&file).unwrap());
+            (file, source)
+        })
+        .collect()
+}
+
+/// Returns whether the source sets the function of the platform, like `platform->isr = platform->soc->isr;`.
+fn assigns(source: &str, field: &str) -> bool {
+    let assignment = format!("->{field} =");
+    source.match_indices(&assignment).any(|(start, _)| {
+        !source[start + assignment.len()..]
+            .trim_start()
+            .starts_with("NULL")
+    })
+}
+
+fn strip_comments(source: &str) -> String {
+    let mut stripped = String::with_capacity(source.len());
+    let mut rest = source;
+
+    while let Some(start) = rest.find("/*") {
+        stripped.push_str(&rest[..start]);
+        rest = rest[start..]
+            .find("*/")
+            .map_or("", |end| &rest[start + end + 2..]);
+    }
+    stripped.push_str(rest);
+
+    stripped
+        .lines()
+        .map(|line| line.split("//").next().unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns the string literals passed to the calls of the function.
+fn string_arguments<'a>(source: &'a str, call: &'a str) -> impl Iterator<Item = &'a str> {
+    source.match_indices(call).filter_map(move |(start, _)| {
+        let arguments = &source[start + call.len()..];
+        let arguments = &arguments[..arguments.find(')')?];
+        let (_, string) = arguments.split_once('"')?;
+
+        string.split('"').next()
+    })
+}
+
+/// Returns the string literals passed to the first call of the function, like the brand and chip of `soc_get`.
+fn call_strings<'a>(source: &'a str, call: &str) -> Vec<&'a str> {
+    let Some(start) = source.find(call) else {
+        return Vec::new();
+    };
+    let arguments = &source[start + call.len()..];
+    let arguments = &arguments[..arguments.find(')').unwrap_or(arguments.len())];
+
+    arguments.split('"').skip(1).step_by(2).collect()
+}
+
+/// Returns the values of the `static int name[]` array.
+fn int_array(source: &str, name: &str) -> Option<Vec<i32>> {
+    let start = source.find(&format!("static int {name}[]"))?;
+    let values = &source[start..];
+    let values = &values[values.find('{')? + 1..values.find('}')?];
+
+    Some(
+        values
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            // Some tables miss commas, making C subtract the following value like in `-1 -1`.
+            .map(|value| {
+                value
+                    .split_whitespace()
+                    .map(|term| term.parse::<i32>().expect("Failed to parse the pin map"))
+                    .sum()
+            })
+            .collect(),
+    )
+}
+
+/// Returns the rows of the `static int name[..][2]` array.
+fn pair_array(source: &str, name: &str) -> Option<Vec<(i32, i32)>> {
+    let start = source.find(&format!("static int {name}["))?;
+    let values = &source[start..];
+    let values = &values[values.find('{')? + 1..values.find("};")?];
+
+    Some(
+        values
+            .split('{')
+            .filter_map(|row| {
+                let (pin, value) = row.split('}').next()?.split_once(',')?;
+                Some((pin.trim().parse().ok()?, value.trim().parse().ok()?))
+            })
+            .collect(),
+    )
 }
@@ -0,0 +1,33 @@
+#include <stdarg.h>
+#include <stdio.h>
+
+#include "wiringx.h"
+#include "log.h"
+
+static wiringXFormattedLog formattedLog = NULL;
+
+void wiringXForwardLog(int prio, char *file, int line, const char *format_str, ...) {
+	char message[1024];
+	va_list ap;
+
+	va_start(ap, format_str);
+	vsnprintf(message, sizeof(message), format_str, ap);
+	va_end(ap);
+
+	if(formattedLog != NULL) {
+		formattedLog(prio, file, line, message);
+	}
+}
+
+void wiringXSetFormattedLog(wiringXFormattedLog func) {
+	formattedLog = func;
+}
+
+/* With the library loaded at runtime, this is done in Rust with the loaded wiringXSetup. */
+#ifndef WIRINGX_RS_DLOPEN
+int wiringXSetupFormattedLog(char *name, wiringXFormattedLog func) {
+	wiringXSetFormattedLog(func);
+
+	return wiringXSetup(name, func != NULL ? wiringXForwardLog : NULL);
+}
+#endif
@@ -0,0 +1,16 @@
+#ifndef _WIRINGX_RS_LOG_H_
+#define _WIRINGX_RS_LOG_H_
+
+/* Log callback receiving the already formatted message, as variadic functions cannot be written in Rust. */
+typedef void (*wiringXFormattedLog)(int prio, const char *file, int line, const char *message);
+
+/* Formats the message and passes it to the callback set with wiringXSetFormattedLog, to be given to wiringXSetup. */
+void wiringXForwardLog(int prio, char *file, int line, const char *format_str, ...);
+
+/* Sets the callback wiringXForwardLog passes the messages to. */
+void wiringXSetFormattedLog(wiringXFormattedLog func);
+
+/* Same as wiringXSetup, with the log messages of wiringX formatted and passed to the callback. */
+int wiringXSetupFormattedLog(char *name, wiringXFormattedLog func);
+
+#endif
@@ -0,0 +1,87 @@
+/* Empty registrations of the platforms and SoCs left out by the cargo features, called by wiringXInit. */
+
+#ifndef WIRINGX_RS_ALLWINNER
+void allwinnerA10Init(void) {}
+void allwinnerA31sInit(void) {}
+void allwinnerH3Init(void) {}
+void allwinnerH5Init(void) {}
+#endif
+
+#ifndef WIRINGX_RS_NXP
+void nxpIMX6DQRMInit(void) {}
+void nxpIMX6SDLRMInit(void) {}
+#endif
+
+#ifndef WIRINGX_RS_BROADCOM
+void broadcom2835Init(void) {}
+void broadcom2836Init(void) {}
+void broadcom2711Init(void) {}
+#endif
+
+#ifndef WIRINGX_RS_AMLOGIC
+void amlogicS805Init(void) {}
+void amlogicS905Init(void) {}
+#endif
+
+#ifndef WIRINGX_RS_SAMSUNG
+void exynos5422Init(void) {}
+#endif
+
+#ifndef WIRINGX_RS_ROCKCHIP
+void rk3399Init(void) {}
+void rk3588Init(void) {}
+#endif
+
+#ifndef WIRINGX_RS_SOPHGO
+void cv180xInit(void) {}
+void sg2002Init(void) {}
+void sg2000Init(void) {}
+#endif
+
+#ifndef WIRINGX_RS_LINKSPRITE
+void pcduino1Init(void) {}
+#endif
+
+#ifndef WIRINGX_RS_LEMAKER
+void bananapi1Init(void) {}
+void bananapiM2Init(void) {}
+#endif
+
+#ifndef WIRINGX_RS_XUNLONG
+void orangepipcpInit(void) {}
+void orangepipc2Init(void) {}
+#endif
+
+#ifndef WIRINGX_RS_SOLIDRUN
+void hummingboardBaseProSDLInit(void) {}
+void hummingboardBaseProDQInit(void) {}
+void hummingboardGateEdgeSDLInit(void) {}
+void hummingboardGateEdgeDQInit(void) {}
+#endif
+
+#ifndef WIRINGX_RS_RASPBERRYPI
+void raspberrypi1b1Init(void) {}
+void raspberrypi1b2Init(void) {}
+void raspberrypi1bpInit(void) {}
+void raspberrypizeroInit(void) {}
+void raspberrypi2Init(void) {}
+void raspberrypi3Init(void) {}
+void raspberrypi4Init(void) {}
+#endif
+
+#ifndef WIRINGX_RS_HARDKERNEL
+void odroidc1Init(void) {}
+void odroidc2Init(void) {}
+void odroidxu4Init(void) {}
+#endif
+
+#ifndef WIRINGX_RS_RADXA
+void rock4Init(void) {}
+void rock5bInit(void) {}
+#endif
+
+#ifndef WIRINGX_RS_MILKV
+void milkv_duoInit(void) {}
+void milkv_duo256mInit(void) {}
+void milkv_duosInit(void) {}
+#endif
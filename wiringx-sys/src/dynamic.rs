@@ -0,0 +1,157 @@
+//! The functions of wiringX, loaded from `libwiringx.so` at runtime with the `dlopen` feature.
+//!
+//! The library is loaded on the first call, from the path in the `WIRINGX_LIBRARY` environment variable
+//! or as `libwiringx.so` from the paths of the dynamic linker. [`load`] tells whether that worked.
+//! Without the library, or if it lacks a function, the functions return `-1` or a null pointer
+//! and do nothing else, except for `delayMicroseconds`, which sleeps.
+//! `wiringXSerialPrintf` is not available, as variadic functions can not be wrapped.
+
+use std::{
+    env,
+    ffi::OsString,
+    os::raw::{c_char, c_int, c_long, c_uchar, c_uint},
+    ptr,
+    sync::OnceLock,
+    thread,
+    time::Duration,
+};
+
+use libloading::Library;
+
+use crate::{digital_value_t, isr_mode_t, pinmode_t, wiringXFormattedLog, wiringXSerial_t};
+
+const LIBRARY: &str = "libwiringx.so";
+
+type Log = Option<
+    unsafe extern "C" fn(prio: c_int, file: *mut c_char, line: c_int, format: *const c_char, ...),
+>;
+
+extern "C" {
+    // Part of the shim compiled into this crate.
+    fn wiringXForwardLog(prio: c_int, file: *mut c_char, line: c_int, format: *const c_char, ...);
+    fn wiringXSetFormattedLog(func: wiringXFormattedLog);
+}
+
+static FUNCTIONS: OnceLock<Result<Functions, String>> = OnceLock::new();
+
+/// Loads the library if that was not tried before, returning why it could not be loaded.
+pub fn load() -> Result<(), &'static str> {
+    functions().map(|_| ())
+}
+
+fn functions() -> Result<&'static Functions, &'static str> {
+    FUNCTIONS
+        .get_or_init(|| {
+            let path = env::var_os("WIRINGX_LIBRARY").unwrap_or_else(|| OsString::from(LIBRARY));
+
+            // Safety: loading runs the initializers of the library, which wiringX does not have.
+            let library = unsafe { Library::new(&path) }
+                .map_err(|e| format!("Failed to load {}: {e}", path.to_string_lossy()))?;
+
+            let functions = unsafe { Functions::load(library) };
+            if functions.wiringXSetup.is_none() {
+                return Err(format!(
+                    "{} is not wiringX, it has no wiringXSetup.",
+                    path.to_string_lossy()
+                ));
+            }
+
+            Ok(functions)
+        })
+        .as_ref()
+        .map_err(String::as_str)
+}
+
+/// Declares the functions of the library, with the value returned without them.
+macro_rules! functions {
+    ($(fn $name:ident($($arg:ident: $ty:ty),* $(,)?) $(-> $ret:ty)? = $missing:expr;)*) => {
+        struct Functions {
+            $($name: Option<unsafe extern "C" fn($($ty),*) $(-> $ret)?>,)*
+            _library: Library,
+        }
+
+        impl Functions {
+            /// Looks up the functions, leaving out the ones the library lacks.
+            unsafe fn load(library: Library) -> Self {
+                Self {
+                    $($name: library
+                        .get::<unsafe extern "C" fn($($ty),*) $(-> $ret)?>(
+                            concat!(stringify!($name), "\0").as_bytes(),
+                        )
+                        .ok()
+                        .map(|symbol| *symbol),)*
+                    _library: library,
+                }
+            }
+        }
+
+        $(
+            /// # Safety
+            ///
+            /// Same as for the function of the C library.
+            pub unsafe fn $name($($arg: $ty),*) $(-> $ret)? {
+                match functions().ok().and_then(|functions| functions.$name) {
+                    Some(function) => function($($arg),*),
+                    None => $missing,
+                }
+            }
+        )*
+    };
+}
+
+functions! {
+    fn delayMicroseconds(arg1: c_uint) = thread::sleep(Duration::from_micros(arg1 as u64));
+    fn pinMode(arg1: c_int, arg2: pinmode_t) -> c_int = -1;
+    fn wiringXSetup(name: *mut c_char, func: Log) -> c_int = -1;
+    fn wiringXGC() -> c_int = -1;
+    fn digitalWrite(arg1: c_int, arg2: digital_value_t) -> c_int = -1;
+    fn digitalRead(arg1: c_int) -> c_int = -1;
+    fn waitForInterrupt(arg1: c_int, arg2: c_int) -> c_int = -1;
+    fn wiringXISR(arg1: c_int, arg2: isr_mode_t) -> c_int = -1;
+    fn wiringXI2CRead(arg1: c_int) -> c_int = -1;
+    fn wiringXI2CReadReg8(arg1: c_int, arg2: c_int) -> c_int = -1;
+    fn wiringXI2CReadReg16(arg1: c_int, arg2: c_int) -> c_int = -1;
+    fn wiringXI2CReadBlockData(arg1: c_int, arg2: c_int, arg3: *mut c_uchar, arg4: c_int) -> c_int = -1;
+    fn wiringXI2CWrite(arg1: c_int, arg2: c_int) -> c_int = -1;
+    fn wiringXI2CWriteReg8(arg1: c_int, arg2: c_int, arg3: c_int) -> c_int = -1;
+    fn wiringXI2CWriteReg16(arg1: c_int, arg2: c_int, arg3: c_int) -> c_int = -1;
+    fn wiringXI2CWriteBlockData(arg1: c_int, arg2: c_int, arg3: *mut c_uchar, arg4: c_int) -> c_int = -1;
+    fn wiringXI2CWriteBlockDataWithSize(arg1: c_int, arg2: c_int, arg3: *mut c_uchar, arg4: c_int) -> c_int = -1;
+    fn wiringXI2CSetup(arg1: *const c_char, arg2: c_int) -> c_int = -1;
+    fn wiringXSPIGetFd(channel: c_int) -> c_int = -1;
+    fn wiringXSPIDataRW(channel: c_int, data: *mut c_uchar, len: c_int) -> c_int = -1;
+    fn wiringXSPISetup(channel: c_int, speed: c_int) -> c_int = -1;
+    fn wiringXSerialOpen(arg1: *const c_char, arg2: wiringXSerial_t) -> c_int = -1;
+    fn wiringXSerialFlush(arg1: c_int) = ();
+    fn wiringXSerialClose(arg1: c_int) = ();
+    fn wiringXSerialPutChar(arg1: c_int, arg2: c_uchar) = ();
+    fn wiringXSerialPuts(arg1: c_int, arg2: *const c_char) = ();
+    fn wiringXSerialDataAvail(arg1: c_int) -> c_int = -1;
+    fn wiringXSerialGetChar(arg1: c_int) -> c_int = -1;
+    fn wiringXPWMSetPeriod(arg1: c_int, arg2: c_long) -> c_int = -1;
+    fn wiringXPWMSetDuty(arg1: c_int, arg2: c_long) -> c_int = -1;
+    fn wiringXPWMSetPolarity(arg1: c_int, arg2: c_int) -> c_int = -1;
+    fn wiringXPWMEnable(arg1: c_int, arg2: c_int) -> c_int = -1;
+    fn wiringXPlatform() -> *mut c_char = ptr::null_mut();
+    fn wiringXValidGPIO(arg1: c_int) -> c_int = -1;
+    fn wiringXSelectableFd(arg1: c_int) -> c_int = -1;
+    fn wiringXSupportedPlatforms(arg1: *mut *mut *mut c_char) -> c_int = -1;
+}
+
+/// Same as `wiringXSetup`, with the log messages of wiringX formatted and passed to the callback.
+///
+/// # Safety
+///
+/// Same as for `wiringXSetup`.
+pub unsafe fn wiringXSetupFormattedLog(name: *mut c_char, func: wiringXFormattedLog) -> c_int {
+    wiringXSetFormattedLog(func);
+
+    wiringXSetup(
+        name,
+        if func.is_some() {
+            Some(wiringXForwardLog)
+        } else {
+            None
+        },
+    )
+}
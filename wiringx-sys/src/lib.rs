@@ -4,3 +4,29 @@
 #![allow(non_snake_case)]
 
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+
+/// A platform registered by the vendored wiringX sources, listed in [`PLATFORMS`] by the build script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlatformSource {
+    /// Name given to `wiringXSetup`, like `milkv_duo`.
+    pub name: &'static str,
+    /// Other names the platform is registered under.
+    pub aliases: &'static [&'static str],
+    /// Directory of the platform sources and cargo feature compiling them in, like `milkv`.
+    pub family: &'static str,
+    /// Pins mapped to a GPIO of the SoC.
+    pub gpio_pins: &'static [i32],
+    /// Pins with interrupts, all GPIO pins unless the platform has a separate interrupt map, none without an ISR function.
+    pub isr_pins: &'static [i32],
+    /// Pins with PWM, the ones of the PWM table of the SoC if the platform passes PWM on.
+    pub pwm_pins: &'static [i32],
+    /// The pins with PWM and their channel of the SoC, like `(2, 10)` for `PWM_10` on pin `2` of the Milk-V Duo.
+    pub pwm_channels: &'static [(i32, u32)],
+}
+
+include!(concat!(env!("OUT_DIR"), "/platforms.rs"));
+
+#[cfg(feature = "dlopen")]
+mod dynamic;
+#[cfg(feature = "dlopen")]
+pub use dynamic::*;
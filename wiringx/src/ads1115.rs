@@ -0,0 +1,304 @@
+//! ADS1115 4 channel 16 bit ADC.
+
+use std::{
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+use crate::{AnalogInput, WiringXError, I2C};
+
+const CONVERSION: u8 = 0x00;
+const CONFIG: u8 = 0x01;
+
+/// Starts a conversion when written, reads as set once no conversion is running.
+const CONFIG_OS: u16 = 0x8000;
+const CONFIG_SINGLE_SHOT: u16 = 0x0100;
+const CONFIG_COMPARATOR_DISABLED: u16 = 0x0003;
+
+/// Extra time a conversion may take beyond its nominal duration, as the internal oscillator varies by 10 %.
+const CONVERSION_MARGIN: Duration = Duration::from_millis(10);
+
+/// A TI ADS1115 analog to digital converter with 4 inputs and a programmable gain amplifier on the I2C bus.
+///
+/// Inputs are converted one at a time on request, against ground or as differential pairs,
+/// which can also be negative. The converter can be cloned to share it, for example between threads,
+/// and hands out its inputs as [`AnalogInput`]s with [`channel`](Self::channel).
+///
+/// Example measuring a thermocouple amplifier between `AIN0` and `AIN1` at address `0x48`:
+/// ```no_run
+/// use wiringx::{Ads1115, Ads1115Input, Ads1115Range, Platform, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let i2c = wiringx.setup_i2c("/dev/i2c-1".into(), 0x48).unwrap();
+///
+/// let adc = Ads1115::new(i2c).unwrap();
+/// adc.set_range(Ads1115Range::V0_256);
+///
+/// println!("{:.5} V", adc.read_voltage(Ads1115Input::Ain0Ain1).unwrap());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Ads1115 {
+    inner: Arc<Mutex<Ads1115Inner>>,
+}
+
+#[derive(Debug)]
+struct Ads1115Inner {
+    i2c: I2C,
+    range: Ads1115Range,
+    data_rate: Ads1115DataRate,
+}
+
+impl Ads1115 {
+    /// Uses the converter on the I2C bus, with the ±2.048 V range and 128 samples per second it starts with.
+    ///
+    /// The converter is checked by reading its configuration.
+    pub fn new(i2c: I2C) -> Result<Self, WiringXError> {
+        let mut data = [0; 2];
+        i2c.read_reg_bytes(CONFIG, &mut data)?;
+
+        Ok(Self {
+            inner: Arc::new(Mutex::new(Ads1115Inner {
+                i2c,
+                range: Ads1115Range::V2_048,
+                data_rate: Ads1115DataRate::Sps128,
+            })),
+        })
+    }
+
+    /// Sets the full scale range of the amplifier for the following conversions.
+    ///
+    /// Inputs must stay between ground and the supply voltage, even when the range exceeds it.
+    #[inline]
+    pub fn set_range(&self, range: Ads1115Range) {
+        self.inner.lock().range = range;
+    }
+
+    /// Returns the full scale range of the amplifier.
+    #[inline]
+    pub fn range(&self) -> Ads1115Range {
+        self.inner.lock().range
+    }
+
+    /// Sets the data rate of the following conversions, slower rates average away more noise.
+    #[inline]
+    pub fn set_data_rate(&self, data_rate: Ads1115DataRate) {
+        self.inner.lock().data_rate = data_rate;
+    }
+
+    /// Returns the data rate of the conversions.
+    #[inline]
+    pub fn data_rate(&self) -> Ads1115DataRate {
+        self.inner.lock().data_rate
+    }
+
+    /// Returns the input as analog input.
+    #[inline]
+    pub fn channel(&self, input: Ads1115Input) -> Ads1115Channel {
+        Ads1115Channel {
+            adc: self.clone(),
+            input,
+        }
+    }
+
+    /// Converts the input, from `-32768` to `32767` over the full scale range.
+    ///
+    /// Inputs against ground only go slightly below `0` from the offset of the converter.
+    /// Returns [`TimedOut`](WiringXError::TimedOut) if the conversion does not finish.
+    pub fn read(&self, input: Ads1115Input) -> Result<i16, WiringXError> {
+        Ok(self.convert(input)?.0)
+    }
+
+    /// Converts the input to volts, negative if a differential input is below its reference.
+    pub fn read_voltage(&self, input: Ads1115Input) -> Result<f32, WiringXError> {
+        let (raw, range) = self.convert(input)?;
+
+        Ok(raw as f32 * range.full_scale() / 32768.0)
+    }
+
+    /// Converts the input, returning the result with the range it was converted with.
+    fn convert(&self, input: Ads1115Input) -> Result<(i16, Ads1115Range), WiringXError> {
+        let inner = self.inner.lock();
+        inner.write_register(
+            CONFIG,
+            CONFIG_OS
+                | (input as u16) << 12
+                | (inner.range as u16) << 9
+                | CONFIG_SINGLE_SHOT
+                | (inner.data_rate as u16) << 5
+                | CONFIG_COMPARATOR_DISABLED,
+        )?;
+
+        let conversion = inner.data_rate.conversion_time();
+        let deadline = Instant::now() + conversion + CONVERSION_MARGIN;
+        thread::sleep(conversion);
+
+        while inner.read_register(CONFIG)? & CONFIG_OS == 0 {
+            if Instant::now() > deadline {
+                return Err(WiringXError::TimedOut);
+            }
+
+            thread::sleep(Duration::from_micros(100));
+        }
+
+        Ok((inner.read_register(CONVERSION)? as i16, inner.range))
+    }
+}
+
+impl Ads1115Inner {
+    /// Reads the register, transmitted most significant byte first unlike SMBus words.
+    fn read_register(&self, register: u8) -> Result<u16, WiringXError> {
+        let mut data = [0; 2];
+        self.i2c.read_reg_bytes(register, &mut data)?;
+
+        Ok(u16::from_be_bytes(data))
+    }
+
+    fn write_register(&self, register: u8, value: u16) -> Result<(), WiringXError> {
+        let [high, low] = value.to_be_bytes();
+        self.i2c.write_bytes(&[register, high, low])?;
+
+        Ok(())
+    }
+}
+
+/// The input of an [`Ads1115`] to convert, a pin against ground or the difference of two pins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Ads1115Input {
+    /// `AIN0` against `AIN1`
+    Ain0Ain1 = 0,
+    /// `AIN0` against `AIN3`
+    Ain0Ain3 = 1,
+    /// `AIN1` against `AIN3`
+    Ain1Ain3 = 2,
+    /// `AIN2` against `AIN3`
+    Ain2Ain3 = 3,
+    /// `AIN0` against ground
+    Ain0 = 4,
+    /// `AIN1` against ground
+    Ain1 = 5,
+    /// `AIN2` against ground
+    Ain2 = 6,
+    /// `AIN3` against ground
+    Ain3 = 7,
+}
+
+impl Ads1115Input {
+    /// Returns whether the input is the difference of two pins, which can be negative.
+    #[inline]
+    pub fn is_differential(self) -> bool {
+        (self as u8) < 4
+    }
+}
+
+/// Full scale range of the amplifier of an [`Ads1115`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Ads1115Range {
+    /// ±6.144 V
+    V6_144 = 0,
+    /// ±4.096 V
+    V4_096 = 1,
+    /// ±2.048 V
+    V2_048 = 2,
+    /// ±1.024 V
+    V1_024 = 3,
+    /// ±0.512 V
+    V0_512 = 4,
+    /// ±0.256 V
+    V0_256 = 5,
+}
+
+impl Ads1115Range {
+    /// Returns the voltage of the highest conversion result.
+    #[inline]
+    pub fn full_scale(self) -> f32 {
+        match self {
+            Self::V6_144 => 6.144,
+            Self::V4_096 => 4.096,
+            Self::V2_048 => 2.048,
+            Self::V1_024 => 1.024,
+            Self::V0_512 => 0.512,
+            Self::V0_256 => 0.256,
+        }
+    }
+}
+
+/// Samples per second of an [`Ads1115`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Ads1115DataRate {
+    Sps8 = 0,
+    Sps16 = 1,
+    Sps32 = 2,
+    Sps64 = 3,
+    Sps128 = 4,
+    Sps250 = 5,
+    Sps475 = 6,
+    Sps860 = 7,
+}
+
+impl Ads1115DataRate {
+    /// Returns the number of samples per second.
+    pub fn samples_per_second(self) -> u32 {
+        match self {
+            Self::Sps8 => 8,
+            Self::Sps16 => 16,
+            Self::Sps32 => 32,
+            Self::Sps64 => 64,
+            Self::Sps128 => 128,
+            Self::Sps250 => 250,
+            Self::Sps475 => 475,
+            Self::Sps860 => 860,
+        }
+    }
+
+    #[inline]
+    fn conversion_time(self) -> Duration {
+        Duration::from_secs(1) / self.samples_per_second()
+    }
+}
+
+/// An input of an [`Ads1115`], measured with the range and data rate set on the converter.
+///
+/// Raw values go from `0` for inputs against ground, and from `-32768` for differential ones, to `32767`.
+#[derive(Debug, Clone)]
+pub struct Ads1115Channel {
+    adc: Ads1115,
+    input: Ads1115Input,
+}
+
+impl Ads1115Channel {
+    /// Returns the input converted.
+    #[inline]
+    pub fn input(&self) -> Ads1115Input {
+        self.input
+    }
+}
+
+impl AnalogInput for Ads1115Channel {
+    fn read_raw(&self) -> Result<i32, WiringXError> {
+        let raw = i32::from(self.adc.read(self.input)?);
+
+        Ok(raw.max(self.min_raw()))
+    }
+
+    #[inline]
+    fn min_raw(&self) -> i32 {
+        if self.input.is_differential() {
+            i16::MIN.into()
+        } else {
+            0
+        }
+    }
+
+    #[inline]
+    fn max_raw(&self) -> i32 {
+        i16::MAX.into()
+    }
+
+    #[inline]
+    fn read_voltage(&self) -> Result<f32, WiringXError> {
+        self.adc.read_voltage(self.input)
+    }
+}
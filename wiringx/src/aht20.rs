@@ -0,0 +1,109 @@
+//! AHT20 temperature and humidity sensors.
+
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{sht31::sensirion_crc8, DeviceFault, WiringXError, I2C};
+
+const INITIALIZE: [u8; 3] = [0xBE, 0x08, 0x00];
+const TRIGGER_MEASUREMENT: [u8; 3] = [0xAC, 0x33, 0x00];
+const SOFT_RESET: u8 = 0xBA;
+
+const STATUS_BUSY: u8 = 0x80;
+const STATUS_CALIBRATED: u8 = 0x08;
+
+/// Time a measurement takes.
+const MEASUREMENT_TIME: Duration = Duration::from_millis(80);
+
+/// An Aosong AHT20 sensor measuring temperature and relative humidity at address `0x38`.
+///
+/// Every measurement is verified with its CRC.
+///
+/// Example:
+/// ```no_run
+/// use wiringx::{Aht20, Platform, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let i2c = wiringx.setup_i2c("/dev/i2c-1".into(), 0x38).unwrap();
+///
+/// let mut sensor = Aht20::new(i2c).unwrap();
+///
+/// let measurement = sensor.measure().unwrap();
+/// println!("{:.1} °C, {:.1} %", measurement.temperature, measurement.humidity);
+/// ```
+#[derive(Debug)]
+pub struct Aht20 {
+    i2c: I2C,
+}
+
+impl Aht20 {
+    /// Resets the sensor and loads its calibration.
+    pub fn new(i2c: I2C) -> Result<Self, WiringXError> {
+        let mut sensor = Self { i2c };
+
+        sensor.i2c.write_bytes(&[SOFT_RESET])?;
+        thread::sleep(Duration::from_millis(20));
+
+        if sensor.status()? & STATUS_CALIBRATED == 0 {
+            sensor.i2c.write_bytes(&INITIALIZE)?;
+            thread::sleep(Duration::from_millis(10));
+
+            if sensor.status()? & STATUS_CALIBRATED == 0 {
+                return Err(WiringXError::DeviceFault(DeviceFault::NotCalibrated));
+            }
+        }
+
+        Ok(sensor)
+    }
+
+    /// Measures temperature and humidity once, which takes about 80 ms.
+    ///
+    /// Returns [`Checksum`](WiringXError::Checksum) if the CRC does not match.
+    pub fn measure(&mut self) -> Result<Aht20Measurement, WiringXError> {
+        self.i2c.write_bytes(&TRIGGER_MEASUREMENT)?;
+        thread::sleep(MEASUREMENT_TIME);
+
+        let deadline = Instant::now() + MEASUREMENT_TIME;
+        let mut data = [0; 7];
+        loop {
+            self.i2c.read_bytes(&mut data)?;
+            if data[0] & STATUS_BUSY == 0 {
+                break;
+            }
+
+            if Instant::now() > deadline {
+                return Err(WiringXError::TimedOut);
+            }
+
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        if sensirion_crc8(&data[..6]) != data[6] {
+            return Err(WiringXError::Checksum);
+        }
+
+        // Both values have 20 bits, sharing the fourth byte.
+        let humidity = (data[1] as u32) << 12 | (data[2] as u32) << 4 | (data[3] as u32) >> 4;
+        let temperature = (data[3] as u32 & 0x0F) << 16 | (data[4] as u32) << 8 | data[5] as u32;
+
+        Ok(Aht20Measurement {
+            temperature: temperature as f32 / (1 << 20) as f32 * 200.0 - 50.0,
+            humidity: humidity as f32 / (1 << 20) as f32 * 100.0,
+        })
+    }
+
+    fn status(&mut self) -> Result<u8, WiringXError> {
+        Ok(self.i2c.read()?)
+    }
+}
+
+/// A measurement of an [`Aht20`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aht20Measurement {
+    /// Temperature in °C.
+    pub temperature: f32,
+    /// Relative humidity in %.
+    pub humidity: f32,
+}
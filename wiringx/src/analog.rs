@@ -0,0 +1,31 @@
+//! Analog inputs.
+
+use crate::WiringXError;
+
+/// An analog input, implemented by the channels of analog to digital converters,
+/// so sensors like potentiometers, thermistors or joysticks can be written once for all of them.
+pub trait AnalogInput {
+    /// Converts the input, from [`min_raw`](Self::min_raw) to [`max_raw`](Self::max_raw).
+    fn read_raw(&self) -> Result<i32, WiringXError>;
+
+    /// Returns the lowest raw value, `0` for inputs measured against ground
+    /// and negative for differential inputs, which can be below their reference.
+    #[inline]
+    fn min_raw(&self) -> i32 {
+        0
+    }
+
+    /// Returns the highest raw value, corresponding to the full scale voltage.
+    fn max_raw(&self) -> i32;
+
+    /// Converts the input to volts.
+    fn read_voltage(&self) -> Result<f32, WiringXError>;
+
+    /// Converts the input to a proportion of the full range, from 0.0 at [`min_raw`](Self::min_raw)
+    /// to 1.0 at [`max_raw`](Self::max_raw).
+    fn read_normalized(&self) -> Result<f32, WiringXError> {
+        let min = self.min_raw() as f32;
+
+        Ok((self.read_raw()? as f32 - min) / (self.max_raw() as f32 - min))
+    }
+}
@@ -0,0 +1,184 @@
+//! APA102 / DotStar LED strips.
+
+use crate::{BitOrder, ColorOrder, Rgb, Spi, SpiMode, WiringXError};
+
+/// A strip of APA102 or SK9822 RGB LEDs, also known as DotStars.
+///
+/// The LEDs have their own clock line, so they work at any SPI speed the strip can carry
+/// and are the recommended choice where [`Ws2812`](super::Ws2812) timing can not be met.
+///
+/// Colors are kept in a frame buffer and sent to the strip with [`show`](Self::show).
+///
+/// Example with gamma correction for a strip of 30 LEDs:
+/// ```no_run
+/// use wiringx::{Apa102, Platform, Rgb, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+///
+/// let mut strip = Apa102::new(wiringx.setup_spi(0, 4_000_000).unwrap(), 30).unwrap();
+/// strip.set_gamma(2.8);
+/// strip.set_global_brightness(8).unwrap();
+///
+/// for (i, pixel) in strip.pixels_mut().iter_mut().enumerate() {
+///     *pixel = Rgb::new(0, (i * 8) as u8, 255 - (i * 8) as u8);
+/// }
+/// strip.show().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct Apa102 {
+    spi: Spi,
+    pixels: Vec<Rgb>,
+    global_brightness: u8,
+    gamma: f32,
+    gamma_table: [u8; 256],
+    color_order: ColorOrder,
+}
+
+impl Apa102 {
+    /// Drives a strip of `len` LEDs from the SPI controller, keeping its speed.
+    ///
+    /// The controller is reconfigured to [`SpiMode::Mode0`] and [`BitOrder::MsbFirst`].
+    pub fn new(mut spi: Spi, len: usize) -> Result<Self, WiringXError> {
+        spi.set_mode(SpiMode::Mode0)?;
+        spi.set_bit_order(BitOrder::MsbFirst)?;
+
+        Ok(Self {
+            spi,
+            pixels: vec![Rgb::BLACK; len],
+            global_brightness: 31,
+            gamma: 1.0,
+            gamma_table: gamma_table(1.0),
+            color_order: ColorOrder::Bgr,
+        })
+    }
+
+    /// Returns the SPI controller.
+    #[inline]
+    pub fn into_inner(self) -> Spi {
+        self.spi
+    }
+
+    /// Returns the number of LEDs of the strip.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.pixels.len()
+    }
+
+    /// Returns true if the strip has no LEDs.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pixels.is_empty()
+    }
+
+    /// Returns the frame buffer.
+    #[inline]
+    pub fn pixels(&self) -> &[Rgb] {
+        &self.pixels
+    }
+
+    /// Returns the frame buffer for modification, the changes get visible with the next [`show`](Self::show).
+    #[inline]
+    pub fn pixels_mut(&mut self) -> &mut [Rgb] {
+        &mut self.pixels
+    }
+
+    /// Sets the color of the LED at the index in the frame buffer.
+    pub fn set_pixel(&mut self, index: usize, color: Rgb) -> Result<(), WiringXError> {
+        let pixel = self
+            .pixels
+            .get_mut(index)
+            .ok_or(WiringXError::InvalidArgument)?;
+        *pixel = color;
+
+        Ok(())
+    }
+
+    /// Sets all LEDs in the frame buffer to the color.
+    #[inline]
+    pub fn fill(&mut self, color: Rgb) {
+        self.pixels.fill(color);
+    }
+
+    /// Switches all LEDs in the frame buffer off.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.fill(Rgb::BLACK);
+    }
+
+    /// Sets the brightness of the LED driver current, from `0` to `31`.
+    ///
+    /// Unlike scaling the colors, this keeps the full color resolution at low brightness.
+    pub fn set_global_brightness(&mut self, brightness: u8) -> Result<(), WiringXError> {
+        if brightness > 31 {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        self.global_brightness = brightness;
+
+        Ok(())
+    }
+
+    /// Returns the brightness of the LED driver current.
+    #[inline]
+    pub fn global_brightness(&self) -> u8 {
+        self.global_brightness
+    }
+
+    /// Sets the gamma the color components are corrected with when sent, `1.0` for none.
+    ///
+    /// Values around `2.8` make fades look linear to the eye.
+    pub fn set_gamma(&mut self, gamma: f32) {
+        self.gamma = gamma;
+        self.gamma_table = gamma_table(gamma);
+    }
+
+    /// Returns the gamma the color components are corrected with.
+    #[inline]
+    pub fn gamma(&self) -> f32 {
+        self.gamma
+    }
+
+    /// Sets the order the strip expects the color components in, [`ColorOrder::Bgr`] by default.
+    #[inline]
+    pub fn set_color_order(&mut self, color_order: ColorOrder) {
+        self.color_order = color_order;
+    }
+
+    /// Returns the order the color components are sent in.
+    #[inline]
+    pub fn color_order(&self) -> ColorOrder {
+        self.color_order
+    }
+
+    /// Sends the frame buffer to the strip.
+    pub fn show(&mut self) -> Result<(), WiringXError> {
+        // Every LED delays the clock by half a cycle, so the end frame needs one bit per two LEDs.
+        let end_frame = self.pixels.len().div_ceil(16).max(4);
+
+        let mut data = Vec::with_capacity(4 + self.pixels.len() * 4 + end_frame);
+        data.extend_from_slice(&[0; 4]);
+
+        for pixel in &self.pixels {
+            data.push(0xE0 | self.global_brightness);
+            data.extend(
+                self.color_order
+                    .arrange(*pixel)
+                    .map(|value| self.gamma_table[value as usize]),
+            );
+        }
+
+        data.resize(data.len() + end_frame, 0xFF);
+
+        self.spi.write(&data)
+    }
+}
+
+fn gamma_table(gamma: f32) -> [u8; 256] {
+    let mut table = [0; 256];
+
+    for (i, value) in table.iter_mut().enumerate() {
+        *value = ((i as f32 / 255.0).powf(gamma) * 255.0).round() as u8;
+    }
+
+    table
+}
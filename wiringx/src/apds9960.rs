@@ -0,0 +1,356 @@
+//! APDS9960 proximity, light and gesture sensors.
+
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{Input, IsrMode, Pin, Value, WiringXError, I2C};
+
+const ENABLE: u8 = 0x80;
+const ATIME: u8 = 0x81;
+const WTIME: u8 = 0x83;
+const PILT: u8 = 0x89;
+const PIHT: u8 = 0x8B;
+const PERS: u8 = 0x8C;
+const CONFIG1: u8 = 0x8D;
+const PPULSE: u8 = 0x8E;
+const CONTROL: u8 = 0x8F;
+const CONFIG2: u8 = 0x90;
+const ID: u8 = 0x92;
+const STATUS: u8 = 0x93;
+const CDATAL: u8 = 0x94;
+const PDATA: u8 = 0x9C;
+const CONFIG3: u8 = 0x9F;
+const GPENTH: u8 = 0xA0;
+const GEXTH: u8 = 0xA1;
+const GCONF1: u8 = 0xA2;
+const GCONF2: u8 = 0xA3;
+const GPULSE: u8 = 0xA6;
+const GCONF3: u8 = 0xAA;
+const GCONF4: u8 = 0xAB;
+const GFLVL: u8 = 0xAE;
+const GSTATUS: u8 = 0xAF;
+const AICLEAR: u8 = 0xE7;
+const GFIFO_U: u8 = 0xFC;
+
+const ENABLE_PON: u8 = 0x01;
+const ENABLE_AEN: u8 = 0x02;
+const ENABLE_PEN: u8 = 0x04;
+const ENABLE_WEN: u8 = 0x08;
+const ENABLE_PIEN: u8 = 0x20;
+const ENABLE_GEN: u8 = 0x40;
+const STATUS_AVALID: u8 = 0x01;
+const STATUS_PVALID: u8 = 0x02;
+const GCONF4_GMODE: u8 = 0x01;
+const GCONF4_GIEN: u8 = 0x02;
+const GCONF4_GFIFO_CLR: u8 = 0x04;
+const GSTATUS_GVALID: u8 = 0x01;
+
+/// Datasets with all photodiodes below this are ignored when decoding gestures.
+const GESTURE_MIN_COUNT: u8 = 10;
+/// Change of the ratios between opposite photodiodes in % from the start to the end of a gesture.
+const GESTURE_SENSITIVITY: i32 = 50;
+
+/// An Avago APDS9960 sensor at address `0x39`, detecting proximity, ambient and colored light,
+/// and swiping gestures over it.
+///
+/// Each engine is enabled on its own. The optional interrupt pin, driven low by the sensor,
+/// saves polling while waiting for gestures or proximity events.
+///
+/// Example reacting to swipes:
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use wiringx::{Apds9960, Input, Platform, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let i2c = wiringx.setup_i2c("/dev/i2c-1".into(), 0x39).unwrap();
+///
+/// let mut sensor = Apds9960::new(i2c, Some(wiringx.gpio_pin::<Input>(15).unwrap())).unwrap();
+/// sensor.enable_gesture(true).unwrap();
+///
+/// loop {
+///     if let Ok(Some(gesture)) = sensor.read_gesture(Duration::from_secs(60)) {
+///         println!("{gesture:?}");
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Apds9960 {
+    i2c: I2C,
+    interrupt: Option<Pin<Input>>,
+    enable: u8,
+}
+
+impl Apds9960 {
+    /// Initializes the sensor with all engines disabled, with the optional interrupt pin.
+    pub fn new(i2c: I2C, interrupt: Option<Pin<Input>>) -> Result<Self, WiringXError> {
+        let mut sensor = Self {
+            i2c,
+            interrupt,
+            enable: 0,
+        };
+
+        // Clones answer with other ids, but behave the same.
+        let id = sensor.read_register(ID)?;
+        if ![0xAB, 0xA8, 0x9C].contains(&id) {
+            return Err(WiringXError::Unsupported);
+        }
+
+        if let Some(interrupt) = &sensor.interrupt {
+            interrupt.set_isr_mode(IsrMode::Falling)?;
+        }
+
+        sensor.write_register(ENABLE, 0)?;
+
+        // 103 ms light integration, 8 proximity pulses of 16 µs at 100 mA and 4x gain.
+        sensor.write_register(ATIME, 219)?;
+        sensor.write_register(WTIME, 246)?;
+        sensor.write_register(PPULSE, 0x87)?;
+        sensor.write_register(CONFIG1, 0x60)?;
+        sensor.write_register(CONTROL, 0x0A)?;
+        sensor.write_register(PERS, 0x11)?;
+        sensor.write_register(CONFIG2, 0x01)?;
+        sensor.write_register(CONFIG3, 0x00)?;
+
+        // Gestures start above a proximity of 40 and end below 30,
+        // with 10 pulses of 32 µs at 4x gain and 2.8 ms between datasets.
+        sensor.write_register(GPENTH, 40)?;
+        sensor.write_register(GEXTH, 30)?;
+        sensor.write_register(GCONF1, 0x40)?;
+        sensor.write_register(GCONF2, 0x41)?;
+        sensor.write_register(GPULSE, 0xC9)?;
+        sensor.write_register(GCONF3, 0x00)?;
+        sensor.write_register(GCONF4, 0x00)?;
+
+        sensor.set_enable(ENABLE_PON, true)?;
+        thread::sleep(Duration::from_millis(6));
+
+        Ok(sensor)
+    }
+
+    /// Enables or disables the proximity engine.
+    #[inline]
+    pub fn enable_proximity(&mut self, enabled: bool) -> Result<(), WiringXError> {
+        self.set_enable(ENABLE_PEN, enabled)
+    }
+
+    /// Enables or disables the ambient and colored light engine.
+    #[inline]
+    pub fn enable_light(&mut self, enabled: bool) -> Result<(), WiringXError> {
+        self.set_enable(ENABLE_AEN, enabled)
+    }
+
+    /// Enables or disables the gesture engine, which also needs the proximity engine to start gestures.
+    ///
+    /// Gestures signal the interrupt pin while they are in progress.
+    pub fn enable_gesture(&mut self, enabled: bool) -> Result<(), WiringXError> {
+        if enabled {
+            self.write_register(GCONF4, GCONF4_GIEN | GCONF4_GFIFO_CLR)?;
+            self.set_enable(ENABLE_PEN | ENABLE_WEN | ENABLE_GEN, true)
+        } else {
+            self.write_register(GCONF4, GCONF4_GFIFO_CLR)?;
+            self.set_enable(ENABLE_WEN | ENABLE_GEN, false)
+        }
+    }
+
+    /// Reads the proximity from `0` far to `255` close, waiting for the first measurement after enabling the engine.
+    pub fn read_proximity(&mut self) -> Result<u8, WiringXError> {
+        self.wait_for_status(STATUS_PVALID)?;
+
+        self.read_register(PDATA)
+    }
+
+    /// Reads the counts of the light channels, waiting for the first integration after enabling the engine.
+    pub fn read_light(&mut self) -> Result<Apds9960Color, WiringXError> {
+        self.wait_for_status(STATUS_AVALID)?;
+
+        let mut data = [0; 8];
+        self.i2c.read_reg_bytes(CDATAL, &mut data)?;
+
+        Ok(Apds9960Color {
+            clear: u16::from_le_bytes([data[0], data[1]]),
+            red: u16::from_le_bytes([data[2], data[3]]),
+            green: u16::from_le_bytes([data[4], data[5]]),
+            blue: u16::from_le_bytes([data[6], data[7]]),
+        })
+    }
+
+    /// Signals the interrupt pin when the proximity leaves the range from low to high, none disables the interrupt.
+    ///
+    /// The interrupt stays asserted until [`clear_interrupts`](Self::clear_interrupts).
+    pub fn set_proximity_interrupt(&mut self, range: Option<(u8, u8)>) -> Result<(), WiringXError> {
+        match range {
+            Some((low, high)) => {
+                self.write_register(PILT, low)?;
+                self.write_register(PIHT, high)?;
+                self.clear_interrupts()?;
+                self.set_enable(ENABLE_PIEN, true)
+            }
+            None => self.set_enable(ENABLE_PIEN, false),
+        }
+    }
+
+    /// Waits until the sensor drives the interrupt pin low.
+    ///
+    /// Returns [`Unsupported`](WiringXError::Unsupported) without an interrupt pin
+    /// and [`TimedOut`](WiringXError::TimedOut) if no interrupt occurred within the timeout.
+    pub fn wait_for_interrupt(&mut self, timeout: Duration) -> Result<(), WiringXError> {
+        let interrupt = self.interrupt.as_ref().ok_or(WiringXError::Unsupported)?;
+
+        // The pin may already be low, which would not cause an edge.
+        if interrupt.read() == Value::Low {
+            return Ok(());
+        }
+
+        interrupt
+            .wait_for_interrupt(timeout)
+            .map_err(|_| WiringXError::TimedOut)
+    }
+
+    /// Clears the proximity and light interrupts.
+    pub fn clear_interrupts(&mut self) -> Result<(), WiringXError> {
+        self.i2c.write_bytes(&[AICLEAR])?;
+
+        Ok(())
+    }
+
+    /// Waits for the next gesture and decodes its direction, none if it was not a clear swipe.
+    ///
+    /// Waits on the interrupt pin if there is one, otherwise polls the sensor every 10 ms.
+    /// Returns [`TimedOut`](WiringXError::TimedOut) if no gesture ended within the timeout.
+    pub fn read_gesture(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<Apds9960Gesture>, WiringXError> {
+        let deadline = Instant::now() + timeout;
+        let mut datasets = Vec::new();
+
+        loop {
+            if self.read_register(GSTATUS)? & GSTATUS_GVALID != 0 {
+                let level = self.read_register(GFLVL)? as usize;
+                let mut data = vec![0; level * 4];
+                self.i2c.read_reg_bytes(GFIFO_U, &mut data)?;
+
+                datasets.extend(data.chunks_exact(4).map(|d| [d[0], d[1], d[2], d[3]]));
+                continue;
+            }
+
+            // The engine leaves the gesture mode once the proximity dropped.
+            if !datasets.is_empty() && self.read_register(GCONF4)? & GCONF4_GMODE == 0 {
+                return Ok(decode_gesture(&datasets));
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(WiringXError::TimedOut);
+            }
+
+            match &self.interrupt {
+                Some(_) if datasets.is_empty() => self.wait_for_interrupt(remaining)?,
+                _ => thread::sleep(remaining.min(Duration::from_millis(10))),
+            }
+        }
+    }
+
+    fn wait_for_status(&mut self, flag: u8) -> Result<(), WiringXError> {
+        let deadline = Instant::now() + Duration::from_millis(500);
+
+        while self.read_register(STATUS)? & flag == 0 {
+            if Instant::now() > deadline {
+                return Err(WiringXError::TimedOut);
+            }
+
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        Ok(())
+    }
+
+    fn set_enable(&mut self, bits: u8, enabled: bool) -> Result<(), WiringXError> {
+        if enabled {
+            self.enable |= bits;
+        } else {
+            self.enable &= !bits;
+        }
+
+        self.write_register(ENABLE, self.enable)
+    }
+
+    #[inline]
+    fn read_register(&mut self, register: u8) -> Result<u8, WiringXError> {
+        Ok(self.i2c.read_reg8(register as i32)?)
+    }
+
+    #[inline]
+    fn write_register(&mut self, register: u8, value: u8) -> Result<(), WiringXError> {
+        self.i2c.write_bytes(&[register, value])?;
+
+        Ok(())
+    }
+}
+
+impl Drop for Apds9960 {
+    fn drop(&mut self) {
+        let _ = self.write_register(ENABLE, 0);
+    }
+}
+
+/// Raw counts of the light channels of an [`Apds9960`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Apds9960Color {
+    /// Unfiltered light.
+    pub clear: u16,
+    /// Red light.
+    pub red: u16,
+    /// Green light.
+    pub green: u16,
+    /// Blue light.
+    pub blue: u16,
+}
+
+/// Direction of a swipe over an [`Apds9960`], seen from above with the pins of the module at the bottom.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Apds9960Gesture {
+    /// Swipe away from the pins
+    Up,
+    /// Swipe towards the pins
+    Down,
+    /// Swipe to the left
+    Left,
+    /// Swipe to the right
+    Right,
+}
+
+/// Decodes the direction from the first and last datasets of up, down, left and right photodiodes the hand covered.
+///
+/// The hand shadows the photodiode it comes from first, which moves the ratios between opposite photodiodes.
+fn decode_gesture(datasets: &[[u8; 4]]) -> Option<Apds9960Gesture> {
+    let mut covered = datasets
+        .iter()
+        .filter(|dataset| dataset.iter().all(|count| *count > GESTURE_MIN_COUNT));
+
+    let first = covered.next()?;
+    let last = covered.next_back()?;
+
+    let ratio = |a: u8, b: u8| (a as i32 - b as i32) * 100 / (a as i32 + b as i32);
+    let up_down = ratio(last[0], last[1]) - ratio(first[0], first[1]);
+    let left_right = ratio(last[2], last[3]) - ratio(first[2], first[3]);
+
+    if up_down.abs() >= left_right.abs() {
+        if up_down <= -GESTURE_SENSITIVITY {
+            Some(Apds9960Gesture::Up)
+        } else if up_down >= GESTURE_SENSITIVITY {
+            Some(Apds9960Gesture::Down)
+        } else {
+            None
+        }
+    } else if left_right <= -GESTURE_SENSITIVITY {
+        Some(Apds9960Gesture::Left)
+    } else if left_right >= GESTURE_SENSITIVITY {
+        Some(Apds9960Gesture::Right)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,112 @@
+//! Asynchronous serial communication on the tokio runtime, enabled with the `tokio` feature.
+
+use std::{
+    io,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use tokio::io::{unix::AsyncFd, AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::{SerialPort, WiringXError};
+
+/// A [`SerialPort`] registered with the tokio reactor, implementing [`AsyncRead`] and [`AsyncWrite`].
+///
+/// Has to be created within a tokio runtime with IO enabled.
+///
+/// Line echo example on `/dev/ttyS1`:
+/// ```no_run
+/// use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+/// use wiringx::{AsyncSerialPort, Platform, WiringX};
+///
+/// # async fn run() {
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let serial = wiringx.serial("/dev/ttyS1", 115200).unwrap();
+///
+/// let (reader, mut writer) = tokio::io::split(AsyncSerialPort::new(serial).unwrap());
+/// let mut lines = BufReader::new(reader).lines();
+///
+/// while let Some(line) = lines.next_line().await.unwrap() {
+///     writer.write_all(line.as_bytes()).await.unwrap();
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct AsyncSerialPort {
+    inner: AsyncFd<SerialPort>,
+}
+
+impl AsyncSerialPort {
+    /// Switches the serial port to non-blocking mode and registers it with the current tokio runtime.
+    pub fn new(serial: SerialPort) -> Result<Self, WiringXError> {
+        serial.set_nonblocking(true)?;
+
+        let inner = AsyncFd::new(serial).map_err(WiringXError::Io)?;
+
+        Ok(Self { inner })
+    }
+
+    /// Returns a reference to the underlying serial port.
+    #[inline]
+    pub fn get_ref(&self) -> &SerialPort {
+        self.inner.get_ref()
+    }
+
+    /// Deregisters the serial port and switches it back to blocking mode.
+    pub fn into_inner(self) -> Result<SerialPort, WiringXError> {
+        let serial = self.inner.into_inner();
+
+        serial.set_nonblocking(false)?;
+
+        Ok(serial)
+    }
+}
+
+impl AsyncRead for AsyncSerialPort {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        loop {
+            let mut guard = ready!(self.inner.poll_read_ready(cx))?;
+
+            let unfilled = buf.initialize_unfilled();
+            match guard.try_io(|inner| Ok(inner.get_ref().read(unfilled)?)) {
+                Ok(result) => {
+                    buf.advance(result?);
+                    return Poll::Ready(Ok(()));
+                }
+                Err(_would_block) => continue,
+            }
+        }
+    }
+}
+
+impl AsyncWrite for AsyncSerialPort {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        loop {
+            let mut guard = ready!(self.inner.poll_write_ready(cx))?;
+
+            match guard.try_io(|inner| Ok(inner.get_ref().write(buf)?)) {
+                Ok(result) => return Poll::Ready(result),
+                Err(_would_block) => continue,
+            }
+        }
+    }
+
+    /// Bytes are handed to the kernel immediately, so there is nothing to flush.
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[inline]
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
@@ -0,0 +1,210 @@
+//! Client for modems and modules controlled with AT commands, like the SIM800 or ESP-AT firmware.
+//!
+//! Example querying the signal quality of a SIM800 while printing incoming calls:
+//! ```no_run
+//! use wiringx::{at::AtClient, Platform, WiringX};
+//!
+//! use std::time::Duration;
+//!
+//! let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+//! let serial = wiringx.serial("/dev/ttyS1", 115200).unwrap();
+//!
+//! let mut modem = AtClient::new(serial);
+//! modem.on_urc("RING", |line| println!("Incoming call: {line}"));
+//!
+//! modem.command("ATE0").unwrap();
+//! let response = modem.command("AT+CSQ").unwrap();
+//! println!("{response:?}");
+//!
+//! loop {
+//!     modem.poll_urcs(Duration::from_secs(1)).unwrap();
+//! }
+//! ```
+
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use thiserror::Error;
+
+use crate::{SerialPort, WiringXError};
+
+/// Final result codes reporting that a command failed.
+const ERROR_CODES: [&str; 5] = ["ERROR", "NO CARRIER", "BUSY", "NO ANSWER", "NO DIALTONE"];
+
+type UrcHandler = Box<dyn FnMut(&str) + Send>;
+
+/// Sends AT commands over a serial port and collects their responses.
+///
+/// Lines starting with a prefix registered with [`on_urc`](Self::on_urc) are unsolicited result codes.
+/// They get passed to their handler whenever they arrive, also while waiting for the response of a command,
+/// and never end up in a response.
+pub struct AtClient {
+    serial: SerialPort,
+    timeout: Duration,
+    urc_handlers: Vec<(String, UrcHandler)>,
+}
+
+impl AtClient {
+    /// Takes ownership of the serial port the module is connected to.
+    ///
+    /// Responses are awaited for one second by default.
+    pub fn new(serial: SerialPort) -> Self {
+        Self {
+            serial,
+            timeout: Duration::from_secs(1),
+            urc_handlers: Vec::new(),
+        }
+    }
+
+    /// Sets how long to wait for the final result code of a command.
+    #[inline]
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Returns how long to wait for the final result code of a command.
+    #[inline]
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Returns the serial port.
+    #[inline]
+    pub fn into_inner(self) -> SerialPort {
+        self.serial
+    }
+
+    /// Registers a handler called with every line starting with the prefix, for example `+CMTI:` or `RING`.
+    pub fn on_urc(
+        &mut self,
+        prefix: impl Into<String>,
+        handler: impl FnMut(&str) + Send + 'static,
+    ) {
+        self.urc_handlers.push((prefix.into(), Box::new(handler)));
+    }
+
+    /// Sends the command and returns the lines of its response, without the echo and the final `OK`.
+    ///
+    /// The command is sent as given followed by `\r`, for example `AT+CSQ`.
+    #[inline]
+    pub fn command(&mut self, command: &str) -> Result<Vec<String>, AtError> {
+        self.command_with_timeout(command, self.timeout)
+    }
+
+    /// Same as [`command`](Self::command), but waits for the response up to the given timeout,
+    /// for commands taking longer than usual like network registration.
+    pub fn command_with_timeout(
+        &mut self,
+        command: &str,
+        timeout: Duration,
+    ) -> Result<Vec<String>, AtError> {
+        self.write_all(command.as_bytes())?;
+        self.write_all(b"\r")?;
+
+        let deadline = Instant::now() + timeout;
+        let mut response = Vec::new();
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let line = self.read_line(remaining)?;
+
+            if line.is_empty() || line == command || self.dispatch_urc(&line) {
+                continue;
+            }
+
+            if line == "OK" {
+                return Ok(response);
+            }
+
+            if ERROR_CODES.contains(&line.as_str())
+                || line.starts_with("+CME ERROR:")
+                || line.starts_with("+CMS ERROR:")
+            {
+                return Err(AtError::Error(line));
+            }
+
+            response.push(line);
+        }
+    }
+
+    /// Waits up to the timeout for unsolicited result codes and passes them to their handlers.
+    ///
+    /// Returns after the first line arrived, or after the timeout without error.
+    /// Lines without a registered handler are discarded.
+    pub fn poll_urcs(&mut self, timeout: Duration) -> Result<(), AtError> {
+        let line = match self.read_line(timeout) {
+            Ok(line) => line,
+            Err(AtError::Serial(WiringXError::TimedOut)) => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        self.dispatch_urc(&line);
+
+        Ok(())
+    }
+
+    /// Writes raw bytes to the module, for example the payload after a `>` prompt.
+    pub fn write_all(&mut self, bytes: &[u8]) -> Result<(), AtError> {
+        let mut written = 0;
+        while written < bytes.len() {
+            written += self.serial.write(&bytes[written..])?;
+        }
+
+        Ok(())
+    }
+
+    /// Passes the line to the first handler whose prefix matches, returning whether there was one.
+    fn dispatch_urc(&mut self, line: &str) -> bool {
+        match self
+            .urc_handlers
+            .iter_mut()
+            .find(|(prefix, _)| line.starts_with(prefix.as_str()))
+        {
+            Some((_, handler)) => {
+                handler(line);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn read_line(&mut self, timeout: Duration) -> Result<String, AtError> {
+        if timeout.is_zero() {
+            return Err(AtError::Serial(WiringXError::TimedOut));
+        }
+
+        let line = self.serial.read_line_timeout(timeout)?;
+
+        Ok(line.trim().to_string())
+    }
+}
+
+impl fmt::Debug for AtClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AtClient")
+            .field("serial", &self.serial)
+            .field("timeout", &self.timeout)
+            .field(
+                "urc_prefixes",
+                &self
+                    .urc_handlers
+                    .iter()
+                    .map(|(prefix, _)| prefix)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+/// Errors when communicating with an AT command module.
+#[derive(Error, Debug)]
+pub enum AtError {
+    /// Gets returned when the serial port fails, or the module did not respond in time.
+    #[error("Serial communication failed: {0}")]
+    Serial(#[from] WiringXError),
+    /// Gets returned when the module answers with an error result code, for example `+CME ERROR: 10`.
+    #[error("The module responded with {0}.")]
+    Error(String),
+}
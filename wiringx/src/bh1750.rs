@@ -0,0 +1,191 @@
+//! BH1750 ambient light sensors.
+
+use std::{thread, time::Duration};
+
+use crate::{WiringXError, I2C};
+
+const POWER_DOWN: u8 = 0x00;
+const POWER_ON: u8 = 0x01;
+const RESET: u8 = 0x07;
+const MEASUREMENT_TIME_HIGH: u8 = 0x40;
+const MEASUREMENT_TIME_LOW: u8 = 0x60;
+
+/// Default value of the measurement time register, the sensitivities of the datasheet refer to it.
+const DEFAULT_MEASUREMENT_TIME: u8 = 69;
+
+/// A ROHM BH1750 sensor measuring illuminance from 1 to 65535 lx, with a spectral response close to the human eye.
+///
+/// Example dimming a display with the ambient light on the I2C bus at address `0x23`:
+/// ```no_run
+/// use wiringx::{Bh1750, Platform, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let i2c = wiringx.setup_i2c("/dev/i2c-1".into(), 0x23).unwrap();
+///
+/// let mut sensor = Bh1750::new(i2c).unwrap();
+///
+/// let lux = sensor.read_lux().unwrap();
+/// let brightness = (lux / 500.0).clamp(0.05, 1.0);
+/// println!("{lux:.1} lx, brightness {:.0} %", brightness * 100.0);
+/// ```
+#[derive(Debug)]
+pub struct Bh1750 {
+    i2c: I2C,
+    mode: Bh1750Mode,
+    measurement_time: u8,
+}
+
+impl Bh1750 {
+    /// Powers on the sensor and starts measuring continuously in [`Bh1750Mode::ContinuousHigh`].
+    ///
+    /// The sensor is at address `0x23`, or `0x5C` if its `ADDR` pin is high.
+    pub fn new(i2c: I2C) -> Result<Self, WiringXError> {
+        let mut sensor = Self {
+            i2c,
+            mode: Bh1750Mode::ContinuousHigh,
+            measurement_time: DEFAULT_MEASUREMENT_TIME,
+        };
+
+        sensor.command(POWER_ON)?;
+        sensor.command(RESET)?;
+        sensor.set_mode(Bh1750Mode::ContinuousHigh)?;
+
+        Ok(sensor)
+    }
+
+    /// Sets the measurement mode, waiting for the first measurement of continuous modes.
+    pub fn set_mode(&mut self, mode: Bh1750Mode) -> Result<(), WiringXError> {
+        self.mode = mode;
+
+        if mode.is_continuous() {
+            self.command(mode as u8)?;
+            thread::sleep(self.conversion_time());
+        }
+
+        Ok(())
+    }
+
+    /// Returns the measurement mode.
+    #[inline]
+    pub fn mode(&self) -> Bh1750Mode {
+        self.mode
+    }
+
+    /// Sets the measurement time register from `31` to `254`, `69` by default.
+    ///
+    /// The sensitivity and the conversion time scale with it, higher values resolve darker light
+    /// and help behind tinted windows, lower values reach up to 100000 lx.
+    pub fn set_measurement_time(&mut self, measurement_time: u8) -> Result<(), WiringXError> {
+        if !(31..=254).contains(&measurement_time) {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        self.command(MEASUREMENT_TIME_HIGH | measurement_time >> 5)?;
+        self.command(MEASUREMENT_TIME_LOW | measurement_time & 0x1F)?;
+        self.measurement_time = measurement_time;
+
+        self.set_mode(self.mode)
+    }
+
+    /// Returns the measurement time register.
+    #[inline]
+    pub fn measurement_time(&self) -> u8 {
+        self.measurement_time
+    }
+
+    /// Returns the longest time one measurement takes in the current mode.
+    pub fn conversion_time(&self) -> Duration {
+        let typical = match self.mode.resolution() {
+            Bh1750Resolution::Low => 24,
+            _ => 180,
+        };
+
+        Duration::from_millis(
+            typical * self.measurement_time as u64 / DEFAULT_MEASUREMENT_TIME as u64,
+        )
+    }
+
+    /// Returns the illuminance in lx, measuring once before in one time modes.
+    pub fn read_lux(&mut self) -> Result<f32, WiringXError> {
+        Ok(self.read_raw()? as f32 / self.counts_per_lux())
+    }
+
+    /// Returns the raw measurement, measuring once before in one time modes.
+    pub fn read_raw(&mut self) -> Result<u16, WiringXError> {
+        // The sensor powers down after one time measurements.
+        if !self.mode.is_continuous() {
+            self.command(POWER_ON)?;
+            self.command(self.mode as u8)?;
+            thread::sleep(self.conversion_time());
+        }
+
+        let mut data = [0; 2];
+        self.i2c.read_bytes(&mut data)?;
+
+        Ok(u16::from_be_bytes(data))
+    }
+
+    /// Powers the sensor down, reading powers it on again for one time modes,
+    /// continuous modes need [`set_mode`](Self::set_mode).
+    #[inline]
+    pub fn power_down(&mut self) -> Result<(), WiringXError> {
+        self.command(POWER_DOWN)
+    }
+
+    /// Returns the counts per lux, 1.2 in the default configuration.
+    fn counts_per_lux(&self) -> f32 {
+        let counts = 1.2 * self.measurement_time as f32 / DEFAULT_MEASUREMENT_TIME as f32;
+
+        match self.mode.resolution() {
+            Bh1750Resolution::High2 => counts * 2.0,
+            _ => counts,
+        }
+    }
+
+    fn command(&mut self, command: u8) -> Result<(), WiringXError> {
+        self.i2c.write_bytes(&[command])?;
+
+        Ok(())
+    }
+}
+
+/// Measurement mode of a [`Bh1750`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Bh1750Mode {
+    /// Measures continuously with a resolution of 1 lx
+    #[default]
+    ContinuousHigh = 0x10,
+    /// Measures continuously with a resolution of 0.5 lx
+    ContinuousHigh2 = 0x11,
+    /// Measures continuously with a resolution of 4 lx in 16 ms
+    ContinuousLow = 0x13,
+    /// Measures once per read with a resolution of 1 lx, powering down in between
+    OneTimeHigh = 0x20,
+    /// Measures once per read with a resolution of 0.5 lx, powering down in between
+    OneTimeHigh2 = 0x21,
+    /// Measures once per read with a resolution of 4 lx, powering down in between
+    OneTimeLow = 0x23,
+}
+
+impl Bh1750Mode {
+    #[inline]
+    fn is_continuous(&self) -> bool {
+        (*self as u8) < 0x20
+    }
+
+    #[inline]
+    fn resolution(&self) -> Bh1750Resolution {
+        match *self as u8 & 0x0F {
+            0x01 => Bh1750Resolution::High2,
+            0x03 => Bh1750Resolution::Low,
+            _ => Bh1750Resolution::High,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bh1750Resolution {
+    High,
+    High2,
+    Low,
+}
@@ -0,0 +1,473 @@
+//! BME280 temperature, pressure and humidity sensors.
+
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{BitOrder, Spi, SpiMode, WiringXError, I2C};
+
+const CALIBRATION_TP: u8 = 0x88;
+const CALIBRATION_H1: u8 = 0xA1;
+const CHIP_ID: u8 = 0xD0;
+const RESET: u8 = 0xE0;
+const CALIBRATION_H: u8 = 0xE1;
+const CTRL_HUM: u8 = 0xF2;
+const STATUS: u8 = 0xF3;
+const CTRL_MEAS: u8 = 0xF4;
+const CONFIG: u8 = 0xF5;
+const DATA: u8 = 0xF7;
+
+const BME280_CHIP_ID: u8 = 0x60;
+const RESET_COMMAND: u8 = 0xB6;
+const STATUS_MEASURING: u8 = 0x08;
+const STATUS_IM_UPDATE: u8 = 0x01;
+
+/// A Bosch BME280 sensor measuring temperature, barometric pressure and relative humidity.
+///
+/// Measurements get compensated with the calibration data stored on each sensor,
+/// using the integer formulas of the datasheet.
+///
+/// The sensor starts in [`Bme280Mode::Forced`] with 1x oversampling of all measurements,
+/// which suits measuring every few seconds or less often.
+///
+/// Example on the I2C bus at address `0x76`:
+/// ```no_run
+/// use wiringx::{Bme280, Platform, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let i2c = wiringx.setup_i2c("/dev/i2c-1".into(), 0x76).unwrap();
+///
+/// let mut sensor = Bme280::new_i2c(i2c).unwrap();
+///
+/// let measurement = sensor.measure().unwrap();
+/// println!(
+///     "{:.1} °C, {:.1} hPa, {:.1} %",
+///     measurement.temperature,
+///     measurement.pressure.unwrap() / 100.0,
+///     measurement.humidity.unwrap()
+/// );
+/// ```
+#[derive(Debug)]
+pub struct Bme280 {
+    interface: Bme280Interface,
+    calibration: Calibration,
+    temperature_oversampling: Bme280Oversampling,
+    pressure_oversampling: Bme280Oversampling,
+    humidity_oversampling: Bme280Oversampling,
+    filter: Bme280Filter,
+    standby: Bme280Standby,
+    mode: Bme280Mode,
+}
+
+/// The bus the sensor is connected with.
+#[derive(Debug)]
+enum Bme280Interface {
+    I2c(I2C),
+    Spi(Spi),
+}
+
+/// Compensation parameters, named like in the datasheet.
+#[derive(Debug, Default)]
+struct Calibration {
+    t1: u16,
+    t2: i16,
+    t3: i16,
+    p1: u16,
+    p2: i16,
+    p3: i16,
+    p4: i16,
+    p5: i16,
+    p6: i16,
+    p7: i16,
+    p8: i16,
+    p9: i16,
+    h1: u8,
+    h2: i16,
+    h3: u8,
+    h4: i16,
+    h5: i16,
+    h6: i8,
+}
+
+impl Bme280 {
+    /// Initializes a sensor on the I2C bus, at address `0x76` or `0x77` depending on its `SDO` pin.
+    pub fn new_i2c(i2c: I2C) -> Result<Self, WiringXError> {
+        Self::init(Bme280Interface::I2c(i2c))
+    }
+
+    /// Initializes a sensor on the SPI bus.
+    ///
+    /// The controller is reconfigured to [`SpiMode::Mode0`] and [`BitOrder::MsbFirst`].
+    pub fn new_spi(mut spi: Spi) -> Result<Self, WiringXError> {
+        spi.set_mode(SpiMode::Mode0)?;
+        spi.set_bit_order(BitOrder::MsbFirst)?;
+
+        Self::init(Bme280Interface::Spi(spi))
+    }
+
+    fn init(interface: Bme280Interface) -> Result<Self, WiringXError> {
+        let mut sensor = Self {
+            interface,
+            calibration: Calibration::default(),
+            temperature_oversampling: Bme280Oversampling::X1,
+            pressure_oversampling: Bme280Oversampling::X1,
+            humidity_oversampling: Bme280Oversampling::X1,
+            filter: Bme280Filter::Off,
+            standby: Bme280Standby::Ms1000,
+            mode: Bme280Mode::Forced,
+        };
+
+        let mut id = [0];
+        sensor.read(CHIP_ID, &mut id)?;
+        if id[0] != BME280_CHIP_ID {
+            return Err(WiringXError::Unsupported);
+        }
+
+        sensor.write(RESET, RESET_COMMAND)?;
+        thread::sleep(Duration::from_millis(2));
+
+        // The calibration data gets copied from its non-volatile memory after the reset.
+        let deadline = Instant::now() + Duration::from_millis(50);
+        while sensor.status()? & STATUS_IM_UPDATE != 0 {
+            if Instant::now() > deadline {
+                return Err(WiringXError::TimedOut);
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        sensor.read_calibration()?;
+        sensor.configure()?;
+
+        Ok(sensor)
+    }
+
+    /// Sets the oversampling of the measurements, trading speed and power for lower noise.
+    ///
+    /// Pressure and humidity can be skipped, temperature is needed to compensate the others.
+    pub fn set_oversampling(
+        &mut self,
+        temperature: Bme280Oversampling,
+        pressure: Bme280Oversampling,
+        humidity: Bme280Oversampling,
+    ) -> Result<(), WiringXError> {
+        if temperature == Bme280Oversampling::Skip {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        self.temperature_oversampling = temperature;
+        self.pressure_oversampling = pressure;
+        self.humidity_oversampling = humidity;
+
+        self.configure()
+    }
+
+    /// Sets the IIR filter smoothing temperature and pressure against short disturbances, like wind or slammed doors.
+    pub fn set_filter(&mut self, filter: Bme280Filter) -> Result<(), WiringXError> {
+        self.filter = filter;
+
+        self.configure()
+    }
+
+    /// Sets the time between measurements in [`Bme280Mode::Normal`].
+    pub fn set_standby(&mut self, standby: Bme280Standby) -> Result<(), WiringXError> {
+        self.standby = standby;
+
+        self.configure()
+    }
+
+    /// Sets whether the sensor measures on request, continuously or not at all.
+    pub fn set_mode(&mut self, mode: Bme280Mode) -> Result<(), WiringXError> {
+        self.mode = mode;
+
+        self.configure()
+    }
+
+    /// Returns the measuring mode of the sensor.
+    #[inline]
+    pub fn mode(&self) -> Bme280Mode {
+        self.mode
+    }
+
+    /// Returns a compensated measurement.
+    ///
+    /// In [`Bme280Mode::Forced`] and [`Bme280Mode::Sleep`] this starts a measurement and waits for it to complete,
+    /// in [`Bme280Mode::Normal`] it returns the latest one.
+    pub fn measure(&mut self) -> Result<Bme280Measurement, WiringXError> {
+        match self.mode {
+            Bme280Mode::Sleep | Bme280Mode::Forced => {
+                // Every write of forced mode starts one measurement, after which the sensor sleeps again.
+                self.write(
+                    CTRL_MEAS,
+                    self.ctrl_meas() & !0x03 | Bme280Mode::Forced as u8,
+                )?;
+                thread::sleep(self.measurement_time());
+
+                let deadline = Instant::now() + Duration::from_millis(50);
+                while self.status()? & STATUS_MEASURING != 0 {
+                    if Instant::now() > deadline {
+                        return Err(WiringXError::TimedOut);
+                    }
+                    thread::sleep(Duration::from_millis(1));
+                }
+            }
+            Bme280Mode::Normal => {}
+        }
+
+        let mut data = [0; 8];
+        self.read(DATA, &mut data)?;
+
+        let adc_p = (data[0] as i32) << 12 | (data[1] as i32) << 4 | (data[2] as i32) >> 4;
+        let adc_t = (data[3] as i32) << 12 | (data[4] as i32) << 4 | (data[5] as i32) >> 4;
+        let adc_h = (data[6] as i32) << 8 | data[7] as i32;
+
+        let t_fine = self.calibration.t_fine(adc_t);
+
+        Ok(Bme280Measurement {
+            temperature: ((t_fine * 5 + 128) >> 8) as f32 / 100.0,
+            pressure: (self.pressure_oversampling != Bme280Oversampling::Skip)
+                .then(|| self.calibration.pressure(adc_p, t_fine) as f32 / 256.0),
+            humidity: (self.humidity_oversampling != Bme280Oversampling::Skip)
+                .then(|| self.calibration.humidity(adc_h, t_fine) as f32 / 1024.0),
+        })
+    }
+
+    /// Returns the maximum time a measurement takes with the current oversampling.
+    pub fn measurement_time(&self) -> Duration {
+        let factor = |oversampling: Bme280Oversampling| match oversampling {
+            Bme280Oversampling::Skip => 0,
+            oversampling => 1 << (oversampling as u64 - 1),
+        };
+
+        let mut micros = 1250 + 2300 * factor(self.temperature_oversampling);
+        for oversampling in [self.pressure_oversampling, self.humidity_oversampling] {
+            if oversampling != Bme280Oversampling::Skip {
+                micros += 2300 * factor(oversampling) + 575;
+            }
+        }
+
+        Duration::from_micros(micros)
+    }
+
+    fn read_calibration(&mut self) -> Result<(), WiringXError> {
+        let mut tp = [0; 24];
+        self.read(CALIBRATION_TP, &mut tp)?;
+        let mut h1 = [0];
+        self.read(CALIBRATION_H1, &mut h1)?;
+        let mut h = [0; 7];
+        self.read(CALIBRATION_H, &mut h)?;
+
+        let word = |i: usize| u16::from_le_bytes([tp[i], tp[i + 1]]);
+
+        self.calibration = Calibration {
+            t1: word(0),
+            t2: word(2) as i16,
+            t3: word(4) as i16,
+            p1: word(6),
+            p2: word(8) as i16,
+            p3: word(10) as i16,
+            p4: word(12) as i16,
+            p5: word(14) as i16,
+            p6: word(16) as i16,
+            p7: word(18) as i16,
+            p8: word(20) as i16,
+            p9: word(22) as i16,
+            h1: h1[0],
+            h2: i16::from_le_bytes([h[0], h[1]]),
+            h3: h[2],
+            // Two 12 bit values sharing the nibbles of one byte.
+            h4: (h[3] as i8 as i16) << 4 | (h[4] & 0x0F) as i16,
+            h5: (h[5] as i8 as i16) << 4 | (h[4] >> 4) as i16,
+            h6: h[6] as i8,
+        };
+
+        Ok(())
+    }
+
+    fn configure(&mut self) -> Result<(), WiringXError> {
+        // Changes of the humidity control only take effect with the next write of the measurement control,
+        // and the configuration may be ignored outside of sleep mode.
+        self.write(CTRL_MEAS, self.ctrl_meas() & !0x03)?;
+        self.write(CTRL_HUM, self.humidity_oversampling as u8)?;
+        self.write(CONFIG, (self.standby as u8) << 5 | (self.filter as u8) << 2)?;
+
+        if self.mode == Bme280Mode::Normal {
+            self.write(CTRL_MEAS, self.ctrl_meas())?;
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn ctrl_meas(&self) -> u8 {
+        (self.temperature_oversampling as u8) << 5
+            | (self.pressure_oversampling as u8) << 2
+            | self.mode as u8
+    }
+
+    fn status(&mut self) -> Result<u8, WiringXError> {
+        let mut status = [0];
+        self.read(STATUS, &mut status)?;
+
+        Ok(status[0])
+    }
+
+    fn read(&mut self, register: u8, data: &mut [u8]) -> Result<(), WiringXError> {
+        match &mut self.interface {
+            Bme280Interface::I2c(i2c) => {
+                i2c.read_reg_bytes(register, data)?;
+
+                Ok(())
+            }
+            Bme280Interface::Spi(spi) => {
+                // The register address is sent with the read bit set, data follows it.
+                let mut message = vec![0; data.len() + 1];
+                message[0] = register | 0x80;
+                spi.read_write(&mut message)?;
+
+                data.copy_from_slice(&message[1..]);
+
+                Ok(())
+            }
+        }
+    }
+
+    fn write(&mut self, register: u8, value: u8) -> Result<(), WiringXError> {
+        match &mut self.interface {
+            Bme280Interface::I2c(i2c) => {
+                i2c.write_bytes(&[register, value])?;
+
+                Ok(())
+            }
+            Bme280Interface::Spi(spi) => spi.write(&[register & 0x7F, value]),
+        }
+    }
+}
+
+impl Calibration {
+    /// Returns the fine temperature the pressure and humidity compensation is based on.
+    fn t_fine(&self, adc_t: i32) -> i32 {
+        let t1 = self.t1 as i32;
+
+        let var1 = (((adc_t >> 3) - (t1 << 1)) * self.t2 as i32) >> 11;
+        let var2 = (((((adc_t >> 4) - t1) * ((adc_t >> 4) - t1)) >> 12) * self.t3 as i32) >> 14;
+
+        var1 + var2
+    }
+
+    /// Returns the pressure in Pascal as fixed point number with 8 fractional bits.
+    fn pressure(&self, adc_p: i32, t_fine: i32) -> u32 {
+        let mut var1 = t_fine as i64 - 128000;
+        let mut var2 = var1 * var1 * self.p6 as i64;
+        var2 += (var1 * self.p5 as i64) << 17;
+        var2 += (self.p4 as i64) << 35;
+        var1 = ((var1 * var1 * self.p3 as i64) >> 8) + ((var1 * self.p2 as i64) << 12);
+        var1 = (((1i64 << 47) + var1) * self.p1 as i64) >> 33;
+
+        if var1 == 0 {
+            return 0;
+        }
+
+        let mut p = 1048576 - adc_p as i64;
+        p = (((p << 31) - var2) * 3125) / var1;
+        let var1 = (self.p9 as i64 * (p >> 13) * (p >> 13)) >> 25;
+        let var2 = (self.p8 as i64 * p) >> 19;
+
+        (((p + var1 + var2) >> 8) + ((self.p7 as i64) << 4)) as u32
+    }
+
+    /// Returns the relative humidity in percent as fixed point number with 10 fractional bits.
+    fn humidity(&self, adc_h: i32, t_fine: i32) -> u32 {
+        let v = t_fine - 76800;
+
+        let mut v = ((((adc_h << 14) - ((self.h4 as i32) << 20) - (self.h5 as i32 * v)) + 16384)
+            >> 15)
+            * (((((((v * self.h6 as i32) >> 10) * (((v * self.h3 as i32) >> 11) + 32768)) >> 10)
+                + 2097152)
+                * self.h2 as i32
+                + 8192)
+                >> 14);
+        v -= ((((v >> 15) * (v >> 15)) >> 7) * self.h1 as i32) >> 4;
+
+        (v.clamp(0, 419430400) >> 12) as u32
+    }
+}
+
+/// A compensated measurement of a [`Bme280`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bme280Measurement {
+    /// Temperature in degrees Celsius.
+    pub temperature: f32,
+    /// Barometric pressure in Pascal, none if skipped.
+    pub pressure: Option<f32>,
+    /// Relative humidity in percent, none if skipped.
+    pub humidity: Option<f32>,
+}
+
+/// Number of samples a measurement of a [`Bme280`] gets averaged from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Bme280Oversampling {
+    /// Measurement skipped
+    Skip = 0,
+    /// 1 sample
+    #[default]
+    X1 = 1,
+    /// 2 samples
+    X2 = 2,
+    /// 4 samples
+    X4 = 3,
+    /// 8 samples
+    X8 = 4,
+    /// 16 samples
+    X16 = 5,
+}
+
+/// Coefficient of the IIR filter of a [`Bme280`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Bme280Filter {
+    /// No filtering
+    #[default]
+    Off = 0,
+    /// Coefficient 2
+    X2 = 1,
+    /// Coefficient 4
+    X4 = 2,
+    /// Coefficient 8
+    X8 = 3,
+    /// Coefficient 16
+    X16 = 4,
+}
+
+/// Inactive time between measurements of a [`Bme280`] in [`Bme280Mode::Normal`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Bme280Standby {
+    /// 0.5 milliseconds
+    Ms0_5 = 0,
+    /// 62.5 milliseconds
+    Ms62_5 = 1,
+    /// 125 milliseconds
+    Ms125 = 2,
+    /// 250 milliseconds
+    Ms250 = 3,
+    /// 500 milliseconds
+    Ms500 = 4,
+    /// 1 second
+    #[default]
+    Ms1000 = 5,
+    /// 10 milliseconds
+    Ms10 = 6,
+    /// 20 milliseconds
+    Ms20 = 7,
+}
+
+/// Measuring mode of a [`Bme280`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Bme280Mode {
+    /// No measurements, lowest power consumption
+    Sleep = 0,
+    /// One measurement on every request, returning to sleep afterwards
+    #[default]
+    Forced = 1,
+    /// Continuous measurements separated by the standby time
+    Normal = 3,
+}
@@ -0,0 +1,403 @@
+//! Setting up the pins from a TOML file, enabled with the `config` feature.
+//!
+//! The file names the platform and maps logical names to GPIO and PWM pins,
+//! so the hardware can be re-pinned without recompiling:
+//! ```toml
+//! # Name of the platform like for wiringX, `generic` for boards wiringX does not support
+//! platform = "milkv_duos"
+//! # Optional: `wiringx` (default), `gpiochip`, `sysfs` or `mock`
+//! backend = "gpiochip"
+//! # With the `gpiochip` backend, the chips numbered consecutively
+//! gpiochips = ["/dev/gpiochip0", "/dev/gpiochip1"]
+//!
+//! [pins.led]
+//! pin = 14
+//! mode = "output"
+//! # Optional: `low` (default) or `high`
+//! initial = "high"
+//!
+//! [pins.button]
+//! pin = 3
+//! mode = "input"
+//! # Optional: `none`, `up` or `down`, left as is if not given
+//! pull = "up"
+//! # Optional: `none` (default), `rising`, `falling` or `both`
+//! edge = "falling"
+//!
+//! [pwm.fan]
+//! pin = 4
+//! # Either the period in ns or the frequency in Hz
+//! frequency = 25000
+//! # Optional: from 0.0 (default) - 1.0
+//! duty_cycle = 0.5
+//! # Optional: `normal` (default) or `inversed`
+//! polarity = "normal"
+//! ```
+//!
+//! Example taking the pins set up from the file:
+//! ```no_run
+//! use wiringx::WiringX;
+//!
+//! let mut pins = WiringX::from_config("board.toml").unwrap();
+//!
+//! let mut led = pins.take_output("led").unwrap();
+//! let button = pins.take_input("button").unwrap();
+//!
+//! led.write(button.read());
+//! ```
+
+use std::{collections::BTreeMap, fs, path::Path, str::FromStr, time::Duration};
+
+use serde::Deserialize;
+
+use crate::{
+    GpioBackend, Input, IsrMode, Output, Pin, Platform, Polarity, Pull, PwmPin, Value, WiringX,
+    WiringXError,
+};
+
+/// The platform and pins of a configuration file, see the [module](self) documentation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    /// The board.
+    pub platform: Platform,
+    /// What drives the GPIO pins.
+    pub backend: GpioBackend,
+    /// The GPIO pins by name.
+    pub pins: BTreeMap<String, PinConfig>,
+    /// The PWM pins by name.
+    pub pwm: BTreeMap<String, PwmConfig>,
+}
+
+/// A GPIO pin of a [`Config`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PinConfig {
+    /// Number of the pin.
+    pub pin: i32,
+    /// Whether it is an input or output, with the settings of each.
+    pub mode: PinModeConfig,
+}
+
+/// Mode of a [`PinConfig`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinModeConfig {
+    /// An input with the resistor pulling it, left as is if none, and the edges to detect
+    Input { pull: Option<Pull>, edge: IsrMode },
+    /// An output driven to the value
+    Output { initial: Value },
+}
+
+/// A PWM pin of a [`Config`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PwmConfig {
+    /// Number of the pin.
+    pub pin: i32,
+    /// Length of a period.
+    pub period: Duration,
+    /// The duty cycle from 0.0 - 1.0.
+    pub duty_cycle: f32,
+    /// Polarity of the signal.
+    pub polarity: Polarity,
+}
+
+impl Config {
+    /// Loads the configuration from the file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, WiringXError> {
+        fs::read_to_string(path).map_err(WiringXError::Io)?.parse()
+    }
+
+    /// Sets up WiringX and all pins of the configuration.
+    ///
+    /// Like with [`WiringX::with_gpio_backend`], the platform and backend do nothing if WiringX was set up before.
+    pub fn build(&self) -> Result<ConfiguredPins, WiringXError> {
+        let wiringx = WiringX::with_gpio_backend(self.platform, self.backend.clone())?;
+
+        let mut pins = ConfiguredPins {
+            wiringx,
+            inputs: BTreeMap::new(),
+            outputs: BTreeMap::new(),
+            pwm: BTreeMap::new(),
+        };
+
+        for (name, config) in &self.pins {
+            match config.mode {
+                PinModeConfig::Input { pull, edge } => {
+                    let pin = wiringx.gpio_pin::<Input>(config.pin)?;
+                    if let Some(pull) = pull {
+                        pin.set_pull(pull)?;
+                    }
+
+                    if edge != IsrMode::None {
+                        pin.set_isr_mode(edge)?;
+                    }
+
+                    pins.inputs.insert(name.clone(), pin);
+                }
+                PinModeConfig::Output { initial } => {
+                    let mut pin = wiringx.gpio_pin::<Output>(config.pin)?;
+                    pin.write(initial);
+
+                    pins.outputs.insert(name.clone(), pin);
+                }
+            }
+        }
+
+        for (name, config) in &self.pwm {
+            let pwm = wiringx.pwm_pin(
+                config.pin,
+                config.period,
+                config.duty_cycle,
+                config.polarity,
+            )?;
+
+            pins.pwm.insert(name.clone(), pwm);
+        }
+
+        Ok(pins)
+    }
+}
+
+impl FromStr for Config {
+    type Err = WiringXError;
+
+    fn from_str(config: &str) -> Result<Self, Self::Err> {
+        let invalid =
+            |message: String| WiringXError::Other(format!("Invalid configuration: {message}"));
+
+        let file: ConfigFile =
+            toml::from_str(config).map_err(|e| invalid(e.message().to_string()))?;
+
+        let platform = Platform::from_string(&file.platform).map_err(|e| invalid(e.to_string()))?;
+
+        let backend = match (file.backend, file.gpiochips) {
+            (BackendName::Gpiochip, Some(chips)) if !chips.is_empty() => {
+                GpioBackend::Gpiochip(chips)
+            }
+            (BackendName::Gpiochip, _) => {
+                return Err(invalid(
+                    "the gpiochip backend needs the gpiochips".to_string(),
+                ))
+            }
+            (_, Some(_)) => {
+                return Err(invalid(
+                    "gpiochips are only used by the gpiochip backend".to_string(),
+                ))
+            }
+            (BackendName::Wiringx, None) => GpioBackend::WiringX,
+            (BackendName::Sysfs, None) => GpioBackend::Sysfs,
+            #[cfg(feature = "mock")]
+            (BackendName::Mock, None) => GpioBackend::Mock,
+            #[cfg(not(feature = "mock"))]
+            (BackendName::Mock, None) => {
+                return Err(invalid(
+                    "the mock backend needs the mock feature".to_string(),
+                ))
+            }
+        };
+
+        let mut pins = BTreeMap::new();
+        for (name, pin) in file.pins {
+            let mode = match pin.mode {
+                ModeName::Input => {
+                    if pin.initial.is_some() {
+                        return Err(invalid(format!("the input {name} has an initial level")));
+                    }
+
+                    PinModeConfig::Input {
+                        pull: pin.pull.map(|pull| match pull {
+                            PullName::None => Pull::None,
+                            PullName::Up => Pull::Up,
+                            PullName::Down => Pull::Down,
+                        }),
+                        edge: match pin.edge.unwrap_or(EdgeName::None) {
+                            EdgeName::None => IsrMode::None,
+                            EdgeName::Rising => IsrMode::Rising,
+                            EdgeName::Falling => IsrMode::Falling,
+                            EdgeName::Both => IsrMode::Both,
+                        },
+                    }
+                }
+                ModeName::Output => {
+                    if pin.pull.is_some() || pin.edge.is_some() {
+                        return Err(invalid(format!("the output {name} has a pull or edge")));
+                    }
+
+                    PinModeConfig::Output {
+                        initial: match pin.initial.unwrap_or(LevelName::Low) {
+                            LevelName::Low => Value::Low,
+                            LevelName::High => Value::High,
+                        },
+                    }
+                }
+            };
+
+            pins.insert(name, PinConfig { pin: pin.pin, mode });
+        }
+
+        let mut pwm = BTreeMap::new();
+        for (name, config) in file.pwm {
+            let period = match (config.period_ns, config.frequency) {
+                (Some(period), None) if period > 0 => Duration::from_nanos(period),
+                (None, Some(frequency)) if frequency > 0.0 && frequency.is_finite() => {
+                    Duration::from_secs_f64(1.0 / frequency)
+                }
+                _ => {
+                    return Err(invalid(format!(
+                        "the PWM pin {name} needs either a positive period_ns or frequency"
+                    )))
+                }
+            };
+
+            pwm.insert(
+                name,
+                PwmConfig {
+                    pin: config.pin,
+                    period,
+                    duty_cycle: config.duty_cycle.clamp(0.0, 1.0),
+                    polarity: match config.polarity {
+                        PolarityName::Normal => Polarity::Normal,
+                        PolarityName::Inversed => Polarity::Inversed,
+                    },
+                },
+            );
+        }
+
+        Ok(Self {
+            platform,
+            backend,
+            pins,
+            pwm,
+        })
+    }
+}
+
+/// The pins set up from a [`Config`], to be taken by their names.
+#[derive(Debug)]
+pub struct ConfiguredPins {
+    wiringx: &'static WiringX,
+    inputs: BTreeMap<String, Pin<Input>>,
+    outputs: BTreeMap<String, Pin<Output>>,
+    pwm: BTreeMap<String, PwmPin>,
+}
+
+impl ConfiguredPins {
+    /// Returns the WiringX instance, for setting up the buses.
+    #[inline]
+    pub fn wiringx(&self) -> &'static WiringX {
+        self.wiringx
+    }
+
+    /// Takes the input with the name, returning [`InvalidArgument`](WiringXError::InvalidArgument)
+    /// if there is no input of that name or it was taken already.
+    pub fn take_input(&mut self, name: &str) -> Result<Pin<Input>, WiringXError> {
+        self.inputs
+            .remove(name)
+            .ok_or(WiringXError::InvalidArgument)
+    }
+
+    /// Takes the output with the name, returning [`InvalidArgument`](WiringXError::InvalidArgument)
+    /// if there is no output of that name or it was taken already.
+    pub fn take_output(&mut self, name: &str) -> Result<Pin<Output>, WiringXError> {
+        self.outputs
+            .remove(name)
+            .ok_or(WiringXError::InvalidArgument)
+    }
+
+    /// Takes the PWM pin with the name, returning [`InvalidArgument`](WiringXError::InvalidArgument)
+    /// if there is no PWM pin of that name or it was taken already.
+    pub fn take_pwm(&mut self, name: &str) -> Result<PwmPin, WiringXError> {
+        self.pwm.remove(name).ok_or(WiringXError::InvalidArgument)
+    }
+}
+
+impl WiringX {
+    /// Loads the configuration file and sets up WiringX and all pins in it, see [`config`](crate::config).
+    #[inline]
+    pub fn from_config(path: impl AsRef<Path>) -> Result<ConfiguredPins, WiringXError> {
+        Config::load(path)?.build()
+    }
+}
+
+/// Layout of the file, converted to a [`Config`] after parsing.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    platform: String,
+    #[serde(default)]
+    backend: BackendName,
+    gpiochips: Option<Vec<std::path::PathBuf>>,
+    #[serde(default)]
+    pins: BTreeMap<String, PinFile>,
+    #[serde(default)]
+    pwm: BTreeMap<String, PwmFile>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PinFile {
+    pin: i32,
+    mode: ModeName,
+    initial: Option<LevelName>,
+    pull: Option<PullName>,
+    edge: Option<EdgeName>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PwmFile {
+    pin: i32,
+    period_ns: Option<u64>,
+    frequency: Option<f64>,
+    #[serde(default)]
+    duty_cycle: f32,
+    #[serde(default)]
+    polarity: PolarityName,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+enum BackendName {
+    #[default]
+    Wiringx,
+    Gpiochip,
+    Sysfs,
+    Mock,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ModeName {
+    Input,
+    Output,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum LevelName {
+    Low,
+    High,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum PullName {
+    None,
+    Up,
+    Down,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum EdgeName {
+    None,
+    Rising,
+    Falling,
+    Both,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+enum PolarityName {
+    #[default]
+    Normal,
+    Inversed,
+}
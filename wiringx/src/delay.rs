@@ -0,0 +1,72 @@
+//! Precise delays.
+
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::gpio::wait_until;
+
+/// Delays the calling thread, sleeping for most of the time and busy waiting for the rest.
+///
+/// Sleeping on Linux overshoots by up to about a hundred microseconds, busy waiting keeps a core occupied.
+/// Delays up to the spin threshold are waited for entirely, longer ones sleep until that threshold
+/// before their end, so they end on time without spinning for long.
+///
+/// With the `embedded-hal` feature it implements `DelayNs`, for drivers taking a delay provider.
+///
+/// Example:
+/// ```
+/// use std::time::Duration;
+///
+/// use wiringx::Delay;
+///
+/// let delay = Delay::new();
+/// delay.delay(Duration::from_micros(50));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Delay {
+    spin_threshold: Duration,
+}
+
+impl Delay {
+    /// Creates a delay busy waiting for the last 200 µs.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            spin_threshold: Duration::from_micros(200),
+        }
+    }
+
+    /// Creates a delay busy waiting for the last part of the delay given by the threshold.
+    ///
+    /// Higher thresholds are more precise on busy systems, zero never busy waits.
+    #[inline]
+    pub const fn with_spin_threshold(spin_threshold: Duration) -> Self {
+        Self { spin_threshold }
+    }
+
+    /// Returns the part of the delay busy waited for.
+    #[inline]
+    pub fn spin_threshold(&self) -> Duration {
+        self.spin_threshold
+    }
+
+    /// Delays the calling thread for at least the duration.
+    pub fn delay(&self, duration: Duration) {
+        let deadline = Instant::now() + duration;
+
+        if duration > self.spin_threshold {
+            thread::sleep(duration - self.spin_threshold);
+        }
+
+        wait_until(deadline);
+    }
+}
+
+impl Default for Delay {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,218 @@
+//! DHT11 and DHT22 humidity and temperature sensors.
+
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use thiserror::Error;
+
+use crate::{gpio::wait_until, Output, Pin, Value};
+
+/// Minimum time between two readings, the sensors answer with stale or broken data when polled faster.
+pub const DHT_MIN_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A DHT11 or DHT22 (AM2302) sensor on a single data pin.
+///
+/// The data line needs a pull-up resistor, which most breakout boards already have.
+///
+/// Example:
+/// ```no_run
+/// use wiringx::{Dht, DhtKind, Output, Platform, WiringX};
+///
+/// use std::{thread, time::Duration};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+///
+/// let mut dht = Dht::new(wiringx.gpio_pin::<Output>(15).unwrap(), DhtKind::Dht22);
+///
+/// loop {
+///     match dht.read() {
+///         Ok(reading) => println!("{} °C, {} %", reading.temperature, reading.humidity),
+///         Err(e) => eprintln!("{e}"),
+///     }
+///     thread::sleep(Duration::from_secs(5));
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Dht {
+    pin: Pin<Output>,
+    kind: DhtKind,
+    last_read: Option<Instant>,
+    last_reading: Option<DhtReading>,
+}
+
+impl Dht {
+    /// Takes ownership of the data pin and releases the line.
+    pub fn new(mut pin: Pin<Output>, kind: DhtKind) -> Self {
+        pin.release();
+
+        Self {
+            pin,
+            kind,
+            last_read: None,
+            last_reading: None,
+        }
+    }
+
+    /// Returns the data pin.
+    #[inline]
+    pub fn into_inner(self) -> Pin<Output> {
+        self.pin
+    }
+
+    /// Returns the type of the sensor.
+    #[inline]
+    pub fn kind(&self) -> DhtKind {
+        self.kind
+    }
+
+    /// Reads temperature and humidity from the sensor.
+    ///
+    /// Called again within [`DHT_MIN_INTERVAL`], the previous reading is returned without asking the sensor.
+    /// If that one failed, this waits until the interval passed before trying again.
+    ///
+    /// The handshake is timed by busy waiting, so reads fail with [`Timeout`](DhtError::Timeout)
+    /// or [`Checksum`](DhtError::Checksum) when the thread gets preempted and should be retried.
+    pub fn read(&mut self) -> Result<DhtReading, DhtError> {
+        if let Some(last_read) = self.last_read {
+            let elapsed = last_read.elapsed();
+
+            if elapsed < DHT_MIN_INTERVAL {
+                if let Some(reading) = self.last_reading {
+                    return Ok(reading);
+                }
+
+                thread::sleep(DHT_MIN_INTERVAL - elapsed);
+            }
+        }
+
+        let result = self.receive().map(|data| self.kind.decode(&data));
+
+        self.last_read = Some(Instant::now());
+        self.last_reading = result.as_ref().ok().copied();
+
+        result
+    }
+
+    /// Performs the start handshake and receives the five data bytes, checking their checksum.
+    fn receive(&mut self) -> Result<[u8; 5], DhtError> {
+        let start = Instant::now();
+        self.pin.drive(Value::Low);
+        wait_until(start + self.kind.start_signal());
+        self.pin.release();
+
+        // The sensor answers with 80 µs low and 80 µs high before the first bit.
+        self.wait_for(Value::Low, 100)
+            .map_err(|_| DhtError::NoResponse)?;
+        self.wait_for(Value::High, 100)?;
+        self.wait_for(Value::Low, 100)?;
+
+        let mut data = [0; 5];
+
+        for i in 0..40 {
+            // Every bit starts with 50 µs low, followed by 26 - 28 µs high for `0` or 70 µs high for `1`.
+            self.wait_for(Value::High, 80)?;
+            let high = self.wait_for(Value::Low, 100)?;
+
+            if high > Duration::from_micros(48) {
+                data[i / 8] |= 0x80 >> (i % 8);
+            }
+        }
+
+        let sum = data[..4]
+            .iter()
+            .fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+        if sum != data[4] {
+            return Err(DhtError::Checksum);
+        }
+
+        Ok(data)
+    }
+
+    /// Waits until the line changes to the value, returning how long that took.
+    fn wait_for(&self, value: Value, timeout_micros: u64) -> Result<Duration, DhtError> {
+        let start = Instant::now();
+        let timeout = Duration::from_micros(timeout_micros);
+
+        while self.pin.read() != value {
+            if start.elapsed() > timeout {
+                return Err(DhtError::Timeout);
+            }
+        }
+
+        Ok(start.elapsed())
+    }
+}
+
+/// The type of a [`Dht`] sensor, they differ in start signal and data format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DhtKind {
+    /// DHT11, whole numbers from 0 to 50 °C and 20 to 90 % humidity.
+    Dht11,
+    /// DHT22 or AM2302, tenths from -40 to 80 °C and 0 to 100 % humidity.
+    Dht22,
+}
+
+impl DhtKind {
+    /// Returns how long the line is pulled low to wake up the sensor.
+    fn start_signal(&self) -> Duration {
+        match self {
+            DhtKind::Dht11 => Duration::from_millis(18),
+            DhtKind::Dht22 => Duration::from_micros(1100),
+        }
+    }
+
+    fn decode(&self, data: &[u8; 5]) -> DhtReading {
+        match self {
+            DhtKind::Dht11 => {
+                let temperature = data[2] as f32 + (data[3] & 0x7F) as f32 / 10.0;
+
+                DhtReading {
+                    temperature: if data[3] & 0x80 != 0 {
+                        -temperature
+                    } else {
+                        temperature
+                    },
+                    humidity: data[0] as f32 + data[1] as f32 / 10.0,
+                }
+            }
+            DhtKind::Dht22 => {
+                let humidity = u16::from_be_bytes([data[0], data[1]]) as f32 / 10.0;
+                let temperature = u16::from_be_bytes([data[2] & 0x7F, data[3]]) as f32 / 10.0;
+
+                DhtReading {
+                    temperature: if data[2] & 0x80 != 0 {
+                        -temperature
+                    } else {
+                        temperature
+                    },
+                    humidity,
+                }
+            }
+        }
+    }
+}
+
+/// A measurement of a [`Dht`] sensor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DhtReading {
+    /// Temperature in degrees Celsius.
+    pub temperature: f32,
+    /// Relative humidity in percent.
+    pub humidity: f32,
+}
+
+/// Errors when reading a DHT sensor.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DhtError {
+    /// Gets returned when the sensor did not answer the start signal.
+    #[error("The sensor did not respond.")]
+    NoResponse,
+    /// Gets returned when the sensor stopped sending in the middle of the transmission.
+    #[error("The transmission of the sensor timed out.")]
+    Timeout,
+    /// Gets returned when the received data does not match its checksum.
+    #[error("The checksum does not match.")]
+    Checksum,
+}
@@ -0,0 +1,238 @@
+//! DMX512 output for stage-lighting control.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+use crate::{gpio::wait_until, Output, Pin, SerialConfig, SerialPort, Value, WiringXError};
+
+/// Baud rate of the DMX512 serial protocol.
+pub const DMX_BAUD_RATE: u32 = 250_000;
+
+/// Number of channels in one DMX512 universe.
+pub const DMX_CHANNELS: usize = 512;
+
+/// Length of the break starting every frame.
+const BREAK: Duration = Duration::from_micros(176);
+/// Length of the mark after the break, before the start code.
+const MARK_AFTER_BREAK: Duration = Duration::from_micros(12);
+/// Shortest time between the start of two frames, limiting the refresh rate to about 40 Hertz.
+const FRAME_INTERVAL: Duration = Duration::from_millis(25);
+/// Duration of one bit at 250 kbaud.
+const BIT: Duration = Duration::from_micros(4);
+
+/// Transmits a DMX512 universe, refreshing all 512 channels continuously on a background thread.
+///
+/// Channels are numbered `1` - `512`, like on lighting desks. The start code sent before them is always `0`.
+///
+/// Example dimming the fixture on channel `1` up:
+/// ```no_run
+/// use wiringx::{Dmx, Platform, WiringX};
+///
+/// use std::{thread, time::Duration};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let serial = wiringx.serial("/dev/ttyS1", 250_000).unwrap();
+///
+/// let dmx = Dmx::new(serial).unwrap();
+///
+/// for value in 0..=255 {
+///     dmx.set_channel(1, value).unwrap();
+///     thread::sleep(Duration::from_millis(10));
+/// }
+///
+/// dmx.stop().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct Dmx {
+    frame: Arc<Mutex<[u8; DMX_CHANNELS + 1]>>,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<Result<(), WiringXError>>>,
+}
+
+/// The line the frames are transmitted on.
+#[derive(Debug)]
+enum DmxOutput {
+    Serial(SerialPort),
+    Gpio(Pin<Output>),
+}
+
+impl Dmx {
+    /// Takes ownership of the serial port, configures it for DMX512, 250 kbaud with 8N2 framing,
+    /// and starts transmitting a frame with all channels at `0`.
+    ///
+    /// The break is generated by the UART, so its transmit pin has to drive the RS-485 transceiver.
+    pub fn new(mut serial: SerialPort) -> Result<Self, WiringXError> {
+        serial.configure(SerialConfig {
+            stop_bits: 2,
+            ..SerialConfig::new(DMX_BAUD_RATE)
+        })?;
+
+        Ok(Self::start(DmxOutput::Serial(serial)))
+    }
+
+    /// Bit-bangs the frames on a GPIO pin, for boards without a UART capable of 250 kbaud.
+    ///
+    /// Every bit is timed by busy waiting, keeping one core occupied while a frame is sent.
+    pub fn with_gpio(mut pin: Pin<Output>) -> Self {
+        pin.write(Value::High);
+
+        Self::start(DmxOutput::Gpio(pin))
+    }
+
+    fn start(mut output: DmxOutput) -> Self {
+        let frame = Arc::new(Mutex::new([0; DMX_CHANNELS + 1]));
+        let running = Arc::new(AtomicBool::new(true));
+
+        let thread = {
+            let frame = frame.clone();
+            let running = running.clone();
+
+            thread::spawn(move || {
+                let result = output.refresh(&frame, &running);
+                running.store(false, Ordering::Release);
+                result
+            })
+        };
+
+        Self {
+            frame,
+            running,
+            thread: Some(thread),
+        }
+    }
+
+    /// Sets the value of a channel, from `1` to `512`.
+    pub fn set_channel(&self, channel: u16, value: u8) -> Result<(), WiringXError> {
+        self.set_channels(channel, &[value])
+    }
+
+    /// Sets consecutive channels starting at the given channel to the values.
+    pub fn set_channels(&self, start: u16, values: &[u8]) -> Result<(), WiringXError> {
+        let start = start as usize;
+        if start == 0 || start + values.len() > DMX_CHANNELS + 1 {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        self.frame.lock()[start..start + values.len()].copy_from_slice(values);
+
+        Ok(())
+    }
+
+    /// Returns the value of a channel, from `1` to `512`.
+    pub fn channel(&self, channel: u16) -> Result<u8, WiringXError> {
+        if channel == 0 || channel as usize > DMX_CHANNELS {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        Ok(self.frame.lock()[channel as usize])
+    }
+
+    /// Sets all channels to `0`.
+    pub fn blackout(&self) {
+        self.frame.lock()[1..].fill(0);
+    }
+
+    /// Returns whether the background thread still transmits frames.
+    ///
+    /// Transmission ends when writing to the output fails, [`stop`](Self::stop) returns the error.
+    #[inline]
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Acquire)
+    }
+
+    /// Stops transmitting after the current frame and returns the error that ended the transmission early, if any.
+    pub fn stop(mut self) -> Result<(), WiringXError> {
+        self.join()
+    }
+
+    fn join(&mut self) -> Result<(), WiringXError> {
+        self.running.store(false, Ordering::Release);
+
+        match self.thread.take() {
+            Some(thread) => thread
+                .join()
+                .map_err(|_| WiringXError::Other("The DMX thread panicked.".to_string()))?,
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for Dmx {
+    fn drop(&mut self) {
+        let _ = self.join();
+    }
+}
+
+impl DmxOutput {
+    /// Sends frames until `running` gets cleared.
+    fn refresh(
+        &mut self,
+        frame: &Mutex<[u8; DMX_CHANNELS + 1]>,
+        running: &AtomicBool,
+    ) -> Result<(), WiringXError> {
+        while running.load(Ordering::Acquire) {
+            let start = Instant::now();
+
+            let data = *frame.lock();
+            match self {
+                DmxOutput::Serial(serial) => send_serial(serial, &data)?,
+                DmxOutput::Gpio(pin) => send_gpio(pin, &data),
+            }
+
+            let elapsed = start.elapsed();
+            if elapsed < FRAME_INTERVAL {
+                thread::sleep(FRAME_INTERVAL - elapsed);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn send_serial(serial: &SerialPort, data: &[u8]) -> Result<(), WiringXError> {
+    serial.send_break(BREAK)?;
+    thread::sleep(MARK_AFTER_BREAK);
+
+    let mut written = 0;
+    while written < data.len() {
+        written += serial.write(&data[written..])?;
+    }
+
+    serial.flush()
+}
+
+/// Sends the frame with every edge timed from the start of the break, so delays do not add up.
+fn send_gpio(pin: &mut Pin<Output>, data: &[u8]) {
+    let start = Instant::now();
+
+    pin.write(Value::Low);
+    let mut next = start + BREAK;
+    wait_until(next);
+
+    pin.write(Value::High);
+    next += MARK_AFTER_BREAK;
+    wait_until(next);
+
+    for byte in data {
+        // One start bit, eight data bits least significant first and two stop bits.
+        let bits = (*byte as u16) << 1 | 0b11 << 9;
+
+        for i in 0..11 {
+            pin.write(if bits >> i & 1 == 1 {
+                Value::High
+            } else {
+                Value::Low
+            });
+            next += BIT;
+            wait_until(next);
+        }
+    }
+}
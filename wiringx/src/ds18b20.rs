@@ -0,0 +1,228 @@
+//! DS18B20 temperature sensor on a 1-Wire bus.
+
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{OneWire, OneWireError, RomCode};
+
+/// Family code of the DS18B20 in its ROM code.
+pub const DS18B20_FAMILY: u8 = 0x28;
+
+const CONVERT_T: u8 = 0x44;
+const WRITE_SCRATCHPAD: u8 = 0x4E;
+const READ_SCRATCHPAD: u8 = 0xBE;
+const COPY_SCRATCHPAD: u8 = 0x48;
+const READ_POWER_SUPPLY: u8 = 0xB4;
+
+/// A DS18B20 temperature sensor, addressed by its ROM code or as the only device on the bus.
+///
+/// The sensor does not own the bus, so several sensors can share one [`OneWire`].
+///
+/// Example reading all sensors on the bus:
+/// ```no_run
+/// use wiringx::{Ds18b20, OneWire, Output, Platform, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let mut bus = OneWire::new(wiringx.gpio_pin::<Output>(15).unwrap());
+///
+/// let sensors = Ds18b20::find_all(&mut bus).unwrap();
+///
+/// Ds18b20::convert_all(&mut bus).unwrap();
+/// for sensor in &sensors {
+///     println!("{:?}: {} °C", sensor.rom(), sensor.read_converted(&mut bus).unwrap());
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ds18b20 {
+    rom: Option<RomCode>,
+    resolution: Ds18b20Resolution,
+}
+
+impl Ds18b20 {
+    /// Addresses the sensor with the given ROM code.
+    ///
+    /// Returns [`Crc`](OneWireError::Crc) if the ROM code is corrupted and
+    /// [`WrongFamily`](OneWireError::WrongFamily) if it does not belong to a DS18B20.
+    pub fn new(rom: RomCode) -> Result<Self, OneWireError> {
+        if !rom.is_valid() {
+            return Err(OneWireError::Crc);
+        }
+        if rom.family() != DS18B20_FAMILY {
+            return Err(OneWireError::WrongFamily);
+        }
+
+        Ok(Self {
+            rom: Some(rom),
+            resolution: Ds18b20Resolution::default(),
+        })
+    }
+
+    /// Addresses the only device on the bus without knowing its ROM code.
+    pub fn single() -> Self {
+        Self {
+            rom: None,
+            resolution: Ds18b20Resolution::default(),
+        }
+    }
+
+    /// Searches the bus and returns all DS18B20 sensors on it.
+    pub fn find_all(bus: &mut OneWire) -> Result<Vec<Self>, OneWireError> {
+        Ok(bus
+            .search()?
+            .into_iter()
+            .filter(|rom| rom.family() == DS18B20_FAMILY)
+            .map(|rom| Self {
+                rom: Some(rom),
+                resolution: Ds18b20Resolution::default(),
+            })
+            .collect())
+    }
+
+    /// Starts a conversion on all sensors on the bus at once and waits until all finished.
+    ///
+    /// Read the results with [`read_converted`](Self::read_converted) afterwards.
+    pub fn convert_all(bus: &mut OneWire) -> Result<(), OneWireError> {
+        convert(bus, Ds18b20Resolution::Bits12, OneWire::skip)
+    }
+
+    /// Returns the ROM code of this sensor, `None` if it is addressed as the only device.
+    #[inline]
+    pub fn rom(&self) -> Option<RomCode> {
+        self.rom
+    }
+
+    /// Returns the resolution the sensor was configured with.
+    #[inline]
+    pub fn resolution(&self) -> Ds18b20Resolution {
+        self.resolution
+    }
+
+    /// Configures the resolution, trading precision for conversion time.
+    ///
+    /// With `persist` the setting gets copied to the EEPROM of the sensor and survives power loss.
+    pub fn set_resolution(
+        &mut self,
+        bus: &mut OneWire,
+        resolution: Ds18b20Resolution,
+        persist: bool,
+    ) -> Result<(), OneWireError> {
+        let scratchpad = self.read_scratchpad(bus)?;
+
+        // The alarm thresholds share the write with the configuration register, so keep them.
+        self.address(bus)?;
+        bus.write(&[
+            WRITE_SCRATCHPAD,
+            scratchpad[2],
+            scratchpad[3],
+            (resolution as u8) << 5 | 0x1F,
+        ]);
+
+        if persist {
+            self.address(bus)?;
+            bus.write_byte(COPY_SCRATCHPAD);
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        self.resolution = resolution;
+
+        Ok(())
+    }
+
+    /// Starts a conversion, waits for it to finish and returns the temperature in degrees Celsius.
+    pub fn read_temperature(&self, bus: &mut OneWire) -> Result<f32, OneWireError> {
+        convert(bus, self.resolution, |bus| self.address(bus))?;
+
+        self.read_converted(bus)
+    }
+
+    /// Returns the temperature of the last conversion in degrees Celsius without starting a new one.
+    ///
+    /// Sensors report `85.0` until their first conversion after power-up.
+    pub fn read_converted(&self, bus: &mut OneWire) -> Result<f32, OneWireError> {
+        let scratchpad = self.read_scratchpad(bus)?;
+
+        let raw = i16::from_le_bytes([scratchpad[0], scratchpad[1]]);
+
+        Ok(raw as f32 / 16.0)
+    }
+
+    /// Reads the nine bytes of the scratchpad and checks their checksum.
+    fn read_scratchpad(&self, bus: &mut OneWire) -> Result<[u8; 9], OneWireError> {
+        self.address(bus)?;
+        bus.write_byte(READ_SCRATCHPAD);
+
+        let mut scratchpad = [0; 9];
+        bus.read(&mut scratchpad);
+
+        // A released bus reads as all ones, which would pass as a valid temperature otherwise.
+        if scratchpad == [0xFF; 9] || OneWire::crc8(&scratchpad[..8]) != scratchpad[8] {
+            return Err(OneWireError::Crc);
+        }
+
+        Ok(scratchpad)
+    }
+
+    fn address(&self, bus: &mut OneWire) -> Result<(), OneWireError> {
+        match &self.rom {
+            Some(rom) => bus.select(rom),
+            None => bus.skip(),
+        }
+    }
+}
+
+/// Starts a conversion on the sensors reached by `address` and waits until it finished.
+///
+/// Externally powered sensors answer read slots with `1` once done, so they get polled.
+/// Parasite powered sensors cannot, for them the full conversion time is waited.
+fn convert(
+    bus: &mut OneWire,
+    resolution: Ds18b20Resolution,
+    address: impl Fn(&mut OneWire) -> Result<(), OneWireError>,
+) -> Result<(), OneWireError> {
+    address(bus)?;
+    bus.write_byte(READ_POWER_SUPPLY);
+    let parasite = !bus.read_bit();
+
+    address(bus)?;
+    bus.write_byte(CONVERT_T);
+
+    if parasite {
+        thread::sleep(resolution.conversion_time());
+        return Ok(());
+    }
+
+    let deadline = Instant::now() + resolution.conversion_time();
+    while Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(10));
+
+        if bus.read_bit() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Temperature resolution of a [`Ds18b20`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Ds18b20Resolution {
+    /// 0.5 °C steps in 93.75 milliseconds
+    Bits9 = 0,
+    /// 0.25 °C steps in 187.5 milliseconds
+    Bits10 = 1,
+    /// 0.125 °C steps in 375 milliseconds
+    Bits11 = 2,
+    /// 0.0625 °C steps in 750 milliseconds
+    #[default]
+    Bits12 = 3,
+}
+
+impl Ds18b20Resolution {
+    /// Returns the maximum time a conversion takes at this resolution.
+    #[inline]
+    pub fn conversion_time(&self) -> Duration {
+        Duration::from_micros(93_750 << *self as u64)
+    }
+}
@@ -0,0 +1,445 @@
+//! Publishing what happens on pins and drivers to telemetry bridges, enabled with the `events` feature.
+//!
+//! While an [`EventSink`] is subscribed, every edge detected on a GPIO [`Pin`](crate::Pin)
+//! and every change of the duty cycle of a [`PwmPin`](crate::PwmPin) is sent to it as an [`Event`].
+//! Sensor readings are sent by the application with [`publish`], as only it knows which ones matter.
+//!
+//! Sinks are called on the thread of the operation, so slow ones delay it.
+//! The reference sinks are [`JsonLinesSink`], writing one JSON object per line,
+//! and [`MqttSink`], publishing to an MQTT broker, for example for Home Assistant or Node-RED.
+//!
+//! Example publishing button presses and temperatures to a broker:
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use wiringx::{
+//!     events::{self, Event, MqttSink},
+//!     Input, IsrMode, Platform, WiringX,
+//! };
+//!
+//! let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+//! let _subscription =
+//!     events::subscribe(MqttSink::connect("192.168.1.10:1883", "duo", "home/duo").unwrap());
+//!
+//! let button = wiringx.gpio_pin::<Input>(3).unwrap();
+//! button.set_isr_mode(IsrMode::Falling).unwrap();
+//!
+//! loop {
+//!     // Sent as `home/duo/gpio/3/edge`
+//!     let _ = button.wait_for_event(Duration::from_secs(60));
+//!
+//!     // Sent as `home/duo/bme280/temperature`
+//!     events::publish(Event::Reading {
+//!         source: "bme280".to_string(),
+//!         quantity: "temperature".to_string(),
+//!         value: 21.5,
+//!         unit: "°C".to_string(),
+//!     });
+//! }
+//! ```
+
+use std::{
+    fmt::Write as _,
+    io::{self, Read, Write},
+    net::{SocketAddr, TcpStream, ToSocketAddrs},
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+use crate::{Edge, WiringXError};
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+static SINKS: Mutex<Vec<(u64, Box<dyn EventSink>)>> = Mutex::new(Vec::new());
+
+/// Receives the events of pins and drivers, see the [module](self) documentation.
+///
+/// Errors of a sink are ignored, so one failing sink does not affect the pins or the other sinks.
+pub trait EventSink: Send {
+    /// Handles the event.
+    fn send(&mut self, event: &Event) -> Result<(), WiringXError>;
+}
+
+/// Something that happened on a pin or driver.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Event {
+    /// An edge was detected on a GPIO input
+    Edge {
+        /// Number of the pin.
+        pin: i32,
+        /// Direction of the edge.
+        edge: Edge,
+        /// Time of the edge on the monotonic clock, see [`EdgeEvent`](crate::EdgeEvent).
+        timestamp: Duration,
+    },
+    /// The duty cycle of a PWM pin was set
+    DutyCycle {
+        /// Number of the pin.
+        pin: i32,
+        /// The duty cycle from 0.0 - 1.0.
+        duty_cycle: f32,
+    },
+    /// A sensor was read
+    Reading {
+        /// Name of the sensor, like `bme280` or `kitchen`.
+        source: String,
+        /// What was measured, like `temperature`.
+        quantity: String,
+        /// The measured value.
+        value: f64,
+        /// Unit of the value, like `°C`.
+        unit: String,
+    },
+}
+
+impl Event {
+    /// Returns the event as JSON object on one line,
+    /// like `{"event":"edge","pin":3,"edge":"rising","timestamp_us":1520}`.
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+
+        let _ = match self {
+            Event::Edge {
+                pin,
+                edge,
+                timestamp,
+            } => write!(
+                json,
+                r#"{{"event":"edge","pin":{pin},"edge":"{}","timestamp_us":{}}}"#,
+                edge_name(*edge),
+                timestamp.as_micros()
+            ),
+            Event::DutyCycle { pin, duty_cycle } => write!(
+                json,
+                r#"{{"event":"duty_cycle","pin":{pin},"duty_cycle":{}}}"#,
+                json_number(*duty_cycle as f64)
+            ),
+            Event::Reading {
+                source,
+                quantity,
+                value,
+                unit,
+            } => write!(
+                json,
+                r#"{{"event":"reading","source":"{}","quantity":"{}","value":{},"unit":"{}"}}"#,
+                json_escape(source),
+                json_escape(quantity),
+                json_number(*value),
+                json_escape(unit)
+            ),
+        };
+
+        json
+    }
+}
+
+fn edge_name(edge: Edge) -> &'static str {
+    match edge {
+        Edge::Rising => "rising",
+        Edge::Falling => "falling",
+    }
+}
+
+/// JSON has no representation for NaN and the infinities.
+fn json_number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+fn json_escape(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len());
+
+    for char in string.chars() {
+        match char {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            char if char.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", char as u32);
+            }
+            char => escaped.push(char),
+        }
+    }
+
+    escaped
+}
+
+/// Subscribes the sink to all events, until the returned [`Subscription`] is dropped.
+pub fn subscribe(sink: impl EventSink + 'static) -> Subscription {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+
+    let mut sinks = SINKS.lock();
+    sinks.push((id, Box::new(sink)));
+    ACTIVE.store(true, Ordering::Release);
+
+    Subscription { id }
+}
+
+/// A sink subscribed with [`subscribe`], which is unsubscribed when dropped.
+#[derive(Debug)]
+pub struct Subscription {
+    id: u64,
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        let mut sinks = SINKS.lock();
+        sinks.retain(|(id, _)| *id != self.id);
+        ACTIVE.store(!sinks.is_empty(), Ordering::Release);
+    }
+}
+
+/// Sends the event to all subscribed sinks, for example a sensor reading.
+pub fn publish(event: Event) {
+    emit(|| event);
+}
+
+/// Sends the event built by the closure to all subscribed sinks, if there are any.
+#[inline]
+pub(crate) fn emit(event: impl FnOnce() -> Event) {
+    if !ACTIVE.load(Ordering::Acquire) {
+        return;
+    }
+
+    let event = event();
+    for (_, sink) in SINKS.lock().iter_mut() {
+        let _ = sink.send(&event);
+    }
+}
+
+/// Writes every event as JSON object on its own line, see [`Event::to_json`].
+///
+/// Works with files, standard output or anything else implementing [`Write`].
+#[derive(Debug)]
+pub struct JsonLinesSink<W: Write + Send> {
+    writer: W,
+}
+
+impl<W: Write + Send> JsonLinesSink<W> {
+    /// Writes the events to the writer.
+    #[inline]
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Returns the writer.
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write + Send> EventSink for JsonLinesSink<W> {
+    fn send(&mut self, event: &Event) -> Result<(), WiringXError> {
+        writeln!(self.writer, "{}", event.to_json()).map_err(WiringXError::Io)?;
+        self.writer.flush().map_err(WiringXError::Io)
+    }
+}
+
+/// How long the broker may take to accept the connection.
+const MQTT_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long reconnecting on the thread of a pin may take, per address and for the answer of the broker.
+const MQTT_RECONNECT_TIMEOUT: Duration = Duration::from_millis(500);
+/// How long writing an event may block before the connection is given up.
+const MQTT_WRITE_TIMEOUT: Duration = Duration::from_millis(500);
+/// The wait after the first failed reconnect, doubled after each further one up to [`MQTT_MAX_BACKOFF`].
+const MQTT_MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MQTT_MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Publishes the events as JSON to an MQTT 3.1.1 broker, without authentication and at most once.
+///
+/// Edges go to `<prefix>/gpio/<pin>/edge`, duty cycles to `<prefix>/pwm/<pin>/duty_cycle`
+/// and readings to `<prefix>/<source>/<quantity>`.
+/// A lost connection is reestablished with the next event, with a short timeout as it blocks the pin.
+/// After a failed attempt, events are dropped until a backoff of up to a minute has passed.
+#[derive(Debug)]
+pub struct MqttSink {
+    addresses: Vec<SocketAddr>,
+    client_id: String,
+    prefix: String,
+    stream: Option<TcpStream>,
+    backoff: Duration,
+    retry_at: Option<Instant>,
+}
+
+impl MqttSink {
+    /// Connects to the broker, like `192.168.1.10:1883`, with the client ID and the prefix of the topics.
+    pub fn connect(
+        address: impl ToSocketAddrs,
+        client_id: &str,
+        prefix: &str,
+    ) -> Result<Self, WiringXError> {
+        let mut sink = Self {
+            addresses: address
+                .to_socket_addrs()
+                .map_err(WiringXError::Io)?
+                .collect(),
+            client_id: client_id.to_string(),
+            prefix: prefix.trim_end_matches('/').to_string(),
+            stream: None,
+            backoff: MQTT_MIN_BACKOFF,
+            retry_at: None,
+        };
+        sink.stream = Some(sink.open(MQTT_CONNECT_TIMEOUT)?);
+
+        Ok(sink)
+    }
+
+    /// Returns the topic the event is published to.
+    pub fn topic(&self, event: &Event) -> String {
+        match event {
+            Event::Edge { pin, .. } => format!("{}/gpio/{pin}/edge", self.prefix),
+            Event::DutyCycle { pin, .. } => format!("{}/pwm/{pin}/duty_cycle", self.prefix),
+            Event::Reading {
+                source, quantity, ..
+            } => format!("{}/{source}/{quantity}", self.prefix),
+        }
+    }
+
+    /// Connects to the first address accepting within the timeout, which also bounds the wait for the broker.
+    fn open(&self, timeout: Duration) -> Result<TcpStream, WiringXError> {
+        let mut error = io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "No address of the MQTT broker.",
+        );
+        let mut stream = None;
+        for address in &self.addresses {
+            match TcpStream::connect_timeout(address, timeout) {
+                Ok(connected) => {
+                    stream = Some(connected);
+                    break;
+                }
+                Err(e) => error = e,
+            }
+        }
+
+        let mut stream = stream.ok_or(WiringXError::Io(error))?;
+        stream.set_nodelay(true).map_err(WiringXError::Io)?;
+        stream
+            .set_write_timeout(Some(MQTT_WRITE_TIMEOUT))
+            .map_err(WiringXError::Io)?;
+
+        // Clean session without keep alive, so the broker does not expect pings between events.
+        let mut connect = Vec::new();
+        push_string(&mut connect, "MQTT")?;
+        connect.extend_from_slice(&[4, 0x02, 0, 0]);
+        push_string(&mut connect, &self.client_id)?;
+        write_packet(&mut stream, 0x10, &connect)?;
+
+        stream
+            .set_read_timeout(Some(timeout))
+            .map_err(WiringXError::Io)?;
+        let mut connack = [0; 4];
+        stream.read_exact(&mut connack).map_err(|e| {
+            if e.kind() == io::ErrorKind::WouldBlock {
+                WiringXError::TimedOut
+            } else {
+                WiringXError::Io(e)
+            }
+        })?;
+
+        if connack[0] != 0x20 || connack[1] != 0x02 {
+            return Err(WiringXError::Other(
+                "Invalid response of the MQTT broker.".to_string(),
+            ));
+        }
+
+        if connack[3] != 0 {
+            return Err(WiringXError::Other(format!(
+                "The MQTT broker refused the connection with code {}.",
+                connack[3]
+            )));
+        }
+
+        Ok(stream)
+    }
+
+    /// Reconnects unless still backing off from the last failed attempt.
+    fn reconnect(&mut self) -> Result<TcpStream, WiringXError> {
+        if self
+            .retry_at
+            .is_some_and(|retry_at| Instant::now() < retry_at)
+        {
+            return Err(WiringXError::Io(io::ErrorKind::NotConnected.into()));
+        }
+
+        match self.open(MQTT_RECONNECT_TIMEOUT) {
+            Ok(stream) => {
+                self.backoff = MQTT_MIN_BACKOFF;
+                self.retry_at = None;
+                Ok(stream)
+            }
+            Err(error) => {
+                self.retry_at = Some(Instant::now() + self.backoff);
+                self.backoff = (self.backoff * 2).min(MQTT_MAX_BACKOFF);
+                Err(error)
+            }
+        }
+    }
+}
+
+impl EventSink for MqttSink {
+    fn send(&mut self, event: &Event) -> Result<(), WiringXError> {
+        let mut publish = Vec::new();
+        push_string(&mut publish, &self.topic(event))?;
+        publish.extend_from_slice(event.to_json().as_bytes());
+
+        let mut stream = match self.stream.take() {
+            Some(stream) => stream,
+            None => self.reconnect()?,
+        };
+
+        write_packet(&mut stream, 0x30, &publish)?;
+        self.stream = Some(stream);
+
+        Ok(())
+    }
+}
+
+impl Drop for MqttSink {
+    fn drop(&mut self) {
+        if let Some(stream) = &mut self.stream {
+            let _ = stream.write_all(&[0xe0, 0x00]);
+        }
+    }
+}
+
+/// Appends the string with its length in front, as MQTT encodes them.
+fn push_string(packet: &mut Vec<u8>, string: &str) -> Result<(), WiringXError> {
+    let length = u16::try_from(string.len()).map_err(|_| WiringXError::InvalidArgument)?;
+
+    packet.extend_from_slice(&length.to_be_bytes());
+    packet.extend_from_slice(string.as_bytes());
+
+    Ok(())
+}
+
+/// Writes the packet of the type with the remaining length in front, 7 bits per byte.
+fn write_packet(stream: &mut TcpStream, header: u8, body: &[u8]) -> Result<(), WiringXError> {
+    let mut packet = Vec::with_capacity(body.len() + 5);
+    packet.push(header);
+
+    let mut length = body.len();
+    loop {
+        let byte = (length % 128) as u8;
+        length /= 128;
+
+        if length > 0 {
+            packet.push(byte | 0x80);
+        } else {
+            packet.push(byte);
+            break;
+        }
+    }
+
+    packet.extend_from_slice(body);
+    stream.write_all(&packet).map_err(WiringXError::Io)
+}
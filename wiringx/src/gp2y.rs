@@ -0,0 +1,177 @@
+//! Sharp GP2Y analog infrared distance sensors.
+
+use crate::{AnalogInput, WiringXError};
+
+/// A Sharp GP2Y infrared distance sensor on any [`AnalogInput`], with an output voltage falling with the distance.
+///
+/// The voltage is converted along the typical curve of the datasheet of the model, interpolating between its points.
+/// Below the shortest distance the voltage falls again, so the sensor should be mounted
+/// where nothing gets closer than that. The output is noisy, every reading is the median of several conversions.
+///
+/// Example with a GP2Y0A21YK0F on an MCP3008 with a 5 V reference:
+/// ```no_run
+/// use wiringx::{Gp2y, Gp2yModel, Mcp3008, Platform, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let adc = Mcp3008::new(wiringx.setup_spi(0, 1_000_000).unwrap(), 5.0).unwrap();
+///
+/// let sensor = Gp2y::new(adc.channel(0).unwrap(), Gp2yModel::Gp2y0a21);
+///
+/// match sensor.read_distance().unwrap() {
+///     Some(distance) => println!("{distance:.1} cm"),
+///     None => println!("out of range"),
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Gp2y<A> {
+    input: A,
+    model: Gp2yModel,
+    samples: usize,
+}
+
+impl<A: AnalogInput> Gp2y<A> {
+    /// Uses the sensor on the analog input, taking the median of 5 conversions per reading.
+    pub fn new(input: A, model: Gp2yModel) -> Self {
+        Self {
+            input,
+            model,
+            samples: 5,
+        }
+    }
+
+    /// Returns the analog input.
+    #[inline]
+    pub fn into_inner(self) -> A {
+        self.input
+    }
+
+    /// Returns the model of the sensor.
+    #[inline]
+    pub fn model(&self) -> Gp2yModel {
+        self.model
+    }
+
+    /// Sets the number of conversions the median is taken of, from `1` to `31`.
+    pub fn set_samples(&mut self, samples: usize) -> Result<(), WiringXError> {
+        if !(1..=31).contains(&samples) {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        self.samples = samples;
+
+        Ok(())
+    }
+
+    /// Returns the number of conversions the median is taken of.
+    #[inline]
+    pub fn samples(&self) -> usize {
+        self.samples
+    }
+
+    /// Reads the median output voltage of the sensor.
+    pub fn read_voltage(&self) -> Result<f32, WiringXError> {
+        let mut voltages = [0.0; 31];
+        let voltages = &mut voltages[..self.samples];
+        for voltage in voltages.iter_mut() {
+            *voltage = self.input.read_voltage()?;
+        }
+
+        voltages.sort_unstable_by(f32::total_cmp);
+
+        Ok(voltages[voltages.len() / 2])
+    }
+
+    /// Reads the distance in cm, none if it is outside of the range of the model.
+    pub fn read_distance(&self) -> Result<Option<f32>, WiringXError> {
+        Ok(self.model.distance(self.read_voltage()?))
+    }
+}
+
+/// Model of a [`Gp2y`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gp2yModel {
+    /// GP2Y0A41SK0F, 4 cm to 30 cm
+    Gp2y0a41,
+    /// GP2Y0A21YK0F, 10 cm to 80 cm
+    Gp2y0a21,
+    /// GP2Y0A02YK0F, 20 cm to 150 cm
+    Gp2y0a02,
+    /// GP2Y0A710K0F, 100 cm to 550 cm
+    Gp2y0a710,
+}
+
+impl Gp2yModel {
+    /// Returns the shortest and longest distance in cm.
+    pub fn range(&self) -> (f32, f32) {
+        let curve = self.curve();
+
+        (curve[0].0, curve[curve.len() - 1].0)
+    }
+
+    /// Converts the output voltage to the distance in cm, none if it is outside of the range.
+    pub fn distance(&self, voltage: f32) -> Option<f32> {
+        // The voltage falls with the distance, so consecutive points enclose it from above and below.
+        self.curve().windows(2).find_map(|points| {
+            let ((near, high), (far, low)) = (points[0], points[1]);
+
+            (low..=high)
+                .contains(&voltage)
+                .then(|| near + (far - near) * (high - voltage) / (high - low))
+        })
+    }
+
+    /// Returns points of distance in cm and voltage of the typical curve, from the shortest distance on.
+    fn curve(&self) -> &'static [(f32, f32)] {
+        match self {
+            Gp2yModel::Gp2y0a41 => &[
+                (4.0, 2.75),
+                (5.0, 2.3),
+                (6.0, 2.0),
+                (8.0, 1.55),
+                (10.0, 1.3),
+                (12.0, 1.1),
+                (15.0, 0.9),
+                (20.0, 0.7),
+                (25.0, 0.55),
+                (30.0, 0.42),
+            ],
+            Gp2yModel::Gp2y0a21 => &[
+                (10.0, 2.3),
+                (15.0, 1.65),
+                (20.0, 1.3),
+                (25.0, 1.08),
+                (30.0, 0.92),
+                (40.0, 0.75),
+                (50.0, 0.6),
+                (60.0, 0.5),
+                (70.0, 0.45),
+                (80.0, 0.4),
+            ],
+            Gp2yModel::Gp2y0a02 => &[
+                (20.0, 2.5),
+                (30.0, 2.0),
+                (40.0, 1.55),
+                (50.0, 1.25),
+                (60.0, 1.05),
+                (70.0, 0.9),
+                (80.0, 0.8),
+                (90.0, 0.72),
+                (100.0, 0.65),
+                (120.0, 0.55),
+                (150.0, 0.45),
+            ],
+            Gp2yModel::Gp2y0a710 => &[
+                (100.0, 2.5),
+                (150.0, 2.05),
+                (200.0, 1.8),
+                (250.0, 1.65),
+                (300.0, 1.55),
+                (350.0, 1.48),
+                (400.0, 1.43),
+                (450.0, 1.39),
+                (500.0, 1.36),
+                (550.0, 1.34),
+            ],
+        }
+    }
+}
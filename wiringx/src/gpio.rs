@@ -1,33 +1,68 @@
 //! General purpose input output related objects.
 
-use std::{collections::HashSet, sync::Arc, time::Duration};
+use std::{
+    collections::HashSet,
+    fmt, io,
+    os::{
+        fd::{AsFd, AsRawFd, BorrowedFd, RawFd},
+        raw::c_int,
+    },
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use parking_lot::Mutex;
+use thiserror::Error;
 use wiringx_sys::{
-    digitalRead, digitalWrite, digital_value_t_HIGH, digital_value_t_LOW, waitForInterrupt,
-    wiringXISR,
+    digitalRead, digitalWrite, digital_value_t_HIGH, digital_value_t_LOW, pinMode,
+    pinmode_t_PINMODE_INPUT, pinmode_t_PINMODE_OUTPUT, waitForInterrupt, wiringXISR,
+    wiringXSelectableFd,
 };
 
-use crate::WiringXError;
+#[cfg(feature = "events")]
+use crate::events::{self, Event};
+#[cfg(feature = "metrics")]
+use crate::metrics;
+#[cfg(feature = "trace")]
+use crate::trace::{self, TraceKind};
+use crate::{
+    reservation::Reservation,
+    safe_state::{self, SafeState},
+    wiringx_call, wiringx_wait, PinOperation, WiringXError,
+};
 
 /// Representation of a GPIO, General Purpose Input Output, pin.
 ///
 /// You receive this struct from the [`WiringX::gpio_pin`](super::WiringX::gpio_pin)
 /// method of the [`WiringX`](super::WiringX) struct.
+///
+/// Depending on the [`GpioBackend`](super::GpioBackend) the pin is driven by wiringX,
+/// is a line requested from a GPIO character device or exported in sysfs, or is simulated.
 #[derive(Debug)]
 pub struct Pin<T: Default> {
     number: i32,
     handle: Arc<Mutex<HashSet<i32>>>,
     mode: T,
+    line: Arc<dyn LineBackend>,
+    _reservation: Option<Reservation>,
+    safe_state: Option<SafeState>,
 }
 
 impl<T: Default> Pin<T> {
     #[inline]
-    pub(super) fn new(number: i32, handle: Arc<Mutex<HashSet<i32>>>) -> Self {
+    pub(super) fn new(
+        number: i32,
+        handle: Arc<Mutex<HashSet<i32>>>,
+        line: Box<dyn LineBackend>,
+        reservation: Option<Reservation>,
+    ) -> Self {
         Self {
             number,
             handle,
             mode: T::default(),
+            line: line.into(),
+            _reservation: reservation,
+            safe_state: None,
         }
     }
 
@@ -40,15 +75,38 @@ impl<T: Default> Pin<T> {
 
 impl Pin<Output> {
     /// Writes a value to the GPIO pin.
+    ///
+    /// Failures are ignored, see [`try_write`](Self::try_write) to notice them.
+    #[inline]
     pub fn write(&mut self, value: Value) {
+        let _ = self.try_write(value);
+    }
+
+    /// Writes a value to the GPIO pin, returning the error if the system rejected it,
+    /// like when the pin was unexported or muxed to another function meanwhile.
+    pub fn try_write(&mut self, value: Value) -> Result<(), WiringXError> {
+        self.line.write(value)?;
         self.mode.value = value;
 
-        let value = match value {
-            Value::High => digital_value_t_HIGH,
-            Value::Low => digital_value_t_LOW,
-        };
+        #[cfg(feature = "trace")]
+        trace::log(self.number, TraceKind::Write(value));
+
+        #[cfg(feature = "metrics")]
+        metrics::level(self.number, value);
 
-        unsafe { digitalWrite(self.number, value) };
+        Ok(())
+    }
+
+    /// Sets the value the pin is driven to when the program panics or is interrupted, or none to leave it as it is.
+    ///
+    /// Only works after [`safe_state::install`](crate::safe_state::install), and ends when the pin is dropped.
+    pub fn set_safe_state(&mut self, value: Option<Value>) {
+        self.safe_state = value.map(|value| {
+            let line = self.line.clone();
+            safe_state::register(move || {
+                let _ = line.set_output(value);
+            })
+        });
     }
 
     /// Toggles the GPIO pin to on if it was off or to off if it was on.
@@ -56,59 +114,356 @@ impl Pin<Output> {
         self.write(self.read().opposite());
     }
 
+    /// Switches the pin to input, leaving the line to a pull-up resistor or the connected device.
+    ///
+    /// Together with [`drive`](Self::drive) this emulates an open-drain output for single-wire protocols.
+    #[inline]
+    pub(crate) fn release(&mut self) {
+        let _ = self.line.release();
+    }
+
+    /// Switches the pin back to output after [`release`](Self::release) and writes the value.
+    #[inline]
+    pub(crate) fn drive(&mut self, value: Value) {
+        self.mode.value = value;
+
+        #[cfg(feature = "trace")]
+        trace::log(self.number, TraceKind::Write(value));
+
+        #[cfg(feature = "metrics")]
+        metrics::level(self.number, value);
+
+        let _ = self.line.set_output(value);
+    }
+
     /// Returns the current value of this GPIO pin.
     #[inline]
     pub fn read(&self) -> Value {
         // in PinMode Output return the current output state
-        let result = unsafe { digitalRead(self.number) };
-
-        if result == 1 {
-            Value::High
-        } else {
-            Value::Low
-        }
+        self.line.read().unwrap_or(self.mode.value)
     }
 }
 
 impl Pin<Input> {
     /// Reads the current state of the GPIO pin.
     pub fn read(&self) -> Value {
-        let result = unsafe { digitalRead(self.number) };
+        let value = self.line.read().unwrap_or_default();
 
-        if result == 1 {
-            Value::High
-        } else {
-            Value::Low
-        }
+        #[cfg(feature = "trace")]
+        trace::log(self.number, TraceKind::Read(value));
+
+        #[cfg(feature = "metrics")]
+        metrics::level(self.number, value);
+
+        value
+    }
+
+    /// Switches the pin to output driving the value, keeping it reserved in between.
+    ///
+    /// Failures are ignored like with [`write`](Pin::write).
+    #[cfg(feature = "rppal-compat")]
+    pub(crate) fn into_output(mut self, value: Value) -> Pin<Output> {
+        let mut pin = Pin {
+            number: self.number,
+            // Taken over, so dropping the input does not free the number for others.
+            handle: std::mem::take(&mut self.handle),
+            mode: Output::default(),
+            line: self.line.clone(),
+            _reservation: self._reservation.take(),
+            safe_state: None,
+        };
+        pin.drive(value);
+
+        pin
     }
 
     /// Sets the interrupt service routine mode of this pin.
     ///
     /// This determines when to trigger the interrupt when using the `wait_for_interrupt` method.
-    pub fn set_isr_mode(&self, mode: IsrMode) -> Result<(), WiringXError> {
-        let result = unsafe { wiringXISR(self.number, mode as u32) };
+    /// Returns [`IsrError::UnsupportedPin`] for edges on pins that can not detect them, see [`supports_interrupts`](Self::supports_interrupts).
+    pub fn set_isr_mode(&self, mode: IsrMode) -> Result<(), IsrError> {
+        if mode == IsrMode::Unknown {
+            return Err(IsrError::UnsupportedMode(mode));
+        }
 
-        if result < 0 {
-            return Err(WiringXError::Other(
-                "Cannot set isr mode of pin to this setting.".to_string(),
-            ));
+        if mode != IsrMode::None && !self.supports_interrupts() {
+            return Err(IsrError::UnsupportedPin(self.number));
         }
 
-        Ok(())
+        self.line.set_input(mode).map_err(|error| match error {
+            WiringXError::InvalidArgument => IsrError::UnsupportedMode(mode),
+            WiringXError::Unsupported => IsrError::UnsupportedPin(self.number),
+            error => IsrError::Io(error.into()),
+        })
+    }
+
+    /// Returns whether the pin can detect edges, otherwise it has to be polled.
+    ///
+    /// With wiringX these are the pins of [`PlatformInfo::isr_pins`](crate::PlatformInfo::isr_pins),
+    /// the other GPIO backends can detect edges on every pin.
+    ///
+    /// Example falling back to polling:
+    /// ```no_run
+    /// use std::{thread, time::Duration};
+    ///
+    /// use wiringx::{Input, IsrMode, Platform, Value, WiringX};
+    ///
+    /// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+    /// let button = wiringx.gpio_pin::<Input>(4).unwrap();
+    ///
+    /// if button.supports_interrupts() {
+    ///     button.set_isr_mode(IsrMode::Falling).unwrap();
+    ///     while button.wait_for_interrupt(Duration::from_secs(10)).is_err() {}
+    /// } else {
+    ///     while button.read() == Value::High {
+    ///         thread::sleep(Duration::from_millis(10));
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    pub fn supports_interrupts(&self) -> bool {
+        self.line.supports_interrupts()
+    }
+
+    /// Enables the pull-up or pull-down resistor of the pin, or disables both.
+    ///
+    /// Only GPIO character devices can configure them, other backends return [`Unsupported`](WiringXError::Unsupported).
+    /// On the mock backend the pin is set to the level the resistor pulls it to.
+    #[inline]
+    pub fn set_pull(&self, pull: Pull) -> Result<(), WiringXError> {
+        self.line.set_pull(pull)
     }
 
     /// Suspends the thread until input to this pin was detected or the function times out.
     ///
-    /// Returns `Ok(())` on successful interrupt read and `Err(InterruptTimeOut)` on timeout.
-    pub fn wait_for_interrupt(&self, timeout_dur: Duration) -> Result<(), InterruptTimeOut> {
-        let result = unsafe { waitForInterrupt(self.number, timeout_dur.as_millis() as i32) };
+    /// Returns `Ok(())` on successful interrupt read and [`InterruptTimedOut`](WiringXError::InterruptTimedOut) on timeout.
+    pub fn wait_for_interrupt(&self, timeout_dur: Duration) -> Result<(), WiringXError> {
+        // Only edges from now on count.
+        self.line.clear_events()?;
 
-        if result < 1 {
-            Err(InterruptTimeOut)
-        } else {
-            Ok(())
+        match self.line.read_event(timeout_dur)? {
+            Some(_event) => {
+                #[cfg(feature = "trace")]
+                trace::log(self.number, TraceKind::Edge(_event.edge));
+
+                #[cfg(feature = "metrics")]
+                metrics::interrupt(self.number);
+
+                #[cfg(feature = "events")]
+                events::emit(|| Event::Edge {
+                    pin: self.number,
+                    edge: _event.edge,
+                    timestamp: _event.timestamp,
+                });
+
+                Ok(())
+            }
+            None => Err(WiringXError::InterruptTimedOut),
         }
     }
+
+    /// Waits for the next edge, returning which one it was and when it was detected,
+    /// or [`InterruptTimedOut`](WiringXError::InterruptTimedOut) if none occurred within the timeout.
+    ///
+    /// On GPIO character devices the edges are queued by the kernel with the time it detected them,
+    /// so none get lost between calls. With wiringX and sysfs only edges from the call on are seen,
+    /// the time is taken when waking up and the edge is derived from the level at that time.
+    pub fn wait_for_event(&self, timeout: Duration) -> Result<EdgeEvent, WiringXError> {
+        let event = self
+            .line
+            .read_event(timeout)?
+            .ok_or(WiringXError::InterruptTimedOut)?;
+
+        #[cfg(feature = "trace")]
+        trace::log(self.number, TraceKind::Edge(event.edge));
+
+        #[cfg(feature = "metrics")]
+        metrics::interrupt(self.number);
+
+        #[cfg(feature = "events")]
+        events::emit(|| Event::Edge {
+            pin: self.number,
+            edge: event.edge,
+            timestamp: event.timestamp,
+        });
+
+        Ok(event)
+    }
+
+    /// Turns the pin into a [`SelectablePin`] detecting the edges of the mode,
+    /// for waiting for them in an event loop.
+    ///
+    /// Returns [`Unsupported`](WiringXError::Unsupported) for simulated pins, which have no file descriptor.
+    pub fn into_selectable(self, mode: IsrMode) -> Result<SelectablePin, WiringXError> {
+        let (fd, priority) = self.interrupt_fd(mode)?;
+
+        Ok(SelectablePin {
+            pin: self,
+            fd,
+            priority,
+        })
+    }
+
+    /// Sets the interrupt mode and returns the file descriptor signaling the edges,
+    /// along with whether they are signaled as priority data instead of as readable data.
+    #[inline]
+    pub(crate) fn interrupt_fd(&self, mode: IsrMode) -> Result<(RawFd, bool), WiringXError> {
+        self.line.interrupt_fd(mode)
+    }
+
+    /// Discards the edges signaled so far, so the file descriptor of [`interrupt_fd`](Self::interrupt_fd)
+    /// is only ready again on the next one.
+    #[inline]
+    pub(crate) fn clear_interrupt(&self) {
+        let _ = self.line.clear_events();
+    }
+}
+
+/// An input [`Pin`] detecting edges, with the file descriptor signaling them,
+/// for waiting for edges in event loops like epoll, mio or tokio together with other sources.
+///
+/// With wiringX and sysfs edges are signaled as priority data, `POLLPRI` or `EPOLLPRI`,
+/// with GPIO character devices as readable data, `POLLIN` or `EPOLLIN`, see [`priority`](Self::priority).
+/// After waking up, [`clear`](Self::clear) has to be called, or the file descriptor stays ready.
+///
+/// You receive this struct from [`Pin::into_selectable`].
+///
+/// Example waiting for a button on pin `3` with `poll`:
+/// ```no_run
+/// use std::os::fd::AsRawFd;
+///
+/// use wiringx::{Input, IsrMode, Platform, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let button = wiringx
+///     .gpio_pin::<Input>(3)
+///     .unwrap()
+///     .into_selectable(IsrMode::Rising)
+///     .unwrap();
+///
+/// let mut fds = [libc::pollfd {
+///     fd: button.as_raw_fd(),
+///     events: if button.priority() { libc::POLLPRI } else { libc::POLLIN },
+///     revents: 0,
+/// }];
+///
+/// loop {
+///     button.clear();
+///     unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as _, -1) };
+///     println!("pressed");
+/// }
+/// ```
+#[derive(Debug)]
+pub struct SelectablePin {
+    pin: Pin<Input>,
+    fd: RawFd,
+    priority: bool,
+}
+
+impl SelectablePin {
+    /// Returns the number of the pin.
+    #[inline]
+    pub fn number(&self) -> i32 {
+        self.pin.number()
+    }
+
+    /// Returns whether edges are signaled as priority data instead of as readable data.
+    #[inline]
+    pub fn priority(&self) -> bool {
+        self.priority
+    }
+
+    /// Discards the edges signaled so far, so the file descriptor is only ready again on the next one.
+    #[inline]
+    pub fn clear(&self) {
+        self.pin.clear_interrupt();
+    }
+
+    /// Reads the current state of the pin.
+    #[inline]
+    pub fn read(&self) -> Value {
+        self.pin.read()
+    }
+
+    /// Returns the pin, which keeps detecting edges.
+    #[inline]
+    pub fn into_inner(self) -> Pin<Input> {
+        self.pin
+    }
+}
+
+impl AsRawFd for SelectablePin {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl AsFd for SelectablePin {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        // Safety: the file descriptor stays open as long as the pin exists.
+        unsafe { BorrowedFd::borrow_raw(self.fd) }
+    }
+}
+
+/// A pin driving a digital value, implemented by native [`Pin`]s and the pins of I/O expanders,
+/// so drivers can be written once for both.
+pub trait DigitalOutput {
+    /// Drives the pin to the value.
+    fn write(&mut self, value: Value) -> Result<(), WiringXError>;
+
+    /// Returns the value the pin is driven to.
+    fn read(&self) -> Result<Value, WiringXError>;
+
+    /// Drives the pin to the opposite of its current value.
+    fn toggle(&mut self) -> Result<(), WiringXError> {
+        let value = DigitalOutput::read(self)?;
+        DigitalOutput::write(self, value.opposite())
+    }
+}
+
+/// A pin reading a digital value, implemented by native [`Pin`]s and the pins of I/O expanders,
+/// so drivers can be written once for both.
+pub trait DigitalInput {
+    /// Returns the current value of the pin.
+    fn read(&self) -> Result<Value, WiringXError>;
+
+    /// Sets on which edges [`wait_for_interrupt`](Self::wait_for_interrupt) returns.
+    fn set_isr_mode(&self, mode: IsrMode) -> Result<(), WiringXError>;
+
+    /// Suspends the thread until an edge was detected, returning [`InterruptTimedOut`](WiringXError::InterruptTimedOut)
+    /// if none happened within the timeout.
+    fn wait_for_interrupt(&self, timeout: Duration) -> Result<(), WiringXError>;
+}
+
+impl DigitalOutput for Pin<Output> {
+    #[inline]
+    fn write(&mut self, value: Value) -> Result<(), WiringXError> {
+        self.try_write(value)
+    }
+
+    #[inline]
+    fn read(&self) -> Result<Value, WiringXError> {
+        Ok(Pin::<Output>::read(self))
+    }
+}
+
+impl DigitalInput for Pin<Input> {
+    #[inline]
+    fn read(&self) -> Result<Value, WiringXError> {
+        Ok(Pin::<Input>::read(self))
+    }
+
+    #[inline]
+    fn set_isr_mode(&self, mode: IsrMode) -> Result<(), WiringXError> {
+        Ok(Pin::<Input>::set_isr_mode(self, mode)?)
+    }
+
+    #[inline]
+    fn wait_for_interrupt(&self, timeout: Duration) -> Result<(), WiringXError> {
+        Pin::<Input>::wait_for_interrupt(self, timeout)
+    }
 }
 
 impl<T: Default> Drop for Pin<T> {
@@ -117,10 +472,213 @@ impl<T: Default> Drop for Pin<T> {
     }
 }
 
+/// What a [`Pin`] is driven by, implemented for wiringX, GPIO character devices, sysfs and the simulation.
+pub(crate) trait LineBackend: fmt::Debug + Send + Sync {
+    /// Reads the level of the line, also of outputs.
+    fn read(&self) -> Result<Value, WiringXError>;
+
+    /// Drives the output to the value.
+    fn write(&self, value: Value) -> Result<(), WiringXError>;
+
+    /// Switches the line to input, detecting the edges of the mode.
+    fn set_input(&self, mode: IsrMode) -> Result<(), WiringXError>;
+
+    /// Switches the line to output, driving the value.
+    fn set_output(&self, value: Value) -> Result<(), WiringXError>;
+
+    /// Switches the line to input as fast as possible, for emulating open-drain outputs.
+    #[inline]
+    fn release(&self) -> Result<(), WiringXError> {
+        self.set_input(IsrMode::None)
+    }
+
+    /// Discards the edges detected so far.
+    fn clear_events(&self) -> Result<(), WiringXError>;
+
+    /// Returns whether edges can be detected on the line.
+    #[inline]
+    fn supports_interrupts(&self) -> bool {
+        true
+    }
+
+    /// Waits for the next detected edge, none if there was none within the timeout.
+    fn read_event(&self, timeout: Duration) -> Result<Option<EdgeEvent>, WiringXError>;
+
+    /// Sets the edges to detect and returns the file descriptor signaling them,
+    /// along with whether they are signaled as priority data instead of as readable data.
+    fn interrupt_fd(&self, _mode: IsrMode) -> Result<(RawFd, bool), WiringXError> {
+        Err(WiringXError::Unsupported)
+    }
+
+    /// Sets the resistor pulling the input, kept when the edges to detect change.
+    fn set_pull(&self, _pull: Pull) -> Result<(), WiringXError> {
+        Err(WiringXError::Unsupported)
+    }
+}
+
+/// A pin driven by wiringX.
+#[derive(Debug)]
+pub(crate) struct WiringXLine {
+    number: i32,
+    isr: bool,
+}
+
+impl WiringXLine {
+    /// Sets the pin up as input, which can detect edges if `isr` is set.
+    pub(crate) fn input(number: i32, isr: bool) -> Result<Self, WiringXError> {
+        let line = Self { number, isr };
+        line.release()?;
+
+        Ok(line)
+    }
+
+    /// Sets the pin up as output, which can detect edges if `isr` is set.
+    pub(crate) fn output(number: i32, isr: bool) -> Result<Self, WiringXError> {
+        let line = Self { number, isr };
+        line.call(PinOperation::SetMode, || unsafe {
+            pinMode(number, pinmode_t_PINMODE_OUTPUT)
+        })?;
+
+        Ok(line)
+    }
+
+    /// Runs the wiringX call, failing with the operation when it returns an error.
+    fn call(
+        &self,
+        operation: PinOperation,
+        call: impl FnOnce() -> c_int,
+    ) -> Result<c_int, WiringXError> {
+        wiringx_call(call).map_err(|errno| WiringXError::Pin {
+            pin: self.number,
+            operation,
+            errno,
+        })
+    }
+}
+
+impl LineBackend for WiringXLine {
+    fn read(&self) -> Result<Value, WiringXError> {
+        let result = self.call(PinOperation::Read, || unsafe { digitalRead(self.number) })?;
+
+        Ok(if result == 1 { Value::High } else { Value::Low })
+    }
+
+    fn write(&self, value: Value) -> Result<(), WiringXError> {
+        let value = match value {
+            Value::High => digital_value_t_HIGH,
+            Value::Low => digital_value_t_LOW,
+        };
+
+        self.call(PinOperation::Write, || unsafe {
+            digitalWrite(self.number, value)
+        })?;
+
+        Ok(())
+    }
+
+    fn set_input(&self, mode: IsrMode) -> Result<(), WiringXError> {
+        // wiringX only takes modes for the pins it can detect edges on.
+        if !self.isr {
+            return match mode {
+                IsrMode::None => self.release(),
+                _ => Err(WiringXError::Unsupported),
+            };
+        }
+
+        self.call(PinOperation::SetIsrMode, || unsafe {
+            wiringXISR(self.number, mode as u32)
+        })?;
+
+        Ok(())
+    }
+
+    fn set_output(&self, value: Value) -> Result<(), WiringXError> {
+        self.call(PinOperation::SetMode, || unsafe {
+            pinMode(self.number, pinmode_t_PINMODE_OUTPUT)
+        })?;
+        self.write(value)
+    }
+
+    /// Only changes the pin mode, as setting the interrupt mode goes through sysfs.
+    #[inline]
+    fn release(&self) -> Result<(), WiringXError> {
+        self.call(PinOperation::SetMode, || unsafe {
+            pinMode(self.number, pinmode_t_PINMODE_INPUT)
+        })?;
+
+        Ok(())
+    }
+
+    fn clear_events(&self) -> Result<(), WiringXError> {
+        // Reading the value file acknowledges the edge, done in the call so wiringX can not close it meanwhile.
+        let _ = wiringx_call(|| {
+            let fd = unsafe { wiringXSelectableFd(self.number) };
+            if fd > 0 {
+                let mut value = 0u8;
+                unsafe { libc::pread(fd, &mut value as *mut u8 as *mut libc::c_void, 1, 0) };
+            }
+
+            0
+        });
+
+        Ok(())
+    }
+
+    #[inline]
+    fn supports_interrupts(&self) -> bool {
+        self.isr
+    }
+
+    /// Edges are only seen from the call on, with the time taken when waking up
+    /// and the edge derived from the level at that time.
+    fn read_event(&self, timeout: Duration) -> Result<Option<EdgeEvent>, WiringXError> {
+        // Negative timeouts would wait forever.
+        let timeout = timeout.as_millis().min(i32::MAX as u128) as i32;
+        let result = wiringx_wait(|| unsafe { waitForInterrupt(self.number, timeout) })?;
+
+        if result < 1 {
+            return Ok(None);
+        }
+
+        Ok(Some(EdgeEvent {
+            edge: match self.read()? {
+                Value::High => Edge::Rising,
+                Value::Low => Edge::Falling,
+            },
+            timestamp: monotonic_now(),
+        }))
+    }
+
+    fn interrupt_fd(&self, mode: IsrMode) -> Result<(RawFd, bool), WiringXError> {
+        // Setting the mode opens the value file of the pin again, without closing the previous one.
+        let previous = wiringx_call(|| unsafe { wiringXSelectableFd(self.number) }).unwrap_or(-1);
+        self.set_input(mode)?;
+
+        let fd = wiringx_call(|| unsafe { wiringXSelectableFd(self.number) }).unwrap_or(-1);
+        if previous > 0 && previous != fd {
+            unsafe { libc::close(previous) };
+        }
+
+        if fd <= 0 {
+            return Err(WiringXError::Unsupported);
+        }
+
+        Ok((fd, true))
+    }
+}
+
+/// Busy waits until the deadline, as sleeping is too coarse for bit-banged protocols.
+#[inline]
+pub(crate) fn wait_until(deadline: Instant) {
+    while Instant::now() < deadline {
+        std::hint::spin_loop();
+    }
+}
+
 /// Sets the pin mode to output, allowing writing to the pin value.
 #[derive(Debug, Clone, Copy, Default)]
 pub struct Output {
-     value: Value,
+    value: Value,
 }
 
 /// Sets the pin mode to input, allowing reading the physical value.
@@ -129,6 +687,7 @@ pub struct Input;
 
 /// Digital voltage value of the pin.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Value {
     /// Low current or "off"
     #[default]
@@ -148,12 +707,96 @@ impl Value {
     }
 }
 
-/// Returned if a interrupt function times out.
-#[derive(Debug, Clone, Copy)]
-pub struct InterruptTimeOut;
+/// An edge detected on an input [`Pin`], see [`Pin::wait_for_event`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EdgeEvent {
+    /// Direction of the edge.
+    pub edge: Edge,
+    /// Time of the edge on the monotonic clock, the time since boot.
+    pub timestamp: Duration,
+}
+
+/// Direction of an [`EdgeEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Edge {
+    /// From low to high
+    Rising,
+    /// From high to low
+    Falling,
+}
+
+/// Returns the time on the monotonic clock the kernel timestamps edges with.
+pub(crate) fn monotonic_now() -> Duration {
+    let mut time = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut time) };
+
+    Duration::new(time.tv_sec as u64, time.tv_nsec as u32)
+}
+
+/// What a pin can be used for, returned by [`WiringX::pin_capabilities`](crate::WiringX::pin_capabilities).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PinCapabilities {
+    /// Whether the pin number exists on the platform.
+    pub valid: bool,
+    /// Whether the pin works as [`Input`] or [`Output`].
+    pub digital: bool,
+    /// Whether the pin works as [`PwmPin`](crate::PwmPin).
+    pub pwm: bool,
+    /// Whether edges can be waited for on the pin as [`Input`].
+    pub isr: bool,
+    /// Bank and offset of the pin, if the GPIO backend knows them.
+    pub bank: Option<BankOffset>,
+}
+
+/// Location of a pin as the line of a GPIO bank, for SoCs numbering the lines of each bank from `0`.
+///
+/// Banks are the GPIO chips of the kernel, in the order given to [`GpioBackend::Gpiochip`](crate::GpioBackend::Gpiochip),
+/// of a [`PlatformSpec`](crate::PlatformSpec), or of their base in sysfs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BankOffset {
+    /// Index of the bank.
+    pub bank: usize,
+    /// Line of the pin in the bank.
+    pub offset: u32,
+}
+
+/// Errors when setting the edges a pin detects, see [`Pin::set_isr_mode`].
+#[derive(Debug, Error)]
+pub enum IsrError {
+    /// Gets returned when the pin can not detect edges, see [`Pin::supports_interrupts`].
+    #[error("Pin {0} can not detect edges, it has to be polled.")]
+    UnsupportedPin(i32),
+    /// Gets returned when the mode is not supported by the GPIO backend.
+    #[error("The interrupt mode {0:?} is not supported.")]
+    UnsupportedMode(IsrMode),
+    /// Gets returned when the system rejects the mode.
+    #[error("Setting the interrupt mode failed: {0}")]
+    Io(io::Error),
+}
+
+/// Resistor pulling an input, see [`Pin::set_pull`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Pull {
+    /// Neither resistor, the input floats when nothing drives it
+    #[default]
+    None,
+    /// Pulled up to high
+    Up,
+    /// Pulled down to low
+    Down,
+}
 
 /// Mode for the interrupt service routine.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum IsrMode {
     Unknown = 0,
     Rising = 2,
@@ -0,0 +1,418 @@
+//! GPIO through the Linux GPIO character devices `/dev/gpiochipN`.
+
+use std::{
+    fs::{File, OpenOptions},
+    io,
+    mem::{size_of, zeroed},
+    os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd},
+    path::PathBuf,
+    sync::atomic::{AtomicU64, AtomicU8, Ordering},
+    time::Duration,
+};
+
+use crate::{gpio::LineBackend, Edge, EdgeEvent, IsrMode, Pull, Value, WiringXError};
+
+const GPIO_IOC_MAGIC: u8 = 0xB4;
+
+/// Linux `_IOR` request number for the GPIO character device.
+const fn gpio_ior(nr: u8, size: usize) -> u32 {
+    (2 << 30) | ((size as u32) << 16) | ((GPIO_IOC_MAGIC as u32) << 8) | nr as u32
+}
+
+/// Linux `_IOWR` request number for the GPIO character device.
+const fn gpio_iowr(nr: u8, size: usize) -> u32 {
+    (3 << 30) | ((size as u32) << 16) | ((GPIO_IOC_MAGIC as u32) << 8) | nr as u32
+}
+
+const GPIO_GET_CHIPINFO_IOCTL: u32 = gpio_ior(0x01, size_of::<GpioChipInfo>());
+const GPIO_V2_GET_LINE_IOCTL: u32 = gpio_iowr(0x07, size_of::<LineRequest>());
+const GPIO_V2_LINE_SET_CONFIG_IOCTL: u32 = gpio_iowr(0x0D, size_of::<LineConfig>());
+const GPIO_V2_LINE_GET_VALUES_IOCTL: u32 = gpio_iowr(0x0E, size_of::<LineValues>());
+const GPIO_V2_LINE_SET_VALUES_IOCTL: u32 = gpio_iowr(0x0F, size_of::<LineValues>());
+
+const LINE_FLAG_INPUT: u64 = 1 << 2;
+const LINE_FLAG_OUTPUT: u64 = 1 << 3;
+const LINE_FLAG_EDGE_RISING: u64 = 1 << 4;
+const LINE_FLAG_EDGE_FALLING: u64 = 1 << 5;
+const LINE_FLAG_BIAS_PULL_UP: u64 = 1 << 8;
+const LINE_FLAG_BIAS_PULL_DOWN: u64 = 1 << 9;
+const LINE_FLAG_BIAS_DISABLED: u64 = 1 << 10;
+
+const LINE_ATTR_ID_OUTPUT_VALUES: u32 = 2;
+const LINE_EVENT_RISING_EDGE: u32 = 1;
+
+/// Name the lines are requested under, shown by tools like `gpioinfo`.
+const CONSUMER: &[u8] = b"wiringx";
+
+/// Mirror of the kernel's `struct gpiochip_info`.
+#[repr(C)]
+struct GpioChipInfo {
+    name: [u8; 32],
+    label: [u8; 32],
+    lines: u32,
+}
+
+/// Mirror of the kernel's `struct gpio_v2_line_attribute`, with the union as its widest member.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct LineAttribute {
+    id: u32,
+    padding: u32,
+    value: u64,
+}
+
+/// Mirror of the kernel's `struct gpio_v2_line_config_attribute`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct LineConfigAttribute {
+    attr: LineAttribute,
+    mask: u64,
+}
+
+/// Mirror of the kernel's `struct gpio_v2_line_config`.
+#[repr(C)]
+struct LineConfig {
+    flags: u64,
+    num_attrs: u32,
+    padding: [u32; 5],
+    attrs: [LineConfigAttribute; 10],
+}
+
+/// Mirror of the kernel's `struct gpio_v2_line_request`.
+#[repr(C)]
+struct LineRequest {
+    offsets: [u32; 64],
+    consumer: [u8; 32],
+    config: LineConfig,
+    num_lines: u32,
+    event_buffer_size: u32,
+    padding: [u32; 5],
+    fd: i32,
+}
+
+/// Mirror of the kernel's `struct gpio_v2_line_values`.
+#[repr(C)]
+struct LineValues {
+    bits: u64,
+    mask: u64,
+}
+
+/// Mirror of the kernel's `struct gpio_v2_line_event`.
+#[repr(C)]
+struct LineEvent {
+    timestamp_ns: u64,
+    id: u32,
+    offset: u32,
+    seqno: u32,
+    line_seqno: u32,
+    padding: [u32; 6],
+}
+
+impl LineConfig {
+    /// Returns the configuration of an input, detecting the edges of the mode, with the bias flags.
+    fn input(mode: IsrMode, bias: u64) -> Result<Self, WiringXError> {
+        let edges = match mode {
+            IsrMode::Rising => LINE_FLAG_EDGE_RISING,
+            IsrMode::Falling => LINE_FLAG_EDGE_FALLING,
+            IsrMode::Both => LINE_FLAG_EDGE_RISING | LINE_FLAG_EDGE_FALLING,
+            IsrMode::None => 0,
+            IsrMode::Unknown => return Err(WiringXError::InvalidArgument),
+        };
+
+        // Safety: the kernel structs are valid with all fields zero.
+        let mut config: Self = unsafe { zeroed() };
+        config.flags = LINE_FLAG_INPUT | edges | bias;
+
+        Ok(config)
+    }
+
+    /// Returns the configuration of an output driving the value.
+    fn output(value: Value) -> Self {
+        let mut config: Self = unsafe { zeroed() };
+        config.flags = LINE_FLAG_OUTPUT;
+        config.num_attrs = 1;
+        config.attrs[0] = LineConfigAttribute {
+            attr: LineAttribute {
+                id: LINE_ATTR_ID_OUTPUT_VALUES,
+                padding: 0,
+                value: value as u64,
+            },
+            mask: 1,
+        };
+
+        config
+    }
+}
+
+/// Which GPIO implementation [`Pin`](crate::Pin)s use, chosen with [`WiringX::with_gpio_backend`](crate::WiringX::with_gpio_backend).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GpioBackend {
+    /// The wiringX library with its board pin numbers
+    #[default]
+    WiringX,
+    /// The Linux GPIO character devices, with the lines of the chips numbered consecutively in the given order,
+    /// so with chips of 32 lines, line `3` of the second chip is pin `35`
+    Gpiochip(Vec<PathBuf>),
+    /// The sysfs GPIO interface with the kernel's global GPIO numbers,
+    /// for boards wiringX does not support, see [`Platform::Generic`](crate::Platform::Generic)
+    Sysfs,
+    /// The simulated pins of a [`MockBoard`](crate::MockBoard), which also drives the PWM pins,
+    /// for running applications without hardware
+    #[cfg(feature = "mock")]
+    Mock,
+}
+
+/// The opened GPIO character devices.
+#[derive(Debug)]
+pub(crate) struct GpioChips {
+    chips: Vec<(File, u32)>,
+}
+
+impl GpioChips {
+    /// Opens the chips and reads their number of lines.
+    pub(crate) fn open(paths: &[PathBuf]) -> Result<Self, WiringXError> {
+        let mut chips = Vec::with_capacity(paths.len());
+
+        for path in paths {
+            let file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(path)
+                .map_err(WiringXError::Io)?;
+
+            let mut info: GpioChipInfo = unsafe { zeroed() };
+            let result =
+                unsafe { libc::ioctl(file.as_raw_fd(), GPIO_GET_CHIPINFO_IOCTL as _, &mut info) };
+            if result < 0 {
+                return Err(WiringXError::Io(io::Error::last_os_error()));
+            }
+
+            chips.push((file, info.lines));
+        }
+
+        Ok(Self { chips })
+    }
+
+    /// Returns the chip and offset of the line with the number, none if there is no such line.
+    fn locate(&self, number: i32) -> Option<(&File, u32)> {
+        let (chip, offset) = self.bank_offset(number)?;
+
+        Some((&self.chips[chip].0, offset))
+    }
+
+    /// Returns the index of the chip and the offset of the line with the number.
+    pub(crate) fn bank_offset(&self, number: i32) -> Option<(usize, u32)> {
+        let mut offset = u32::try_from(number).ok()?;
+
+        for (chip, (_, lines)) in self.chips.iter().enumerate() {
+            if offset < *lines {
+                return Some((chip, offset));
+            }
+
+            offset -= lines;
+        }
+
+        None
+    }
+
+    /// Returns the number of the line of the chip at the index, numbering the lines of all chips in order.
+    pub(crate) fn number(&self, chip: usize, line: u32) -> Option<i32> {
+        let (_, lines) = self.chips.get(chip)?;
+        if line >= *lines {
+            return None;
+        }
+
+        let before: u32 = self.chips[..chip].iter().map(|(_, lines)| lines).sum();
+
+        i32::try_from(before + line).ok()
+    }
+
+    /// Returns whether a line with the number exists.
+    #[inline]
+    pub(crate) fn is_valid(&self, number: i32) -> bool {
+        self.locate(number).is_some()
+    }
+
+    /// Requests the line with the number as input.
+    #[inline]
+    pub(crate) fn request_input(&self, number: i32) -> Result<GpioLine, WiringXError> {
+        self.request(number, LineConfig::input(IsrMode::None, 0)?)
+    }
+
+    /// Requests the line with the number as output, driving it low.
+    #[inline]
+    pub(crate) fn request_output(&self, number: i32) -> Result<GpioLine, WiringXError> {
+        self.request(number, LineConfig::output(Value::Low))
+    }
+
+    fn request(&self, number: i32, config: LineConfig) -> Result<GpioLine, WiringXError> {
+        let (chip, offset) = self.locate(number).ok_or(WiringXError::InvalidPin)?;
+
+        let mut request: LineRequest = unsafe { zeroed() };
+        request.offsets[0] = offset;
+        request.consumer[..CONSUMER.len()].copy_from_slice(CONSUMER);
+        request.config = config;
+        request.num_lines = 1;
+
+        let result =
+            unsafe { libc::ioctl(chip.as_raw_fd(), GPIO_V2_GET_LINE_IOCTL as _, &mut request) };
+        if result < 0 {
+            let error = io::Error::last_os_error();
+            return Err(if error.raw_os_error() == Some(libc::EBUSY) {
+                WiringXError::PinUsed
+            } else {
+                WiringXError::Io(error)
+            });
+        }
+
+        Ok(GpioLine {
+            fd: unsafe { OwnedFd::from_raw_fd(request.fd) },
+            edges: AtomicU8::new(IsrMode::None as u8),
+            bias: AtomicU64::new(0),
+        })
+    }
+}
+
+/// A requested line of a GPIO character device, released when dropped.
+#[derive(Debug)]
+pub(crate) struct GpioLine {
+    fd: OwnedFd,
+    /// The edges detected, to keep them when the bias changes.
+    edges: AtomicU8,
+    /// The bias flags, to keep them when the edges change.
+    bias: AtomicU64,
+}
+
+impl GpioLine {
+    fn ioctl<T>(&self, request: u32, arg: &mut T) -> Result<(), WiringXError> {
+        let result = unsafe { libc::ioctl(self.fd.as_raw_fd(), request as _, arg as *mut T) };
+
+        if result < 0 {
+            Err(WiringXError::Io(io::Error::last_os_error()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl LineBackend for GpioLine {
+    /// Reads the level of the line, also of outputs.
+    fn read(&self) -> Result<Value, WiringXError> {
+        let mut values = LineValues { bits: 0, mask: 1 };
+        self.ioctl(GPIO_V2_LINE_GET_VALUES_IOCTL, &mut values)?;
+
+        Ok(if values.bits & 1 == 1 {
+            Value::High
+        } else {
+            Value::Low
+        })
+    }
+
+    /// Drives the output to the value.
+    fn write(&self, value: Value) -> Result<(), WiringXError> {
+        let mut values = LineValues {
+            bits: value as u64,
+            mask: 1,
+        };
+
+        self.ioctl(GPIO_V2_LINE_SET_VALUES_IOCTL, &mut values)
+    }
+
+    /// Switches the line to input, detecting the edges of the mode.
+    fn set_input(&self, mode: IsrMode) -> Result<(), WiringXError> {
+        let mut config = LineConfig::input(mode, self.bias.load(Ordering::Relaxed))?;
+
+        self.ioctl(GPIO_V2_LINE_SET_CONFIG_IOCTL, &mut config)?;
+        self.edges.store(mode as u8, Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    /// Switches the line to output, driving the value.
+    fn set_output(&self, value: Value) -> Result<(), WiringXError> {
+        let mut config = LineConfig::output(value);
+
+        self.ioctl(GPIO_V2_LINE_SET_CONFIG_IOCTL, &mut config)
+    }
+
+    /// Reconfigures the input with the bias, keeping the edges detected.
+    fn set_pull(&self, pull: Pull) -> Result<(), WiringXError> {
+        let bias = match pull {
+            Pull::None => LINE_FLAG_BIAS_DISABLED,
+            Pull::Up => LINE_FLAG_BIAS_PULL_UP,
+            Pull::Down => LINE_FLAG_BIAS_PULL_DOWN,
+        };
+
+        let mode = match self.edges.load(Ordering::Relaxed) {
+            mode if mode == IsrMode::Rising as u8 => IsrMode::Rising,
+            mode if mode == IsrMode::Falling as u8 => IsrMode::Falling,
+            mode if mode == IsrMode::Both as u8 => IsrMode::Both,
+            _ => IsrMode::None,
+        };
+
+        let mut config = LineConfig::input(mode, bias)?;
+        self.ioctl(GPIO_V2_LINE_SET_CONFIG_IOCTL, &mut config)?;
+        self.bias.store(bias, Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    /// Discards the edges detected so far.
+    fn clear_events(&self) -> Result<(), WiringXError> {
+        while self.read_event(Duration::ZERO)?.is_some() {}
+
+        Ok(())
+    }
+
+    /// Waits for the next detected edge with the time the kernel detected it at, none if there was none within the timeout.
+    fn read_event(&self, timeout: Duration) -> Result<Option<EdgeEvent>, WiringXError> {
+        let mut poll = libc::pollfd {
+            fd: self.fd.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+
+        let timeout = timeout.as_millis().min(i32::MAX as u128) as i32;
+        let result = unsafe { libc::poll(&mut poll, 1, timeout) };
+        if result < 0 {
+            let error = io::Error::last_os_error();
+            return if error.kind() == io::ErrorKind::Interrupted {
+                Ok(None)
+            } else {
+                Err(WiringXError::Io(error))
+            };
+        }
+
+        if result == 0 {
+            return Ok(None);
+        }
+
+        let mut event: LineEvent = unsafe { zeroed() };
+        let read = unsafe {
+            libc::read(
+                self.fd.as_raw_fd(),
+                &mut event as *mut LineEvent as *mut libc::c_void,
+                size_of::<LineEvent>(),
+            )
+        };
+        if read != size_of::<LineEvent>() as isize {
+            return Err(WiringXError::Io(io::Error::last_os_error()));
+        }
+
+        Ok(Some(EdgeEvent {
+            edge: if event.id == LINE_EVENT_RISING_EDGE {
+                Edge::Rising
+            } else {
+                Edge::Falling
+            },
+            timestamp: Duration::from_nanos(event.timestamp_ns),
+        }))
+    }
+
+    fn interrupt_fd(&self, mode: IsrMode) -> Result<(RawFd, bool), WiringXError> {
+        self.set_input(mode)?;
+
+        Ok((self.fd.as_raw_fd(), false))
+    }
+}
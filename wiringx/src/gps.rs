@@ -0,0 +1,466 @@
+//! Reading position fixes from GPS receivers speaking NMEA 0183 over a serial port.
+//!
+//! Example printing the position of a receiver on `/dev/ttyS1`:
+//! ```no_run
+//! use wiringx::{gps::Gps, Platform, WiringX};
+//!
+//! let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+//! let serial = wiringx.serial("/dev/ttyS1", 9600).unwrap();
+//!
+//! let mut gps = Gps::new(serial);
+//!
+//! for fix in gps.fixes() {
+//!     match fix {
+//!         Ok(fix) => println!(
+//!             "{}, {} at {:?} m with {} satellites",
+//!             fix.latitude, fix.longitude, fix.altitude, fix.satellites
+//!         ),
+//!         Err(e) => eprintln!("{e}"),
+//!     }
+//! }
+//! ```
+
+use std::time::Duration;
+
+use thiserror::Error;
+
+use crate::{SerialPort, WiringXError};
+
+/// Reads NMEA sentences from a GPS receiver.
+#[derive(Debug)]
+pub struct Gps {
+    serial: SerialPort,
+    timeout: Duration,
+    last_rmc: Option<Rmc>,
+}
+
+impl Gps {
+    /// Takes ownership of the serial port the receiver is connected to.
+    ///
+    /// Sentences are awaited for two seconds by default, receivers usually send them every second.
+    pub fn new(serial: SerialPort) -> Self {
+        Self {
+            serial,
+            timeout: Duration::from_secs(2),
+            last_rmc: None,
+        }
+    }
+
+    /// Sets how long to wait for the next sentence.
+    #[inline]
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Returns how long to wait for the next sentence.
+    #[inline]
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Returns the serial port.
+    #[inline]
+    pub fn into_inner(self) -> SerialPort {
+        self.serial
+    }
+
+    /// Reads the next sentence and validates its checksum.
+    pub fn read_sentence(&mut self) -> Result<Sentence, GpsError> {
+        let line = self.serial.read_until(b'\n', self.timeout)?;
+        let line = std::str::from_utf8(&line).map_err(|_| GpsError::InvalidSentence)?;
+
+        let sentence = parse(line)?;
+        if let Sentence::Rmc(rmc) = &sentence {
+            self.last_rmc = Some(rmc.clone());
+        }
+
+        Ok(sentence)
+    }
+
+    /// Returns an endless iterator over received sentences.
+    #[inline]
+    pub fn sentences(&mut self) -> Sentences<'_> {
+        Sentences { gps: self }
+    }
+
+    /// Returns an endless iterator over position fixes.
+    ///
+    /// A fix is yielded for every `GGA` sentence reporting a valid position,
+    /// completed with the date, speed and course of the preceding `RMC` sentence.
+    #[inline]
+    pub fn fixes(&mut self) -> Fixes<'_> {
+        Fixes { gps: self }
+    }
+}
+
+/// Iterator over received sentences, returned by [`Gps::sentences`].
+#[derive(Debug)]
+pub struct Sentences<'a> {
+    gps: &'a mut Gps,
+}
+
+impl Iterator for Sentences<'_> {
+    type Item = Result<Sentence, GpsError>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.gps.read_sentence())
+    }
+}
+
+/// Iterator over position fixes, returned by [`Gps::fixes`].
+#[derive(Debug)]
+pub struct Fixes<'a> {
+    gps: &'a mut Gps,
+}
+
+impl Iterator for Fixes<'_> {
+    type Item = Result<Fix, GpsError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let gga = match self.gps.read_sentence() {
+                Ok(Sentence::Gga(gga)) => gga,
+                Ok(_) => continue,
+                Err(e) => return Some(Err(e)),
+            };
+
+            let (Some(latitude), Some(longitude)) = (gga.latitude, gga.longitude) else {
+                continue;
+            };
+            if gga.quality == FixQuality::Invalid {
+                continue;
+            }
+
+            // Only take over the date if the RMC sentence belongs to the same fix.
+            let rmc = self
+                .gps
+                .last_rmc
+                .as_ref()
+                .filter(|rmc| rmc.time == gga.time && rmc.valid);
+
+            return Some(Ok(Fix {
+                time: gga.time,
+                date: rmc.and_then(|rmc| rmc.date),
+                latitude,
+                longitude,
+                altitude: gga.altitude,
+                satellites: gga.satellites,
+                quality: gga.quality,
+                hdop: gga.hdop,
+                speed: rmc.and_then(|rmc| rmc.speed),
+                course: rmc.and_then(|rmc| rmc.course),
+            }));
+        }
+    }
+}
+
+/// A position fix of the receiver.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fix {
+    /// Time of the fix in UTC.
+    pub time: Option<Time>,
+    /// Date of the fix in UTC, if a matching `RMC` sentence was received.
+    pub date: Option<Date>,
+    /// Latitude in degrees, positive north of the equator.
+    pub latitude: f64,
+    /// Longitude in degrees, positive east of the prime meridian.
+    pub longitude: f64,
+    /// Altitude above mean sea level in meters.
+    pub altitude: Option<f64>,
+    /// Number of satellites used for the fix.
+    pub satellites: u8,
+    /// How the fix was obtained.
+    pub quality: FixQuality,
+    /// Horizontal dilution of precision, lower is better.
+    pub hdop: Option<f32>,
+    /// Speed over ground in knots.
+    pub speed: Option<f32>,
+    /// Course over ground in degrees from true north.
+    pub course: Option<f32>,
+}
+
+/// A parsed NMEA sentence.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sentence {
+    /// Global positioning system fix data.
+    Gga(Gga),
+    /// Recommended minimum specific GNSS data.
+    Rmc(Rmc),
+    /// Any other sentence with a valid checksum, containing the sentence without `$` and checksum.
+    Other(String),
+}
+
+/// Fix data of a `GGA` sentence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gga {
+    /// Time of the fix in UTC.
+    pub time: Option<Time>,
+    /// Latitude in degrees, positive north of the equator.
+    pub latitude: Option<f64>,
+    /// Longitude in degrees, positive east of the prime meridian.
+    pub longitude: Option<f64>,
+    /// How the fix was obtained.
+    pub quality: FixQuality,
+    /// Number of satellites used for the fix.
+    pub satellites: u8,
+    /// Horizontal dilution of precision, lower is better.
+    pub hdop: Option<f32>,
+    /// Altitude above mean sea level in meters.
+    pub altitude: Option<f64>,
+}
+
+/// Navigation data of an `RMC` sentence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rmc {
+    /// Time of the fix in UTC.
+    pub time: Option<Time>,
+    /// Whether the receiver reports the data as valid.
+    pub valid: bool,
+    /// Latitude in degrees, positive north of the equator.
+    pub latitude: Option<f64>,
+    /// Longitude in degrees, positive east of the prime meridian.
+    pub longitude: Option<f64>,
+    /// Speed over ground in knots.
+    pub speed: Option<f32>,
+    /// Course over ground in degrees from true north.
+    pub course: Option<f32>,
+    /// Date of the fix in UTC.
+    pub date: Option<Date>,
+}
+
+/// Time of day in UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Time {
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    pub millisecond: u16,
+}
+
+/// Calendar date in UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Date {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+/// How a fix was obtained, as reported in `GGA` sentences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixQuality {
+    /// No position available
+    Invalid,
+    /// Standalone satellite positioning
+    Gps,
+    /// Differential GPS
+    Dgps,
+    /// Precise positioning service
+    Pps,
+    /// Real time kinematic with fixed integers
+    Rtk,
+    /// Real time kinematic with floating integers
+    FloatRtk,
+    /// Dead reckoning
+    Estimated,
+    /// Position entered manually
+    Manual,
+    /// Simulator
+    Simulation,
+}
+
+impl From<u8> for FixQuality {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => Self::Gps,
+            2 => Self::Dgps,
+            3 => Self::Pps,
+            4 => Self::Rtk,
+            5 => Self::FloatRtk,
+            6 => Self::Estimated,
+            7 => Self::Manual,
+            8 => Self::Simulation,
+            _ => Self::Invalid,
+        }
+    }
+}
+
+/// Parses one NMEA sentence like `$GPGGA,...*47`, validating its checksum.
+///
+/// Trailing line endings are ignored. Sentences of all talkers, like `GP`, `GN` or `GL`, are accepted.
+///
+/// ```
+/// use wiringx::gps::{parse, Sentence};
+///
+/// let sentence = parse("$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47").unwrap();
+///
+/// let Sentence::Gga(gga) = sentence else { panic!() };
+/// assert_eq!(gga.satellites, 8);
+/// assert_eq!(gga.altitude, Some(545.4));
+/// ```
+pub fn parse(line: &str) -> Result<Sentence, GpsError> {
+    // NMEA sentences are ASCII, which lets the fields be sliced by byte without splitting a character.
+    if !line.is_ascii() {
+        return Err(GpsError::InvalidSentence);
+    }
+
+    let line = line.trim_end_matches(['\r', '\n']);
+    let body = line.strip_prefix('$').ok_or(GpsError::InvalidSentence)?;
+    let (body, checksum) = body.rsplit_once('*').ok_or(GpsError::InvalidSentence)?;
+
+    let checksum = u8::from_str_radix(checksum, 16).map_err(|_| GpsError::InvalidSentence)?;
+    if body.bytes().fold(0, |sum, byte| sum ^ byte) != checksum {
+        return Err(GpsError::Checksum);
+    }
+
+    let mut fields = body.split(',');
+    let address = fields.next().ok_or(GpsError::InvalidSentence)?;
+    let fields: Vec<&str> = fields.collect();
+
+    match address.get(2..) {
+        Some("GGA") => parse_gga(&fields).map(Sentence::Gga),
+        Some("RMC") => parse_rmc(&fields).map(Sentence::Rmc),
+        _ => Ok(Sentence::Other(body.to_string())),
+    }
+}
+
+fn parse_gga(fields: &[&str]) -> Result<Gga, GpsError> {
+    if fields.len() < 9 {
+        return Err(GpsError::InvalidSentence);
+    }
+
+    Ok(Gga {
+        time: parse_time(fields[0])?,
+        latitude: parse_coordinate(fields[1], fields[2], 2)?,
+        longitude: parse_coordinate(fields[3], fields[4], 3)?,
+        quality: parse_number::<u8>(fields[5])?.unwrap_or(0).into(),
+        satellites: parse_number(fields[6])?.unwrap_or(0),
+        hdop: parse_number(fields[7])?,
+        altitude: parse_number(fields[8])?,
+    })
+}
+
+fn parse_rmc(fields: &[&str]) -> Result<Rmc, GpsError> {
+    if fields.len() < 9 {
+        return Err(GpsError::InvalidSentence);
+    }
+
+    Ok(Rmc {
+        time: parse_time(fields[0])?,
+        valid: fields[1] == "A",
+        latitude: parse_coordinate(fields[2], fields[3], 2)?,
+        longitude: parse_coordinate(fields[4], fields[5], 3)?,
+        speed: parse_number(fields[6])?,
+        course: parse_number(fields[7])?,
+        date: parse_date(fields[8])?,
+    })
+}
+
+/// Parses an optional number, empty fields are `None`.
+fn parse_number<T: std::str::FromStr>(field: &str) -> Result<Option<T>, GpsError> {
+    if field.is_empty() {
+        return Ok(None);
+    }
+
+    field
+        .parse()
+        .map(Some)
+        .map_err(|_| GpsError::InvalidSentence)
+}
+
+/// Parses a time in the `hhmmss.sss` format.
+fn parse_time(field: &str) -> Result<Option<Time>, GpsError> {
+    if field.is_empty() {
+        return Ok(None);
+    }
+
+    let number = |range: std::ops::Range<usize>| {
+        field
+            .get(range)
+            .and_then(|digits| digits.parse::<u8>().ok())
+            .ok_or(GpsError::InvalidSentence)
+    };
+
+    let fraction = field.get(7..).unwrap_or("");
+    let millisecond = if fraction.is_empty() {
+        0
+    } else {
+        let digits = fraction
+            .get(..fraction.len().min(3))
+            .ok_or(GpsError::InvalidSentence)?;
+        let value: u16 = digits.parse().map_err(|_| GpsError::InvalidSentence)?;
+        value * 10u16.pow(3 - digits.len() as u32)
+    };
+
+    Ok(Some(Time {
+        hour: number(0..2)?,
+        minute: number(2..4)?,
+        second: number(4..6)?,
+        millisecond,
+    }))
+}
+
+/// Parses a date in the `ddmmyy` format.
+fn parse_date(field: &str) -> Result<Option<Date>, GpsError> {
+    if field.is_empty() {
+        return Ok(None);
+    }
+    if field.len() != 6 {
+        return Err(GpsError::InvalidSentence);
+    }
+
+    let number = |range: std::ops::Range<usize>| {
+        field
+            .get(range)
+            .and_then(|digits| digits.parse::<u8>().ok())
+            .ok_or(GpsError::InvalidSentence)
+    };
+
+    Ok(Some(Date {
+        year: 2000 + number(4..6)? as u16,
+        month: number(2..4)?,
+        day: number(0..2)?,
+    }))
+}
+
+/// Parses a coordinate in the `(d)ddmm.mmmm` format with its hemisphere into degrees.
+fn parse_coordinate(
+    field: &str,
+    hemisphere: &str,
+    degree_digits: usize,
+) -> Result<Option<f64>, GpsError> {
+    if field.is_empty() {
+        return Ok(None);
+    }
+
+    let degrees: f64 = field
+        .get(..degree_digits)
+        .and_then(|degrees| degrees.parse().ok())
+        .ok_or(GpsError::InvalidSentence)?;
+    let minutes: f64 = field
+        .get(degree_digits..)
+        .and_then(|minutes| minutes.parse().ok())
+        .ok_or(GpsError::InvalidSentence)?;
+
+    let value = degrees + minutes / 60.0;
+
+    match hemisphere {
+        "N" | "E" => Ok(Some(value)),
+        "S" | "W" => Ok(Some(-value)),
+        _ => Err(GpsError::InvalidSentence),
+    }
+}
+
+/// Errors when reading from a GPS receiver.
+#[derive(Error, Debug)]
+pub enum GpsError {
+    /// Gets returned when the serial port fails, or no sentence arrived in time.
+    #[error("Serial communication failed: {0}")]
+    Serial(#[from] WiringXError),
+    /// Gets returned when the checksum of the sentence does not match its content.
+    #[error("The sentence checksum does not match.")]
+    Checksum,
+    /// Gets returned when the received line is not a well-formed NMEA sentence.
+    #[error("The sentence is not valid NMEA.")]
+    InvalidSentence,
+}
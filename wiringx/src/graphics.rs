@@ -0,0 +1,92 @@
+//! [`embedded-graphics`](embedded_graphics_core) trait implementations, enabled with the `embedded-graphics-core` feature.
+//!
+//! Drawing only changes the framebuffers of the displays, call their `flush` method to show the result.
+
+use std::convert::Infallible;
+
+use embedded_graphics_core::{
+    pixelcolor::{raw::RawU16, BinaryColor, Rgb565},
+    prelude::{Dimensions, DrawTarget, OriginDimensions, RawData, Size},
+    primitives::Rectangle,
+    Pixel,
+};
+
+use crate::{Ssd1306, Tft};
+
+impl OriginDimensions for Ssd1306 {
+    #[inline]
+    fn size(&self) -> Size {
+        Size::new(self.width() as u32, self.height() as u32)
+    }
+}
+
+impl DrawTarget for Ssd1306 {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if let (Ok(x), Ok(y)) = (u8::try_from(point.x), u8::try_from(point.y)) {
+                self.set_pixel(x, y, color.is_on());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.buffer_mut()
+            .fill(if color.is_on() { 0xFF } else { 0x00 });
+
+        Ok(())
+    }
+}
+
+impl OriginDimensions for Tft {
+    #[inline]
+    fn size(&self) -> Size {
+        Size::new(self.width() as u32, self.height() as u32)
+    }
+}
+
+impl DrawTarget for Tft {
+    type Color = Rgb565;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if let (Ok(x), Ok(y)) = (u16::try_from(point.x), u16::try_from(point.y)) {
+                self.set_pixel(x, y, RawU16::from(color).into_inner());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&self.bounding_box());
+        if let Some(bottom_right) = area.bottom_right() {
+            self.fill_rect(
+                area.top_left.x as u16,
+                area.top_left.y as u16,
+                (bottom_right.x - area.top_left.x + 1) as u16,
+                (bottom_right.y - area.top_left.y + 1) as u16,
+                RawU16::from(color).into_inner(),
+            );
+        }
+
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.fill(RawU16::from(color).into_inner());
+
+        Ok(())
+    }
+}
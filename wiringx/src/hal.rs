@@ -0,0 +1,342 @@
+//! [`embedded-hal`](embedded_hal) trait implementations, enabled with the `embedded-hal` or `eh1` feature.
+
+use std::time::Duration;
+
+use embedded_hal::{
+    delay::DelayNs,
+    digital::{self, InputPin, OutputPin, StatefulOutputPin},
+    i2c::{self, I2c, NoAcknowledgeSource},
+    pwm::{self, SetDutyCycle},
+    spi::{self, ErrorKind, ErrorType, Operation, SpiBus, SpiDevice},
+};
+
+use crate::{
+    i2c::transfer,
+    soft_spi,
+    spi::{check_len, SpiIocTransfer},
+    Delay, Input, Output, Pin, PwmPin, SoftSpi, Spi, SpiBusDevice, Value, WiringXError, I2C,
+};
+#[cfg(feature = "drivers")]
+use crate::{pca9685, Pca9685Channel};
+
+impl digital::Error for WiringXError {
+    #[inline]
+    fn kind(&self) -> digital::ErrorKind {
+        digital::ErrorKind::Other
+    }
+}
+
+/// Writing fails like [`Pin::try_write`].
+impl digital::ErrorType for Pin<Output> {
+    type Error = WiringXError;
+}
+
+impl OutputPin for Pin<Output> {
+    #[inline]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.try_write(Value::Low)
+    }
+
+    #[inline]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.try_write(Value::High)
+    }
+}
+
+/// Reads back the level of the pin, like [`Pin::read`].
+impl StatefulOutputPin for Pin<Output> {
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.read() == Value::High)
+    }
+
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.read() == Value::Low)
+    }
+
+    #[inline]
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        self.try_write(self.read().opposite())
+    }
+}
+
+/// Reading can not fail, waiting for edges with the `async` feature can.
+impl digital::ErrorType for Pin<Input> {
+    type Error = WiringXError;
+}
+
+impl InputPin for Pin<Input> {
+    #[inline]
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.read() == Value::High)
+    }
+
+    #[inline]
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.read() == Value::Low)
+    }
+}
+
+impl spi::Error for WiringXError {
+    #[inline]
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
+
+impl DelayNs for Delay {
+    #[inline]
+    fn delay_ns(&mut self, ns: u32) {
+        self.delay(Duration::from_nanos(ns as u64));
+    }
+
+    #[inline]
+    fn delay_us(&mut self, us: u32) {
+        self.delay(Duration::from_micros(us as u64));
+    }
+
+    #[inline]
+    fn delay_ms(&mut self, ms: u32) {
+        self.delay(Duration::from_millis(ms as u64));
+    }
+}
+
+impl pwm::Error for WiringXError {
+    #[inline]
+    fn kind(&self) -> pwm::ErrorKind {
+        pwm::ErrorKind::Other
+    }
+}
+
+impl pwm::ErrorType for PwmPin {
+    type Error = WiringXError;
+}
+
+/// The duty cycle is set in nanoseconds of the period, for periods up to 65.535 µs.
+/// Longer periods are divided into 65535 steps.
+impl SetDutyCycle for PwmPin {
+    #[inline]
+    fn max_duty_cycle(&self) -> u16 {
+        self.period().as_nanos().clamp(1, u16::MAX as u128) as u16
+    }
+
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        let max = self.max_duty_cycle();
+        if duty > max {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        PwmPin::set_duty_cycle(self, duty as f32 / max as f32)
+    }
+}
+
+#[cfg(feature = "drivers")]
+impl pwm::ErrorType for Pca9685Channel {
+    type Error = WiringXError;
+}
+
+/// The duty cycle is set in the 4096 steps of the expander.
+#[cfg(feature = "drivers")]
+impl SetDutyCycle for Pca9685Channel {
+    #[inline]
+    fn max_duty_cycle(&self) -> u16 {
+        pca9685::STEPS
+    }
+
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        if duty > pca9685::STEPS {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        Pca9685Channel::set_duty_cycle(self, duty as f32 / pca9685::STEPS as f32)
+    }
+}
+
+impl i2c::Error for WiringXError {
+    fn kind(&self) -> i2c::ErrorKind {
+        match self {
+            WiringXError::Io(error)
+                if matches!(error.raw_os_error(), Some(libc::ENXIO | libc::EREMOTEIO)) =>
+            {
+                i2c::ErrorKind::NoAcknowledge(NoAcknowledgeSource::Unknown)
+            }
+            _ => i2c::ErrorKind::Other,
+        }
+    }
+}
+
+impl i2c::ErrorType for I2C {
+    type Error = WiringXError;
+}
+
+/// Sends all operations as one combined transfer.
+///
+/// Only the address the device was set up with can be used,
+/// others return [`InvalidArgument`](WiringXError::InvalidArgument).
+impl I2c for I2C {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [i2c::Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        if address as i32 != self.address() {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        transfer(self.fd(), address as u16, operations)
+    }
+}
+
+impl ErrorType for Spi {
+    type Error = WiringXError;
+}
+
+/// Every call is one message, so chip-select gets toggled by the kernel around each of them.
+impl SpiBus for Spi {
+    #[inline]
+    fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        Spi::read(self, words)
+    }
+
+    #[inline]
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        Spi::write(self, words)
+    }
+
+    #[inline]
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+        Spi::transfer(self, read, write)
+    }
+
+    #[inline]
+    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        self.read_write(words)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Sends all operations as one message, keeping the kernel managed chip-select asserted in between.
+impl SpiDevice for Spi {
+    #[inline]
+    fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Self::Error> {
+        self.message(&spi_transfers(operations, self.speed())?)
+    }
+}
+
+/// Converts the operations into the transfers of one spidev message.
+pub(crate) fn spi_transfers(
+    operations: &mut [Operation<'_, u8>],
+    speed: u32,
+) -> Result<Vec<SpiIocTransfer>, WiringXError> {
+    let mut transfers = Vec::with_capacity(operations.len());
+
+    for operation in operations.iter_mut() {
+        match operation {
+            Operation::Read(words) => {
+                check_len(words.len())?;
+                transfers.push(SpiIocTransfer::read(words, speed));
+            }
+            Operation::Write(words) => {
+                check_len(words.len())?;
+                transfers.push(SpiIocTransfer::write(words, speed));
+            }
+            Operation::Transfer(read, write) => {
+                check_len(read.len().max(write.len()))?;
+                SpiIocTransfer::push_transfer(&mut transfers, read, write, speed);
+            }
+            Operation::TransferInPlace(words) => {
+                check_len(words.len())?;
+                transfers.push(SpiIocTransfer::read_write(words, speed));
+            }
+            Operation::DelayNs(ns) => {
+                let mut micros = ns.div_ceil(1000);
+                while micros > 0 {
+                    let chunk = micros.min(u16::MAX as u32);
+                    transfers.push(SpiIocTransfer::delay(chunk as u16));
+                    micros -= chunk;
+                }
+            }
+        }
+    }
+
+    Ok(transfers)
+}
+
+impl ErrorType for SoftSpi {
+    type Error = WiringXError;
+}
+
+/// Every call asserts the optional chip-select pin around it.
+impl SpiBus for SoftSpi {
+    #[inline]
+    fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        SoftSpi::read(self, words)
+    }
+
+    #[inline]
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        SoftSpi::write(self, words)
+    }
+
+    #[inline]
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+        SoftSpi::transfer(self, read, write)
+    }
+
+    #[inline]
+    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        self.read_write(words)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Keeps the optional chip-select pin asserted for the whole transaction.
+impl SpiDevice for SoftSpi {
+    fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Self::Error> {
+        self.selected(self.speed(), |shifter| {
+            for operation in operations.iter_mut() {
+                match operation {
+                    Operation::Read(words) => shifter.read(words),
+                    Operation::Write(words) => shifter.write(words),
+                    Operation::Transfer(read, write) => shifter.transfer(read, write),
+                    Operation::TransferInPlace(words) => shifter.read_write(words),
+                    Operation::DelayNs(ns) => soft_spi::delay(ns.div_ceil(1000)),
+                }
+            }
+        });
+
+        Ok(())
+    }
+}
+
+impl ErrorType for SpiBusDevice {
+    type Error = WiringXError;
+}
+
+/// Keeps the chip-select pin of the device asserted for the whole transaction.
+impl SpiDevice for SpiBusDevice {
+    fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Self::Error> {
+        SpiBusDevice::transaction(self, |bus| {
+            for operation in operations.iter_mut() {
+                match operation {
+                    Operation::Read(words) => bus.read(words)?,
+                    Operation::Write(words) => bus.write(words)?,
+                    Operation::Transfer(read, write) => bus.transfer(read, write)?,
+                    Operation::TransferInPlace(words) => bus.read_write(words)?,
+                    Operation::DelayNs(ns) => soft_spi::delay(ns.div_ceil(1000)),
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
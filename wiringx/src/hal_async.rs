@@ -0,0 +1,270 @@
+//! [`embedded-hal-async`](embedded_hal_async) trait implementations on the tokio runtime, enabled with the `async` feature.
+//!
+//! Pins wait for edges on the file descriptor signaling them, registered with the tokio reactor.
+//! I2C and SPI transfers block in the kernel, so they run on the blocking threads of tokio
+//! on a duplicate of the file descriptor, which stays valid even if the future is dropped and the device closed meanwhile.
+//! All of them have to be awaited within a tokio runtime with IO enabled.
+
+use std::os::fd::{AsRawFd, BorrowedFd, RawFd};
+
+use embedded_hal_async::{
+    digital::Wait,
+    i2c::{I2c, Operation as I2cOperation},
+    spi::{Operation, SpiBus, SpiDevice},
+};
+use tokio::io::{unix::AsyncFd, Interest};
+
+use crate::{hal::spi_transfers, i2c, spi, Input, IsrMode, Pin, Spi, Value, WiringXError, I2C};
+
+impl Wait for Pin<Input> {
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        wait_for_level(self, Value::High).await
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        wait_for_level(self, Value::Low).await
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        wait_for_edge(self, IsrMode::Rising).await
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        wait_for_edge(self, IsrMode::Falling).await
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        wait_for_edge(self, IsrMode::Both).await
+    }
+}
+
+/// Sends all operations as one combined transfer.
+///
+/// Only the address the device was set up with can be used,
+/// others return [`InvalidArgument`](WiringXError::InvalidArgument).
+impl I2c for I2C {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [I2cOperation<'_>],
+    ) -> Result<(), Self::Error> {
+        if address as i32 != self.address() {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        let mut buffers: Vec<(bool, Vec<u8>)> = operations
+            .iter()
+            .map(|operation| match operation {
+                I2cOperation::Read(buf) => (true, vec![0; buf.len()]),
+                I2cOperation::Write(buf) => (false, buf.to_vec()),
+            })
+            .collect();
+
+        let buffers = spawn_blocking(self.fd(), move |fd| {
+            let mut operations: Vec<I2cOperation<'_>> = buffers
+                .iter_mut()
+                .map(|(read, buf)| {
+                    if *read {
+                        I2cOperation::Read(buf)
+                    } else {
+                        I2cOperation::Write(buf)
+                    }
+                })
+                .collect();
+            i2c::transfer(fd, address as u16, &mut operations)?;
+
+            Ok(buffers)
+        })
+        .await?;
+
+        copy_reads(
+            operations
+                .iter_mut()
+                .filter_map(|operation| match operation {
+                    I2cOperation::Read(buf) => Some(&mut **buf),
+                    I2cOperation::Write(_) => None,
+                }),
+            buffers
+                .into_iter()
+                .filter_map(|(read, buf)| read.then_some(buf)),
+        );
+
+        Ok(())
+    }
+}
+
+/// Every call is one message, so chip-select gets toggled by the kernel around each of them.
+impl SpiBus for Spi {
+    async fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        SpiDevice::transaction(self, &mut [Operation::Read(words)]).await
+    }
+
+    async fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        SpiDevice::transaction(self, &mut [Operation::Write(words)]).await
+    }
+
+    async fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+        SpiDevice::transaction(self, &mut [Operation::Transfer(read, write)]).await
+    }
+
+    async fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        SpiDevice::transaction(self, &mut [Operation::TransferInPlace(words)]).await
+    }
+
+    #[inline]
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Sends all operations as one message, keeping the kernel managed chip-select asserted in between.
+impl SpiDevice for Spi {
+    async fn transaction(
+        &mut self,
+        operations: &mut [Operation<'_, u8>],
+    ) -> Result<(), Self::Error> {
+        let mut buffers: Vec<SpiBuffer> = operations.iter().map(SpiBuffer::new).collect();
+
+        let speed = self.speed();
+        let buffers = spawn_blocking(self.get_fd(), move |fd| {
+            let mut operations: Vec<Operation<'_, u8>> =
+                buffers.iter_mut().map(SpiBuffer::operation).collect();
+            spi::message(fd, &spi_transfers(&mut operations, speed)?)?;
+
+            Ok(buffers)
+        })
+        .await?;
+
+        copy_reads(
+            operations
+                .iter_mut()
+                .filter_map(|operation| match operation {
+                    Operation::Read(buf)
+                    | Operation::Transfer(buf, _)
+                    | Operation::TransferInPlace(buf) => Some(&mut **buf),
+                    Operation::Write(_) | Operation::DelayNs(_) => None,
+                }),
+            buffers.into_iter().filter_map(SpiBuffer::into_read),
+        );
+
+        Ok(())
+    }
+}
+
+/// Owned copy of an SPI operation, for transfers on the blocking threads.
+enum SpiBuffer {
+    Read(Vec<u8>),
+    Write(Vec<u8>),
+    Transfer(Vec<u8>, Vec<u8>),
+    TransferInPlace(Vec<u8>),
+    DelayNs(u32),
+}
+
+impl SpiBuffer {
+    fn new(operation: &Operation<'_, u8>) -> Self {
+        match operation {
+            Operation::Read(buf) => SpiBuffer::Read(vec![0; buf.len()]),
+            Operation::Write(buf) => SpiBuffer::Write(buf.to_vec()),
+            Operation::Transfer(read, write) => {
+                SpiBuffer::Transfer(vec![0; read.len()], write.to_vec())
+            }
+            Operation::TransferInPlace(buf) => SpiBuffer::TransferInPlace(buf.to_vec()),
+            Operation::DelayNs(ns) => SpiBuffer::DelayNs(*ns),
+        }
+    }
+
+    fn operation(&mut self) -> Operation<'_, u8> {
+        match self {
+            SpiBuffer::Read(buf) => Operation::Read(buf),
+            SpiBuffer::Write(buf) => Operation::Write(buf),
+            SpiBuffer::Transfer(read, write) => Operation::Transfer(read, write),
+            SpiBuffer::TransferInPlace(buf) => Operation::TransferInPlace(buf),
+            SpiBuffer::DelayNs(ns) => Operation::DelayNs(*ns),
+        }
+    }
+
+    /// Returns the data read, none for operations not reading.
+    fn into_read(self) -> Option<Vec<u8>> {
+        match self {
+            SpiBuffer::Read(buf)
+            | SpiBuffer::Transfer(buf, _)
+            | SpiBuffer::TransferInPlace(buf) => Some(buf),
+            SpiBuffer::Write(_) | SpiBuffer::DelayNs(_) => None,
+        }
+    }
+}
+
+/// Copies the data read on the blocking threads back into the buffers of the operations.
+fn copy_reads<'a>(
+    targets: impl Iterator<Item = &'a mut [u8]>,
+    sources: impl Iterator<Item = Vec<u8>>,
+) {
+    for (target, source) in targets.zip(sources) {
+        target.copy_from_slice(&source);
+    }
+}
+
+/// Runs the blocking function with a duplicate of the file descriptor on the blocking threads of tokio.
+async fn spawn_blocking<T, F>(fd: RawFd, f: F) -> Result<T, WiringXError>
+where
+    T: Send + 'static,
+    F: FnOnce(RawFd) -> Result<T, WiringXError> + Send + 'static,
+{
+    let fd = unsafe { BorrowedFd::borrow_raw(fd) }
+        .try_clone_to_owned()
+        .map_err(WiringXError::Io)?;
+
+    tokio::task::spawn_blocking(move || f(fd.as_raw_fd()))
+        .await
+        .map_err(|_| WiringXError::Other("The blocking transfer panicked.".to_string()))?
+}
+
+/// Waits until the pin has the level, returning right away if it has it already.
+async fn wait_for_level(pin: &Pin<Input>, level: Value) -> Result<(), WiringXError> {
+    let (fd, interest) = interrupt_fd(pin, IsrMode::Both)?;
+
+    loop {
+        // Clearing the pending edge before reading the level keeps a change in between from getting lost.
+        pin.clear_interrupt();
+        if pin.read() == level {
+            return Ok(());
+        }
+
+        fd.ready(interest)
+            .await
+            .map_err(WiringXError::Io)?
+            .clear_ready();
+    }
+}
+
+/// Waits for the next edge of the mode.
+async fn wait_for_edge(pin: &Pin<Input>, mode: IsrMode) -> Result<(), WiringXError> {
+    let (fd, interest) = interrupt_fd(pin, mode)?;
+
+    pin.clear_interrupt();
+    fd.ready(interest)
+        .await
+        .map_err(WiringXError::Io)?
+        .clear_ready();
+
+    Ok(())
+}
+
+/// Sets the interrupt mode of the pin and registers the file descriptor signaling its edges,
+/// along with the readiness they are signaled with.
+fn interrupt_fd(
+    pin: &Pin<Input>,
+    mode: IsrMode,
+) -> Result<(AsyncFd<RawFd>, Interest), WiringXError> {
+    let (fd, priority) = pin.interrupt_fd(mode)?;
+    let interest = if priority {
+        Interest::PRIORITY
+    } else {
+        Interest::READABLE
+    };
+
+    Ok((
+        AsyncFd::with_interest(fd, interest).map_err(WiringXError::Io)?,
+        interest,
+    ))
+}
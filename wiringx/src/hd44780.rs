@@ -0,0 +1,330 @@
+//! HD44780 compatible character LCDs.
+
+use std::{fmt, thread, time::Duration};
+
+use crate::{DigitalOutput, Output, Pin, Value, WiringXError, I2C};
+
+const CLEAR_DISPLAY: u8 = 0x01;
+const RETURN_HOME: u8 = 0x02;
+const ENTRY_MODE_SET: u8 = 0x04;
+const DISPLAY_CONTROL: u8 = 0x08;
+const CURSOR_SHIFT: u8 = 0x10;
+const FUNCTION_SET: u8 = 0x20;
+const SET_CGRAM_ADDRESS: u8 = 0x40;
+const SET_DDRAM_ADDRESS: u8 = 0x80;
+
+const ENTRY_LEFT_TO_RIGHT: u8 = 0x02;
+const DISPLAY_ON: u8 = 0x04;
+const CURSOR_ON: u8 = 0x02;
+const BLINK_ON: u8 = 0x01;
+const SHIFT_DISPLAY: u8 = 0x08;
+const SHIFT_RIGHT: u8 = 0x04;
+const TWO_LINES: u8 = 0x08;
+
+/// Pins of the PCF8574 on the common I2C backpacks.
+const BACKPACK_RS: u8 = 0x01;
+const BACKPACK_ENABLE: u8 = 0x04;
+const BACKPACK_BACKLIGHT: u8 = 0x08;
+
+/// A character LCD with an HD44780 compatible controller, like the common 16x2 and 20x4 modules.
+///
+/// Connected either with four data lines in parallel or through a PCF8574 I2C backpack.
+/// The parallel lines can be any [`DigitalOutput`], native [`Pin`]s or the pins of an I/O expander.
+/// The read/write line has to be tied to ground for parallel wiring, the display is never read.
+///
+/// Implements [`fmt::Write`], so text can be written with [`write!`],
+/// where `\n` continues at the start of the next row.
+///
+/// Example with an I2C backpack at address `0x27`:
+/// ```no_run
+/// use wiringx::{Hd44780, Platform, WiringX};
+///
+/// use std::fmt::Write;
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let i2c = wiringx.setup_i2c("/dev/i2c-1".into(), 0x27).unwrap();
+///
+/// let mut lcd = Hd44780::new_i2c(i2c, 16, 2).unwrap();
+///
+/// write!(lcd, "Hello,\nworld!").unwrap();
+/// ```
+#[derive(Debug)]
+pub struct Hd44780<P = Pin<Output>> {
+    bus: LcdBus<P>,
+    columns: u8,
+    rows: u8,
+    row: u8,
+    display_control: u8,
+}
+
+/// The wiring of the display.
+#[derive(Debug)]
+enum LcdBus<P> {
+    Gpio { rs: P, enable: P, data: [P; 4] },
+    I2c { i2c: I2C, backlight: bool },
+}
+
+impl Hd44780 {
+    /// Initializes a display behind a PCF8574 I2C backpack, with the backlight switched on.
+    pub fn new_i2c(i2c: I2C, columns: u8, rows: u8) -> Result<Self, WiringXError> {
+        Self::init(
+            LcdBus::I2c {
+                i2c,
+                backlight: true,
+            },
+            columns,
+            rows,
+        )
+    }
+}
+
+impl<P: DigitalOutput> Hd44780<P> {
+    /// Initializes a display wired in parallel, with the data pins `D4` to `D7` in that order.
+    pub fn new_gpio(
+        rs: P,
+        enable: P,
+        data: [P; 4],
+        columns: u8,
+        rows: u8,
+    ) -> Result<Self, WiringXError> {
+        Self::init(LcdBus::Gpio { rs, enable, data }, columns, rows)
+    }
+
+    fn init(bus: LcdBus<P>, columns: u8, rows: u8) -> Result<Self, WiringXError> {
+        if columns == 0 || columns > 40 || rows == 0 || rows > 4 {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        let mut lcd = Self {
+            bus,
+            columns,
+            rows,
+            row: 0,
+            display_control: DISPLAY_ON,
+        };
+
+        // The controller may be in 8 bit or in the middle of a 4 bit transfer,
+        // so it is forced into 8 bit mode first, then switched to 4 bit mode.
+        thread::sleep(Duration::from_millis(50));
+        lcd.write_nibble(0x03, false)?;
+        thread::sleep(Duration::from_micros(4500));
+        lcd.write_nibble(0x03, false)?;
+        thread::sleep(Duration::from_micros(4500));
+        lcd.write_nibble(0x03, false)?;
+        thread::sleep(Duration::from_micros(150));
+        lcd.write_nibble(0x02, false)?;
+
+        let lines = if rows > 1 { TWO_LINES } else { 0 };
+        lcd.command(FUNCTION_SET | lines)?;
+        lcd.command(DISPLAY_CONTROL | lcd.display_control)?;
+        lcd.clear()?;
+        lcd.command(ENTRY_MODE_SET | ENTRY_LEFT_TO_RIGHT)?;
+
+        Ok(lcd)
+    }
+
+    /// Returns the number of columns of the display.
+    #[inline]
+    pub fn columns(&self) -> u8 {
+        self.columns
+    }
+
+    /// Returns the number of rows of the display.
+    #[inline]
+    pub fn rows(&self) -> u8 {
+        self.rows
+    }
+
+    /// Clears the display and moves the cursor to the top left.
+    pub fn clear(&mut self) -> Result<(), WiringXError> {
+        self.command(CLEAR_DISPLAY)?;
+        thread::sleep(Duration::from_millis(2));
+
+        self.row = 0;
+
+        Ok(())
+    }
+
+    /// Moves the cursor to the top left and undoes scrolling.
+    pub fn home(&mut self) -> Result<(), WiringXError> {
+        self.command(RETURN_HOME)?;
+        thread::sleep(Duration::from_millis(2));
+
+        self.row = 0;
+
+        Ok(())
+    }
+
+    /// Moves the cursor to the column and row, both starting at `0`.
+    pub fn set_cursor(&mut self, column: u8, row: u8) -> Result<(), WiringXError> {
+        if column >= self.columns || row >= self.rows {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        let offsets = [0x00, 0x40, self.columns, 0x40 + self.columns];
+        self.command(SET_DDRAM_ADDRESS | (offsets[row as usize] + column))?;
+
+        self.row = row;
+
+        Ok(())
+    }
+
+    /// Switches the display on or off, keeping its content.
+    #[inline]
+    pub fn set_display(&mut self, on: bool) -> Result<(), WiringXError> {
+        self.set_display_control(DISPLAY_ON, on)
+    }
+
+    /// Shows or hides the underline cursor.
+    #[inline]
+    pub fn set_cursor_visible(&mut self, visible: bool) -> Result<(), WiringXError> {
+        self.set_display_control(CURSOR_ON, visible)
+    }
+
+    /// Enables or disables the blinking block cursor.
+    #[inline]
+    pub fn set_blink(&mut self, blink: bool) -> Result<(), WiringXError> {
+        self.set_display_control(BLINK_ON, blink)
+    }
+
+    /// Switches the backlight, only supported by I2C backpacks.
+    pub fn set_backlight(&mut self, on: bool) -> Result<(), WiringXError> {
+        match &mut self.bus {
+            LcdBus::I2c { i2c, backlight } => {
+                *backlight = on;
+                i2c.write(if on { BACKPACK_BACKLIGHT } else { 0 } as i32)?;
+
+                Ok(())
+            }
+            LcdBus::Gpio { .. } => Err(WiringXError::Unsupported),
+        }
+    }
+
+    /// Shifts the whole content one column to the left.
+    #[inline]
+    pub fn scroll_left(&mut self) -> Result<(), WiringXError> {
+        self.command(CURSOR_SHIFT | SHIFT_DISPLAY)
+    }
+
+    /// Shifts the whole content one column to the right.
+    #[inline]
+    pub fn scroll_right(&mut self) -> Result<(), WiringXError> {
+        self.command(CURSOR_SHIFT | SHIFT_DISPLAY | SHIFT_RIGHT)
+    }
+
+    /// Defines one of the eight custom characters, `0` to `7`, from its rows of five pixels from top to bottom.
+    ///
+    /// The character gets shown by writing its location with [`write_byte`](Self::write_byte).
+    /// Moves the cursor to the top left.
+    pub fn create_char(&mut self, location: u8, bitmap: [u8; 8]) -> Result<(), WiringXError> {
+        if location > 7 {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        self.command(SET_CGRAM_ADDRESS | location << 3)?;
+        for row in bitmap {
+            self.write_byte(row & 0x1F)?;
+        }
+
+        self.set_cursor(0, 0)
+    }
+
+    /// Writes a raw character code at the cursor, for example a custom character.
+    #[inline]
+    pub fn write_byte(&mut self, byte: u8) -> Result<(), WiringXError> {
+        self.send(byte, true)
+    }
+
+    /// Writes the text at the cursor, moving to the start of the next row on `\n`.
+    ///
+    /// Characters the display does not have in its ROM are shown as `?`.
+    pub fn write_str(&mut self, text: &str) -> Result<(), WiringXError> {
+        for character in text.chars() {
+            if character == '\n' {
+                let row = (self.row + 1) % self.rows;
+                self.set_cursor(0, row)?;
+            } else if character.is_ascii() && !character.is_ascii_control() {
+                self.write_byte(character as u8)?;
+            } else {
+                self.write_byte(b'?')?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn set_display_control(&mut self, flag: u8, on: bool) -> Result<(), WiringXError> {
+        if on {
+            self.display_control |= flag;
+        } else {
+            self.display_control &= !flag;
+        }
+
+        self.command(DISPLAY_CONTROL | self.display_control)
+    }
+
+    #[inline]
+    fn command(&mut self, command: u8) -> Result<(), WiringXError> {
+        self.send(command, false)
+    }
+
+    fn send(&mut self, byte: u8, data: bool) -> Result<(), WiringXError> {
+        self.write_nibble(byte >> 4, data)?;
+        self.write_nibble(byte & 0x0F, data)?;
+
+        // Most instructions take 37 µs to execute.
+        thread::sleep(Duration::from_micros(50));
+
+        Ok(())
+    }
+
+    /// Puts four bits on the data lines and latches them with a pulse on the enable line.
+    fn write_nibble(&mut self, nibble: u8, data: bool) -> Result<(), WiringXError> {
+        match &mut self.bus {
+            LcdBus::Gpio {
+                rs,
+                enable,
+                data: lines,
+            } => {
+                rs.write(level(data))?;
+                for (i, line) in lines.iter_mut().enumerate() {
+                    line.write(level(nibble >> i & 1 == 1))?;
+                }
+
+                enable.write(Value::High)?;
+                thread::sleep(Duration::from_micros(1));
+                enable.write(Value::Low)?;
+            }
+            LcdBus::I2c { i2c, backlight } => {
+                let mut byte = nibble << 4;
+                if data {
+                    byte |= BACKPACK_RS;
+                }
+                if *backlight {
+                    byte |= BACKPACK_BACKLIGHT;
+                }
+
+                i2c.write((byte | BACKPACK_ENABLE) as i32)?;
+                i2c.write(byte as i32)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<P: DigitalOutput> fmt::Write for Hd44780<P> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        Hd44780::write_str(self, s).map_err(|_| fmt::Error)
+    }
+}
+
+#[inline]
+fn level(high: bool) -> Value {
+    if high {
+        Value::High
+    } else {
+        Value::Low
+    }
+}
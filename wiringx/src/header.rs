@@ -0,0 +1,415 @@
+//! Layouts of the pin headers of the boards.
+
+use crate::Platform;
+
+/// A pin header of a board, see [`Platform::header_layout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Header {
+    /// Name of the header as printed on the board, like `J3`.
+    pub name: &'static str,
+    /// The pins, ordered by their position.
+    pub pins: &'static [HeaderPin],
+}
+
+impl Header {
+    /// Returns the pin at the physical position, starting at `1`.
+    pub fn pin(&self, position: u8) -> Option<&HeaderPin> {
+        self.pins.iter().find(|pin| pin.position == position)
+    }
+
+    /// Returns the wiringX number of the GPIO at the physical position.
+    pub fn gpio_at(&self, position: u8) -> Option<i32> {
+        match self.pin(position)?.kind {
+            HeaderPinKind::Gpio { pin, .. } => Some(pin),
+            _ => None,
+        }
+    }
+
+    /// Returns the physical position of the GPIO with the wiringX number.
+    pub fn position_of(&self, gpio_pin: i32) -> Option<u8> {
+        self.pins
+            .iter()
+            .find(|pin| matches!(pin.kind, HeaderPinKind::Gpio { pin, .. } if pin == gpio_pin))
+            .map(|pin| pin.position)
+    }
+}
+
+/// A pin of a [`Header`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct HeaderPin {
+    /// Physical position on the header, starting at `1`.
+    pub position: u8,
+    /// What the pin is connected to.
+    pub kind: HeaderPinKind,
+}
+
+/// What a [`HeaderPin`] is connected to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum HeaderPinKind {
+    /// A GPIO of the SoC
+    Gpio {
+        /// wiringX number of the pin
+        pin: i32,
+        /// Name of the GPIO of the SoC, like `XGPIOA[28]`
+        pad: &'static str,
+    },
+    /// A supply, with its label like `3V3` or `VSYS`
+    Power(&'static str),
+    /// Ground
+    Ground,
+    /// Anything else, like `RUN` or the audio pins, with its label
+    Other(&'static str),
+}
+
+impl Platform {
+    /// Returns the pin headers of the board, empty for the platforms without layout data yet.
+    ///
+    /// Data is available for the Milk-V Duo family, the Raspberry Pis and the Orange Pi Zero 3.
+    /// The second header of the Milk-V Duo S is numbered in the order of the wiringX pins.
+    pub fn header_layout(&self) -> &'static [Header] {
+        match self {
+            Self::MilkVDuo => &[Header {
+                name: "J1",
+                pins: &MILKV_DUO,
+            }],
+            Self::MilkVDuo256M => &[Header {
+                name: "J1",
+                pins: &MILKV_DUO256M,
+            }],
+            Self::MilkVDuoS => &[
+                Header {
+                    name: "J3",
+                    pins: &MILKV_DUOS_J3,
+                },
+                Header {
+                    name: "J4",
+                    pins: &MILKV_DUOS_J4,
+                },
+            ],
+            Self::RaspberryPi1b1 => &[Header {
+                name: "P1",
+                pins: &RASPBERRYPI_REV1,
+            }],
+            Self::RaspberryPi1b2 => &[Header {
+                name: "P1",
+                pins: &RASPBERRYPI_REV2,
+            }],
+            Self::RaspberryPi1bPlus
+            | Self::RaspberryPi2
+            | Self::RaspberryPi3
+            | Self::RaspberryPi4
+            | Self::RaspberryPiZero => &[Header {
+                name: "J8",
+                pins: &RASPBERRYPI_40,
+            }],
+            Self::OrangePiZero3 => &[Header {
+                name: "26-pin",
+                pins: &ORANGEPI_ZERO3,
+            }],
+            _ => &[],
+        }
+    }
+}
+
+const fn gpio(position: u8, pin: i32, pad: &'static str) -> HeaderPin {
+    HeaderPin {
+        position,
+        kind: HeaderPinKind::Gpio { pin, pad },
+    }
+}
+
+const fn power(position: u8, label: &'static str) -> HeaderPin {
+    HeaderPin {
+        position,
+        kind: HeaderPinKind::Power(label),
+    }
+}
+
+const fn ground(position: u8) -> HeaderPin {
+    HeaderPin {
+        position,
+        kind: HeaderPinKind::Ground,
+    }
+}
+
+const fn other(position: u8, label: &'static str) -> HeaderPin {
+    HeaderPin {
+        position,
+        kind: HeaderPinKind::Other(label),
+    }
+}
+
+const MILKV_DUO: [HeaderPin; 40] = [
+    gpio(1, 0, "XGPIOA[28]"),
+    gpio(2, 1, "XGPIOA[29]"),
+    ground(3),
+    gpio(4, 2, "PWR_GPIO[26]"),
+    gpio(5, 3, "PWR_GPIO[25]"),
+    gpio(6, 4, "PWR_GPIO[19]"),
+    gpio(7, 5, "PWR_GPIO[20]"),
+    ground(8),
+    gpio(9, 6, "PWR_GPIO[23]"),
+    gpio(10, 7, "PWR_GPIO[22]"),
+    gpio(11, 8, "PWR_GPIO[21]"),
+    gpio(12, 9, "PWR_GPIO[18]"),
+    ground(13),
+    gpio(14, 10, "XGPIOC[9]"),
+    gpio(15, 11, "XGPIOC[10]"),
+    gpio(16, 12, "XGPIOA[16]"),
+    gpio(17, 13, "XGPIOA[17]"),
+    ground(18),
+    gpio(19, 14, "XGPIOA[14]"),
+    gpio(20, 15, "XGPIOA[15]"),
+    gpio(21, 16, "XGPIOA[23]"),
+    gpio(22, 17, "XGPIOA[24]"),
+    ground(23),
+    gpio(24, 18, "XGPIOA[22]"),
+    gpio(25, 19, "XGPIOA[25]"),
+    gpio(26, 20, "XGPIOA[27]"),
+    gpio(27, 21, "XGPIOA[26]"),
+    ground(28),
+    gpio(29, 22, "PWR_GPIO[4]"),
+    other(30, "RUN"),
+    gpio(31, 26, "XGPIOB[3]"),
+    gpio(32, 27, "XGPIOB[6]"),
+    ground(33),
+    other(34, "NC"),
+    other(35, "NC"),
+    power(36, "3V3"),
+    other(37, "3V3_EN"),
+    ground(38),
+    power(39, "VSYS"),
+    power(40, "VBUS"),
+];
+
+const MILKV_DUO256M: [HeaderPin; 40] = [
+    gpio(1, 0, "XGPIOA[28]"),
+    gpio(2, 1, "XGPIOA[29]"),
+    ground(3),
+    gpio(4, 2, "XGPIOA[19]"),
+    gpio(5, 3, "XGPIOA[18]"),
+    gpio(6, 4, "PWR_GPIO[19]"),
+    gpio(7, 5, "PWR_GPIO[20]"),
+    ground(8),
+    gpio(9, 6, "PWR_GPIO[23]"),
+    gpio(10, 7, "PWR_GPIO[22]"),
+    gpio(11, 8, "PWR_GPIO[21]"),
+    gpio(12, 9, "PWR_GPIO[18]"),
+    ground(13),
+    gpio(14, 10, "XGPIOC[14]"),
+    gpio(15, 11, "XGPIOC[15]"),
+    gpio(16, 12, "XGPIOA[16]"),
+    gpio(17, 13, "XGPIOA[17]"),
+    ground(18),
+    gpio(19, 14, "XGPIOA[14]"),
+    gpio(20, 15, "XGPIOA[15]"),
+    gpio(21, 16, "XGPIOA[23]"),
+    gpio(22, 17, "XGPIOA[24]"),
+    ground(23),
+    gpio(24, 18, "XGPIOA[22]"),
+    gpio(25, 19, "XGPIOA[25]"),
+    gpio(26, 20, "XGPIOA[27]"),
+    gpio(27, 21, "XGPIOA[26]"),
+    ground(28),
+    gpio(29, 22, "PWR_GPIO[4]"),
+    other(30, "RUN"),
+    gpio(31, 26, "XGPIOB[3]"),
+    gpio(32, 27, "XGPIOB[6]"),
+    ground(33),
+    other(34, "NC"),
+    other(35, "NC"),
+    power(36, "3V3"),
+    other(37, "3V3_EN"),
+    ground(38),
+    power(39, "VSYS"),
+    power(40, "VBUS"),
+];
+
+const MILKV_DUOS_J3: [HeaderPin; 26] = [
+    power(1, "3V3"),
+    power(2, "VSYS"),
+    gpio(3, 3, "XGPIOB[20]"),
+    power(4, "VSYS"),
+    gpio(5, 5, "XGPIOB[21]"),
+    ground(6),
+    gpio(7, 7, "XGPIOB[18]"),
+    gpio(8, 8, "XGPIOA[16]"),
+    gpio(9, 9, "XGPIOB[17]"),
+    gpio(10, 10, "XGPIOA[17]"),
+    gpio(11, 11, "XGPIOB[11]"),
+    gpio(12, 12, "XGPIOB[19]"),
+    gpio(13, 13, "XGPIOB[12]"),
+    ground(14),
+    gpio(15, 15, "XGPIOB[22]"),
+    gpio(16, 16, "XGPIOA[20]"),
+    other(17, "NC"),
+    gpio(18, 18, "XGPIOA[19]"),
+    gpio(19, 19, "XGPIOB[13]"),
+    ground(20),
+    gpio(21, 21, "XGPIOB[14]"),
+    gpio(22, 22, "XGPIOA[18]"),
+    gpio(23, 23, "XGPIOB[15]"),
+    gpio(24, 24, "XGPIOB[16]"),
+    ground(25),
+    gpio(26, 26, "XGPIOA[28]"),
+];
+
+const MILKV_DUOS_J4: [HeaderPin; 25] = [
+    gpio(1, 27, "XGPIOC[13]"),
+    gpio(2, 28, "XGPIOC[15]"),
+    gpio(3, 29, "XGPIOC[12]"),
+    gpio(4, 30, "XGPIOC[14]"),
+    ground(5),
+    ground(6),
+    gpio(7, 33, "XGPIOC[17]"),
+    gpio(8, 34, "XGPIOC[21]"),
+    gpio(9, 35, "XGPIOC[16]"),
+    gpio(10, 36, "XGPIOC[20]"),
+    ground(11),
+    ground(12),
+    gpio(13, 39, "XGPIOC[19]"),
+    gpio(14, 40, "PWR_GPIO[0]"),
+    gpio(15, 41, "XGPIOC[18]"),
+    gpio(16, 42, "PWR_GPIO[1]"),
+    power(17, "3V3"),
+    gpio(18, 44, "PWR_GPIO[2]"),
+    other(19, "AUDIO_IN_L"),
+    gpio(20, 46, "XGPIOB[3]"),
+    other(21, "AUDIO_IN_R"),
+    gpio(22, 48, "XGPIOB[2]"),
+    other(23, "AUDIO_OUT_L"),
+    gpio(24, 50, "XGPIOB[1]"),
+    other(25, "AUDIO_OUT_R"),
+];
+
+const RASPBERRYPI_REV1: [HeaderPin; 26] = [
+    power(1, "3V3"),
+    power(2, "5V"),
+    gpio(3, 8, "GPIO0"),
+    power(4, "5V"),
+    gpio(5, 9, "GPIO1"),
+    ground(6),
+    gpio(7, 7, "GPIO4"),
+    gpio(8, 15, "GPIO14"),
+    ground(9),
+    gpio(10, 16, "GPIO15"),
+    gpio(11, 0, "GPIO17"),
+    gpio(12, 1, "GPIO18"),
+    gpio(13, 2, "GPIO21"),
+    ground(14),
+    gpio(15, 3, "GPIO22"),
+    gpio(16, 4, "GPIO23"),
+    power(17, "3V3"),
+    gpio(18, 5, "GPIO24"),
+    gpio(19, 12, "GPIO10"),
+    ground(20),
+    gpio(21, 13, "GPIO9"),
+    gpio(22, 6, "GPIO25"),
+    gpio(23, 14, "GPIO11"),
+    gpio(24, 10, "GPIO8"),
+    ground(25),
+    gpio(26, 11, "GPIO7"),
+];
+
+const RASPBERRYPI_REV2: [HeaderPin; 26] = [
+    power(1, "3V3"),
+    power(2, "5V"),
+    gpio(3, 8, "GPIO2"),
+    power(4, "5V"),
+    gpio(5, 9, "GPIO3"),
+    ground(6),
+    gpio(7, 7, "GPIO4"),
+    gpio(8, 15, "GPIO14"),
+    ground(9),
+    gpio(10, 16, "GPIO15"),
+    gpio(11, 0, "GPIO17"),
+    gpio(12, 1, "GPIO18"),
+    gpio(13, 2, "GPIO27"),
+    ground(14),
+    gpio(15, 3, "GPIO22"),
+    gpio(16, 4, "GPIO23"),
+    power(17, "3V3"),
+    gpio(18, 5, "GPIO24"),
+    gpio(19, 12, "GPIO10"),
+    ground(20),
+    gpio(21, 13, "GPIO9"),
+    gpio(22, 6, "GPIO25"),
+    gpio(23, 14, "GPIO11"),
+    gpio(24, 10, "GPIO8"),
+    ground(25),
+    gpio(26, 11, "GPIO7"),
+];
+
+const RASPBERRYPI_40: [HeaderPin; 40] = [
+    power(1, "3V3"),
+    power(2, "5V"),
+    gpio(3, 8, "GPIO2"),
+    power(4, "5V"),
+    gpio(5, 9, "GPIO3"),
+    ground(6),
+    gpio(7, 7, "GPIO4"),
+    gpio(8, 15, "GPIO14"),
+    ground(9),
+    gpio(10, 16, "GPIO15"),
+    gpio(11, 0, "GPIO17"),
+    gpio(12, 1, "GPIO18"),
+    gpio(13, 2, "GPIO27"),
+    ground(14),
+    gpio(15, 3, "GPIO22"),
+    gpio(16, 4, "GPIO23"),
+    power(17, "3V3"),
+    gpio(18, 5, "GPIO24"),
+    gpio(19, 12, "GPIO10"),
+    ground(20),
+    gpio(21, 13, "GPIO9"),
+    gpio(22, 6, "GPIO25"),
+    gpio(23, 14, "GPIO11"),
+    gpio(24, 10, "GPIO8"),
+    ground(25),
+    gpio(26, 11, "GPIO7"),
+    gpio(27, 30, "GPIO0"),
+    gpio(28, 31, "GPIO1"),
+    gpio(29, 21, "GPIO5"),
+    ground(30),
+    gpio(31, 22, "GPIO6"),
+    gpio(32, 26, "GPIO12"),
+    gpio(33, 23, "GPIO13"),
+    ground(34),
+    gpio(35, 24, "GPIO19"),
+    gpio(36, 27, "GPIO16"),
+    gpio(37, 25, "GPIO26"),
+    gpio(38, 28, "GPIO20"),
+    ground(39),
+    gpio(40, 29, "GPIO21"),
+];
+const ORANGEPI_ZERO3: [HeaderPin; 26] = [
+    power(1, "3V3"),
+    power(2, "5V"),
+    gpio(3, 0, "PH5"),
+    power(4, "5V"),
+    gpio(5, 1, "PH4"),
+    ground(6),
+    gpio(7, 2, "PC9"),
+    gpio(8, 3, "PH2"),
+    ground(9),
+    gpio(10, 4, "PH3"),
+    gpio(11, 5, "PC6"),
+    gpio(12, 6, "PC11"),
+    gpio(13, 7, "PC5"),
+    ground(14),
+    gpio(15, 8, "PC8"),
+    gpio(16, 9, "PC15"),
+    power(17, "3V3"),
+    gpio(18, 10, "PC14"),
+    gpio(19, 11, "PH7"),
+    ground(20),
+    gpio(21, 12, "PH8"),
+    gpio(22, 13, "PC7"),
+    gpio(23, 14, "PH6"),
+    gpio(24, 15, "PH9"),
+    ground(25),
+    gpio(26, 16, "PC10"),
+];
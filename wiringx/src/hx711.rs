@@ -0,0 +1,232 @@
+//! HX711 load cell amplifiers.
+
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{gpio::wait_until, Input, Output, Pin, Value, WiringXError};
+
+/// Time the clock is held high and low per bit, well below the 60 µs that power the chip down.
+const CLOCK_HALF_PERIOD: Duration = Duration::from_micros(1);
+/// Longest time to wait for a conversion, the chip converts at 10 or 80 samples per second.
+const READY_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// An HX711 24 bit ADC for load cells, bit-banged on a data and a clock pin.
+///
+/// Weights get calculated from the raw counts by subtracting the offset measured with [`tare`](Self::tare)
+/// and dividing by the scale set with [`set_scale`](Self::set_scale), in counts per gram.
+///
+/// Example calibrating with a known 500 gram weight:
+/// ```no_run
+/// use wiringx::{Hx711, Hx711Gain, Input, Output, Platform, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+///
+/// let mut scale = Hx711::new(
+///     wiringx.gpio_pin::<Input>(14).unwrap(),
+///     wiringx.gpio_pin::<Output>(15).unwrap(),
+///     Hx711Gain::A128,
+/// )
+/// .unwrap();
+///
+/// scale.tare(10).unwrap();
+/// // Place the weight now.
+/// let counts = scale.read_average(10).unwrap() - scale.offset();
+/// scale.set_scale(counts as f32 / 500.0).unwrap();
+///
+/// println!("{:.1} g", scale.read_weight(5).unwrap());
+/// ```
+#[derive(Debug)]
+pub struct Hx711 {
+    data: Pin<Input>,
+    clock: Pin<Output>,
+    gain: Hx711Gain,
+    offset: i32,
+    scale: f32,
+}
+
+impl Hx711 {
+    /// Takes ownership of the pins and selects the channel and gain for the following conversions.
+    pub fn new(
+        data: Pin<Input>,
+        mut clock: Pin<Output>,
+        gain: Hx711Gain,
+    ) -> Result<Self, WiringXError> {
+        clock.write(Value::Low);
+
+        let mut hx711 = Self {
+            data,
+            clock,
+            gain,
+            offset: 0,
+            scale: 1.0,
+        };
+
+        // The gain only applies from the conversion after the one it was selected with.
+        hx711.read_raw()?;
+
+        Ok(hx711)
+    }
+
+    /// Returns true if a conversion is ready to be read.
+    #[inline]
+    pub fn is_ready(&self) -> bool {
+        self.data.read() == Value::Low
+    }
+
+    /// Selects the channel and gain, discarding one conversion so the following ones use it.
+    pub fn set_gain(&mut self, gain: Hx711Gain) -> Result<(), WiringXError> {
+        self.gain = gain;
+        self.read_raw()?;
+
+        Ok(())
+    }
+
+    /// Returns the selected channel and gain.
+    #[inline]
+    pub fn gain(&self) -> Hx711Gain {
+        self.gain
+    }
+
+    /// Waits for the next conversion and returns its raw counts.
+    ///
+    /// The bits are clocked by busy waiting, as the chip powers down if the clock stays high for 60 µs.
+    pub fn read_raw(&mut self) -> Result<i32, WiringXError> {
+        let deadline = Instant::now() + READY_TIMEOUT;
+        while !self.is_ready() {
+            if Instant::now() > deadline {
+                return Err(WiringXError::TimedOut);
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        let mut value = 0u32;
+        let mut edge = Instant::now();
+
+        for _ in 0..24 {
+            self.clock.write(Value::High);
+            edge += CLOCK_HALF_PERIOD;
+            wait_until(edge);
+
+            value = value << 1 | (self.data.read() == Value::High) as u32;
+
+            self.clock.write(Value::Low);
+            edge += CLOCK_HALF_PERIOD;
+            wait_until(edge);
+        }
+
+        // Extra pulses select the channel and gain of the next conversion.
+        for _ in 0..self.gain.pulses() {
+            self.clock.write(Value::High);
+            edge += CLOCK_HALF_PERIOD;
+            wait_until(edge);
+
+            self.clock.write(Value::Low);
+            edge += CLOCK_HALF_PERIOD;
+            wait_until(edge);
+        }
+
+        // Sign extends the 24 bit two's complement value.
+        Ok(((value << 8) as i32) >> 8)
+    }
+
+    /// Returns the average raw counts of the number of conversions.
+    pub fn read_average(&mut self, samples: u32) -> Result<i32, WiringXError> {
+        if samples == 0 {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        let mut sum = 0i64;
+        for _ in 0..samples {
+            sum += self.read_raw()? as i64;
+        }
+
+        Ok((sum / samples as i64) as i32)
+    }
+
+    /// Sets the current load as zero, averaged from the number of conversions.
+    pub fn tare(&mut self, samples: u32) -> Result<(), WiringXError> {
+        self.offset = self.read_average(samples)?;
+
+        Ok(())
+    }
+
+    /// Sets the raw counts of the empty load cell.
+    #[inline]
+    pub fn set_offset(&mut self, offset: i32) {
+        self.offset = offset;
+    }
+
+    /// Returns the raw counts of the empty load cell.
+    #[inline]
+    pub fn offset(&self) -> i32 {
+        self.offset
+    }
+
+    /// Sets the calibration factor in raw counts per gram.
+    pub fn set_scale(&mut self, scale: f32) -> Result<(), WiringXError> {
+        if scale == 0.0 || !scale.is_finite() {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        self.scale = scale;
+
+        Ok(())
+    }
+
+    /// Returns the calibration factor in raw counts per gram.
+    #[inline]
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Returns the weight in grams, averaged from the number of conversions.
+    pub fn read_weight(&mut self, samples: u32) -> Result<f32, WiringXError> {
+        let counts = self.read_average(samples)? - self.offset;
+
+        Ok(counts as f32 / self.scale)
+    }
+
+    /// Powers the chip down by holding the clock high.
+    pub fn power_down(&mut self) {
+        self.clock.write(Value::Low);
+        self.clock.write(Value::High);
+        thread::sleep(Duration::from_micros(60));
+    }
+
+    /// Powers the chip up again, which resets the gain to [`Hx711Gain::A128`] until the next read.
+    pub fn power_up(&mut self) -> Result<(), WiringXError> {
+        self.clock.write(Value::Low);
+
+        if self.gain != Hx711Gain::A128 {
+            self.read_raw()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Input channel and gain of an [`Hx711`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Hx711Gain {
+    /// Channel A with a gain of 128, for ±20 mV
+    #[default]
+    A128,
+    /// Channel A with a gain of 64, for ±40 mV
+    A64,
+    /// Channel B with a gain of 32, for ±80 mV
+    B32,
+}
+
+impl Hx711Gain {
+    /// Returns the clock pulses after the data bits selecting this gain.
+    #[inline]
+    fn pulses(&self) -> u32 {
+        match self {
+            Hx711Gain::A128 => 1,
+            Hx711Gain::B32 => 2,
+            Hx711Gain::A64 => 3,
+        }
+    }
+}
@@ -8,7 +8,36 @@ use wiringx_sys::{
     wiringXI2CWriteReg8,
 };
 
-use crate::{Hand, WiringXError};
+use crate::{Claim, Hand, WiringXError};
+
+#[cfg(feature = "embedded-hal")]
+use embedded_hal::i2c::Operation;
+
+#[cfg(feature = "embedded-hal")]
+const I2C_RDWR: libc::c_ulong = 0x0707;
+#[cfg(feature = "embedded-hal")]
+const I2C_M_RD: u16 = 0x0001;
+/// Most messages the kernel takes in one combined transfer.
+#[cfg(feature = "embedded-hal")]
+const I2C_RDWR_MAX_MSGS: usize = 42;
+
+/// Mirror of the kernel's `struct i2c_msg`.
+#[cfg(feature = "embedded-hal")]
+#[repr(C)]
+struct I2cMsg {
+    addr: u16,
+    flags: u16,
+    len: u16,
+    buf: *mut u8,
+}
+
+/// Mirror of the kernel's `struct i2c_rdwr_ioctl_data`.
+#[cfg(feature = "embedded-hal")]
+#[repr(C)]
+struct I2cRdwrIoctlData {
+    msgs: *mut I2cMsg,
+    nmsgs: u32,
+}
 
 /// An Inter-integrated circuit communication instance.
 ///
@@ -25,12 +54,8 @@ impl I2C {
     pub(super) fn new(
         dev: PathBuf,
         addr: i32,
-        handles: Hand<(PathBuf, i32)>,
+        claim: Claim<(PathBuf, i32)>,
     ) -> Result<Self, WiringXError> {
-        if handles.lock().contains(&(dev.clone(), addr)) {
-            return Err(WiringXError::PinUsed);
-        }
-
         let path_string = CString::new(dev.to_str().ok_or(WiringXError::Other(
             "Path contains illegal symbols.".to_string(),
         ))?)
@@ -42,15 +67,25 @@ impl I2C {
             return Err(WiringXError::Unsupported);
         }
 
-        handles.lock().insert((dev.clone(), addr));
-
         Ok(Self {
             id: (dev, addr),
-            handles,
+            handles: claim.keep(),
             fd: fd_result,
         })
     }
 
+    /// Returns the address of the device.
+    #[inline]
+    pub fn address(&self) -> i32 {
+        self.id.1
+    }
+
+    #[cfg(feature = "embedded-hal")]
+    #[inline]
+    pub(crate) fn fd(&self) -> RawFd {
+        self.fd
+    }
+
     /// Reads one byte of data.
     pub fn read(&self) -> Result<u8, I2CError> {
         let result = unsafe { wiringXI2CRead(self.fd) };
@@ -81,6 +116,23 @@ impl I2C {
         }
     }
 
+    /// Reads as many bytes as fit into the buffer in one transfer.
+    pub fn read_bytes(&self, data: &mut [u8]) -> Result<(), I2CError> {
+        let result =
+            unsafe { libc::read(self.fd, data.as_mut_ptr() as *mut libc::c_void, data.len()) };
+        if result < 0 || result as usize != data.len() {
+            Err(I2CError::Read)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Reads consecutive registers starting at the given one, for devices incrementing the register address.
+    pub fn read_reg_bytes(&self, register: u8, data: &mut [u8]) -> Result<(), I2CError> {
+        self.write_bytes(&[register]).map_err(|_| I2CError::Read)?;
+        self.read_bytes(data)
+    }
+
     /// Writes the address of the register, preparing data writes on the device.
     pub fn write(&self, register: i32) -> Result<(), I2CError> {
         let result = unsafe { wiringXI2CWrite(self.fd, register) };
@@ -101,6 +153,17 @@ impl I2C {
         }
     }
 
+    /// Writes all bytes in one transfer, for devices taking longer messages than single registers.
+    pub fn write_bytes(&self, data: &[u8]) -> Result<(), I2CError> {
+        let result =
+            unsafe { libc::write(self.fd, data.as_ptr() as *const libc::c_void, data.len()) };
+        if result < 0 || result as usize != data.len() {
+            Err(I2CError::Write)
+        } else {
+            Ok(())
+        }
+    }
+
     /// Writes two bytes of data to the given register.
     pub fn write_reg16(&self, register: i32, value: u16) -> Result<(), I2CError> {
         let result = unsafe { wiringXI2CWriteReg8(self.fd, register, value as i32) };
@@ -118,6 +181,51 @@ impl Drop for I2C {
     }
 }
 
+/// Executes the operations as one combined transfer with repeated starts in between.
+#[cfg(feature = "embedded-hal")]
+pub(crate) fn transfer(
+    fd: RawFd,
+    address: u16,
+    operations: &mut [Operation<'_>],
+) -> Result<(), WiringXError> {
+    if operations.is_empty() {
+        return Ok(());
+    }
+
+    if operations.len() > I2C_RDWR_MAX_MSGS {
+        return Err(WiringXError::InvalidArgument);
+    }
+
+    let mut msgs = Vec::with_capacity(operations.len());
+    for operation in operations.iter_mut() {
+        let (flags, len, buf) = match operation {
+            Operation::Read(buf) => (I2C_M_RD, buf.len(), buf.as_mut_ptr()),
+            // The kernel does not write to the buffers of write messages.
+            Operation::Write(buf) => (0, buf.len(), buf.as_ptr() as *mut u8),
+        };
+
+        msgs.push(I2cMsg {
+            addr: address,
+            flags,
+            len: u16::try_from(len).map_err(|_| WiringXError::InvalidArgument)?,
+            buf,
+        });
+    }
+
+    let data = I2cRdwrIoctlData {
+        msgs: msgs.as_mut_ptr(),
+        nmsgs: msgs.len() as u32,
+    };
+
+    let result = unsafe { libc::ioctl(fd, I2C_RDWR as _, &data) };
+
+    if result < 0 {
+        Err(WiringXError::Io(std::io::Error::last_os_error()))
+    } else {
+        Ok(())
+    }
+}
+
 /// Errors when reading or writing from the I2C device.
 #[derive(Error, Debug, Clone, Copy)]
 pub enum I2CError {
@@ -0,0 +1,186 @@
+//! On-chip ADCs through the Linux Industrial I/O sysfs interface `/sys/bus/iio/devices`, enabled with the `iio` feature.
+
+use std::{
+    fs::{self, File},
+    io,
+    os::unix::fs::FileExt,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{AnalogInput, WiringXError};
+
+const IIO_DEVICES: &str = "/sys/bus/iio/devices";
+
+/// An analog to digital converter exposed by the kernel's Industrial I/O subsystem, like the SARADC of the Milk-V Duo.
+///
+/// Hands out its voltage channels as [`AnalogInput`]s with [`channel`](Self::channel).
+/// The resolution is taken from the buffer description of the channel if the driver has one,
+/// otherwise it has to be set with [`with_resolution`](Self::with_resolution).
+///
+/// Example reading channel `1` of the SARADC:
+/// ```no_run
+/// use wiringx::{AnalogInput, IioAdc};
+///
+/// let adc = IioAdc::find("cvitek-saradc").unwrap().with_resolution(12);
+/// let channel = adc.channel(1).unwrap();
+///
+/// println!("{} of {}, {:.2} V", channel.read_raw().unwrap(), channel.max_raw(), channel.read_voltage().unwrap());
+/// ```
+#[derive(Debug, Clone)]
+pub struct IioAdc {
+    path: Arc<PathBuf>,
+    resolution: Option<u8>,
+}
+
+impl IioAdc {
+    /// Uses the device in the directory, for example `/sys/bus/iio/devices/iio:device0`.
+    pub fn new(path: impl Into<PathBuf>) -> Result<Self, WiringXError> {
+        let path = path.into();
+        if !path.join("name").exists() {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        Ok(Self {
+            path: Arc::new(path),
+            resolution: None,
+        })
+    }
+
+    /// Uses the first device with the name given by its driver.
+    pub fn find(name: &str) -> Result<Self, WiringXError> {
+        for entry in fs::read_dir(IIO_DEVICES).map_err(WiringXError::Io)? {
+            let path = entry.map_err(WiringXError::Io)?.path();
+
+            if fs::read_to_string(path.join("name")).is_ok_and(|found| found.trim() == name) {
+                return Self::new(path);
+            }
+        }
+
+        Err(WiringXError::Other(format!("No IIO device named {name}.")))
+    }
+
+    /// Sets the resolution in bits, for drivers that do not describe it.
+    pub fn with_resolution(mut self, bits: u8) -> Self {
+        self.resolution = Some(bits.clamp(1, 16));
+        self
+    }
+
+    /// Returns the directory of the device.
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the name given by the driver.
+    pub fn name(&self) -> Result<String, WiringXError> {
+        Ok(self.attribute("name")?.trim().to_string())
+    }
+
+    /// Returns the voltage channel with the number.
+    ///
+    /// Returns [`InvalidArgument`](WiringXError::InvalidArgument) if the device does not have it
+    /// and [`Unsupported`](WiringXError::Unsupported) if its resolution is not known.
+    pub fn channel(&self, channel: u32) -> Result<IioChannel, WiringXError> {
+        let raw = File::open(self.path.join(format!("in_voltage{channel}_raw"))).map_err(|e| {
+            if e.kind() == io::ErrorKind::NotFound {
+                WiringXError::InvalidArgument
+            } else {
+                WiringXError::Io(e)
+            }
+        })?;
+
+        let bits = match self.resolution {
+            Some(bits) => bits,
+            None => self.described_resolution(channel)?,
+        };
+
+        // Volts per step, the kernel gives the scale in mV.
+        let scale = self
+            .number(&format!("in_voltage{channel}_scale"))
+            .or_else(|| self.number("in_voltage_scale"))
+            .map(|scale| scale / 1000.0);
+        let offset = self
+            .number(&format!("in_voltage{channel}_offset"))
+            .or_else(|| self.number("in_voltage_offset"))
+            .unwrap_or(0.0);
+
+        Ok(IioChannel {
+            channel,
+            raw,
+            max_raw: (1 << bits) - 1,
+            scale,
+            offset,
+        })
+    }
+
+    /// Reads the resolution from a type like `le:u12/16>>0` in the buffer description.
+    fn described_resolution(&self, channel: u32) -> Result<u8, WiringXError> {
+        let description = self
+            .attribute(&format!("scan_elements/in_voltage{channel}_type"))
+            .map_err(|_| WiringXError::Unsupported)?;
+
+        description
+            .split(':')
+            .nth(1)
+            .and_then(|format| format.get(1..))
+            .and_then(|format| format.split('/').next())
+            .and_then(|bits| bits.parse::<u8>().ok())
+            .filter(|bits| (1..=16).contains(bits))
+            .ok_or(WiringXError::Unsupported)
+    }
+
+    fn attribute(&self, attribute: &str) -> Result<String, WiringXError> {
+        fs::read_to_string(self.path.join(attribute)).map_err(WiringXError::Io)
+    }
+
+    fn number(&self, attribute: &str) -> Option<f32> {
+        self.attribute(attribute).ok()?.trim().parse().ok()
+    }
+}
+
+/// A voltage channel of an [`IioAdc`].
+#[derive(Debug)]
+pub struct IioChannel {
+    channel: u32,
+    raw: File,
+    max_raw: i32,
+    scale: Option<f32>,
+    offset: f32,
+}
+
+impl IioChannel {
+    /// Returns the channel number.
+    #[inline]
+    pub fn number(&self) -> u32 {
+        self.channel
+    }
+}
+
+impl AnalogInput for IioChannel {
+    fn read_raw(&self) -> Result<i32, WiringXError> {
+        let mut buffer = [0; 16];
+        let length = self.raw.read_at(&mut buffer, 0).map_err(WiringXError::Io)?;
+
+        std::str::from_utf8(&buffer[..length])
+            .ok()
+            .and_then(|raw| raw.trim().parse::<i32>().ok())
+            .map(|raw| raw.clamp(0, self.max_raw))
+            .ok_or_else(|| WiringXError::Other("Invalid IIO reading.".to_string()))
+    }
+
+    #[inline]
+    fn max_raw(&self) -> i32 {
+        self.max_raw
+    }
+
+    /// Converts the input to volts with the scale and offset of the driver.
+    ///
+    /// Returns [`Unsupported`](WiringXError::Unsupported) if the driver has no scale, use
+    /// [`read_normalized`](AnalogInput::read_normalized) with the reference voltage then.
+    fn read_voltage(&self) -> Result<f32, WiringXError> {
+        let scale = self.scale.ok_or(WiringXError::Unsupported)?;
+
+        Ok((self.read_raw()? as f32 + self.offset) * scale)
+    }
+}
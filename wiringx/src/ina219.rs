@@ -0,0 +1,212 @@
+//! INA219 current and power monitors.
+
+use crate::{DeviceFault, WiringXError, I2C};
+
+const CONFIG: u8 = 0x00;
+const SHUNT_VOLTAGE: u8 = 0x01;
+const BUS_VOLTAGE: u8 = 0x02;
+const POWER: u8 = 0x03;
+const CURRENT: u8 = 0x04;
+const CALIBRATION: u8 = 0x05;
+
+const CONFIG_RESET: u16 = 0x8000;
+const CONFIG_BUS_32V: u16 = 0x2000;
+/// Measures shunt and bus voltage continuously.
+const CONFIG_CONTINUOUS: u16 = 0x0007;
+const BUS_VOLTAGE_OVERFLOW: u16 = 0x0001;
+
+/// Voltage over the shunt in V, where the shunt voltage register reads `0x8000`.
+const SHUNT_FULL_SCALE: f32 = 0.04;
+/// Fixed scale of the calibration register from the datasheet.
+const CALIBRATION_SCALE: f32 = 0.04096;
+
+/// A TI INA219 monitor measuring the voltage of a supply and the current through a shunt resistor on the high side.
+///
+/// The chip computes current and power from the shunt voltage with the calibration derived from the shunt resistance
+/// and the largest expected current, which also picks the smallest sufficient shunt voltage range.
+///
+/// Example monitoring a battery through the common modules with a 0.1 Ω shunt at address `0x40`:
+/// ```no_run
+/// use wiringx::{Ina219, Platform, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let i2c = wiringx.setup_i2c("/dev/i2c-1".into(), 0x40).unwrap();
+///
+/// let mut monitor = Ina219::new(i2c, 0.1, 2.0).unwrap();
+///
+/// let measurement = monitor.read().unwrap();
+/// println!(
+///     "{:.2} V, {:.3} A, {:.2} W",
+///     measurement.bus_voltage, measurement.current, measurement.power
+/// );
+/// ```
+#[derive(Debug)]
+pub struct Ina219 {
+    i2c: I2C,
+    config: u16,
+    calibration: u16,
+    current_lsb: f32,
+}
+
+impl Ina219 {
+    /// Resets the chip and calibrates it for the shunt resistance in Ω and the largest current in A through it,
+    /// with the 32 V bus voltage range and no averaging.
+    ///
+    /// Returns [`InvalidArgument`](WiringXError::InvalidArgument) if the current causes more than 320 mV over the shunt.
+    pub fn new(i2c: I2C, shunt_resistance: f32, max_current: f32) -> Result<Self, WiringXError> {
+        if shunt_resistance <= 0.0 || max_current <= 0.0 {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        // The shunt voltage range doubles with every gain step.
+        let max_shunt_voltage = max_current * shunt_resistance;
+        let gain = (0..4u16)
+            .find(|gain| max_shunt_voltage <= SHUNT_FULL_SCALE * (1 << gain) as f32)
+            .ok_or(WiringXError::InvalidArgument)?;
+
+        let current_lsb = max_current / 32768.0;
+        let calibration = (CALIBRATION_SCALE / (current_lsb * shunt_resistance)) as u32;
+        // The lowest bit of the register is not used.
+        let calibration = (calibration.min(0xFFFE) & !1) as u16;
+
+        let mut monitor = Self {
+            i2c,
+            config: CONFIG_BUS_32V | gain << 11 | 0x3 << 7 | 0x3 << 3 | CONFIG_CONTINUOUS,
+            calibration,
+            current_lsb,
+        };
+
+        monitor.write_register(CONFIG, CONFIG_RESET)?;
+        monitor.write_register(CONFIG, monitor.config)?;
+        monitor.write_register(CALIBRATION, calibration)?;
+
+        Ok(monitor)
+    }
+
+    /// Sets the bus voltage range, which is 32 V by default.
+    pub fn set_bus_range(&mut self, range: Ina219BusRange) -> Result<(), WiringXError> {
+        match range {
+            Ina219BusRange::V16 => self.config &= !CONFIG_BUS_32V,
+            Ina219BusRange::V32 => self.config |= CONFIG_BUS_32V,
+        }
+
+        self.write_register(CONFIG, self.config)
+    }
+
+    /// Sets how many samples of both voltages get averaged, `1` or a power of two up to `128`.
+    ///
+    /// Each sample takes 532 µs, averaging suppresses noise of switching loads.
+    pub fn set_averaging(&mut self, samples: u8) -> Result<(), WiringXError> {
+        if !samples.is_power_of_two() {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        // Single 12 bit samples are mode 0x3, averaging starts at 0x9 for two samples.
+        let mode = match samples {
+            1 => 0x3,
+            samples => 0x8 | samples.trailing_zeros() as u16,
+        };
+
+        self.config = self.config & !0x07F8 | mode << 7 | mode << 3;
+
+        self.write_register(CONFIG, self.config)
+    }
+
+    /// Reads the voltage of the supply on the load side of the shunt in V.
+    pub fn bus_voltage(&mut self) -> Result<f32, WiringXError> {
+        let raw = self.read_register(BUS_VOLTAGE)?;
+
+        Ok((raw >> 3) as f32 * 0.004)
+    }
+
+    /// Reads the voltage over the shunt in V, negative if the current flows backwards.
+    pub fn shunt_voltage(&mut self) -> Result<f32, WiringXError> {
+        let raw = self.read_register(SHUNT_VOLTAGE)? as i16;
+
+        Ok(raw as f32 * 0.000_01)
+    }
+
+    /// Reads the current through the shunt in A, negative if it flows backwards.
+    ///
+    /// Returns [`OutOfRange`](DeviceFault::OutOfRange) if the current is beyond the calibrated range.
+    pub fn current(&mut self) -> Result<f32, WiringXError> {
+        self.check_overflow()?;
+        let raw = self.read_register(CURRENT)? as i16;
+
+        Ok(raw as f32 * self.current_lsb)
+    }
+
+    /// Reads the power drawn by the load in W.
+    ///
+    /// Returns [`OutOfRange`](DeviceFault::OutOfRange) if the current is beyond the calibrated range.
+    pub fn power(&mut self) -> Result<f32, WiringXError> {
+        self.check_overflow()?;
+        let raw = self.read_register(POWER)?;
+
+        Ok(raw as f32 * self.current_lsb * 20.0)
+    }
+
+    /// Reads all values at once.
+    ///
+    /// Returns [`OutOfRange`](DeviceFault::OutOfRange) if the current is beyond the calibrated range.
+    pub fn read(&mut self) -> Result<Ina219Measurement, WiringXError> {
+        Ok(Ina219Measurement {
+            shunt_voltage: self.shunt_voltage()?,
+            current: self.current()?,
+            power: self.power()?,
+            bus_voltage: self.bus_voltage()?,
+        })
+    }
+
+    /// Checks the overflow flag, and restores the calibration the chip loses on brownouts.
+    fn check_overflow(&mut self) -> Result<(), WiringXError> {
+        if self.read_register(CALIBRATION)? != self.calibration {
+            self.write_register(CONFIG, self.config)?;
+            self.write_register(CALIBRATION, self.calibration)?;
+        }
+
+        if self.read_register(BUS_VOLTAGE)? & BUS_VOLTAGE_OVERFLOW != 0 {
+            return Err(WiringXError::DeviceFault(DeviceFault::OutOfRange));
+        }
+
+        Ok(())
+    }
+
+    /// Reads the register, transmitted most significant byte first unlike SMBus words.
+    fn read_register(&mut self, register: u8) -> Result<u16, WiringXError> {
+        let mut data = [0; 2];
+        self.i2c.read_reg_bytes(register, &mut data)?;
+
+        Ok(u16::from_be_bytes(data))
+    }
+
+    fn write_register(&mut self, register: u8, value: u16) -> Result<(), WiringXError> {
+        let [high, low] = value.to_be_bytes();
+        self.i2c.write_bytes(&[register, high, low])?;
+
+        Ok(())
+    }
+}
+
+/// Measured values of an [`Ina219`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ina219Measurement {
+    /// Voltage of the supply on the load side of the shunt in V.
+    pub bus_voltage: f32,
+    /// Voltage over the shunt in V.
+    pub shunt_voltage: f32,
+    /// Current through the shunt in A.
+    pub current: f32,
+    /// Power drawn by the load in W.
+    pub power: f32,
+}
+
+/// Bus voltage range of an [`Ina219`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Ina219BusRange {
+    /// Up to 16 V
+    V16,
+    /// Up to 26 V, the largest voltage the chip takes
+    #[default]
+    V32,
+}
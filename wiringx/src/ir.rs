@@ -0,0 +1,432 @@
+//! Infrared remote control receivers and transmitters.
+
+use std::time::{Duration, Instant};
+
+use crate::{gpio::wait_until, Input, IsrMode, Output, Pin, Polarity, PwmPin, Value, WiringXError};
+
+/// Silence ending a frame, longer than any space within NEC or RC5 frames.
+const FRAME_GAP: Duration = Duration::from_millis(10);
+/// Longest time after a frame in which an NEC repeat code still belongs to it, repeats come every 108 ms.
+const NEC_REPEAT_WINDOW: Duration = Duration::from_millis(250);
+/// Relative deviation of pulse lengths still accepted, interrupt latency stretches and shrinks them.
+const TOLERANCE: f32 = 0.35;
+
+const NEC_LEADER_MARK: Duration = Duration::from_micros(9000);
+const NEC_LEADER_SPACE: Duration = Duration::from_micros(4500);
+const NEC_REPEAT_SPACE: Duration = Duration::from_micros(2250);
+const NEC_UNIT: Duration = Duration::from_micros(562);
+const NEC_ONE_SPACE: Duration = Duration::from_micros(1687);
+const RC5_HALF_BIT: Duration = Duration::from_micros(889);
+
+/// Carrier frequency of most remote controls.
+pub const IR_CARRIER_FREQUENCY: u32 = 38_000;
+/// Proportion of the carrier period the LED is on, a third is common for remote controls.
+const CARRIER_DUTY_CYCLE: f32 = 1.0 / 3.0;
+
+/// A receiver for infrared remote controls, on the output of a TSOP style demodulator.
+///
+/// Edges on the pin are timestamped from its interrupts and decoded as NEC or RC5 frames.
+/// Timing is at the mercy of the interrupt latency, so frames may get lost on busy systems.
+///
+/// Example printing the buttons pressed on a remote:
+/// ```no_run
+/// use wiringx::{Input, IrReceiver, Platform, WiringX};
+///
+/// use std::time::Duration;
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+///
+/// let mut receiver = IrReceiver::new(wiringx.gpio_pin::<Input>(15).unwrap()).unwrap();
+///
+/// loop {
+///     if let Ok(event) = receiver.receive(Duration::from_secs(1)) {
+///         println!("{event:?}");
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct IrReceiver {
+    pin: Pin<Input>,
+    last_event: Option<(IrEvent, Instant)>,
+    last_toggle: bool,
+}
+
+impl IrReceiver {
+    /// Takes ownership of the pin the demodulator output is connected to, idling high.
+    pub fn new(pin: Pin<Input>) -> Result<Self, WiringXError> {
+        pin.set_isr_mode(IsrMode::Both)?;
+
+        Ok(Self {
+            pin,
+            last_event: None,
+            last_toggle: false,
+        })
+    }
+
+    /// Returns the pin.
+    #[inline]
+    pub fn into_inner(self) -> Pin<Input> {
+        self.pin
+    }
+
+    /// Waits for the next NEC or RC5 frame and returns its decoded event.
+    ///
+    /// Held buttons are reported as repeated events, NEC repeat codes carry the address and command of their frame.
+    /// Frames in other protocols are skipped. Returns [`TimedOut`](WiringXError::TimedOut)
+    /// if no frame started within the timeout.
+    pub fn receive(&mut self, timeout: Duration) -> Result<IrEvent, WiringXError> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let pulses = self.receive_raw(remaining)?;
+            let now = Instant::now();
+
+            let event = if is_nec_repeat(&pulses) {
+                match self.last_event {
+                    Some((event, time))
+                        if event.protocol == IrProtocol::Nec && now - time < NEC_REPEAT_WINDOW =>
+                    {
+                        Some(IrEvent {
+                            repeat: true,
+                            ..event
+                        })
+                    }
+                    _ => None,
+                }
+            } else if let Some((address, command)) = decode_nec(&pulses) {
+                Some(IrEvent {
+                    protocol: IrProtocol::Nec,
+                    address,
+                    command,
+                    repeat: false,
+                })
+            } else if let Some((toggle, address, command)) = decode_rc5(&pulses) {
+                // The toggle bit only flips when the button gets pressed again.
+                let repeat = matches!(
+                    self.last_event,
+                    Some((event, _)) if event.protocol == IrProtocol::Rc5
+                        && event.address == address
+                        && event.command == command
+                        && self.last_toggle == toggle
+                );
+                self.last_toggle = toggle;
+
+                Some(IrEvent {
+                    protocol: IrProtocol::Rc5,
+                    address,
+                    command,
+                    repeat,
+                })
+            } else {
+                None
+            };
+
+            if let Some(event) = event {
+                self.last_event = Some((event, now));
+                return Ok(event);
+            }
+        }
+    }
+
+    /// Waits for the next frame of any protocol and returns the lengths of its alternating marks and spaces,
+    /// starting with a mark.
+    ///
+    /// Returns [`TimedOut`](WiringXError::TimedOut) if no frame started within the timeout.
+    pub fn receive_raw(&mut self, timeout: Duration) -> Result<Vec<Duration>, WiringXError> {
+        let deadline = Instant::now() + timeout;
+
+        // A frame starts with the line pulled low for the first mark.
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(WiringXError::TimedOut);
+            }
+
+            if self.pin.wait_for_interrupt(remaining).is_ok() && self.pin.read() == Value::Low {
+                break;
+            }
+        }
+
+        let mut pulses = Vec::new();
+        let mut edge = Instant::now();
+
+        while self.pin.wait_for_interrupt(FRAME_GAP).is_ok() {
+            let now = Instant::now();
+            pulses.push(now - edge);
+            edge = now;
+        }
+
+        Ok(pulses)
+    }
+}
+
+/// An event decoded by an [`IrReceiver`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IrEvent {
+    /// Protocol of the frame.
+    pub protocol: IrProtocol,
+    /// Address of the device, 8 bit or 16 bit for extended NEC and 5 bit for RC5.
+    pub address: u16,
+    /// Command, usually the button.
+    pub command: u8,
+    /// True if the button is held down.
+    pub repeat: bool,
+}
+
+/// Protocol of an [`IrEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IrProtocol {
+    /// NEC and extended NEC, used by most cheap remotes
+    Nec,
+    /// Philips RC5 and its extension to 128 commands
+    Rc5,
+}
+
+/// A transmitter for infrared remote controls, driving an IR LED with a modulated carrier.
+///
+/// The carrier is generated by a [`PwmPin`] where available, or by toggling a GPIO pin timed by busy waiting,
+/// which only works on boards fast enough to toggle it every 13 µs.
+/// The LED usually needs a transistor, as GPIO pins can not drive the current for a useful range.
+///
+/// Example sending the power button of a remote on PWM pin `11`:
+/// ```no_run
+/// use wiringx::{IrTransmitter, Platform, Polarity, WiringX};
+///
+/// use std::time::Duration;
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let pwm = wiringx
+///     .pwm_pin(11, Duration::from_micros(26), 0.0, Polarity::Normal)
+///     .unwrap();
+///
+/// let mut transmitter = IrTransmitter::with_pwm(pwm).unwrap();
+/// transmitter.send_nec(0x00, 0x45).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct IrTransmitter {
+    carrier: Carrier,
+    frequency: u32,
+}
+
+/// The pin the carrier is generated on.
+#[derive(Debug)]
+enum Carrier {
+    Pwm(PwmPin),
+    Gpio(Pin<Output>),
+}
+
+impl IrTransmitter {
+    /// Generates the carrier with the PWM pin, reconfigured to 38 kHz.
+    pub fn with_pwm(mut pwm: PwmPin) -> Result<Self, WiringXError> {
+        pwm.set_duty_cycle(0.0)?;
+        pwm.set_polarity(Polarity::Normal)?;
+
+        let mut transmitter = Self {
+            carrier: Carrier::Pwm(pwm),
+            frequency: IR_CARRIER_FREQUENCY,
+        };
+        transmitter.set_carrier_frequency(IR_CARRIER_FREQUENCY)?;
+
+        Ok(transmitter)
+    }
+
+    /// Generates the carrier by toggling the GPIO pin at 38 kHz.
+    pub fn with_gpio(mut pin: Pin<Output>) -> Self {
+        pin.write(Value::Low);
+
+        Self {
+            carrier: Carrier::Gpio(pin),
+            frequency: IR_CARRIER_FREQUENCY,
+        }
+    }
+
+    /// Sets the carrier frequency in Hertz, usually between 30 and 56 kHz, for example 36 kHz for RC5.
+    pub fn set_carrier_frequency(&mut self, frequency: u32) -> Result<(), WiringXError> {
+        if frequency == 0 {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        if let Carrier::Pwm(pwm) = &mut self.carrier {
+            pwm.set_period(Duration::from_secs(1) / frequency)?;
+        }
+        self.frequency = frequency;
+
+        Ok(())
+    }
+
+    /// Returns the carrier frequency in Hertz.
+    #[inline]
+    pub fn carrier_frequency(&self) -> u32 {
+        self.frequency
+    }
+
+    /// Sends an NEC frame, an address above `0xFF` is sent as extended NEC.
+    pub fn send_nec(&mut self, address: u16, command: u8) -> Result<(), WiringXError> {
+        let [low, high] = address.to_le_bytes();
+        let address = if address > 0xFF {
+            [low, high]
+        } else {
+            [low, !low]
+        };
+
+        let mut pulses = vec![NEC_LEADER_MARK, NEC_LEADER_SPACE];
+        for byte in [address[0], address[1], command, !command] {
+            for i in 0..8 {
+                pulses.push(NEC_UNIT);
+                pulses.push(if byte >> i & 1 == 1 {
+                    NEC_ONE_SPACE
+                } else {
+                    NEC_UNIT
+                });
+            }
+        }
+        pulses.push(NEC_UNIT);
+
+        self.send_raw(&pulses)
+    }
+
+    /// Sends an NEC repeat code, telling the receiver the last button is still held.
+    ///
+    /// Remotes send them every 108 milliseconds from the start of the frame.
+    #[inline]
+    pub fn send_nec_repeat(&mut self) -> Result<(), WiringXError> {
+        self.send_raw(&[NEC_LEADER_MARK, NEC_REPEAT_SPACE, NEC_UNIT])
+    }
+
+    /// Sends alternating marks with the carrier and spaces without, starting with a mark.
+    ///
+    /// Every edge is timed from the start of the sequence, so delays do not add up.
+    pub fn send_raw(&mut self, pulses: &[Duration]) -> Result<(), WiringXError> {
+        let half_period = Duration::from_secs(1) / self.frequency / 2;
+        let mut edge = Instant::now();
+
+        for (i, pulse) in pulses.iter().enumerate() {
+            let mark = i % 2 == 0;
+            let end = edge + *pulse;
+
+            match &mut self.carrier {
+                Carrier::Pwm(pwm) => {
+                    pwm.set_duty_cycle(if mark { CARRIER_DUTY_CYCLE } else { 0.0 })?;
+                    wait_until(end);
+                }
+                Carrier::Gpio(pin) if mark => {
+                    while edge < end {
+                        pin.write(Value::High);
+                        edge += half_period;
+                        wait_until(edge);
+                        pin.write(Value::Low);
+                        edge += half_period;
+                        wait_until(edge);
+                    }
+                }
+                Carrier::Gpio(_) => wait_until(end),
+            }
+
+            edge = end;
+        }
+
+        if let Carrier::Pwm(pwm) = &mut self.carrier {
+            pwm.set_duty_cycle(0.0)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[inline]
+fn matches_duration(actual: Duration, expected: Duration) -> bool {
+    let tolerance = expected.mul_f32(TOLERANCE);
+
+    actual + tolerance >= expected && actual <= expected + tolerance
+}
+
+/// Returns true for the leader and single mark of an NEC repeat code.
+fn is_nec_repeat(pulses: &[Duration]) -> bool {
+    pulses.len() >= 2
+        && pulses.len() <= 3
+        && matches_duration(pulses[0], NEC_LEADER_MARK)
+        && matches_duration(pulses[1], NEC_REPEAT_SPACE)
+}
+
+/// Decodes an NEC frame into address and command, the address is 16 bit wide if not followed by its inverse.
+fn decode_nec(pulses: &[Duration]) -> Option<(u16, u8)> {
+    // Leader, 32 bits of mark and space, then the final mark.
+    if pulses.len() < 66
+        || !matches_duration(pulses[0], NEC_LEADER_MARK)
+        || !matches_duration(pulses[1], NEC_LEADER_SPACE)
+    {
+        return None;
+    }
+
+    let mut data = 0u32;
+    for (i, bit) in pulses[2..66].chunks_exact(2).enumerate() {
+        if !matches_duration(bit[0], NEC_UNIT) {
+            return None;
+        }
+
+        if matches_duration(bit[1], NEC_ONE_SPACE) {
+            data |= 1 << i;
+        } else if !matches_duration(bit[1], NEC_UNIT) {
+            return None;
+        }
+    }
+
+    let [address, address_inverse, command, command_inverse] = data.to_le_bytes();
+    if command != !command_inverse {
+        return None;
+    }
+
+    if address == !address_inverse {
+        Some((address as u16, command))
+    } else {
+        Some((u16::from_le_bytes([address, address_inverse]), command))
+    }
+}
+
+/// Decodes an RC5 frame into toggle bit, address and command.
+fn decode_rc5(pulses: &[Duration]) -> Option<(bool, u16, u8)> {
+    // The first half of the start bit is a space, invisible before the first mark.
+    let mut halves = vec![false];
+    for (i, pulse) in pulses.iter().enumerate() {
+        let mark = i % 2 == 0;
+
+        let count = if matches_duration(*pulse, RC5_HALF_BIT) {
+            1
+        } else if matches_duration(*pulse, RC5_HALF_BIT * 2) {
+            2
+        } else {
+            return None;
+        };
+
+        halves.extend(std::iter::repeat_n(mark, count));
+    }
+
+    // A final space merges into the frame gap.
+    if halves.len() == 27 {
+        halves.push(false);
+    }
+    if halves.len() != 28 {
+        return None;
+    }
+
+    // Manchester coded, `1` is a space followed by a mark.
+    let mut bits = 0u16;
+    for half in halves.chunks_exact(2) {
+        let bit = match (half[0], half[1]) {
+            (false, true) => 1,
+            (true, false) => 0,
+            _ => return None,
+        };
+        bits = bits << 1 | bit;
+    }
+
+    // The second start bit is the inverted seventh command bit in extended RC5.
+    let extension = if bits >> 12 & 1 == 0 { 0x40 } else { 0 };
+
+    Some((
+        bits >> 11 & 1 == 1,
+        bits >> 6 & 0x1F,
+        extension | (bits & 0x3F) as u8,
+    ))
+}
@@ -0,0 +1,174 @@
+//! Analog joysticks.
+
+use crate::{AnalogInput, DigitalInput, Value, WiringXError};
+
+/// Samples averaged when calibrating the center.
+const CALIBRATION_SAMPLES: u32 = 16;
+
+/// An analog thumb joystick like the KY-023, with a potentiometer per axis on any two [`AnalogInput`]s
+/// and its push button on any [`DigitalInput`].
+///
+/// Axes are normalized to -1.0 to 1.0 around their center, with a dead zone around it
+/// so the resting stick reads exactly 0.0. The button is active low, it needs a pull-up resistor.
+///
+/// Example driving a robot with the joystick on an MCP3008:
+/// ```no_run
+/// use wiringx::{Input, Joystick, Mcp3008, Platform, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let adc = Mcp3008::new(wiringx.setup_spi(0, 1_000_000).unwrap(), 3.3).unwrap();
+///
+/// let mut joystick = Joystick::new(
+///     adc.channel(0).unwrap(),
+///     adc.channel(1).unwrap(),
+///     wiringx.gpio_pin::<Input>(15).unwrap(),
+/// );
+/// joystick.calibrate_center().unwrap();
+///
+/// loop {
+///     let state = joystick.read().unwrap();
+///     let (left, right) = (state.y + state.x, state.y - state.x);
+///     println!("motors {left:.2} {right:.2}, horn {}", state.pressed);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Joystick<X, Y, B> {
+    x: X,
+    y: Y,
+    button: B,
+    center: (f32, f32),
+    dead_zone: f32,
+    inverted: (bool, bool),
+}
+
+impl<X: AnalogInput, Y: AnalogInput, B: DigitalInput> Joystick<X, Y, B> {
+    /// Creates a joystick from the inputs of both axes and the button,
+    /// assuming the center in the middle of the range and with a dead zone of 0.05.
+    pub fn new(x: X, y: Y, button: B) -> Self {
+        Self {
+            x,
+            y,
+            button,
+            center: (0.5, 0.5),
+            dead_zone: 0.05,
+            inverted: (false, false),
+        }
+    }
+
+    /// Returns the inputs of both axes and the button.
+    #[inline]
+    pub fn into_inner(self) -> (X, Y, B) {
+        (self.x, self.y, self.button)
+    }
+
+    /// Measures the center of both axes, the stick has to rest while calibrating.
+    pub fn calibrate_center(&mut self) -> Result<(), WiringXError> {
+        let mut sum = (0.0, 0.0);
+        for _ in 0..CALIBRATION_SAMPLES {
+            sum.0 += self.x.read_normalized()?;
+            sum.1 += self.y.read_normalized()?;
+        }
+
+        self.set_center(
+            sum.0 / CALIBRATION_SAMPLES as f32,
+            sum.1 / CALIBRATION_SAMPLES as f32,
+        )
+    }
+
+    /// Sets the center of both axes as proportion of their range, exclusive 0.0 to 1.0.
+    pub fn set_center(&mut self, x: f32, y: f32) -> Result<(), WiringXError> {
+        if !(x > 0.0 && x < 1.0 && y > 0.0 && y < 1.0) {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        self.center = (x, y);
+
+        Ok(())
+    }
+
+    /// Returns the center of both axes as proportion of their range.
+    #[inline]
+    pub fn center(&self) -> (f32, f32) {
+        self.center
+    }
+
+    /// Sets the part of the axes around the center reading 0.0, from 0.0 to below 1.0.
+    pub fn set_dead_zone(&mut self, dead_zone: f32) -> Result<(), WiringXError> {
+        if !(0.0..1.0).contains(&dead_zone) {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        self.dead_zone = dead_zone;
+
+        Ok(())
+    }
+
+    /// Returns the dead zone around the center.
+    #[inline]
+    pub fn dead_zone(&self) -> f32 {
+        self.dead_zone
+    }
+
+    /// Inverts the axes, for joysticks mounted rotated.
+    #[inline]
+    pub fn set_inverted(&mut self, x: bool, y: bool) {
+        self.inverted = (x, y);
+    }
+
+    /// Reads the X axis, from -1.0 to 1.0.
+    pub fn read_x(&self) -> Result<f32, WiringXError> {
+        let value = self.x.read_normalized()?;
+
+        Ok(self.normalize(value, self.center.0, self.inverted.0))
+    }
+
+    /// Reads the Y axis, from -1.0 to 1.0.
+    pub fn read_y(&self) -> Result<f32, WiringXError> {
+        let value = self.y.read_normalized()?;
+
+        Ok(self.normalize(value, self.center.1, self.inverted.1))
+    }
+
+    /// Returns whether the button is pressed.
+    pub fn is_pressed(&self) -> Result<bool, WiringXError> {
+        Ok(self.button.read()? == Value::Low)
+    }
+
+    /// Reads both axes and the button.
+    pub fn read(&self) -> Result<JoystickState, WiringXError> {
+        Ok(JoystickState {
+            x: self.read_x()?,
+            y: self.read_y()?,
+            pressed: self.is_pressed()?,
+        })
+    }
+
+    /// Maps the proportion of the range to -1.0 to 1.0, the center to 0.0, then applies the dead zone.
+    fn normalize(&self, value: f32, center: f32, inverted: bool) -> f32 {
+        let value = if value >= center {
+            (value - center) / (1.0 - center)
+        } else {
+            (value - center) / center
+        };
+
+        let magnitude = ((value.abs() - self.dead_zone) / (1.0 - self.dead_zone)).clamp(0.0, 1.0);
+        let value = magnitude.copysign(value);
+
+        if inverted {
+            -value
+        } else {
+            value
+        }
+    }
+}
+
+/// Position of the axes and state of the button of a [`Joystick`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JoystickState {
+    /// X axis from -1.0 to 1.0.
+    pub x: f32,
+    /// Y axis from -1.0 to 1.0.
+    pub y: f32,
+    /// Whether the button is pressed.
+    pub pressed: bool,
+}
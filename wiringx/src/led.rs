@@ -0,0 +1,63 @@
+//! Color types shared by the LED strip drivers.
+
+/// A color with eight bits per channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    /// Switched off pixel.
+    pub const BLACK: Self = Self::new(0, 0, 0);
+
+    /// Returns the color of the red, green and blue components.
+    #[inline]
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Returns the color dimmed to `brightness`, from `0` for off to `255` for unchanged.
+    #[inline]
+    pub fn scale(&self, brightness: u8) -> Self {
+        Self::new(
+            scale(self.r, brightness),
+            scale(self.g, brightness),
+            scale(self.b, brightness),
+        )
+    }
+}
+
+/// Order in which a strip expects the color components on the wire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorOrder {
+    Rgb,
+    Rbg,
+    Grb,
+    Gbr,
+    Brg,
+    Bgr,
+}
+
+impl ColorOrder {
+    /// Returns the components of the color in this order.
+    #[inline]
+    pub fn arrange(&self, color: Rgb) -> [u8; 3] {
+        let Rgb { r, g, b } = color;
+
+        match self {
+            ColorOrder::Rgb => [r, g, b],
+            ColorOrder::Rbg => [r, b, g],
+            ColorOrder::Grb => [g, r, b],
+            ColorOrder::Gbr => [g, b, r],
+            ColorOrder::Brg => [b, r, g],
+            ColorOrder::Bgr => [b, g, r],
+        }
+    }
+}
+
+#[inline]
+fn scale(value: u8, brightness: u8) -> u8 {
+    ((value as u16 * (brightness as u16 + 1)) >> 8) as u8
+}
@@ -21,52 +21,388 @@
 mod platform;
 pub use platform::*;
 
+mod header;
+pub use header::*;
+
 mod gpio;
 pub use gpio::*;
 
+mod gpiochip;
+pub use gpiochip::*;
+
+mod sysfs;
+use sysfs::SysfsLine;
+
+mod privileges;
+
+mod reservation;
+use reservation::Reservation;
+pub use reservation::PIN_LOCK_DIR;
+
+#[cfg(feature = "mock")]
+mod mock;
+#[cfg(feature = "mock")]
+pub use mock::{MockBoard, MockPwm};
+
 mod i2c;
 pub use i2c::*;
 
 mod pwm;
 pub use pwm::*;
 
+mod analog;
+pub use analog::*;
+
+#[cfg(feature = "iio")]
+mod iio;
+#[cfg(feature = "iio")]
+pub use iio::*;
+
+mod delay;
+pub use delay::*;
+
 mod spi;
 pub use spi::*;
 
+mod soft_spi;
+pub use soft_spi::*;
+
+mod spi_manager;
+pub use spi_manager::*;
+
+mod one_wire;
+pub use one_wire::*;
+
+#[cfg(feature = "drivers")]
+mod ds18b20;
+#[cfg(feature = "drivers")]
+pub use ds18b20::*;
+
+#[cfg(feature = "drivers")]
+mod dht;
+#[cfg(feature = "drivers")]
+pub use dht::*;
+
+#[cfg(feature = "drivers")]
+mod led;
+#[cfg(feature = "drivers")]
+pub use led::*;
+
+#[cfg(feature = "drivers")]
+mod ws2812;
+#[cfg(feature = "drivers")]
+pub use ws2812::*;
+
+#[cfg(feature = "drivers")]
+mod apa102;
+#[cfg(feature = "drivers")]
+pub use apa102::*;
+
+#[cfg(feature = "drivers")]
+mod hd44780;
+#[cfg(feature = "drivers")]
+pub use hd44780::*;
+
+#[cfg(feature = "drivers")]
+mod ssd1306;
+#[cfg(feature = "drivers")]
+pub use ssd1306::*;
+
+#[cfg(feature = "drivers")]
+mod mcp23017;
+#[cfg(feature = "drivers")]
+pub use mcp23017::*;
+
+#[cfg(feature = "drivers")]
+mod pcf8574;
+#[cfg(feature = "drivers")]
+pub use pcf8574::*;
+
+#[cfg(feature = "drivers")]
+mod pca9685;
+#[cfg(feature = "drivers")]
+pub use pca9685::*;
+
+#[cfg(feature = "drivers")]
+mod servo;
+#[cfg(feature = "drivers")]
+pub use servo::*;
+
+#[cfg(feature = "drivers")]
+mod mcp3008;
+#[cfg(feature = "drivers")]
+pub use mcp3008::*;
+
+#[cfg(feature = "drivers")]
+mod ads1115;
+#[cfg(feature = "drivers")]
+pub use ads1115::*;
+
+#[cfg(feature = "drivers")]
+mod bme280;
+#[cfg(feature = "drivers")]
+pub use bme280::*;
+
+#[cfg(feature = "drivers")]
+mod mpu6050;
+#[cfg(feature = "drivers")]
+pub use mpu6050::*;
+
+#[cfg(feature = "drivers")]
+mod hx711;
+#[cfg(feature = "drivers")]
+pub use hx711::*;
+
+#[cfg(feature = "drivers")]
+mod tm1637;
+#[cfg(feature = "drivers")]
+pub use tm1637::*;
+
+#[cfg(feature = "drivers")]
+mod ir;
+#[cfg(feature = "drivers")]
+pub use ir::*;
+
+#[cfg(feature = "drivers")]
+mod stepper;
+#[cfg(feature = "drivers")]
+pub use stepper::*;
+
+#[cfg(feature = "drivers")]
+mod nrf24;
+#[cfg(feature = "drivers")]
+pub use nrf24::*;
+
+#[cfg(feature = "drivers")]
+mod mcp2515;
+#[cfg(feature = "drivers")]
+pub use mcp2515::*;
+
+#[cfg(feature = "drivers")]
+mod tft;
+#[cfg(feature = "drivers")]
+pub use tft::*;
+
+#[cfg(feature = "drivers")]
+mod max31855;
+#[cfg(feature = "drivers")]
+pub use max31855::*;
+
+#[cfg(feature = "drivers")]
+mod ina219;
+#[cfg(feature = "drivers")]
+pub use ina219::*;
+
+#[cfg(feature = "drivers")]
+mod vl53l0x;
+#[cfg(feature = "drivers")]
+pub use vl53l0x::*;
+
+#[cfg(feature = "drivers")]
+mod bh1750;
+#[cfg(feature = "drivers")]
+pub use bh1750::*;
+
+#[cfg(feature = "drivers")]
+mod sht31;
+#[cfg(feature = "drivers")]
+pub use sht31::*;
+
+#[cfg(feature = "drivers")]
+mod aht20;
+#[cfg(feature = "drivers")]
+pub use aht20::*;
+
+#[cfg(feature = "drivers")]
+mod tcs34725;
+#[cfg(feature = "drivers")]
+pub use tcs34725::*;
+
+#[cfg(feature = "drivers")]
+mod apds9960;
+#[cfg(feature = "drivers")]
+pub use apds9960::*;
+
+#[cfg(feature = "drivers")]
+mod pcf8591;
+#[cfg(feature = "drivers")]
+pub use pcf8591::*;
+
+#[cfg(feature = "drivers")]
+mod motion;
+#[cfg(feature = "drivers")]
+pub use motion::*;
+
+#[cfg(feature = "drivers")]
+mod joystick;
+#[cfg(feature = "drivers")]
+pub use joystick::*;
+
+#[cfg(feature = "drivers")]
+mod gp2y;
+#[cfg(feature = "drivers")]
+pub use gp2y::*;
+
+#[cfg(feature = "drivers")]
+mod seven_segment;
+#[cfg(feature = "drivers")]
+pub use seven_segment::*;
+
+#[cfg(feature = "drivers")]
+mod relay;
+#[cfg(feature = "drivers")]
+pub use relay::*;
+
 pub use uart::*;
 mod uart;
 
+#[cfg(feature = "drivers")]
+pub mod modbus;
+
+#[cfg(feature = "drivers")]
+mod midi;
+#[cfg(feature = "drivers")]
+pub use midi::*;
+
+#[cfg(feature = "drivers")]
+mod dmx;
+#[cfg(feature = "drivers")]
+pub use dmx::*;
+
+#[cfg(feature = "drivers")]
+pub mod gps;
+
+#[cfg(feature = "drivers")]
+pub mod at;
+
+pub mod pinmux;
+
+pub mod safe_state;
+
+#[cfg(feature = "trace")]
+pub mod trace;
+
+#[cfg(feature = "events")]
+pub mod events;
+
+#[cfg(feature = "metrics")]
+pub mod metrics;
+
+#[cfg(feature = "config")]
+pub mod config;
+
+#[cfg(feature = "rppal-compat")]
+pub mod rppal;
+
+#[cfg(feature = "tokio")]
+mod async_uart;
+#[cfg(feature = "tokio")]
+pub use async_uart::*;
+
+#[cfg(feature = "embedded-hal")]
+mod hal;
+
+#[cfg(feature = "async")]
+mod hal_async;
+
+#[cfg(any(feature = "log", feature = "tracing"))]
+mod logging;
+
+#[cfg(all(feature = "smart-leds-trait", feature = "drivers"))]
+mod smart_leds;
+
+#[cfg(all(feature = "embedded-graphics-core", feature = "drivers"))]
+mod graphics;
+
 use thiserror::Error;
 
 use std::{
     any::TypeId,
     collections::HashSet,
+    ffi::CStr,
+    fmt, fs,
+    hash::Hash,
     io,
-    os::fd::RawFd,
+    ops::RangeInclusive,
+    os::{fd::RawFd, raw::c_int},
     path::PathBuf,
-    sync::{Arc, OnceLock},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Once,
+    },
     time::Duration,
 };
 
-use parking_lot::Mutex;
+use gpio::{LineBackend, WiringXLine};
+#[cfg(feature = "mock")]
+use mock::MockLine;
+use parking_lot::{Mutex, RwLock, RwLockWriteGuard};
+use pwm::{PwmBackend, SysfsPwm, WiringXPwm};
 
-use wiringx_sys::{
-    pinMode, pinmode_t_PINMODE_INPUT, pinmode_t_PINMODE_OUTPUT, wiringXGC, wiringXSelectableFd,
-    wiringXSetup, wiringXValidGPIO,
-};
+#[cfg(not(any(feature = "log", feature = "tracing")))]
+use wiringx_sys::wiringXSetup;
+#[cfg(any(feature = "log", feature = "tracing"))]
+use wiringx_sys::wiringXSetupFormattedLog;
+use wiringx_sys::{wiringXGC, wiringXPlatform, wiringXSelectableFd, wiringXValidGPIO};
 
-static WIRINGX: OnceLock<WiringX> = OnceLock::new();
+/// The instance set up last, until it is shut down.
+static WIRINGX: Mutex<Option<&'static WiringX>> = Mutex::new(None);
+/// Registers [`cleanup_at_exit`] once.
+static AT_EXIT: Once = Once::new();
+/// Held during the calls into wiringX, see [`wiringx_call`], and set while wiringX is set up.
+static CALLS: Mutex<bool> = Mutex::new(false);
+/// Read while waiting for interrupts in wiringX, which can not hold [`CALLS`] without blocking every other pin,
+/// and written while cleaning wiringX up, see [`wiringx_wait`].
+static WAITS: RwLock<()> = RwLock::new(());
 
 /// A pin handle
 type Hand<T> = Arc<Mutex<HashSet<T>>>;
 
+/// A pin or interface claimed in its [`Hand`] while it is set up, released again if the setup fails.
+///
+/// Claiming checks and inserts in one step, so two threads setting up the same pin can not both get it.
+pub(crate) struct Claim<T: Eq + Hash> {
+    handles: Hand<T>,
+    key: Option<T>,
+}
+
+impl<T: Eq + Hash> Claim<T> {
+    /// Keeps the claim once the setup worked, returning the handles for the handle type to release it on drop.
+    pub(crate) fn keep(mut self) -> Hand<T> {
+        self.key = None;
+        self.handles.clone()
+    }
+}
+
+impl<T: Eq + Hash> Drop for Claim<T> {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.handles.lock().remove(&key);
+        }
+    }
+}
+
 /// Instance of WiringX
 ///
 /// Used to register pins and interfaces to be safely used.
 ///
 /// Keeps track of all pins and protocol instances.
 ///
-/// Can be shared across threads safely.
+/// Can be shared across threads safely. The pins and interfaces it sets up are [`Send`] and [`Sync`] as well,
+/// so they can be moved into the thread using them, like a control loop owning its outputs:
+/// ```no_run
+/// use std::{thread, time::Duration};
+///
+/// use wiringx::{Output, Platform, Value, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let mut led = wiringx.gpio_pin::<Output>(0).unwrap();
+///
+/// thread::spawn(move || loop {
+///     led.toggle();
+///     thread::sleep(Duration::from_millis(500));
+/// });
+/// ```
 #[derive(Clone, Debug)]
 pub struct WiringX {
     platform: Platform,
@@ -75,96 +411,604 @@ pub struct WiringX {
     i2c_handles: Hand<(PathBuf, i32)>,
     spi_handles: Hand<i32>,
     uart_handles: Hand<PathBuf>,
+    lines: Arc<Lines>,
+    active: Arc<AtomicBool>,
+    pin_locks: Arc<Mutex<Option<PathBuf>>>,
+}
+
+// Checks that the handles stay usable from other threads, whatever their fields become.
+const _: () = {
+    const fn send_sync<T: Send + Sync>() {}
+
+    send_sync::<WiringX>();
+    send_sync::<Pin<Input>>();
+    send_sync::<Pin<Output>>();
+    send_sync::<SelectablePin>();
+    send_sync::<PwmPin>();
+    send_sync::<I2C>();
+    send_sync::<Spi>();
+    send_sync::<SerialPort>();
+    #[cfg(feature = "mock")]
+    send_sync::<MockBoard>();
+};
+
+/// Where pins not driven by wiringX come from.
+#[derive(Debug)]
+enum Lines {
+    WiringX,
+    Gpiochip(GpioChips),
+    Sysfs,
+    #[cfg(feature = "mock")]
+    Mock(Arc<MockBoard>),
 }
 
 impl WiringX {
     /// Sets up WiringX for the given board.
     ///
-    /// When called a second time, the platform argument does not do anything.
-    /// Instead the same instance will be returned.
+    /// There is one instance per process, so libraries combined into one binary can each call this.
+    /// Calls after the first successful one return the same instance if given the same platform,
+    /// and [`AlreadyInitialized`](WiringXError::AlreadyInitialized) if given another one.
+    /// Calls from several threads at once are serialized, so wiringX is only ever set up by one of them.
+    ///
+    /// wiringX prints its warnings and errors to stderr, with the `log` or `tracing` feature
+    /// they go to the crate of the same name instead, with the target `wiringx`.
+    ///
+    /// With the `dlopen` feature wiringX is not built into the binary but loaded from `libwiringx.so` here,
+    /// or the path in the `WIRINGX_LIBRARY` environment variable. If it is missing an [`InitError`](WiringXError::InitError)
+    /// is returned, after which WiringX can still be set up without it:
+    /// ```no_run
+    /// use wiringx::{GpioBackend, Platform, WiringX};
+    ///
+    /// let wiringx = WiringX::new(Platform::MilkVDuo).or_else(|_| {
+    ///     WiringX::with_gpio_backend(
+    ///         Platform::Generic,
+    ///         GpioBackend::Gpiochip(vec!["/dev/gpiochip0".into()]),
+    ///     )
+    /// });
+    /// ```
+    #[inline]
     pub fn new(platform: Platform) -> Result<&'static Self, WiringXError> {
-        let error = OnceLock::new();
+        Self::with_gpio_backend(platform, GpioBackend::WiringX)
+    }
+
+    /// Sets up WiringX for the board it runs on, see [`Platform::detect`].
+    ///
+    /// Returns [`InitError`](WiringXError::InitError) if the board could not be detected.
+    pub fn detect() -> Result<&'static Self, WiringXError> {
+        let platform = Platform::detect().ok_or_else(|| {
+            WiringXError::InitError(
+                "Could not detect the platform from the device tree".to_string(),
+            )
+        })?;
+
+        Self::new(platform)
+    }
+
+    /// Sets up WiringX for the given board, with GPIO pins driven by the given backend.
+    ///
+    /// PWM, I2C, SPI and UART go through wiringX, except for PWM with the mock backend. Like with [`new`](Self::new),
+    /// calls after the first successful one return the same instance, or an error if the platform differs.
+    /// Their backend argument does not do anything.
+    /// wiringX is not set up at all for [`Platform::Generic`], [`Platform::Custom`] and the boards it does not support,
+    /// like [`Platform::OrangePiZero3`], so only the sysfs, gpiochip and mock backends work.
+    ///
+    /// Example using the GPIO character device, for kernels without the sysfs GPIO interface:
+    /// ```no_run
+    /// use wiringx::{GpioBackend, Output, Platform, WiringX};
+    ///
+    /// let wiringx = WiringX::with_gpio_backend(
+    ///     Platform::MilkVDuoS,
+    ///     GpioBackend::Gpiochip(vec!["/dev/gpiochip0".into()]),
+    /// )
+    /// .unwrap();
+    ///
+    /// // Line `14` of `gpiochip0`
+    /// let mut pin = wiringx.gpio_pin::<Output>(14).unwrap();
+    /// pin.toggle();
+    /// ```
+    pub fn with_gpio_backend(
+        platform: Platform,
+        backend: GpioBackend,
+    ) -> Result<&'static Self, WiringXError> {
+        // Setting up is serialized and only stored when it worked, so a failed attempt can be followed
+        // by one with another platform or backend. wiringX itself is only set up once nothing else can fail,
+        // and always while holding the lock, as its setup and cleanup are not thread safe.
+        let mut current = WIRINGX.lock();
+
+        if let Some(wiringx) = *current {
+            if wiringx.platform != platform {
+                return Err(WiringXError::AlreadyInitialized(wiringx.platform));
+            }
+
+            return Ok(wiringx);
+        }
+
+        if !platform.is_compiled_in() {
+            return Err(WiringXError::InitError(format!(
+                "The support for {platform} is not compiled in, it needs the `{}` feature",
+                platform.feature().unwrap_or_default()
+            )));
+        }
+
+        let lines = match &backend {
+            GpioBackend::WiringX if !platform.uses_wiringx() => {
+                return Err(WiringXError::InitError(format!(
+                    "The {platform} platform needs a GPIO backend other than wiringX"
+                )));
+            }
+            GpioBackend::WiringX => Lines::WiringX,
+            GpioBackend::Gpiochip(paths) => {
+                Lines::Gpiochip(GpioChips::open(paths).map_err(|e| {
+                    WiringXError::InitError(format!("Failed to open the GPIO chips: {e}"))
+                })?)
+            }
+            GpioBackend::Sysfs => Lines::Sysfs,
+            #[cfg(feature = "mock")]
+            GpioBackend::Mock => Lines::Mock(Arc::new(MockBoard::new())),
+        };
+
+        if platform.uses_wiringx() {
+            #[cfg(feature = "dlopen")]
+            wiringx_sys::load().map_err(|e| WiringXError::InitError(e.to_string()))?;
 
-        let wiringx = WIRINGX.get_or_init(|| {
-            let result = unsafe { wiringXSetup(platform.as_c_addr(), None) };
+            // wiringX only compares the name, it does not keep it.
+            let name = platform.c_name();
+
+            #[cfg(any(feature = "log", feature = "tracing"))]
+            let result = unsafe {
+                wiringXSetupFormattedLog(name.as_ptr() as *mut _, Some(logging::forward))
+            };
+            #[cfg(not(any(feature = "log", feature = "tracing")))]
+            let result = unsafe { wiringXSetup(name.as_ptr() as *mut _, None) };
 
             if result != 0 {
-                error.get_or_init(|| "Failed to initialize WiringX");
+                // wiringX counts itself as set up even when it failed, and would skip the next attempt.
+                unsafe { wiringXGC() };
+
+                return Err(WiringXError::InitError(
+                    "Failed to initialize WiringX".to_string(),
+                ));
             };
 
-            WiringX {
-                platform,
-                gpio_handles: Mutex::new(HashSet::new()).into(),
-                pwm_handles: Mutex::new(HashSet::new()).into(),
-                i2c_handles: Mutex::new(HashSet::new()).into(),
-                spi_handles: Mutex::new(HashSet::new()).into(),
-                uart_handles: Mutex::new(HashSet::new()).into(),
-            }
+            *CALLS.lock() = true;
+        }
+
+        // Leaked, as references to it may outlive a shutdown, which then only fail.
+        let wiringx = Box::leak(Box::new(WiringX {
+            platform,
+            gpio_handles: Mutex::new(HashSet::new()).into(),
+            pwm_handles: Mutex::new(HashSet::new()).into(),
+            i2c_handles: Mutex::new(HashSet::new()).into(),
+            spi_handles: Mutex::new(HashSet::new()).into(),
+            uart_handles: Mutex::new(HashSet::new()).into(),
+            lines: Arc::new(lines),
+            active: Arc::new(AtomicBool::new(true)),
+            pin_locks: Arc::new(Mutex::new(None)),
+        }));
+        *current = Some(wiringx);
+
+        AT_EXIT.call_once(|| unsafe {
+            libc::atexit(cleanup_at_exit);
         });
 
-        if let Some(error) = error.get() {
-            Err(WiringXError::InitError(error.to_string()))
+        Ok(wiringx)
+    }
+
+    /// Shuts WiringX down, so it can be set up again, for example with another platform after the configuration changed.
+    ///
+    /// The instances live until they are shut down, dropping clones of them does not clean anything up.
+    /// There is no `Drop` implementation cleaning up or failing while pins are alive, as [`new`](Self::new)
+    /// hands out `&'static` references, which are never dropped, and dropping a clone must not clean up
+    /// while the others are still used. This method is where pins still alive are reported instead,
+    /// and the instance still set up is cleaned up when the process exits normally,
+    /// by returning from `main` or calling [`std::process::exit`].
+    ///
+    /// Cleaning up lets wiringX switch the pins it set up as outputs back to inputs, so they float after
+    /// the process exits, whether it shut down or not. Outputs that have to keep their level, like a relay kept on,
+    /// need an external pull resistor. Pins still driven by other threads while the process exits fail from then on.
+    ///
+    /// Returns [`InUse`](WiringXError::InUse) with their number if pins or interfaces set up with this instance
+    /// are still alive, or being set up by another thread, they have to be dropped first and are not released here. Afterwards this instance and its clones return [`ShutDown`](WiringXError::ShutDown)
+    /// instead of setting anything up.
+    pub fn shutdown(&self) -> Result<(), WiringXError> {
+        let mut current = WIRINGX.lock();
+
+        if !self.active.load(Ordering::Acquire) {
+            return Err(WiringXError::ShutDown);
+        }
+
+        let in_use = self.in_use();
+        if in_use > 0 {
+            return Err(WiringXError::InUse(in_use));
+        }
+
+        self.active.store(false, Ordering::Release);
+        self.cleanup();
+
+        if current.is_some_and(|wiringx| Arc::ptr_eq(&wiringx.active, &self.active)) {
+            *current = None;
+        }
+
+        Ok(())
+    }
+
+    /// Claims the pin or interface in the handles, see [`Claim`].
+    ///
+    /// Returns [`PinUsed`](WiringXError::PinUsed) if it is already set up.
+    /// Claims are made while holding the lock [`shutdown`](Self::shutdown) holds, so it either sees them
+    /// or they see this instance shut down.
+    fn claim<T: Eq + Hash + Clone>(
+        &self,
+        handles: &Hand<T>,
+        key: T,
+    ) -> Result<Claim<T>, WiringXError> {
+        let _current = WIRINGX.lock();
+        self.check_active()?;
+
+        if !handles.lock().insert(key.clone()) {
+            return Err(WiringXError::PinUsed);
+        }
+
+        Ok(Claim {
+            handles: handles.clone(),
+            key: Some(key),
+        })
+    }
+
+    /// Returns the number of pins and interfaces still alive.
+    fn in_use(&self) -> usize {
+        self.gpio_handles.lock().len()
+            + self.pwm_handles.lock().len()
+            + self.i2c_handles.lock().len()
+            + self.spi_handles.lock().len()
+            + self.uart_handles.lock().len()
+    }
+
+    /// Cleans wiringX up, waiting for the interrupts waited for, of which there are none once no pins are left.
+    fn cleanup(&self) {
+        self.cleanup_locked(WAITS.write());
+    }
+
+    fn cleanup_locked(&self, _waits: RwLockWriteGuard<'_, ()>) {
+        if self.platform.uses_wiringx() {
+            // wiringX unmaps the GPIO registers and closes the pin files, so calls of other threads still owning pins
+            // must not run meanwhile. Afterwards they fail without calling into wiringX.
+            let mut calls = CALLS.lock();
+            unsafe {
+                wiringXGC();
+            }
+            *calls = false;
+        }
+    }
+
+    fn check_active(&self) -> Result<(), WiringXError> {
+        if self.active.load(Ordering::Acquire) {
+            Ok(())
         } else {
-            Ok(wiringx)
+            Err(WiringXError::ShutDown)
         }
     }
 
+    /// Returns the instance set up before, if there is one.
+    #[cfg(feature = "rppal-compat")]
+    #[inline]
+    pub(crate) fn instance() -> Option<&'static Self> {
+        *WIRINGX.lock()
+    }
+
     /// Returns the WiringX platform of this instance.
     #[inline]
     pub fn platform(&self) -> Platform {
         self.platform
     }
 
+    /// Reserves the pins set up from now on across processes, with advisory locks on `pin-N.lock` files in the directory,
+    /// usually [`PIN_LOCK_DIR`].
+    ///
+    /// Pins used by this instance are only tracked in this process, so two services could otherwise drive the same pin.
+    /// Pins locked by another process return [`PinUsed`](WiringXError::PinUsed), and the locks are released
+    /// when the pins are dropped or the process exits. Only processes doing the same respect the locks.
+    ///
+    /// ```no_run
+    /// use wiringx::{Output, Platform, WiringX, PIN_LOCK_DIR};
+    ///
+    /// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+    /// wiringx.lock_pins_in(PIN_LOCK_DIR).unwrap();
+    ///
+    /// let pin = wiringx.gpio_pin::<Output>(0).unwrap();
+    /// ```
+    pub fn lock_pins_in(&self, dir: impl Into<PathBuf>) -> Result<(), WiringXError> {
+        self.check_active()?;
+
+        let dir = dir.into();
+        fs::create_dir_all(&dir).map_err(WiringXError::Io)?;
+        *self.pin_locks.lock() = Some(dir);
+
+        Ok(())
+    }
+
+    /// Locks the pin if enabled with [`lock_pins_in`](Self::lock_pins_in).
+    fn reserve(&self, pin_number: i32) -> Result<Option<Reservation>, WiringXError> {
+        match &*self.pin_locks.lock() {
+            Some(dir) => Reservation::acquire(dir, pin_number).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Switches the process to the unprivileged user and group, given by their name or number, after setting up as root.
+    ///
+    /// The registers wiringX maps and the files of the pins and interfaces already set up stay usable.
+    /// Setting up more can fail afterwards, like exporting sysfs GPIOs, and so can the PWM of the Milk-V boards,
+    /// which writes to sysfs on every change unless the files are made accessible to the user.
+    /// The supplementary groups are dropped as well, and this can not be undone.
+    pub fn drop_privileges(&self, user: &str, group: &str) -> Result<(), WiringXError> {
+        self.check_active()?;
+        privileges::drop_to(user, group)
+    }
+
+    /// Returns the name wiringX reports for the platform it is set up for, like `milkv_duos`.
+    ///
+    /// Matches [`Platform::name`] when wiringX set up the platform asked for, which this allows to verify.
+    /// Returns [`Unsupported`](WiringXError::Unsupported) for the platforms wiringX is not set up for,
+    /// like [`Platform::Generic`].
+    pub fn platform_name(&self) -> Result<String, WiringXError> {
+        self.check_active()?;
+
+        if !self.platform.uses_wiringx() {
+            return Err(WiringXError::Unsupported);
+        }
+
+        // The name belongs to the platform, which wiringX frees when cleaning up.
+        let calls = CALLS.lock();
+        let name = if *calls {
+            unsafe { wiringXPlatform() }
+        } else {
+            std::ptr::null()
+        };
+        if name.is_null() {
+            return Err(WiringXError::Other(
+                "wiringX has no platform set up".to_string(),
+            ));
+        }
+
+        Ok(unsafe { CStr::from_ptr(name) }
+            .to_string_lossy()
+            .into_owned())
+    }
+
+    /// Returns the model, revision and SoC of the board the program runs on, as described by the kernel.
+    ///
+    /// Unlike [`platform`](Self::platform) this is read from the board, for example to adjust the pinout
+    /// between revisions of the hardware.
+    #[inline]
+    pub fn board_info(&self) -> BoardInfo {
+        BoardInfo::read()
+    }
+
+    /// Returns the pin headers of the board, see [`Platform::header_layout`].
+    #[inline]
+    pub fn header_layout(&self) -> &'static [Header] {
+        self.platform.header_layout()
+    }
+
+    /// Returns the simulated board, if set up with [`GpioBackend::Mock`].
+    #[cfg(feature = "mock")]
+    #[inline]
+    pub fn mock_board(&self) -> Option<&MockBoard> {
+        match &*self.lines {
+            Lines::Mock(board) => Some(board),
+            _ => None,
+        }
+    }
+
     /// Returns true if the given GPIO number is valid for this platform.
+    ///
+    /// With wiringX driving the pins, the number has to be one of [`PlatformInfo::gpio_pins`]
+    /// before wiringX is asked at all.
     pub fn valid_gpio(&self, gpio_pin: i32) -> bool {
-        let result = unsafe { wiringXValidGPIO(gpio_pin) };
+        if self.check_active().is_err() {
+            return false;
+        }
+
+        let Some(gpio_pin) = self.line_number(gpio_pin) else {
+            return false;
+        };
 
-        result == 0
+        match &*self.lines {
+            Lines::WiringX => {
+                let pins = self.platform.info().gpio_pins;
+                if !pins.is_empty() && !pins.contains(&gpio_pin) {
+                    return false;
+                }
+            }
+            Lines::Gpiochip(chips) => return chips.is_valid(gpio_pin),
+            // Whether the number exists only shows when exporting it.
+            Lines::Sysfs => return gpio_pin >= 0,
+            #[cfg(feature = "mock")]
+            Lines::Mock(_) => return gpio_pin >= 0,
+        }
+
+        matches!(
+            wiringx_call(|| unsafe { wiringXValidGPIO(gpio_pin) }),
+            Ok(0)
+        )
+    }
+
+    /// Returns what the given pin can be used for on this platform and GPIO backend, without setting it up.
+    ///
+    /// PWM goes through wiringX whatever the GPIO backend, or sysfs for the platforms wiringX does not support,
+    /// so it is available on the pins of [`PlatformInfo::pwm_pins`], or on any pin with the mock backend. Edges can be detected on the pins of [`PlatformInfo::isr_pins`]
+    /// with wiringX, and on any pin with the other backends.
+    pub fn pin_capabilities(&self, gpio_pin: i32) -> PinCapabilities {
+        let info = self.platform.info();
+        let valid = self.valid_gpio(gpio_pin);
+
+        let pwm = match &*self.lines {
+            #[cfg(feature = "mock")]
+            Lines::Mock(_) => gpio_pin >= 0,
+            _ => info.pwm_pins.contains(&gpio_pin),
+        };
+
+        let isr = match &*self.lines {
+            Lines::WiringX => valid && info.isr_pins.contains(&gpio_pin),
+            _ => valid,
+        };
+
+        PinCapabilities {
+            valid,
+            digital: valid,
+            pwm,
+            isr,
+            bank: self.bank_offset(gpio_pin),
+        }
+    }
+
+    /// Returns the bank and offset of the pin, none if the GPIO backend does not know the banks, like wiringX.
+    pub fn bank_offset(&self, gpio_pin: i32) -> Option<BankOffset> {
+        let (bank, offset) = match (self.platform.spec(), &*self.lines) {
+            (Some(spec), _) => spec.line(gpio_pin)?,
+            (None, Lines::Gpiochip(chips)) => chips.bank_offset(gpio_pin)?,
+            (None, Lines::Sysfs) => {
+                sysfs::chips()
+                    .into_iter()
+                    .enumerate()
+                    .find_map(|(bank, (base, lines))| {
+                        let offset = u32::try_from(gpio_pin.checked_sub(base)?).ok()?;
+                        (offset < lines).then_some((bank, offset))
+                    })?
+            }
+            _ => return None,
+        };
+
+        Some(BankOffset { bank, offset })
+    }
+
+    /// Returns the number of the pin at the offset of the bank, the reverse of [`bank_offset`](Self::bank_offset).
+    pub fn gpio_number(&self, bank: usize, offset: u32) -> Option<i32> {
+        match (self.platform.spec(), &*self.lines) {
+            (Some(spec), _) => spec.pin_at(bank, offset),
+            (None, Lines::Gpiochip(chips)) => chips.number(bank, offset),
+            (None, Lines::Sysfs) => {
+                let (base, lines) = *sysfs::chips().get(bank)?;
+                if offset >= lines {
+                    return None;
+                }
+
+                base.checked_add(i32::try_from(offset).ok()?)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the number of the pin for the GPIO backend, the pin itself for the platforms without a spec.
+    fn line_number(&self, gpio_pin: i32) -> Option<i32> {
+        let Some(spec) = self.platform.spec() else {
+            return Some(gpio_pin);
+        };
+
+        match &*self.lines {
+            Lines::WiringX => None,
+            Lines::Gpiochip(chips) => {
+                let (chip, line) = spec.line(gpio_pin)?;
+                chips.number(chip, line)
+            }
+            Lines::Sysfs => spec.sysfs_number(gpio_pin),
+            #[cfg(feature = "mock")]
+            Lines::Mock(_) => spec.line(gpio_pin).map(|_| gpio_pin),
+        }
     }
 
     /// Returns a raw file descriptor to the given GPIO pin.
+    ///
+    /// Only works for pins driven by wiringX, [`Pin::into_selectable`] works with every backend.
     pub fn selectable_fd(&self, gpio_pin: i32) -> Result<RawFd, WiringXError> {
-        if !self.valid_gpio(gpio_pin) {
-            return Err(WiringXError::InvalidPin);
-        }
+        self.check_active()?;
 
-        let fd = unsafe { wiringXSelectableFd(gpio_pin) };
-        if fd < 0 {
-            Err(WiringXError::Io(io::Error::last_os_error()))
+        self.check_pin(gpio_pin)?;
+
+        wiringx_call(|| unsafe { wiringXSelectableFd(gpio_pin) }).map_err(|errno| match errno {
+            Some(errno) => WiringXError::Io(io::Error::from_raw_os_error(errno)),
+            None => WiringXError::Unsupported,
+        })
+    }
+
+    /// Returns [`NoSuchPin`](WiringXError::NoSuchPin) if the pin is not valid, see [`valid_gpio`](Self::valid_gpio).
+    fn check_pin(&self, gpio_pin: i32) -> Result<(), WiringXError> {
+        if self.valid_gpio(gpio_pin) {
+            Ok(())
         } else {
-            Ok(fd)
+            Err(WiringXError::NoSuchPin {
+                pin: gpio_pin,
+                platform: self.platform,
+            })
         }
     }
 
     /// Returns a handle to a pin marked either as [`Input`] or [`Output`]
+    ///
+    /// Returns [`NoSuchPin`](WiringXError::NoSuchPin) for numbers that are not valid for the platform,
+    /// see [`valid_gpio`](Self::valid_gpio), without passing them on.
     pub fn gpio_pin<State: 'static + Default>(
         &self,
         pin_number: i32,
     ) -> Result<Pin<State>, WiringXError> {
-        if self.gpio_handles.lock().contains(&pin_number) {
-            return Err(WiringXError::PinUsed);
-        }
+        self.check_active()?;
+        self.check_pin(pin_number)?;
 
-        if !self.valid_gpio(pin_number) {
-            return Err(WiringXError::InvalidPin);
-        }
+        let claim = self.claim(&self.gpio_handles, pin_number)?;
 
+        let number = self
+            .line_number(pin_number)
+            .ok_or(WiringXError::InvalidPin)?;
+        let reservation = self.reserve(pin_number)?;
+        let isr = self.pin_capabilities(pin_number).isr;
         let type_id = TypeId::of::<State>();
 
-        if type_id == TypeId::of::<Input>() {
-            unsafe { pinMode(pin_number, pinmode_t_PINMODE_INPUT) }
+        let line: Box<dyn LineBackend> = if type_id == TypeId::of::<Input>() {
+            match &*self.lines {
+                Lines::WiringX => Box::new(WiringXLine::input(number, isr)?),
+                Lines::Gpiochip(chips) => Box::new(chips.request_input(number)?),
+                Lines::Sysfs => Box::new(SysfsLine::input(number)?),
+                #[cfg(feature = "mock")]
+                Lines::Mock(board) => Box::new(MockLine::input(board.clone(), number)),
+            }
         } else if type_id == TypeId::of::<Output>() {
-            unsafe { pinMode(pin_number, pinmode_t_PINMODE_OUTPUT) }
+            match &*self.lines {
+                Lines::WiringX => Box::new(WiringXLine::output(number, isr)?),
+                Lines::Gpiochip(chips) => Box::new(chips.request_output(number)?),
+                Lines::Sysfs => Box::new(SysfsLine::output(number)?),
+                #[cfg(feature = "mock")]
+                Lines::Mock(board) => Box::new(MockLine::output(board.clone(), number)),
+            }
         } else {
             return Err(WiringXError::InvalidStateType);
         };
 
-        self.gpio_handles.lock().insert(pin_number);
+        Ok(Pin::new(pin_number, claim.keep(), line, reservation))
+    }
 
-        Ok(Pin::new(pin_number, self.gpio_handles.clone()))
+    /// Returns a handle to the pin at the offset of the bank, like [`gpio_pin`](Self::gpio_pin).
+    ///
+    /// Returns [`InvalidPin`](WiringXError::InvalidPin) if there is no such pin or the GPIO backend does not know the banks,
+    /// see [`gpio_number`](Self::gpio_number).
+    #[inline]
+    pub fn gpio_pin_at<State: 'static + Default>(
+        &self,
+        bank: usize,
+        offset: u32,
+    ) -> Result<Pin<State>, WiringXError> {
+        let pin_number = self
+            .gpio_number(bank, offset)
+            .ok_or(WiringXError::InvalidPin)?;
+
+        self.gpio_pin(pin_number)
     }
 
     /// Enables and returns a handle to a pulse-width modulated pin, if supported.
+    ///
+    /// Returns [`Unsupported`](WiringXError::Unsupported) before touching the pin if it has no PWM,
+    /// see [`pin_capabilities`](Self::pin_capabilities), or [`NoSuchPin`](WiringXError::NoSuchPin)
+    /// if the platform does not have the pin at all.
     #[inline]
     pub fn pwm_pin(
         &self,
@@ -173,9 +1017,29 @@ impl WiringX {
         duty_cycle: f32,
         polarity: Polarity,
     ) -> Result<PwmPin, WiringXError> {
+        self.check_active()?;
+
+        if !self.pin_capabilities(pin_number).pwm {
+            self.check_pin(pin_number)?;
+            return Err(WiringXError::Unsupported);
+        }
+
+        let backend: Arc<dyn PwmBackend> = match &*self.lines {
+            #[cfg(feature = "mock")]
+            Lines::Mock(board) => board.clone(),
+            _ => match self.platform.spec() {
+                Some(spec) => Arc::new(SysfsPwm { spec }),
+                None => Arc::new(WiringXPwm),
+            },
+        };
+
+        let claim = self.claim(&self.pwm_handles, pin_number)?;
+
         PwmPin::new(
             pin_number,
-            self.pwm_handles.clone(),
+            claim,
+            self.reserve(pin_number)?,
+            backend,
             period,
             duty_cycle,
             polarity,
@@ -185,30 +1049,60 @@ impl WiringX {
     /// Sets up an inter-integrated circuit instance for the given I2C device path, for example `/dev/i2c-1`, and the device address.
     #[inline]
     pub fn setup_i2c(&self, dev: PathBuf, addr: i32) -> Result<I2C, WiringXError> {
-        I2C::new(dev, addr, self.i2c_handles.clone())
+        let claim = self.claim(&self.i2c_handles, (dev.clone(), addr))?;
+        I2C::new(dev, addr, claim)
     }
 
     /// Sets up an serial peripheral interface instance for the given device channel.
     ///
-    /// Speed is measured in Hertz here.
+    /// Speed is measured in Hertz here. The mode, bit order and speed can be changed later on the returned [`Spi`].
     #[inline]
     pub fn setup_spi(&self, channel: i32, speed: u32) -> Result<Spi, WiringXError> {
-        Spi::new(channel, speed as i32, self.spi_handles.clone())
+        let claim = self.claim(&self.spi_handles, channel)?;
+        Spi::new(channel, speed, claim)
+    }
+
+    /// Sets up a software driven serial peripheral interface on the given GPIO pin numbers.
+    ///
+    /// Speed is measured in Hertz here. See [`SoftSpi::new`] to build the bus from existing pins.
+    pub fn setup_soft_spi(
+        &self,
+        sclk: i32,
+        mosi: i32,
+        miso: i32,
+        cs: Option<i32>,
+        speed: u32,
+    ) -> Result<SoftSpi, WiringXError> {
+        let sclk = self.gpio_pin::<Output>(sclk)?;
+        let mosi = self.gpio_pin::<Output>(mosi)?;
+        let miso = self.gpio_pin::<Input>(miso)?;
+        let cs = cs.map(|cs| self.gpio_pin::<Output>(cs)).transpose()?;
+
+        SoftSpi::new(sclk, mosi, miso, cs, speed)
     }
 
     /// Sets up a universal asynchronous receiver-transmitter instance with the provided device path and configuration.
     #[inline]
-    pub fn setup_uart(&self, dev: PathBuf, config: SerialConfig) -> Result<Uart, WiringXError> {
-        Uart::new(dev, config, self.uart_handles.clone())
+    pub fn setup_uart(
+        &self,
+        dev: PathBuf,
+        config: SerialConfig,
+    ) -> Result<SerialPort, WiringXError> {
+        let claim = self.claim(&self.uart_handles, dev.clone())?;
+        SerialPort::new(dev, config, claim)
     }
-}
 
-impl Drop for WiringX {
+    /// Opens the serial port at the given device path, for example `/dev/ttyS1`, with the given baud rate.
+    ///
+    /// Uses eight data bits, no parity, one stop bit and no flow control.
+    /// Use [`setup_uart`](Self::setup_uart) for other line settings.
     #[inline]
-    fn drop(&mut self) {
-        unsafe {
-            wiringXGC();
-        }
+    pub fn serial(
+        &self,
+        dev: impl Into<PathBuf>,
+        baud_rate: u32,
+    ) -> Result<SerialPort, WiringXError> {
+        self.setup_uart(dev.into(), SerialConfig::new(baud_rate))
     }
 }
 
@@ -222,9 +1116,22 @@ pub enum WiringXError {
     InitError(String),
     #[error("An unexpected error occured: {0}")]
     Other(String),
+    /// Gets returned when setting up WiringX for another platform than the one it is already set up for.
+    #[error("WiringX is already set up for {0}.")]
+    AlreadyInitialized(Platform),
+    /// Gets returned when using an instance of WiringX after it was shut down.
+    #[error("WiringX was shut down.")]
+    ShutDown,
+    /// Gets returned when shutting down while pins or interfaces are still alive.
+    #[error("WiringX can not be shut down while {0} pins or interfaces are still in use.")]
+    InUse(usize),
     /// A function was used with a pin that is not supported for the given platform.
     #[error("The given pin does not exist for this platform.")]
     InvalidPin,
+    /// Gets returned when setting up a pin with a number that is not valid for the platform and GPIO backend,
+    /// see [`WiringX::valid_gpio`].
+    #[error("Pin {pin} does not exist on {platform}.")]
+    NoSuchPin { pin: i32, platform: Platform },
     /// The provided pin already has an instance. Pins can only exist once.
     #[error("The given pin is already used. Pin instances can only exist once.")]
     PinUsed,
@@ -237,10 +1144,213 @@ pub enum WiringXError {
     /// Gets returned if the provided config for UART is not valid.
     #[error("The provided UART config is not valid: {0}")]
     InvalidUARTConfig(InvalidUARTConfig),
+    /// Gets returned when an operation did not finish within the given time.
+    #[error("The operation timed out.")]
+    TimedOut,
+    /// Gets returned when waiting for an edge on a pin, but none occurred within the timeout.
+    #[error("No interrupt occurred within the timeout.")]
+    InterruptTimedOut,
     /// Gets returned when a value is not accepted by the device.
     #[error("Failed to write value: Invalid argument")]
     InvalidArgument,
+    /// Gets returned when a pin can not detect the edges asked for.
+    #[error("Interrupt setup failed: {0}")]
+    Isr(#[from] IsrError),
+    /// Gets returned when the checksum sent by a device does not match the data, which got corrupted on the way.
+    #[error("The checksum of the data received from the device does not match.")]
+    Checksum,
+    /// Gets returned when a device reports that it can not deliver what was asked for.
+    #[error("{0}")]
+    DeviceFault(DeviceFault),
+    /// Gets returned when the thermocouple of a MAX31855 has a fault, see `Max31855::read_temperature`.
+    #[error("Thermocouple fault: {0}")]
+    Thermocouple(#[from] Max31855Fault),
+    /// Gets returned when a driver fails to communicate with its I2C device.
+    #[error("I2C communication failed: {0}")]
+    I2C(#[from] I2CError),
+    /// Gets returned when the system rejects an operation on a pin,
+    /// along with the error number it gave if any, like `libc::EINVAL` for a PWM period the chip can not do.
+    #[error("Pin {pin}: {operation} failed{}", errno_message(.errno))]
+    Pin {
+        pin: i32,
+        operation: PinOperation,
+        errno: Option<i32>,
+    },
+    /// Gets returned when the PWM period or duty of a pin is rejected, with the time tried.
+    /// If the time is outside the range the other of the two leaves for it, that range is given and the
+    /// error number is none. Otherwise the system rejected the time, the error number tells why and no range is
+    /// given, as the limits of the PWM controller are not known.
+    #[error(
+        "Pin {pin}: {operation} to {} ns failed{}{}",
+        .time.as_nanos(),
+        range_message(.accepted),
+        errno_message(.errno)
+    )]
+    PwmTime {
+        pin: i32,
+        operation: PinOperation,
+        time: Duration,
+        accepted: Option<RangeInclusive<Duration>>,
+        errno: Option<i32>,
+    },
     /// Io os error.
     #[error("IO error: {0}")]
     Io(io::Error),
 }
+
+/// Fault of the thermocouple of a MAX31855, see [`WiringXError::Thermocouple`].
+///
+/// Defined without the `drivers` feature, so the variants of [`WiringXError`] do not depend on features.
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum Max31855Fault {
+    /// The thermocouple is not connected or broken
+    #[error("The thermocouple is not connected.")]
+    OpenCircuit,
+    /// The thermocouple is shorted to ground
+    #[error("The thermocouple is shorted to ground.")]
+    ShortToGround,
+    /// The thermocouple is shorted to VCC
+    #[error("The thermocouple is shorted to VCC.")]
+    ShortToVcc,
+}
+
+/// A condition reported by a device, see [`WiringXError::DeviceFault`].
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeviceFault {
+    /// The device could not load its calibration
+    #[error("The device could not load its calibration.")]
+    NotCalibrated,
+    /// The quantity is beyond the range the device is set up for, like a saturated sensor
+    #[error("The measurement is beyond the range the device is set up for.")]
+    OutOfRange,
+    /// The buffer of the device overflowed, so data was lost
+    #[error("The buffer of the device overflowed, data was lost.")]
+    BufferOverflow,
+    /// The device did not acknowledge the data
+    #[error("The device did not acknowledge.")]
+    NoAcknowledge,
+}
+
+/// The operation on a pin that failed, see [`WiringXError::Pin`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PinOperation {
+    SetMode,
+    Read,
+    Write,
+    SetIsrMode,
+    SetPwmPeriod,
+    SetPwmDutyCycle,
+    SetPwmPolarity,
+    EnablePwm,
+}
+
+impl fmt::Display for PinOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::SetMode => "setting the mode",
+            Self::Read => "reading",
+            Self::Write => "writing",
+            Self::SetIsrMode => "setting the interrupt mode",
+            Self::SetPwmPeriod => "setting the PWM period",
+            Self::SetPwmDutyCycle => "setting the PWM duty cycle",
+            Self::SetPwmPolarity => "setting the PWM polarity",
+            Self::EnablePwm => "enabling or disabling PWM",
+        })
+    }
+}
+
+fn range_message(range: &Option<RangeInclusive<Duration>>) -> String {
+    range
+        .as_ref()
+        .map(|range| {
+            format!(
+                ", it has to be within {} to {} ns",
+                range.start().as_nanos(),
+                range.end().as_nanos()
+            )
+        })
+        .unwrap_or_default()
+}
+
+fn errno_message(errno: &Option<i32>) -> String {
+    errno
+        .map(|errno| format!(": {}", io::Error::from_raw_os_error(errno)))
+        .unwrap_or_default()
+}
+
+/// Cleans up the instance still set up when the process exits, like [`WiringX::shutdown`] without waiting for the pins,
+/// which revert from outputs to inputs.
+extern "C" fn cleanup_at_exit() {
+    // Another thread may hold the lock while the process exits, then there is no cleanup rather than a deadlock.
+    let Some(current) = WIRINGX.try_lock() else {
+        return;
+    };
+
+    if let Some(wiringx) = *current {
+        if wiringx.active.swap(false, Ordering::AcqRel) {
+            // A thread waiting for an interrupt without timeout would keep the process from exiting,
+            // then wiringX is left as it is.
+            if let Some(waits) = WAITS.try_write() {
+                wiringx.cleanup_locked(waits);
+            }
+        }
+    }
+}
+
+/// Runs a call into wiringX, returning the errno it left behind if it failed,
+/// none if it failed without a system call failing.
+///
+/// Calls are serialized, as wiringX keeps the pin modes in globals and sets a pin by reading and writing back
+/// the register of its whole bank, which would lose the writes of other threads to pins of the same bank.
+/// They are also serialized with cleaning wiringX up, after which they fail without being made.
+pub(crate) fn wiringx_call(call: impl FnOnce() -> c_int) -> Result<c_int, Option<i32>> {
+    let calls = CALLS.lock();
+    if !*calls {
+        return Err(None);
+    }
+
+    unsafe { *libc::__errno_location() = 0 };
+
+    let result = call();
+    if result >= 0 {
+        return Ok(result);
+    }
+
+    Err(io::Error::last_os_error()
+        .raw_os_error()
+        .filter(|&errno| errno != 0))
+}
+
+/// Runs a blocking wait for an interrupt in wiringX, which is not serialized with the other calls,
+/// but keeps wiringX from being cleaned up until it returns.
+///
+/// Returns [`ShutDown`](WiringXError::ShutDown) without waiting if wiringX is not set up anymore.
+pub(crate) fn wiringx_wait(wait: impl FnOnce() -> c_int) -> Result<c_int, WiringXError> {
+    let _waits = WAITS.read();
+    if !*CALLS.lock() {
+        return Err(WiringXError::ShutDown);
+    }
+
+    Ok(wait())
+}
+
+impl From<WiringXError> for io::Error {
+    fn from(error: WiringXError) -> Self {
+        match error {
+            WiringXError::Io(error) | WiringXError::Isr(IsrError::Io(error)) => error,
+            WiringXError::TimedOut | WiringXError::InterruptTimedOut => {
+                io::Error::new(io::ErrorKind::TimedOut, error)
+            }
+            WiringXError::Pin {
+                errno: Some(errno), ..
+            }
+            | WiringXError::PwmTime {
+                errno: Some(errno), ..
+            } => io::Error::new(io::Error::from_raw_os_error(errno).kind(), error),
+            WiringXError::PwmTime { errno: None, .. } => {
+                io::Error::new(io::ErrorKind::InvalidInput, error)
+            }
+            error => io::Error::other(error),
+        }
+    }
+}
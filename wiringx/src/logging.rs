@@ -0,0 +1,82 @@
+//! Forwarding the log messages of wiringX to the `log` or `tracing` crate, enabled with the feature of the same name.
+//!
+//! Without either, wiringX prints its warnings and errors to stderr.
+//! With both, messages go to `tracing` only, which can forward them to `log` itself.
+
+use std::{
+    borrow::Cow,
+    ffi::{c_char, c_int, CStr},
+};
+
+/// Severity of a wiringX message, from its syslog priority.
+#[derive(Debug, Clone, Copy)]
+enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+impl Level {
+    fn from_priority(priority: c_int) -> Self {
+        match priority {
+            ..=libc::LOG_ERR => Level::Error,
+            libc::LOG_WARNING => Level::Warn,
+            libc::LOG_NOTICE | libc::LOG_INFO => Level::Info,
+            _ => Level::Debug,
+        }
+    }
+}
+
+/// Log callback handed to wiringX, receiving the formatted messages.
+pub(crate) unsafe extern "C" fn forward(
+    priority: c_int,
+    file: *const c_char,
+    line: c_int,
+    message: *const c_char,
+) {
+    let text = |ptr: *const c_char| {
+        if ptr.is_null() {
+            Cow::Borrowed("")
+        } else {
+            unsafe { CStr::from_ptr(ptr) }.to_string_lossy()
+        }
+    };
+
+    emit(
+        Level::from_priority(priority),
+        &text(file),
+        line as u32,
+        &text(message),
+    );
+}
+
+#[cfg(feature = "tracing")]
+fn emit(level: Level, file: &str, line: u32, message: &str) {
+    match level {
+        Level::Error => tracing::error!(target: "wiringx", file, line, "{message}"),
+        Level::Warn => tracing::warn!(target: "wiringx", file, line, "{message}"),
+        Level::Info => tracing::info!(target: "wiringx", file, line, "{message}"),
+        Level::Debug => tracing::debug!(target: "wiringx", file, line, "{message}"),
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+fn emit(level: Level, file: &str, line: u32, message: &str) {
+    let level = match level {
+        Level::Error => log::Level::Error,
+        Level::Warn => log::Level::Warn,
+        Level::Info => log::Level::Info,
+        Level::Debug => log::Level::Debug,
+    };
+
+    log::logger().log(
+        &log::Record::builder()
+            .level(level)
+            .target("wiringx")
+            .file(Some(file))
+            .line(Some(line))
+            .args(format_args!("{message}"))
+            .build(),
+    );
+}
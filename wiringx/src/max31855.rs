@@ -0,0 +1,106 @@
+//! MAX31855 thermocouple converters.
+
+use crate::{BitOrder, Max31855Fault, Spi, SpiMode, WiringXError};
+
+const FAULT: u32 = 1 << 16;
+const SHORT_TO_VCC: u32 = 1 << 2;
+const SHORT_TO_GROUND: u32 = 1 << 1;
+const OPEN_CIRCUIT: u32 = 1 << 0;
+
+/// A MAX31855 converter for K type thermocouples on the SPI bus, measuring from -200 °C to 1350 °C.
+///
+/// The chip converts continuously, every read returns the latest conversion.
+/// It has no input, so only the clock and MISO lines need to be connected.
+///
+/// Example monitoring a kiln:
+/// ```no_run
+/// use wiringx::{Max31855, Platform, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+///
+/// let mut thermocouple = Max31855::new(wiringx.setup_spi(0, 4_000_000).unwrap()).unwrap();
+///
+/// let reading = thermocouple.read().unwrap();
+/// match reading.fault {
+///     Some(fault) => eprintln!("Thermocouple fault: {fault:?}"),
+///     None => println!("{} °C", reading.thermocouple),
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Max31855 {
+    spi: Spi,
+}
+
+impl Max31855 {
+    /// Takes ownership of the SPI bus, reconfiguring it to [`SpiMode::Mode0`] and [`BitOrder::MsbFirst`].
+    ///
+    /// The chip takes clock speeds up to 5 MHz.
+    pub fn new(mut spi: Spi) -> Result<Self, WiringXError> {
+        spi.set_mode(SpiMode::Mode0)?;
+        spi.set_bit_order(BitOrder::MsbFirst)?;
+
+        Ok(Self { spi })
+    }
+
+    /// Returns the SPI bus.
+    #[inline]
+    pub fn into_inner(self) -> Spi {
+        self.spi
+    }
+
+    /// Reads the latest conversion.
+    pub fn read(&mut self) -> Result<Max31855Reading, WiringXError> {
+        let mut frame = [0; 4];
+        self.spi.read(&mut frame)?;
+
+        Ok(decode(u32::from_be_bytes(frame)))
+    }
+
+    /// Reads the temperature at the tip of the thermocouple in °C.
+    ///
+    /// Returns [`Thermocouple`](WiringXError::Thermocouple) with the fault if the thermocouple has one.
+    pub fn read_temperature(&mut self) -> Result<f32, WiringXError> {
+        let reading = self.read()?;
+
+        match reading.fault {
+            None => Ok(reading.thermocouple),
+            Some(fault) => Err(fault.into()),
+        }
+    }
+}
+
+/// A conversion of a [`Max31855`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Max31855Reading {
+    /// Temperature at the tip of the thermocouple in °C, in steps of 0.25 °C, meaningless with a fault.
+    pub thermocouple: f32,
+    /// Temperature of the chip, where the thermocouple wires meet the board (the cold junction), in °C.
+    pub cold_junction: f32,
+    /// Fault of the thermocouple, none if it is fine.
+    pub fault: Option<Max31855Fault>,
+}
+
+/// Decodes the 32 bit frame, with the signed 14 bit thermocouple temperature in the upper bits
+/// and the signed 12 bit cold junction temperature from bit 4 on.
+fn decode(frame: u32) -> Max31855Reading {
+    let thermocouple = (frame as i32 >> 18) as f32 * 0.25;
+    let cold_junction = ((frame << 16) as i32 >> 20) as f32 * 0.0625;
+
+    let fault = if frame & FAULT == 0 {
+        None
+    } else if frame & OPEN_CIRCUIT != 0 {
+        Some(Max31855Fault::OpenCircuit)
+    } else if frame & SHORT_TO_GROUND != 0 {
+        Some(Max31855Fault::ShortToGround)
+    } else if frame & SHORT_TO_VCC != 0 {
+        Some(Max31855Fault::ShortToVcc)
+    } else {
+        None
+    };
+
+    Max31855Reading {
+        thermocouple,
+        cold_junction,
+        fault,
+    }
+}
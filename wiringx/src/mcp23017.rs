@@ -0,0 +1,398 @@
+//! MCP23017 16 bit I/O expander.
+
+use std::{
+    marker::PhantomData,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+use crate::{DigitalInput, DigitalOutput, Input, IsrMode, Output, Pin, Value, WiringXError, I2C};
+
+// Register addresses of port A in the default `IOCON.BANK = 0` layout, port B follows at the next address.
+const IODIR: i32 = 0x00;
+const GPINTEN: i32 = 0x04;
+const INTCON: i32 = 0x08;
+const IOCON: i32 = 0x0A;
+const GPPU: i32 = 0x0C;
+const INTF: i32 = 0x0E;
+const INTCAP: i32 = 0x10;
+const GPIO: i32 = 0x12;
+const OLAT: i32 = 0x14;
+
+/// Mirrors the interrupt outputs, so `INTA` and `INTB` both report changes of all 16 channels.
+const IOCON_MIRROR: u8 = 0x40;
+
+/// Time waited on the interrupt pin at once, so an edge missed between checks only delays the wakeup.
+const INTERRUPT_SLICE: Duration = Duration::from_millis(50);
+
+/// An MCP23017 I/O expander adding 16 GPIO channels over I2C.
+///
+/// Its channels are handed out as [`Mcp23017Pin`]s implementing [`DigitalOutput`] and [`DigitalInput`],
+/// just like native [`Pin`]s. Channels `0` - `7` are `GPA0` - `GPA7`, `8` - `15` are `GPB0` - `GPB7`.
+///
+/// Interrupts need the `INTA` or `INTB` output connected to a native input pin,
+/// see [`with_interrupt`](Self::with_interrupt).
+///
+/// Example blinking an LED on `GPA0` while waiting for a button on `GPB0`:
+/// ```no_run
+/// use wiringx::{Input, IsrMode, Mcp23017, Platform, WiringX};
+///
+/// use std::time::Duration;
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let i2c = wiringx.setup_i2c("/dev/i2c-1".into(), 0x20).unwrap();
+///
+/// let expander = Mcp23017::with_interrupt(i2c, wiringx.gpio_pin::<Input>(16).unwrap()).unwrap();
+///
+/// let mut led = expander.output_pin(0).unwrap();
+/// let button = expander.input_pin(8).unwrap();
+/// button.set_pull_up(true).unwrap();
+/// button.set_isr_mode(IsrMode::Falling).unwrap();
+///
+/// while button.wait_for_interrupt(Duration::from_millis(500)).is_err() {
+///     led.toggle().unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Mcp23017 {
+    inner: Arc<Mutex<Mcp23017Inner>>,
+    interrupt: Option<Arc<Pin<Input>>>,
+}
+
+#[derive(Debug)]
+struct Mcp23017Inner {
+    i2c: I2C,
+    /// Channels handed out as pins.
+    used: u16,
+    /// Cached output latches.
+    olat: u16,
+    /// Channels with interrupts enabled on rising and on falling edges.
+    rising: u16,
+    falling: u16,
+    /// Channels with an interrupt not yet picked up by their pin.
+    pending: u16,
+}
+
+impl Mcp23017 {
+    /// Initializes the expander with all channels as inputs, without interrupt support.
+    pub fn new(i2c: I2C) -> Result<Self, WiringXError> {
+        Self::init(i2c, None)
+    }
+
+    /// Initializes the expander with all channels as inputs, with its interrupt output connected to the pin.
+    ///
+    /// Both interrupt outputs report all channels, so either `INTA` or `INTB` can be used.
+    pub fn with_interrupt(i2c: I2C, interrupt: Pin<Input>) -> Result<Self, WiringXError> {
+        interrupt.set_isr_mode(IsrMode::Falling)?;
+
+        Self::init(i2c, Some(interrupt))
+    }
+
+    fn init(i2c: I2C, interrupt: Option<Pin<Input>>) -> Result<Self, WiringXError> {
+        i2c.write_reg8(IOCON, IOCON_MIRROR)?;
+
+        let mut inner = Mcp23017Inner {
+            i2c,
+            used: 0,
+            olat: 0,
+            rising: 0,
+            falling: 0,
+            pending: 0,
+        };
+        inner.write16(IODIR, 0xFFFF)?;
+        inner.write16(GPPU, 0x0000)?;
+        inner.write16(GPINTEN, 0x0000)?;
+        inner.write16(INTCON, 0x0000)?;
+        inner.write16(OLAT, 0x0000)?;
+
+        Ok(Self {
+            inner: Arc::new(Mutex::new(inner)),
+            interrupt: interrupt.map(Arc::new),
+        })
+    }
+
+    /// Returns the channel as output pin, driven low initially.
+    pub fn output_pin(&self, channel: u8) -> Result<Mcp23017Pin<Output>, WiringXError> {
+        let pin = self.take(channel)?;
+
+        let mut inner = self.inner.lock();
+        inner.olat &= !pin.mask();
+        let olat = inner.olat;
+        inner.write16(OLAT, olat)?;
+        inner.update(IODIR, pin.mask(), false)?;
+        drop(inner);
+
+        Ok(pin)
+    }
+
+    /// Returns the channel as input pin, without pull-up.
+    pub fn input_pin(&self, channel: u8) -> Result<Mcp23017Pin<Input>, WiringXError> {
+        let pin = self.take(channel)?;
+
+        let mut inner = self.inner.lock();
+        inner.update(IODIR, pin.mask(), true)?;
+        inner.update(GPPU, pin.mask(), false)?;
+        drop(inner);
+
+        Ok(pin)
+    }
+
+    /// Reads all 16 channels at once, `GPA0` in the least significant bit.
+    pub fn read_port(&self) -> Result<u16, WiringXError> {
+        self.inner.lock().read16(GPIO)
+    }
+
+    /// Writes the output latches of all 16 channels at once, `GPA0` in the least significant bit.
+    ///
+    /// Only channels configured as outputs drive their value.
+    pub fn write_port(&self, value: u16) -> Result<(), WiringXError> {
+        let mut inner = self.inner.lock();
+        inner.olat = value;
+        inner.write16(OLAT, value)
+    }
+
+    fn take<T>(&self, channel: u8) -> Result<Mcp23017Pin<T>, WiringXError> {
+        if channel > 15 {
+            return Err(WiringXError::InvalidPin);
+        }
+
+        let mut inner = self.inner.lock();
+        if inner.used & 1 << channel != 0 {
+            return Err(WiringXError::PinUsed);
+        }
+        inner.used |= 1 << channel;
+
+        Ok(Mcp23017Pin {
+            expander: self.clone(),
+            channel,
+            mode: PhantomData,
+        })
+    }
+}
+
+impl Mcp23017Inner {
+    fn read16(&self, register: i32) -> Result<u16, WiringXError> {
+        Ok(self.i2c.read_reg16(register)?)
+    }
+
+    fn write16(&mut self, register: i32, value: u16) -> Result<(), WiringXError> {
+        let [a, b] = value.to_le_bytes();
+        self.i2c.write_reg8(register, a)?;
+        self.i2c.write_reg8(register + 1, b)?;
+
+        Ok(())
+    }
+
+    /// Sets or clears the bits of the mask in the register pair.
+    fn update(&mut self, register: i32, mask: u16, set: bool) -> Result<(), WiringXError> {
+        let value = self.read16(register)?;
+        let value = if set { value | mask } else { value & !mask };
+
+        self.write16(register, value)
+    }
+
+    /// Picks up the flagged channels and marks those whose edge matches their interrupt mode as pending.
+    fn collect_interrupts(&mut self) -> Result<(), WiringXError> {
+        let flags = self.read16(INTF)?;
+        if flags == 0 {
+            return Ok(());
+        }
+
+        // Reading the captured values also clears the interrupt.
+        let captured = self.read16(INTCAP)?;
+
+        self.pending |= flags & ((captured & self.rising) | (!captured & self.falling));
+
+        Ok(())
+    }
+}
+
+/// A channel of an [`Mcp23017`], marked as either [`Input`] or [`Output`] like native [`Pin`]s.
+///
+/// Every channel can only be taken once, dropping the pin releases it.
+#[derive(Debug)]
+pub struct Mcp23017Pin<T> {
+    expander: Mcp23017,
+    channel: u8,
+    mode: PhantomData<T>,
+}
+
+impl<T> Mcp23017Pin<T> {
+    /// Returns the channel number of this pin, from `0` to `15`.
+    #[inline]
+    pub fn number(&self) -> u8 {
+        self.channel
+    }
+
+    #[inline]
+    fn mask(&self) -> u16 {
+        1 << self.channel
+    }
+
+    fn level(&self) -> Result<Value, WiringXError> {
+        let port = self.expander.inner.lock().read16(GPIO)?;
+
+        Ok(if port & self.mask() != 0 {
+            Value::High
+        } else {
+            Value::Low
+        })
+    }
+}
+
+impl Mcp23017Pin<Output> {
+    /// Writes a value to the pin.
+    pub fn write(&mut self, value: Value) -> Result<(), WiringXError> {
+        let mut inner = self.expander.inner.lock();
+
+        match value {
+            Value::High => inner.olat |= self.mask(),
+            Value::Low => inner.olat &= !self.mask(),
+        }
+        let olat = inner.olat;
+
+        inner.write16(OLAT, olat)
+    }
+
+    /// Toggles the pin to on if it was off or to off if it was on.
+    pub fn toggle(&mut self) -> Result<(), WiringXError> {
+        let value = self.read();
+        self.write(value.opposite())
+    }
+
+    /// Returns the value the pin is driven to.
+    pub fn read(&self) -> Value {
+        if self.expander.inner.lock().olat & self.mask() != 0 {
+            Value::High
+        } else {
+            Value::Low
+        }
+    }
+}
+
+impl Mcp23017Pin<Input> {
+    /// Reads the current state of the pin.
+    #[inline]
+    pub fn read(&self) -> Result<Value, WiringXError> {
+        self.level()
+    }
+
+    /// Enables or disables the internal 100 kΩ pull-up resistor.
+    pub fn set_pull_up(&self, enabled: bool) -> Result<(), WiringXError> {
+        self.expander
+            .inner
+            .lock()
+            .update(GPPU, self.mask(), enabled)
+    }
+
+    /// Sets on which edges [`wait_for_interrupt`](Self::wait_for_interrupt) returns.
+    ///
+    /// Returns [`Unsupported`](WiringXError::Unsupported) if the expander was created without interrupt pin.
+    pub fn set_isr_mode(&self, mode: IsrMode) -> Result<(), WiringXError> {
+        if self.expander.interrupt.is_none() {
+            return Err(WiringXError::Unsupported);
+        }
+
+        let (rising, falling) = match mode {
+            IsrMode::Rising => (true, false),
+            IsrMode::Falling => (false, true),
+            IsrMode::Both => (true, true),
+            IsrMode::None | IsrMode::Unknown => (false, false),
+        };
+
+        let mask = self.mask();
+        let mut inner = self.expander.inner.lock();
+        inner.rising = if rising {
+            inner.rising | mask
+        } else {
+            inner.rising & !mask
+        };
+        inner.falling = if falling {
+            inner.falling | mask
+        } else {
+            inner.falling & !mask
+        };
+        inner.pending &= !mask;
+
+        // The expander interrupts on every change, edges get filtered when collected.
+        inner.update(GPINTEN, mask, rising || falling)
+    }
+
+    /// Suspends the thread until an edge selected with [`set_isr_mode`](Self::set_isr_mode) was detected on this pin.
+    ///
+    /// Returns [`InterruptTimedOut`](WiringXError::InterruptTimedOut) if none happened within the timeout.
+    pub fn wait_for_interrupt(&self, timeout: Duration) -> Result<(), WiringXError> {
+        let interrupt = self
+            .expander
+            .interrupt
+            .as_ref()
+            .ok_or(WiringXError::Unsupported)?;
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            {
+                let mut inner = self.expander.inner.lock();
+                inner.collect_interrupts()?;
+
+                if inner.pending & self.mask() != 0 {
+                    inner.pending &= !self.mask();
+                    return Ok(());
+                }
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(WiringXError::InterruptTimedOut);
+            }
+
+            // The interrupt output stays low until the flags were collected, so only wait while it is high.
+            if interrupt.read() == Value::High {
+                let _ = interrupt.wait_for_interrupt(remaining.min(INTERRUPT_SLICE));
+            }
+        }
+    }
+}
+
+impl<T> Drop for Mcp23017Pin<T> {
+    fn drop(&mut self) {
+        let mask = self.mask();
+        let mut inner = self.expander.inner.lock();
+
+        inner.used &= !mask;
+        inner.rising &= !mask;
+        inner.falling &= !mask;
+        inner.pending &= !mask;
+        let _ = inner.update(GPINTEN, mask, false);
+    }
+}
+
+impl DigitalOutput for Mcp23017Pin<Output> {
+    #[inline]
+    fn write(&mut self, value: Value) -> Result<(), WiringXError> {
+        Mcp23017Pin::<Output>::write(self, value)
+    }
+
+    #[inline]
+    fn read(&self) -> Result<Value, WiringXError> {
+        Ok(Mcp23017Pin::<Output>::read(self))
+    }
+}
+
+impl DigitalInput for Mcp23017Pin<Input> {
+    #[inline]
+    fn read(&self) -> Result<Value, WiringXError> {
+        Mcp23017Pin::<Input>::read(self)
+    }
+
+    #[inline]
+    fn set_isr_mode(&self, mode: IsrMode) -> Result<(), WiringXError> {
+        Mcp23017Pin::<Input>::set_isr_mode(self, mode)
+    }
+
+    #[inline]
+    fn wait_for_interrupt(&self, timeout: Duration) -> Result<(), WiringXError> {
+        Mcp23017Pin::<Input>::wait_for_interrupt(self, timeout)
+    }
+}
@@ -0,0 +1,544 @@
+//! MCP2515 CAN bus controllers.
+
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{BitOrder, Input, IsrMode, Pin, Spi, SpiMode, Value, WiringXError};
+
+const RESET: u8 = 0xC0;
+const READ: u8 = 0x03;
+const WRITE: u8 = 0x02;
+const BIT_MODIFY: u8 = 0x05;
+const READ_STATUS: u8 = 0xA0;
+/// Reads a receive buffer from its identifier on, `| 0x04` for the second one.
+const READ_RX_BUFFER: u8 = 0x90;
+/// Loads a transmit buffer from its identifier on, `| 0x02` and `| 0x04` for the other ones.
+const LOAD_TX_BUFFER: u8 = 0x40;
+/// Requests to send the transmit buffers of the lower three bits.
+const REQUEST_TO_SEND: u8 = 0x80;
+
+const TEC: u8 = 0x1C;
+const REC: u8 = 0x1D;
+const CANSTAT: u8 = 0x0E;
+const CANCTRL: u8 = 0x0F;
+const CNF3: u8 = 0x28;
+const CNF2: u8 = 0x29;
+const CNF1: u8 = 0x2A;
+const CANINTE: u8 = 0x2B;
+const CANINTF: u8 = 0x2C;
+const EFLG: u8 = 0x2D;
+const TXB0CTRL: u8 = 0x30;
+const RXB0CTRL: u8 = 0x60;
+const RXB1CTRL: u8 = 0x70;
+const RXM0SIDH: u8 = 0x20;
+const RXF_SIDH: [u8; 6] = [0x00, 0x04, 0x08, 0x10, 0x14, 0x18];
+
+const MODE_MASK: u8 = 0xE0;
+const CONFIGURATION_MODE: u8 = 0x80;
+const CNF2_BTLMODE: u8 = 0x80;
+const TXB_TXREQ: u8 = 0x08;
+const RXB0_BUKT: u8 = 0x04;
+const INT_RX0: u8 = 0x01;
+const INT_RX1: u8 = 0x02;
+const SIDL_EXIDE: u8 = 0x08;
+const SIDL_SRR: u8 = 0x10;
+const DLC_RTR: u8 = 0x40;
+const EFLG_TXBO: u8 = 0x20;
+const EFLG_TXEP: u8 = 0x10;
+const EFLG_RXEP: u8 = 0x08;
+const EFLG_RX0OVR: u8 = 0x40;
+const EFLG_RX1OVR: u8 = 0x80;
+
+/// Longest time the controller takes to change its mode.
+const MODE_TIMEOUT: Duration = Duration::from_millis(10);
+
+/// An MCP2515 CAN 2.0B controller on the SPI bus, with its optional interrupt pin.
+///
+/// Gives boards access to a CAN bus through an external transceiver, without SocketCAN support in the kernel.
+/// The controller has three transmit buffers and two receive buffers,
+/// received frames pass through two masks and six acceptance filters, which accept all frames by default.
+///
+/// Example answering requests for the temperature on a 500 kbit/s bus with a 8 MHz crystal:
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use wiringx::{CanFrame, CanId, Input, Mcp2515, Platform, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+///
+/// let mut can = Mcp2515::new(
+///     wiringx.setup_spi(0, 8_000_000).unwrap(),
+///     8_000_000,
+///     500_000,
+///     Some(wiringx.gpio_pin::<Input>(15).unwrap()),
+/// )
+/// .unwrap();
+///
+/// loop {
+///     let request = can.receive_timeout(Duration::from_secs(60)).unwrap();
+///     if request.id() == CanId::Standard(0x100) {
+///         let response = CanFrame::new(CanId::Standard(0x101), &[21]).unwrap();
+///         can.send(&response, Duration::from_millis(100)).unwrap();
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Mcp2515 {
+    spi: Spi,
+    irq: Option<Pin<Input>>,
+    mode: Mcp2515Mode,
+}
+
+impl Mcp2515 {
+    /// Resets the controller and joins the bus in [`Normal`](Mcp2515Mode::Normal) mode at the bit rate.
+    ///
+    /// The oscillator is the frequency of the crystal on the module, usually 8 or 16 MHz.
+    /// The SPI controller is reconfigured to [`SpiMode::Mode0`] and [`BitOrder::MsbFirst`], it may run at up to 10 MHz.
+    pub fn new(
+        mut spi: Spi,
+        oscillator: u32,
+        bitrate: u32,
+        irq: Option<Pin<Input>>,
+    ) -> Result<Self, WiringXError> {
+        let timing = bit_timing(oscillator, bitrate).ok_or(WiringXError::InvalidArgument)?;
+
+        spi.set_mode(SpiMode::Mode0)?;
+        spi.set_bit_order(BitOrder::MsbFirst)?;
+
+        if let Some(irq) = &irq {
+            irq.set_isr_mode(IsrMode::Falling)?;
+        }
+
+        let mut can = Self {
+            spi,
+            irq,
+            mode: Mcp2515Mode::Normal,
+        };
+
+        // Resetting enters the configuration mode.
+        can.spi.write(&[RESET])?;
+        thread::sleep(Duration::from_millis(5));
+        if can.read_register(CANSTAT)? & MODE_MASK != CONFIGURATION_MODE {
+            return Err(WiringXError::Unsupported);
+        }
+
+        let [cnf1, cnf2, cnf3] = timing;
+        can.write_register(CNF1, cnf1)?;
+        can.write_register(CNF2, cnf2)?;
+        can.write_register(CNF3, cnf3)?;
+        can.write_register(RXB0CTRL, RXB0_BUKT)?;
+        can.write_register(RXB1CTRL, 0)?;
+        can.write_register(CANINTF, 0)?;
+        can.write_register(CANINTE, INT_RX0 | INT_RX1)?;
+
+        can.set_mode(Mcp2515Mode::Normal)?;
+
+        Ok(can)
+    }
+
+    /// Switches the operating mode.
+    pub fn set_mode(&mut self, mode: Mcp2515Mode) -> Result<(), WiringXError> {
+        self.request_mode(mode as u8)?;
+        self.mode = mode;
+
+        Ok(())
+    }
+
+    /// Returns the operating mode.
+    #[inline]
+    pub fn mode(&self) -> Mcp2515Mode {
+        self.mode
+    }
+
+    /// Sets the mask of the receive buffer `0` or `1`, bits set in it have to match the filters of the buffer.
+    ///
+    /// Standard masks only apply to standard identifiers and the first 11 bits of extended ones,
+    /// extended masks also to the lower 18 bits of extended identifiers.
+    pub fn set_mask(&mut self, buffer: u8, mask: CanId) -> Result<(), WiringXError> {
+        if buffer > 1 {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        let mut id = encode_id(mask)?;
+        // The bit marking extended identifiers has no meaning in masks.
+        id[1] &= !SIDL_EXIDE;
+
+        self.configure(|can| can.write_registers(RXM0SIDH + buffer * 4, &id))
+    }
+
+    /// Sets the acceptance filter from `0` to `5`, filters `0` and `1` belong to receive buffer `0`, the others to buffer `1`.
+    ///
+    /// A filter only matches frames of its kind of identifier.
+    pub fn set_filter(&mut self, filter: u8, id: CanId) -> Result<(), WiringXError> {
+        let address = *RXF_SIDH
+            .get(filter as usize)
+            .ok_or(WiringXError::InvalidArgument)?;
+        let id = encode_id(id)?;
+
+        self.configure(|can| can.write_registers(address, &id))
+    }
+
+    /// Queues the frame in a free transmit buffer, the controller sends it as soon as the bus is idle.
+    ///
+    /// Returns [`TimedOut`](WiringXError::TimedOut) if no buffer became free within the timeout,
+    /// which happens when no other node acknowledges the frames.
+    pub fn send(&mut self, frame: &CanFrame, timeout: Duration) -> Result<(), WiringXError> {
+        let deadline = Instant::now() + timeout;
+
+        let buffer = loop {
+            if let Some(buffer) = self.free_tx_buffer()? {
+                break buffer;
+            }
+
+            if Instant::now() > deadline {
+                return Err(WiringXError::TimedOut);
+            }
+
+            thread::sleep(Duration::from_micros(100));
+        };
+
+        let id = encode_id(frame.id)?;
+        let mut message = [0; 14];
+        message[0] = LOAD_TX_BUFFER | buffer << 1;
+        message[1..5].copy_from_slice(&id);
+        message[5] = frame.dlc | if frame.remote { DLC_RTR } else { 0 };
+        let len = frame.data().len();
+        message[6..6 + len].copy_from_slice(frame.data());
+
+        self.spi.write(&message[..6 + len])?;
+        self.spi.write(&[REQUEST_TO_SEND | 1 << buffer])
+    }
+
+    /// Returns the next received frame, none if both receive buffers are empty.
+    pub fn receive(&mut self) -> Result<Option<CanFrame>, WiringXError> {
+        let mut message = [READ_STATUS, 0];
+        self.spi.read_write(&mut message)?;
+        let status = message[1];
+
+        let buffer = if status & INT_RX0 != 0 {
+            0
+        } else if status & INT_RX1 != 0 {
+            1
+        } else {
+            return Ok(None);
+        };
+
+        // Reading the buffer this way clears its interrupt flag.
+        let mut message = [0; 14];
+        message[0] = READ_RX_BUFFER | buffer << 2;
+        self.spi.read_write(&mut message)?;
+
+        Ok(Some(decode_frame(&message[1..])))
+    }
+
+    /// Waits for the next received frame.
+    ///
+    /// Waits on the interrupt pin if there is one, otherwise polls the controller every millisecond.
+    /// Returns [`TimedOut`](WiringXError::TimedOut) if none arrived within the timeout.
+    pub fn receive_timeout(&mut self, timeout: Duration) -> Result<CanFrame, WiringXError> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            if let Some(frame) = self.receive()? {
+                return Ok(frame);
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(WiringXError::TimedOut);
+            }
+
+            match &self.irq {
+                Some(irq) if irq.read() == Value::High => {
+                    let _ = irq.wait_for_interrupt(remaining);
+                }
+                _ => thread::sleep(remaining.min(Duration::from_millis(1))),
+            }
+        }
+    }
+
+    /// Returns the error state of the controller on the bus.
+    pub fn bus_state(&mut self) -> Result<CanBusState, WiringXError> {
+        let flags = self.read_register(EFLG)?;
+
+        Ok(if flags & EFLG_TXBO != 0 {
+            CanBusState::BusOff
+        } else if flags & (EFLG_TXEP | EFLG_RXEP) != 0 {
+            CanBusState::ErrorPassive
+        } else {
+            CanBusState::ErrorActive
+        })
+    }
+
+    /// Returns the transmit and receive error counters.
+    pub fn error_counters(&mut self) -> Result<(u8, u8), WiringXError> {
+        Ok((self.read_register(TEC)?, self.read_register(REC)?))
+    }
+
+    /// Returns whether frames were lost because both receive buffers were full, and clears the flags.
+    pub fn take_overflow(&mut self) -> Result<bool, WiringXError> {
+        let overflow = self.read_register(EFLG)? & (EFLG_RX0OVR | EFLG_RX1OVR) != 0;
+        if overflow {
+            self.modify_register(EFLG, EFLG_RX0OVR | EFLG_RX1OVR, 0)?;
+        }
+
+        Ok(overflow)
+    }
+
+    fn free_tx_buffer(&mut self) -> Result<Option<u8>, WiringXError> {
+        for buffer in 0..3 {
+            if self.read_register(TXB0CTRL + buffer * 0x10)? & TXB_TXREQ == 0 {
+                return Ok(Some(buffer));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Runs the closure in configuration mode, then returns to the previous mode.
+    fn configure(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<(), WiringXError>,
+    ) -> Result<(), WiringXError> {
+        self.request_mode(CONFIGURATION_MODE)?;
+        let result = f(self);
+        self.request_mode(self.mode as u8)?;
+
+        result
+    }
+
+    fn request_mode(&mut self, mode: u8) -> Result<(), WiringXError> {
+        self.modify_register(CANCTRL, MODE_MASK, mode)?;
+
+        let deadline = Instant::now() + MODE_TIMEOUT;
+        while self.read_register(CANSTAT)? & MODE_MASK != mode {
+            // Leaving the sleep mode takes bus activity or wakes up in listen only mode.
+            if Instant::now() > deadline {
+                return Err(WiringXError::TimedOut);
+            }
+
+            thread::sleep(Duration::from_micros(100));
+        }
+
+        Ok(())
+    }
+
+    fn read_register(&mut self, register: u8) -> Result<u8, WiringXError> {
+        let mut message = [READ, register, 0];
+        self.spi.read_write(&mut message)?;
+
+        Ok(message[2])
+    }
+
+    #[inline]
+    fn write_register(&mut self, register: u8, value: u8) -> Result<(), WiringXError> {
+        self.write_registers(register, &[value])
+    }
+
+    fn write_registers(&mut self, register: u8, values: &[u8]) -> Result<(), WiringXError> {
+        let mut message = Vec::with_capacity(values.len() + 2);
+        message.extend_from_slice(&[WRITE, register]);
+        message.extend_from_slice(values);
+
+        self.spi.write(&message)
+    }
+
+    #[inline]
+    fn modify_register(&mut self, register: u8, mask: u8, value: u8) -> Result<(), WiringXError> {
+        self.spi.write(&[BIT_MODIFY, register, mask, value])
+    }
+}
+
+impl Drop for Mcp2515 {
+    fn drop(&mut self) {
+        let _ = self.request_mode(Mcp2515Mode::Sleep as u8);
+    }
+}
+
+/// Operating mode of an [`Mcp2515`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mcp2515Mode {
+    /// Sends, receives and acknowledges frames on the bus
+    #[default]
+    Normal = 0x00,
+    /// Low power mode, wakes up on bus activity
+    Sleep = 0x20,
+    /// Receives its own frames without touching the bus, for testing
+    Loopback = 0x40,
+    /// Receives frames without acknowledging them, to monitor the bus or detect its bit rate
+    ListenOnly = 0x60,
+}
+
+/// Error state of a CAN node, which moves on with the error counters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CanBusState {
+    /// Normal operation, errors are signalled actively
+    ErrorActive,
+    /// More than 127 errors counted, errors are only signalled passively
+    ErrorPassive,
+    /// More than 255 transmit errors counted, the node no longer takes part in the bus
+    BusOff,
+}
+
+/// Identifier of a CAN frame, which also sets its priority with lower values winning the bus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CanId {
+    /// 11 bit identifier of CAN 2.0A
+    Standard(u16),
+    /// 29 bit identifier of CAN 2.0B
+    Extended(u32),
+}
+
+/// A CAN 2.0 frame of up to 8 data bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CanFrame {
+    id: CanId,
+    remote: bool,
+    dlc: u8,
+    data: [u8; 8],
+}
+
+impl CanFrame {
+    /// Creates a data frame, returns [`InvalidArgument`](WiringXError::InvalidArgument)
+    /// if the identifier is out of range or there are more than 8 bytes.
+    pub fn new(id: CanId, data: &[u8]) -> Result<Self, WiringXError> {
+        if data.len() > 8 || !id_in_range(id) {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        let mut frame = Self {
+            id,
+            remote: false,
+            dlc: data.len() as u8,
+            data: [0; 8],
+        };
+        frame.data[..data.len()].copy_from_slice(data);
+
+        Ok(frame)
+    }
+
+    /// Creates a remote frame requesting the number of bytes from the node sending the identifier.
+    pub fn new_remote(id: CanId, len: u8) -> Result<Self, WiringXError> {
+        if len > 8 || !id_in_range(id) {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        Ok(Self {
+            id,
+            remote: true,
+            dlc: len,
+            data: [0; 8],
+        })
+    }
+
+    /// Returns the identifier.
+    #[inline]
+    pub fn id(&self) -> CanId {
+        self.id
+    }
+
+    /// Returns whether this is a remote frame.
+    #[inline]
+    pub fn is_remote(&self) -> bool {
+        self.remote
+    }
+
+    /// Returns the data length code, the number of data bytes or the requested ones of remote frames.
+    #[inline]
+    pub fn dlc(&self) -> u8 {
+        self.dlc
+    }
+
+    /// Returns the data, empty for remote frames.
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        if self.remote {
+            &[]
+        } else {
+            &self.data[..self.dlc as usize]
+        }
+    }
+}
+
+fn id_in_range(id: CanId) -> bool {
+    match id {
+        CanId::Standard(id) => id < 1 << 11,
+        CanId::Extended(id) => id < 1 << 29,
+    }
+}
+
+/// Encodes the identifier into the `SIDH`, `SIDL`, `EID8` and `EID0` registers.
+fn encode_id(id: CanId) -> Result<[u8; 4], WiringXError> {
+    if !id_in_range(id) {
+        return Err(WiringXError::InvalidArgument);
+    }
+
+    Ok(match id {
+        CanId::Standard(id) => [(id >> 3) as u8, (id << 5) as u8, 0, 0],
+        CanId::Extended(id) => [
+            (id >> 21) as u8,
+            ((id >> 18) as u8) << 5 | SIDL_EXIDE | (id >> 16) as u8 & 0x03,
+            (id >> 8) as u8,
+            id as u8,
+        ],
+    })
+}
+
+/// Decodes a frame from the `SIDH`, `SIDL`, `EID8`, `EID0`, `DLC` and data registers of a receive buffer.
+fn decode_frame(registers: &[u8]) -> CanFrame {
+    let standard = (registers[0] as u16) << 3 | (registers[1] >> 5) as u16;
+
+    let (id, remote) = if registers[1] & SIDL_EXIDE != 0 {
+        let extended = (standard as u32) << 18
+            | ((registers[1] & 0x03) as u32) << 16
+            | (registers[2] as u32) << 8
+            | registers[3] as u32;
+        (CanId::Extended(extended), registers[4] & DLC_RTR != 0)
+    } else {
+        (CanId::Standard(standard), registers[1] & SIDL_SRR != 0)
+    };
+
+    let dlc = (registers[4] & 0x0F).min(8);
+    let mut data = [0; 8];
+    data.copy_from_slice(&registers[5..13]);
+
+    CanFrame {
+        id,
+        remote,
+        dlc,
+        data,
+    }
+}
+
+/// Finds `CNF1`, `CNF2` and `CNF3` for the bit rate, with 8 to 20 time quanta per bit sampled at about 75 %.
+fn bit_timing(oscillator: u32, bitrate: u32) -> Option<[u8; 3]> {
+    if bitrate == 0 {
+        return None;
+    }
+
+    // More time quanta allow a more precise sample point, so they are tried first.
+    (8..=20u32).rev().find_map(|quanta| {
+        let divider = bitrate.checked_mul(quanta * 2)?;
+        if !oscillator.is_multiple_of(divider) {
+            return None;
+        }
+
+        let prescaler = oscillator / divider;
+        if !(1..=64).contains(&prescaler) {
+            return None;
+        }
+
+        let phase2 = (quanta / 4).max(2);
+        let remaining = quanta - 1 - phase2;
+        let propagation = remaining / 2;
+        let phase1 = remaining - propagation;
+
+        Some([
+            (prescaler - 1) as u8,
+            CNF2_BTLMODE | ((phase1 - 1) as u8) << 3 | (propagation - 1) as u8,
+            (phase2 - 1) as u8,
+        ])
+    })
+}
@@ -0,0 +1,144 @@
+//! MCP3008 8 channel 10 bit ADC.
+
+use std::sync::Arc;
+
+use crate::{AnalogInput, BitOrder, Spi, SpiMode, WiringXError};
+
+/// Highest conversion result of the 10 bit converter.
+pub const MCP3008_MAX: u16 = 1023;
+
+/// An MCP3008 analog to digital converter with 8 channels on the SPI bus, also covering the 4 channel MCP3004.
+///
+/// The controller should be clocked at most 1.35 MHz at 2.7 V or 3.6 MHz at 5 V supply.
+/// The converter can be cloned to share it, for example between threads,
+/// and hands out its channels as [`AnalogInput`]s with [`channel`](Self::channel).
+///
+/// Example reading a potentiometer on channel `0` with a 3.3 V reference:
+/// ```no_run
+/// use wiringx::{Mcp3008, Platform, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+///
+/// let adc = Mcp3008::new(wiringx.setup_spi(0, 1_000_000).unwrap(), 3.3).unwrap();
+///
+/// println!("{} of 1023, {:.2} V", adc.read(0).unwrap(), adc.read_voltage(0).unwrap());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Mcp3008 {
+    spi: Arc<Spi>,
+    reference: f32,
+}
+
+impl Mcp3008 {
+    /// Uses the converter on the SPI controller, with the voltage on its `VREF` pin.
+    ///
+    /// The controller is reconfigured to [`SpiMode::Mode0`] and [`BitOrder::MsbFirst`].
+    pub fn new(mut spi: Spi, reference: f32) -> Result<Self, WiringXError> {
+        if reference <= 0.0 {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        spi.set_mode(SpiMode::Mode0)?;
+        spi.set_bit_order(BitOrder::MsbFirst)?;
+
+        Ok(Self {
+            spi: Arc::new(spi),
+            reference,
+        })
+    }
+
+    /// Returns the reference voltage conversions are scaled with.
+    #[inline]
+    pub fn reference(&self) -> f32 {
+        self.reference
+    }
+
+    /// Returns the channel from `0` to `7` as analog input measured against ground.
+    pub fn channel(&self, channel: u8) -> Result<Mcp3008Channel, WiringXError> {
+        if channel > 7 {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        Ok(Mcp3008Channel {
+            adc: self.clone(),
+            channel,
+        })
+    }
+
+    /// Converts the voltage of the channel from `0` to `7` against ground, from `0` to `1023`.
+    #[inline]
+    pub fn read(&self, channel: u8) -> Result<u16, WiringXError> {
+        self.convert(channel, true)
+    }
+
+    /// Converts the voltage of the channel from `0` to `7` to volts.
+    pub fn read_voltage(&self, channel: u8) -> Result<f32, WiringXError> {
+        Ok(self.read(channel)? as f32 * self.reference / (MCP3008_MAX + 1) as f32)
+    }
+
+    /// Converts the voltage between a pair of channels.
+    ///
+    /// Pair `0` measures channel `0` against `1`, pair `1` channel `1` against `0`,
+    /// pair `2` channel `2` against `3` and so on up to pair `7`.
+    /// Results are `0` whenever the first channel is below the second.
+    #[inline]
+    pub fn read_differential(&self, pair: u8) -> Result<u16, WiringXError> {
+        self.convert(pair, false)
+    }
+
+    /// Converts all 8 channels against ground, one after another.
+    pub fn read_all(&self) -> Result<[u16; 8], WiringXError> {
+        let mut values = [0; 8];
+
+        for (channel, value) in values.iter_mut().enumerate() {
+            *value = self.read(channel as u8)?;
+        }
+
+        Ok(values)
+    }
+
+    fn convert(&self, channel: u8, single_ended: bool) -> Result<u16, WiringXError> {
+        if channel > 7 {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        // The start bit goes last in the first byte, so the 10 result bits end byte aligned.
+        let mode = if single_ended { 0x80 } else { 0x00 };
+        let mut data = [0x01, mode | channel << 4, 0x00];
+        self.spi.read_write(&mut data)?;
+
+        Ok(u16::from(data[1] & 0x03) << 8 | u16::from(data[2]))
+    }
+}
+
+/// A channel of an [`Mcp3008`], measured against ground.
+#[derive(Debug, Clone)]
+pub struct Mcp3008Channel {
+    adc: Mcp3008,
+    channel: u8,
+}
+
+impl Mcp3008Channel {
+    /// Returns the channel number, from `0` to `7`.
+    #[inline]
+    pub fn number(&self) -> u8 {
+        self.channel
+    }
+}
+
+impl AnalogInput for Mcp3008Channel {
+    #[inline]
+    fn read_raw(&self) -> Result<i32, WiringXError> {
+        Ok(self.adc.read(self.channel)?.into())
+    }
+
+    #[inline]
+    fn max_raw(&self) -> i32 {
+        MCP3008_MAX.into()
+    }
+
+    #[inline]
+    fn read_voltage(&self) -> Result<f32, WiringXError> {
+        self.adc.read_voltage(self.channel)
+    }
+}
@@ -0,0 +1,310 @@
+//! Metrics of the pins in the Prometheus text format, enabled with the `metrics` feature.
+//!
+//! Once collecting is started with [`serve`] or [`start`], the levels written to and read from GPIO [`Pin`](crate::Pin)s,
+//! how often they changed after the first one, the detected edges and the duty cycles of [`PwmPin`](crate::PwmPin)s are counted.
+//! Sensor readings are added by the application with [`set_reading`].
+//!
+//! | Metric | Type | Labels |
+//! |---|---|---|
+//! | `wiringx_pin_level` | gauge, `0` or `1` | `pin` |
+//! | `wiringx_pin_changes_total` | counter | `pin` |
+//! | `wiringx_pin_interrupts_total` | counter, `rate()` gives the interrupt rate | `pin` |
+//! | `wiringx_pwm_duty_cycle` | gauge, from 0.0 - 1.0 | `pin` |
+//! | `wiringx_reading` | gauge | `source`, `quantity` |
+//!
+//! Example serving the metrics on port `9100` to be scraped at `/metrics`:
+//! ```no_run
+//! use std::{thread, time::Duration};
+//!
+//! use wiringx::{metrics, Output, Platform, WiringX};
+//!
+//! let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+//! let _server = metrics::serve("0.0.0.0:9100").unwrap();
+//!
+//! let mut pin = wiringx.gpio_pin::<Output>(0).unwrap();
+//!
+//! loop {
+//!     pin.toggle();
+//!     metrics::set_reading("cpu", "temperature", 48.2);
+//!     thread::sleep(Duration::from_secs(1));
+//! }
+//! ```
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    io::{self, BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use parking_lot::Mutex;
+
+use crate::{Value, WiringXError};
+
+static COLLECTING: AtomicBool = AtomicBool::new(false);
+static METRICS: Mutex<Metrics> = Mutex::new(Metrics {
+    pins: BTreeMap::new(),
+    duty_cycles: BTreeMap::new(),
+    readings: BTreeMap::new(),
+});
+
+/// How often the listener checks whether it should stop.
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long a scraper may take to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+struct Metrics {
+    pins: BTreeMap<i32, PinMetrics>,
+    duty_cycles: BTreeMap<i32, f32>,
+    readings: BTreeMap<(String, String), f64>,
+}
+
+#[derive(Debug, Default)]
+struct PinMetrics {
+    level: Option<Value>,
+    changes: u64,
+    interrupts: u64,
+}
+
+/// Starts collecting the metrics, without serving them, for exposing [`render`] some other way.
+#[inline]
+pub fn start() {
+    COLLECTING.store(true, Ordering::Release);
+}
+
+/// Returns whether the metrics are collected, so callers can skip gathering what only gets collected.
+#[inline]
+pub(crate) fn is_collecting() -> bool {
+    COLLECTING.load(Ordering::Acquire)
+}
+
+/// Records the level written to or read from the pin, counting it as change if it differs from the last one.
+#[inline]
+pub(crate) fn level(pin: i32, value: Value) {
+    if !is_collecting() {
+        return;
+    }
+
+    let mut metrics = METRICS.lock();
+    let pin = metrics.pins.entry(pin).or_default();
+    if pin.level.is_some_and(|level| level != value) {
+        pin.changes += 1;
+    }
+
+    pin.level = Some(value);
+}
+
+/// Counts an edge detected on the pin.
+#[inline]
+pub(crate) fn interrupt(pin: i32) {
+    if !is_collecting() {
+        return;
+    }
+
+    METRICS.lock().pins.entry(pin).or_default().interrupts += 1;
+}
+
+/// Records the duty cycle set on the PWM pin.
+#[inline]
+pub(crate) fn duty_cycle(pin: i32, duty_cycle: f32) {
+    if !is_collecting() {
+        return;
+    }
+
+    METRICS.lock().duty_cycles.insert(pin, duty_cycle);
+}
+
+/// Sets the last reading of the quantity measured by the source, like `temperature` of `bme280`.
+///
+/// Readings are kept even before collecting is started.
+pub fn set_reading(source: &str, quantity: &str, value: f64) {
+    METRICS
+        .lock()
+        .readings
+        .insert((source.to_string(), quantity.to_string()), value);
+}
+
+/// Returns the metrics collected so far in the Prometheus text format.
+pub fn render() -> String {
+    let metrics = METRICS.lock();
+    let mut text = String::new();
+
+    let _ = writeln!(
+        text,
+        "# HELP wiringx_pin_level Last level written to or read from the GPIO pin.\n# TYPE wiringx_pin_level gauge"
+    );
+    for (pin, metrics) in &metrics.pins {
+        if let Some(level) = metrics.level {
+            let _ = writeln!(
+                text,
+                "wiringx_pin_level{{pin=\"{pin}\"}} {}",
+                u8::from(level == Value::High)
+            );
+        }
+    }
+
+    let _ = writeln!(
+        text,
+        "# HELP wiringx_pin_changes_total Changes of the level of the GPIO pin since it was first seen.\n# TYPE wiringx_pin_changes_total counter"
+    );
+    for (pin, metrics) in &metrics.pins {
+        let _ = writeln!(
+            text,
+            "wiringx_pin_changes_total{{pin=\"{pin}\"}} {}",
+            metrics.changes
+        );
+    }
+
+    let _ = writeln!(
+        text,
+        "# HELP wiringx_pin_interrupts_total Edges detected on the GPIO pin.\n# TYPE wiringx_pin_interrupts_total counter"
+    );
+    for (pin, metrics) in &metrics.pins {
+        let _ = writeln!(
+            text,
+            "wiringx_pin_interrupts_total{{pin=\"{pin}\"}} {}",
+            metrics.interrupts
+        );
+    }
+
+    let _ = writeln!(
+        text,
+        "# HELP wiringx_pwm_duty_cycle Duty cycle of the PWM pin.\n# TYPE wiringx_pwm_duty_cycle gauge"
+    );
+    for (pin, duty_cycle) in &metrics.duty_cycles {
+        let _ = writeln!(text, "wiringx_pwm_duty_cycle{{pin=\"{pin}\"}} {duty_cycle}");
+    }
+
+    let _ = writeln!(
+        text,
+        "# HELP wiringx_reading Last sensor reading.\n# TYPE wiringx_reading gauge"
+    );
+    for ((source, quantity), value) in &metrics.readings {
+        let _ = writeln!(
+            text,
+            "wiringx_reading{{source=\"{}\",quantity=\"{}\"}} {}",
+            escape_label(source),
+            escape_label(quantity),
+            sample(*value)
+        );
+    }
+
+    text
+}
+
+fn sample(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Starts collecting the metrics and serves them over HTTP at `/metrics` on the address, like `0.0.0.0:9100`.
+///
+/// The requests are answered one after another on a thread, until the returned [`MetricsServer`] is dropped.
+pub fn serve(address: impl ToSocketAddrs) -> Result<MetricsServer, WiringXError> {
+    let listener = TcpListener::bind(address).map_err(WiringXError::Io)?;
+    listener.set_nonblocking(true).map_err(WiringXError::Io)?;
+    let address = listener.local_addr().map_err(WiringXError::Io)?;
+
+    start();
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread = thread::spawn({
+        let stop = stop.clone();
+
+        move || {
+            while !stop.load(Ordering::Acquire) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        let _ = respond(stream);
+                    }
+                    // Nothing to accept, or the connection was aborted before.
+                    Err(_) => thread::sleep(ACCEPT_INTERVAL),
+                }
+            }
+        }
+    });
+
+    Ok(MetricsServer {
+        address,
+        stop,
+        thread: Some(thread),
+    })
+}
+
+fn respond(stream: TcpStream) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+
+    let mut reader = BufReader::new(stream);
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+
+    // The headers are not needed, but have to be read before answering.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && header.trim_end() != "" {
+        header.clear();
+    }
+
+    let mut parts = request.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics")) => ("200 OK", render()),
+        (Some("GET"), Some(_)) => ("404 Not Found", String::new()),
+        _ => ("405 Method Not Allowed", String::new()),
+    };
+
+    let mut stream = reader.into_inner();
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+/// The HTTP listener started with [`serve`], which stops when dropped.
+///
+/// The metrics keep being collected after it stopped.
+#[derive(Debug)]
+pub struct MetricsServer {
+    address: SocketAddr,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MetricsServer {
+    /// Returns the address the listener is bound to, with the port chosen by the system if `0` was given.
+    #[inline]
+    pub fn local_addr(&self) -> SocketAddr {
+        self.address
+    }
+}
+
+impl Drop for MetricsServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
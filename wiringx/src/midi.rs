@@ -0,0 +1,123 @@
+//! MIDI output over a serial port.
+
+use crate::{SerialConfig, SerialPort, WiringXError};
+
+/// Baud rate of the MIDI serial protocol.
+pub const MIDI_BAUD_RATE: u32 = 31250;
+
+/// Sends MIDI messages over a serial port, for example to drive a synthesizer.
+///
+/// Channels are numbered `0` - `15`, all other values are 7 bit wide, from `0` to `127`.
+///
+/// Example playing middle C on the first channel:
+/// ```no_run
+/// use wiringx::{Midi, Platform, WiringX};
+///
+/// use std::{thread, time::Duration};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let serial = wiringx.serial("/dev/ttyS1", 31250).unwrap();
+///
+/// let mut midi = Midi::new(serial).unwrap();
+///
+/// midi.note_on(0, 60, 100).unwrap();
+/// thread::sleep(Duration::from_millis(500));
+/// midi.note_off(0, 60, 0).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct Midi {
+    serial: SerialPort,
+}
+
+impl Midi {
+    /// Takes ownership of the serial port and configures it for MIDI, 31250 baud with 8N1 framing.
+    pub fn new(mut serial: SerialPort) -> Result<Self, WiringXError> {
+        serial.configure(SerialConfig::new(MIDI_BAUD_RATE))?;
+
+        Ok(Self { serial })
+    }
+
+    /// Returns the serial port.
+    #[inline]
+    pub fn into_inner(self) -> SerialPort {
+        self.serial
+    }
+
+    /// Starts playing a note.
+    #[inline]
+    pub fn note_on(&mut self, channel: u8, note: u8, velocity: u8) -> Result<(), WiringXError> {
+        self.channel_message(0x90, channel, &[note, velocity])
+    }
+
+    /// Stops playing a note.
+    #[inline]
+    pub fn note_off(&mut self, channel: u8, note: u8, velocity: u8) -> Result<(), WiringXError> {
+        self.channel_message(0x80, channel, &[note, velocity])
+    }
+
+    /// Changes the pressure of a note that is already playing.
+    #[inline]
+    pub fn aftertouch(&mut self, channel: u8, note: u8, pressure: u8) -> Result<(), WiringXError> {
+        self.channel_message(0xA0, channel, &[note, pressure])
+    }
+
+    /// Sets the controller to the given value, for example controller `7` for the channel volume.
+    #[inline]
+    pub fn control_change(
+        &mut self,
+        channel: u8,
+        controller: u8,
+        value: u8,
+    ) -> Result<(), WiringXError> {
+        self.channel_message(0xB0, channel, &[controller, value])
+    }
+
+    /// Selects the instrument of the channel.
+    #[inline]
+    pub fn program_change(&mut self, channel: u8, program: u8) -> Result<(), WiringXError> {
+        self.channel_message(0xC0, channel, &[program])
+    }
+
+    /// Bends the pitch of the channel, from `-8192` to `8191` with `0` being the center.
+    pub fn pitch_bend(&mut self, channel: u8, value: i16) -> Result<(), WiringXError> {
+        if !(-8192..=8191).contains(&value) {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        let value = (value + 8192) as u16;
+
+        self.channel_message(0xE0, channel, &[(value & 0x7F) as u8, (value >> 7) as u8])
+    }
+
+    /// Stops all notes playing on the channel.
+    #[inline]
+    pub fn all_notes_off(&mut self, channel: u8) -> Result<(), WiringXError> {
+        self.control_change(channel, 123, 0)
+    }
+
+    /// Sends raw MIDI bytes, for example system exclusive messages.
+    pub fn send(&mut self, bytes: &[u8]) -> Result<(), WiringXError> {
+        let mut written = 0;
+        while written < bytes.len() {
+            written += self.serial.write(&bytes[written..])?;
+        }
+
+        Ok(())
+    }
+
+    fn channel_message(
+        &mut self,
+        status: u8,
+        channel: u8,
+        data: &[u8],
+    ) -> Result<(), WiringXError> {
+        if channel > 15 || data.iter().any(|byte| *byte > 127) {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        let mut message = [status | channel, 0, 0];
+        message[1..=data.len()].copy_from_slice(data);
+
+        self.send(&message[..=data.len()])
+    }
+}
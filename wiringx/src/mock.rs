@@ -0,0 +1,323 @@
+//! In-memory GPIO and PWM for running applications without hardware, enabled with the `mock` feature.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    io,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use parking_lot::{Condvar, Mutex};
+
+use crate::{
+    gpio::{monotonic_now, LineBackend},
+    pwm::PwmBackend,
+    Edge, EdgeEvent, IsrMode, Polarity, Pull, Value, WiringXError,
+};
+
+/// Simulated board the pins of [`GpioBackend::Mock`](crate::GpioBackend::Mock) are driven by.
+///
+/// Levels of inputs are scripted with [`set_level`](Self::set_level), firing interrupts on their edges,
+/// while the levels of outputs and the state of PWM pins can be inspected.
+/// Every GPIO and PWM number is valid. Like the rest of [`WiringX`](crate::WiringX) the board
+/// exists once per process, so tests running in parallel should use different pin numbers.
+///
+/// Example testing a button handler:
+/// ```
+/// use std::time::Duration;
+///
+/// use wiringx::{GpioBackend, Input, IsrMode, Output, Platform, Value, WiringX};
+///
+/// let wiringx = WiringX::with_gpio_backend(Platform::Generic, GpioBackend::Mock).unwrap();
+/// let board = wiringx.mock_board().unwrap();
+///
+/// let button = wiringx.gpio_pin::<Input>(3).unwrap();
+/// let mut led = wiringx.gpio_pin::<Output>(4).unwrap();
+/// button.set_isr_mode(IsrMode::Rising).unwrap();
+///
+/// board.set_level(3, Value::High);
+/// if button.wait_for_event(Duration::from_millis(10)).is_ok() {
+///     led.toggle();
+/// }
+///
+/// assert_eq!(board.level(4), Value::High);
+/// ```
+#[derive(Debug, Default)]
+pub struct MockBoard {
+    state: Mutex<MockState>,
+    changed: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    pins: HashMap<i32, MockPin>,
+    pwms: HashMap<i32, MockPwm>,
+}
+
+#[derive(Debug, Default)]
+struct MockPin {
+    level: Value,
+    output: bool,
+    mode: Option<IsrMode>,
+    events: VecDeque<EdgeEvent>,
+}
+
+/// State of a simulated PWM pin, see [`MockBoard::pwm`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MockPwm {
+    /// Period of a cycle.
+    pub period: Duration,
+    /// Time of a cycle the signal is high.
+    pub duty: Duration,
+    /// Polarity of the signal.
+    pub polarity: Polarity,
+    /// Whether the signal is output.
+    pub enabled: bool,
+}
+
+impl Default for MockPwm {
+    fn default() -> Self {
+        Self {
+            period: Duration::ZERO,
+            duty: Duration::ZERO,
+            polarity: Polarity::Normal,
+            enabled: false,
+        }
+    }
+}
+
+impl MockBoard {
+    /// Creates a board with all pins low.
+    #[inline]
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drives the pin from the outside, like a connected device would.
+    ///
+    /// A change of the level queues an edge for the pin, if its interrupt mode includes it.
+    pub fn set_level(&self, number: i32, level: Value) {
+        let mut state = self.state.lock();
+        let pin = state.pins.entry(number).or_default();
+
+        if pin.level == level {
+            return;
+        }
+        pin.level = level;
+
+        let edge = match level {
+            Value::High => Edge::Rising,
+            Value::Low => Edge::Falling,
+        };
+        let detected = matches!(
+            (pin.mode, edge),
+            (Some(IsrMode::Both), _)
+                | (Some(IsrMode::Rising), Edge::Rising)
+                | (Some(IsrMode::Falling), Edge::Falling)
+        );
+
+        if detected {
+            pin.events.push_back(EdgeEvent {
+                edge,
+                timestamp: monotonic_now(),
+            });
+            self.changed.notify_all();
+        }
+    }
+
+    /// Returns the level of the pin, driven by itself as output or from the outside as input.
+    #[inline]
+    pub fn level(&self, number: i32) -> Value {
+        self.state
+            .lock()
+            .pins
+            .get(&number)
+            .map(|pin| pin.level)
+            .unwrap_or_default()
+    }
+
+    /// Returns whether the pin is currently an output.
+    #[inline]
+    pub fn is_output(&self, number: i32) -> bool {
+        self.state
+            .lock()
+            .pins
+            .get(&number)
+            .is_some_and(|pin| pin.output)
+    }
+
+    /// Returns the state of the PWM pin, none if it was never set up.
+    #[inline]
+    pub fn pwm(&self, number: i32) -> Option<MockPwm> {
+        self.state.lock().pwms.get(&number).copied()
+    }
+
+    pub(crate) fn write(&self, number: i32, value: Value) {
+        self.state.lock().pins.entry(number).or_default().level = value;
+    }
+
+    pub(crate) fn set_input(&self, number: i32, mode: IsrMode) -> Result<(), WiringXError> {
+        if mode == IsrMode::Unknown {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        let mut state = self.state.lock();
+        let pin = state.pins.entry(number).or_default();
+        pin.output = false;
+        pin.mode = (mode != IsrMode::None).then_some(mode);
+        pin.events.clear();
+
+        Ok(())
+    }
+
+    pub(crate) fn set_output(&self, number: i32, value: Value) {
+        let mut state = self.state.lock();
+        let pin = state.pins.entry(number).or_default();
+        pin.output = true;
+        pin.mode = None;
+        pin.level = value;
+        pin.events.clear();
+    }
+
+    pub(crate) fn clear_events(&self, number: i32) {
+        if let Some(pin) = self.state.lock().pins.get_mut(&number) {
+            pin.events.clear();
+        }
+    }
+
+    pub(crate) fn read_event(&self, number: i32, timeout: Duration) -> Option<EdgeEvent> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.state.lock();
+
+        loop {
+            if let Some(event) = state
+                .pins
+                .get_mut(&number)
+                .and_then(|pin| pin.events.pop_front())
+            {
+                return Some(event);
+            }
+
+            if self.changed.wait_until(&mut state, deadline).timed_out() {
+                return None;
+            }
+        }
+    }
+}
+
+impl PwmBackend for MockBoard {
+    /// Sets the PWM period in ns like wiringX, failing with `EINVAL` below the duty cycle like the kernel.
+    fn set_period(&self, number: i32, period: i64) -> io::Result<()> {
+        let mut state = self.state.lock();
+        let pwm = state.pwms.entry(number).or_default();
+
+        match u64::try_from(period) {
+            Ok(period) if Duration::from_nanos(period) >= pwm.duty => {
+                pwm.period = Duration::from_nanos(period);
+                Ok(())
+            }
+            _ => Err(io::Error::from_raw_os_error(libc::EINVAL)),
+        }
+    }
+
+    /// Sets the PWM duty cycle in ns like wiringX, failing with `EINVAL` above the period like the kernel.
+    fn set_duty(&self, number: i32, duty: i64) -> io::Result<()> {
+        let mut state = self.state.lock();
+        let pwm = state.pwms.entry(number).or_default();
+
+        match u64::try_from(duty) {
+            Ok(duty) if Duration::from_nanos(duty) <= pwm.period => {
+                pwm.duty = Duration::from_nanos(duty);
+                Ok(())
+            }
+            _ => Err(io::Error::from_raw_os_error(libc::EINVAL)),
+        }
+    }
+
+    fn set_polarity(&self, number: i32, polarity: i32) -> io::Result<()> {
+        let polarity = match polarity {
+            0 => Polarity::Normal,
+            1 => Polarity::Inversed,
+            _ => return Err(io::Error::from_raw_os_error(libc::EINVAL)),
+        };
+
+        self.state.lock().pwms.entry(number).or_default().polarity = polarity;
+
+        Ok(())
+    }
+
+    fn enable(&self, number: i32, enabled: i32) -> io::Result<()> {
+        self.state.lock().pwms.entry(number).or_default().enabled = enabled != 0;
+
+        Ok(())
+    }
+}
+
+/// A pin of the [`MockBoard`].
+#[derive(Debug)]
+pub(crate) struct MockLine {
+    board: Arc<MockBoard>,
+    number: i32,
+}
+
+impl MockLine {
+    /// Makes the pin of the board an input.
+    pub(crate) fn input(board: Arc<MockBoard>, number: i32) -> Self {
+        let _ = board.set_input(number, IsrMode::None);
+
+        Self { board, number }
+    }
+
+    /// Makes the pin of the board an output, driving it low.
+    pub(crate) fn output(board: Arc<MockBoard>, number: i32) -> Self {
+        board.set_output(number, Value::Low);
+
+        Self { board, number }
+    }
+}
+
+impl LineBackend for MockLine {
+    #[inline]
+    fn read(&self) -> Result<Value, WiringXError> {
+        Ok(self.board.level(self.number))
+    }
+
+    #[inline]
+    fn write(&self, value: Value) -> Result<(), WiringXError> {
+        self.board.write(self.number, value);
+        Ok(())
+    }
+
+    #[inline]
+    fn set_input(&self, mode: IsrMode) -> Result<(), WiringXError> {
+        self.board.set_input(self.number, mode)
+    }
+
+    #[inline]
+    fn set_output(&self, value: Value) -> Result<(), WiringXError> {
+        self.board.set_output(self.number, value);
+        Ok(())
+    }
+
+    /// Sets the level the resistor pulls the pin to, as if nothing was connected.
+    fn set_pull(&self, pull: Pull) -> Result<(), WiringXError> {
+        match pull {
+            Pull::Up => self.board.set_level(self.number, Value::High),
+            Pull::Down => self.board.set_level(self.number, Value::Low),
+            Pull::None => {}
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn clear_events(&self) -> Result<(), WiringXError> {
+        self.board.clear_events(self.number);
+        Ok(())
+    }
+
+    #[inline]
+    fn read_event(&self, timeout: Duration) -> Result<Option<EdgeEvent>, WiringXError> {
+        Ok(self.board.read_event(self.number, timeout))
+    }
+}
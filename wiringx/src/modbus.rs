@@ -0,0 +1,387 @@
+//! Modbus RTU master over a serial port.
+//!
+//! Example reading two holding registers of the device with address `1`:
+//! ```no_run
+//! use wiringx::{modbus::ModbusMaster, Platform, WiringX};
+//!
+//! let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+//! let serial = wiringx.serial("/dev/ttyS1", 9600).unwrap();
+//!
+//! let mut modbus = ModbusMaster::new(serial);
+//!
+//! let registers = modbus.read_holding_registers(1, 0x0000, 2).unwrap();
+//! println!("{registers:?}");
+//!
+//! modbus.write_single_coil(1, 0x0010, true).unwrap();
+//! ```
+
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use thiserror::Error;
+
+use crate::{SerialPort, WiringXError};
+
+const READ_COILS: u8 = 0x01;
+const READ_DISCRETE_INPUTS: u8 = 0x02;
+const READ_HOLDING_REGISTERS: u8 = 0x03;
+const READ_INPUT_REGISTERS: u8 = 0x04;
+const WRITE_SINGLE_COIL: u8 = 0x05;
+const WRITE_SINGLE_REGISTER: u8 = 0x06;
+const WRITE_MULTIPLE_REGISTERS: u8 = 0x10;
+
+/// Calculates the Modbus CRC16 checksum of the given bytes.
+///
+/// The checksum is appended to frames in little endian byte order.
+pub fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0xFFFF;
+
+    for byte in data {
+        crc ^= *byte as u16;
+        for _ in 0..8 {
+            if crc & 1 == 1 {
+                crc = (crc >> 1) ^ 0xA001;
+            } else {
+                crc >>= 1;
+            }
+        }
+    }
+
+    crc
+}
+
+/// A Modbus RTU master sending requests to devices on the serial line.
+///
+/// Keeps the silent interval of 3.5 characters between frames required by the RTU framing.
+#[derive(Debug)]
+pub struct ModbusMaster {
+    serial: SerialPort,
+    timeout: Duration,
+    frame_gap: Duration,
+    last_frame: Instant,
+}
+
+impl ModbusMaster {
+    /// Takes ownership of the serial port, using its current baud rate to time the frames.
+    ///
+    /// Responses are awaited for one second by default.
+    pub fn new(serial: SerialPort) -> Self {
+        let frame_gap = frame_gap(serial.baud_rate());
+
+        Self {
+            serial,
+            timeout: Duration::from_secs(1),
+            frame_gap,
+            last_frame: Instant::now(),
+        }
+    }
+
+    /// Sets how long to wait for a response from a device.
+    #[inline]
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Returns how long to wait for a response from a device.
+    #[inline]
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Returns the serial port.
+    #[inline]
+    pub fn into_inner(self) -> SerialPort {
+        self.serial
+    }
+
+    /// Reads `count` coils, from `1` to `2000`, starting at the given address.
+    pub fn read_coils(
+        &mut self,
+        slave: u8,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<bool>, ModbusError> {
+        self.read_bits(slave, READ_COILS, address, count)
+    }
+
+    /// Reads `count` discrete inputs, from `1` to `2000`, starting at the given address.
+    pub fn read_discrete_inputs(
+        &mut self,
+        slave: u8,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<bool>, ModbusError> {
+        self.read_bits(slave, READ_DISCRETE_INPUTS, address, count)
+    }
+
+    /// Reads `count` holding registers, from `1` to `125`, starting at the given address.
+    pub fn read_holding_registers(
+        &mut self,
+        slave: u8,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<u16>, ModbusError> {
+        self.read_registers(slave, READ_HOLDING_REGISTERS, address, count)
+    }
+
+    /// Reads `count` input registers, from `1` to `125`, starting at the given address.
+    pub fn read_input_registers(
+        &mut self,
+        slave: u8,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<u16>, ModbusError> {
+        self.read_registers(slave, READ_INPUT_REGISTERS, address, count)
+    }
+
+    /// Switches the coil at the given address on or off.
+    ///
+    /// Slave address `0` broadcasts the request to all devices without waiting for a response.
+    pub fn write_single_coil(
+        &mut self,
+        slave: u8,
+        address: u16,
+        value: bool,
+    ) -> Result<(), ModbusError> {
+        let value: u16 = if value { 0xFF00 } else { 0x0000 };
+
+        let mut request = vec![slave, WRITE_SINGLE_COIL];
+        request.extend_from_slice(&address.to_be_bytes());
+        request.extend_from_slice(&value.to_be_bytes());
+
+        self.write_request(&request)
+    }
+
+    /// Writes the value to the holding register at the given address.
+    ///
+    /// Slave address `0` broadcasts the request to all devices without waiting for a response.
+    pub fn write_single_register(
+        &mut self,
+        slave: u8,
+        address: u16,
+        value: u16,
+    ) -> Result<(), ModbusError> {
+        let mut request = vec![slave, WRITE_SINGLE_REGISTER];
+        request.extend_from_slice(&address.to_be_bytes());
+        request.extend_from_slice(&value.to_be_bytes());
+
+        self.write_request(&request)
+    }
+
+    /// Writes `1` to `123` values to consecutive holding registers starting at the given address.
+    ///
+    /// Slave address `0` broadcasts the request to all devices without waiting for a response.
+    pub fn write_multiple_registers(
+        &mut self,
+        slave: u8,
+        address: u16,
+        values: &[u16],
+    ) -> Result<(), ModbusError> {
+        if values.is_empty() || values.len() > 123 {
+            return Err(ModbusError::InvalidRequest);
+        }
+
+        let mut request = vec![slave, WRITE_MULTIPLE_REGISTERS];
+        request.extend_from_slice(&address.to_be_bytes());
+        request.extend_from_slice(&(values.len() as u16).to_be_bytes());
+        request.push(values.len() as u8 * 2);
+        for value in values {
+            request.extend_from_slice(&value.to_be_bytes());
+        }
+
+        self.write_request(&request)
+    }
+
+    fn read_bits(
+        &mut self,
+        slave: u8,
+        function: u8,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<bool>, ModbusError> {
+        if slave == 0 || count == 0 || count > 2000 {
+            return Err(ModbusError::InvalidRequest);
+        }
+
+        let data = self.read_request(slave, function, address, count)?;
+        if data.len() != (count as usize).div_ceil(8) {
+            return Err(ModbusError::InvalidResponse);
+        }
+
+        Ok((0..count as usize)
+            .map(|i| data[i / 8] >> (i % 8) & 1 == 1)
+            .collect())
+    }
+
+    fn read_registers(
+        &mut self,
+        slave: u8,
+        function: u8,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<u16>, ModbusError> {
+        if slave == 0 || count == 0 || count > 125 {
+            return Err(ModbusError::InvalidRequest);
+        }
+
+        let data = self.read_request(slave, function, address, count)?;
+        if data.len() != count as usize * 2 {
+            return Err(ModbusError::InvalidResponse);
+        }
+
+        Ok(data
+            .chunks_exact(2)
+            .map(|word| u16::from_be_bytes([word[0], word[1]]))
+            .collect())
+    }
+
+    /// Sends a read request and returns the data bytes of the response.
+    fn read_request(
+        &mut self,
+        slave: u8,
+        function: u8,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<u8>, ModbusError> {
+        let mut request = vec![slave, function];
+        request.extend_from_slice(&address.to_be_bytes());
+        request.extend_from_slice(&count.to_be_bytes());
+
+        self.send(&request)?;
+
+        let mut byte_count = [0];
+        let header = self.receive_header(slave, function)?;
+        self.serial
+            .read_exact_timeout(&mut byte_count, self.timeout)?;
+
+        let mut data = vec![0; byte_count[0] as usize];
+        self.serial.read_exact_timeout(&mut data, self.timeout)?;
+
+        let mut frame = header.to_vec();
+        frame.push(byte_count[0]);
+        frame.extend_from_slice(&data);
+        self.receive_crc(&frame)?;
+
+        Ok(data)
+    }
+
+    /// Sends a write request, which gets echoed in the first four data bytes of the response.
+    fn write_request(&mut self, request: &[u8]) -> Result<(), ModbusError> {
+        self.send(request)?;
+
+        let (slave, function) = (request[0], request[1]);
+        if slave == 0 {
+            return Ok(());
+        }
+
+        let header = self.receive_header(slave, function)?;
+        let mut echo = [0; 4];
+        self.serial.read_exact_timeout(&mut echo, self.timeout)?;
+
+        let mut frame = header.to_vec();
+        frame.extend_from_slice(&echo);
+        self.receive_crc(&frame)?;
+
+        if echo != request[2..6] {
+            return Err(ModbusError::InvalidResponse);
+        }
+
+        Ok(())
+    }
+
+    /// Waits for the silent interval, then sends the request with its checksum appended.
+    fn send(&mut self, request: &[u8]) -> Result<(), ModbusError> {
+        let elapsed = self.last_frame.elapsed();
+        if elapsed < self.frame_gap {
+            thread::sleep(self.frame_gap - elapsed);
+        }
+
+        // Drop leftovers of earlier, possibly broken, responses.
+        self.serial.clear();
+
+        let mut frame = request.to_vec();
+        frame.extend_from_slice(&crc16(request).to_le_bytes());
+
+        let mut written = 0;
+        while written < frame.len() {
+            written += self.serial.write(&frame[written..])?;
+        }
+        self.serial.flush()?;
+
+        self.last_frame = Instant::now();
+
+        Ok(())
+    }
+
+    /// Reads the address and function code of a response, handling exception responses.
+    fn receive_header(&mut self, slave: u8, function: u8) -> Result<[u8; 2], ModbusError> {
+        let mut header = [0; 2];
+        self.serial.read_exact_timeout(&mut header, self.timeout)?;
+
+        if header[0] != slave {
+            return Err(ModbusError::InvalidResponse);
+        }
+
+        if header[1] == function | 0x80 {
+            let mut code = [0];
+            self.serial.read_exact_timeout(&mut code, self.timeout)?;
+            self.receive_crc(&[header[0], header[1], code[0]])?;
+
+            return Err(ModbusError::Exception(code[0]));
+        }
+
+        if header[1] != function {
+            return Err(ModbusError::InvalidResponse);
+        }
+
+        Ok(header)
+    }
+
+    /// Reads the checksum following the frame and compares it.
+    fn receive_crc(&mut self, frame: &[u8]) -> Result<(), ModbusError> {
+        let mut crc = [0; 2];
+        self.serial.read_exact_timeout(&mut crc, self.timeout)?;
+
+        self.last_frame = Instant::now();
+
+        if u16::from_le_bytes(crc) != crc16(frame) {
+            Err(ModbusError::Crc)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Returns the silent interval of 3.5 characters of 11 bits at the given baud rate.
+///
+/// Fixed to 1.75 milliseconds above 19200 baud, as recommended by the specification.
+fn frame_gap(baud_rate: u32) -> Duration {
+    if baud_rate > 19200 || baud_rate == 0 {
+        Duration::from_micros(1750)
+    } else {
+        Duration::from_micros(38_500_000 / baud_rate as u64)
+    }
+}
+
+/// Errors when communicating with a Modbus device.
+#[derive(Error, Debug)]
+pub enum ModbusError {
+    /// Gets returned when the serial port fails, or the device did not respond in time.
+    #[error("Serial communication failed: {0}")]
+    Serial(#[from] WiringXError),
+    /// Gets returned when the checksum of the response does not match its content.
+    #[error("The response checksum does not match.")]
+    Crc,
+    /// Gets returned when the device answers with an exception code,
+    /// for example `2` for an illegal data address.
+    #[error("The device responded with exception code {0}.")]
+    Exception(u8),
+    /// Gets returned when the response does not belong to the request.
+    #[error("The response does not match the request.")]
+    InvalidResponse,
+    /// Gets returned when the request exceeds the limits of the protocol.
+    #[error("The request exceeds the limits of Modbus.")]
+    InvalidRequest,
+}
@@ -0,0 +1,272 @@
+//! PIR motion sensors.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use crate::{Input, IsrMode, Pin, Value, WiringXError};
+
+/// Time waited on the pin at once, so an edge missed between checks only delays the event.
+const INTERRUPT_SLICE: Duration = Duration::from_millis(50);
+
+/// A passive infrared motion sensor like the HC-SR501, whose output goes high while it detects motion.
+///
+/// The sensor needs up to a minute after power-up until its output is reliable, events are only reported after that warm-up.
+/// Short low phases of the output while someone keeps moving are suppressed,
+/// so a [`Cleared`](MotionEvent::Cleared) only follows once the output stayed low for the clear delay.
+///
+/// Events can be waited for with [`wait_for_event`](Self::wait_for_event), iterated with [`events`](Self::events)
+/// or handed to a callback on a background thread with [`on_event`](Self::on_event).
+///
+/// Example switching on a light while someone is in the room:
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use wiringx::{Input, MotionEvent, MotionSensor, Output, Platform, Value, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+///
+/// let mut light = wiringx.gpio_pin::<Output>(14).unwrap();
+/// let mut sensor = MotionSensor::new(
+///     wiringx.gpio_pin::<Input>(15).unwrap(),
+///     Duration::from_secs(60),
+/// )
+/// .unwrap();
+/// sensor.set_clear_delay(Duration::from_secs(30));
+///
+/// for event in sensor.events() {
+///     match event.unwrap() {
+///         MotionEvent::Motion => light.write(Value::High),
+///         MotionEvent::Cleared => light.write(Value::Low),
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct MotionSensor {
+    pin: Pin<Input>,
+    ready_at: Instant,
+    clear_delay: Duration,
+    motion: bool,
+}
+
+impl MotionSensor {
+    /// Takes ownership of the pin connected to the sensor output, ignoring it until the warm-up elapsed from now.
+    ///
+    /// The clear delay starts at 2 s.
+    pub fn new(pin: Pin<Input>, warm_up: Duration) -> Result<Self, WiringXError> {
+        pin.set_isr_mode(IsrMode::Both)?;
+
+        Ok(Self {
+            pin,
+            ready_at: Instant::now() + warm_up,
+            clear_delay: Duration::from_secs(2),
+            motion: false,
+        })
+    }
+
+    /// Returns the pin.
+    #[inline]
+    pub fn into_inner(self) -> Pin<Input> {
+        self.pin
+    }
+
+    /// Sets how long the output has to stay low before motion counts as cleared.
+    #[inline]
+    pub fn set_clear_delay(&mut self, delay: Duration) {
+        self.clear_delay = delay;
+    }
+
+    /// Returns how long the output has to stay low before motion counts as cleared.
+    #[inline]
+    pub fn clear_delay(&self) -> Duration {
+        self.clear_delay
+    }
+
+    /// Returns whether the warm-up elapsed.
+    #[inline]
+    pub fn is_ready(&self) -> bool {
+        Instant::now() >= self.ready_at
+    }
+
+    /// Returns whether motion was reported and not cleared yet.
+    #[inline]
+    pub fn is_motion(&self) -> bool {
+        self.motion
+    }
+
+    /// Waits for the next event, after the warm-up.
+    ///
+    /// Returns [`TimedOut`](WiringXError::TimedOut) if none occurred within the timeout.
+    pub fn wait_for_event(&mut self, timeout: Duration) -> Result<MotionEvent, WiringXError> {
+        let deadline = Instant::now() + timeout;
+
+        if !self.is_ready() {
+            if deadline < self.ready_at {
+                thread::sleep(deadline.saturating_duration_since(Instant::now()));
+                return Err(WiringXError::TimedOut);
+            }
+
+            thread::sleep(self.ready_at.saturating_duration_since(Instant::now()));
+        }
+
+        loop {
+            match (self.motion, self.pin.read()) {
+                (false, Value::High) => {
+                    self.motion = true;
+                    return Ok(MotionEvent::Motion);
+                }
+                (true, Value::Low) => {
+                    // Motion is only cleared if the output does not go high again during the delay.
+                    let clear_at = Instant::now() + self.clear_delay;
+                    if self.wait_for_level(Value::High, clear_at.min(deadline)) {
+                        continue;
+                    }
+
+                    if Instant::now() >= clear_at {
+                        self.motion = false;
+                        return Ok(MotionEvent::Cleared);
+                    }
+                }
+                (motion, _) => {
+                    let level = if motion { Value::Low } else { Value::High };
+                    if self.wait_for_level(level, deadline) {
+                        continue;
+                    }
+                }
+            }
+
+            if Instant::now() >= deadline {
+                return Err(WiringXError::TimedOut);
+            }
+        }
+    }
+
+    /// Returns an endless iterator waiting for the events one after another.
+    #[inline]
+    pub fn events(&mut self) -> MotionEvents<'_> {
+        MotionEvents { sensor: self }
+    }
+
+    /// Waits for events on a background thread and calls the callback with each of them,
+    /// until the returned watcher is stopped or dropped.
+    pub fn on_event<F>(mut self, mut callback: F) -> MotionWatcher
+    where
+        F: FnMut(MotionEvent) + Send + 'static,
+    {
+        let running = Arc::new(AtomicBool::new(true));
+
+        let thread = {
+            let running = running.clone();
+
+            thread::spawn(move || {
+                while running.load(Ordering::Acquire) {
+                    match self.wait_for_event(INTERRUPT_SLICE) {
+                        Ok(event) => callback(event),
+                        Err(WiringXError::TimedOut) => {}
+                        Err(e) => {
+                            running.store(false, Ordering::Release);
+                            return Err(e);
+                        }
+                    }
+                }
+
+                Ok(self)
+            })
+        };
+
+        MotionWatcher {
+            running,
+            thread: Some(thread),
+        }
+    }
+
+    /// Waits until the pin has the level or the deadline passed, returning whether it has the level.
+    fn wait_for_level(&self, level: Value, deadline: Instant) -> bool {
+        loop {
+            if self.pin.read() == level {
+                return true;
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return false;
+            }
+
+            let _ = self.pin.wait_for_interrupt(remaining.min(INTERRUPT_SLICE));
+        }
+    }
+}
+
+/// Endless iterator over the events of a [`MotionSensor`], see [`MotionSensor::events`].
+#[derive(Debug)]
+pub struct MotionEvents<'a> {
+    sensor: &'a mut MotionSensor,
+}
+
+impl Iterator for MotionEvents<'_> {
+    type Item = Result<MotionEvent, WiringXError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.sensor.wait_for_event(Duration::from_secs(3600)) {
+                Err(WiringXError::TimedOut) => continue,
+                result => return Some(result),
+            }
+        }
+    }
+}
+
+/// Calls a callback with the events of a [`MotionSensor`] on a background thread, see [`MotionSensor::on_event`].
+#[derive(Debug)]
+pub struct MotionWatcher {
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<Result<MotionSensor, WiringXError>>>,
+}
+
+impl MotionWatcher {
+    /// Returns whether the background thread still waits for events.
+    ///
+    /// Watching ends when reading the pin fails, [`stop`](Self::stop) returns the error.
+    #[inline]
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Acquire)
+    }
+
+    /// Stops watching and returns the sensor, or the error that ended watching early.
+    pub fn stop(mut self) -> Result<MotionSensor, WiringXError> {
+        self.join()
+    }
+
+    fn join(&mut self) -> Result<MotionSensor, WiringXError> {
+        self.running.store(false, Ordering::Release);
+
+        match self.thread.take() {
+            Some(thread) => thread.join().map_err(|_| {
+                WiringXError::Other("The motion sensor thread panicked.".to_string())
+            })?,
+            None => Err(WiringXError::Other(
+                "The motion sensor was already returned.".to_string(),
+            )),
+        }
+    }
+}
+
+impl Drop for MotionWatcher {
+    fn drop(&mut self) {
+        let _ = self.join();
+    }
+}
+
+/// Event of a [`MotionSensor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MotionEvent {
+    /// Motion started
+    Motion,
+    /// No motion for the clear delay
+    Cleared,
+}
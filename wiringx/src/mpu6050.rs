@@ -0,0 +1,471 @@
+//! MPU6050 accelerometer and gyroscope, with orientation filters.
+
+use std::{thread, time::Duration};
+
+use crate::{DeviceFault, WiringXError, I2C};
+
+const SMPLRT_DIV: u8 = 0x19;
+const CONFIG: u8 = 0x1A;
+const GYRO_CONFIG: u8 = 0x1B;
+const ACCEL_CONFIG: u8 = 0x1C;
+const FIFO_EN: u8 = 0x23;
+const INT_STATUS: u8 = 0x3A;
+const ACCEL_XOUT_H: u8 = 0x3B;
+const TEMP_OUT_H: u8 = 0x41;
+const USER_CTRL: u8 = 0x6A;
+const PWR_MGMT_1: u8 = 0x6B;
+const FIFO_COUNT_H: u8 = 0x72;
+const FIFO_R_W: u8 = 0x74;
+const WHO_AM_I: u8 = 0x75;
+
+const DEVICE_RESET: u8 = 0x80;
+/// Clocks the sensor from the PLL of the X gyroscope, more stable than the internal oscillator.
+const CLOCK_PLL_X: u8 = 0x01;
+const USER_CTRL_FIFO_EN: u8 = 0x40;
+const USER_CTRL_FIFO_RESET: u8 = 0x04;
+/// Writes accelerometer and gyroscope samples to the FIFO.
+const FIFO_EN_ACCEL_GYRO: u8 = 0x78;
+const INT_STATUS_FIFO_OVERFLOW: u8 = 0x10;
+
+/// Bytes of one accelerometer and gyroscope sample in the FIFO.
+const FIFO_SAMPLE_LEN: usize = 12;
+
+/// An InvenSense MPU6050 inertial measurement unit with a 3 axis accelerometer and a 3 axis gyroscope.
+///
+/// Samples are scaled to g and degrees per second according to the configured ranges.
+/// Orientation can be estimated from them with a [`ComplementaryFilter`] or a [`MadgwickFilter`].
+///
+/// Example printing the orientation on the I2C bus at address `0x68`:
+/// ```no_run
+/// use wiringx::{MadgwickFilter, Mpu6050, Platform, WiringX};
+///
+/// use std::time::Instant;
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let i2c = wiringx.setup_i2c("/dev/i2c-1".into(), 0x68).unwrap();
+///
+/// let mut imu = Mpu6050::new(i2c).unwrap();
+/// let mut filter = MadgwickFilter::new(0.1);
+///
+/// let mut last = Instant::now();
+/// loop {
+///     let sample = imu.read().unwrap();
+///     let orientation = filter.update(&sample, last.elapsed());
+///     last = Instant::now();
+///
+///     println!("{orientation:?}");
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Mpu6050 {
+    i2c: I2C,
+    accel_range: AccelRange,
+    gyro_range: GyroRange,
+}
+
+impl Mpu6050 {
+    /// Resets and wakes up the sensor, with ±2 g and ±250 °/s ranges and the low pass filter off.
+    pub fn new(i2c: I2C) -> Result<Self, WiringXError> {
+        // Some clones answer with their own id, but behave the same.
+        let id = i2c.read_reg8(WHO_AM_I as i32)?;
+        if id != 0x68 && id != 0x98 {
+            return Err(WiringXError::Unsupported);
+        }
+
+        let mut imu = Self {
+            i2c,
+            accel_range: AccelRange::G2,
+            gyro_range: GyroRange::Dps250,
+        };
+
+        imu.write(PWR_MGMT_1, DEVICE_RESET)?;
+        thread::sleep(Duration::from_millis(100));
+        imu.write(PWR_MGMT_1, CLOCK_PLL_X)?;
+
+        Ok(imu)
+    }
+
+    /// Sets the measuring range of the accelerometer, trading resolution for range.
+    pub fn set_accel_range(&mut self, range: AccelRange) -> Result<(), WiringXError> {
+        self.write(ACCEL_CONFIG, (range as u8) << 3)?;
+        self.accel_range = range;
+
+        Ok(())
+    }
+
+    /// Returns the measuring range of the accelerometer.
+    #[inline]
+    pub fn accel_range(&self) -> AccelRange {
+        self.accel_range
+    }
+
+    /// Sets the measuring range of the gyroscope, trading resolution for range.
+    pub fn set_gyro_range(&mut self, range: GyroRange) -> Result<(), WiringXError> {
+        self.write(GYRO_CONFIG, (range as u8) << 3)?;
+        self.gyro_range = range;
+
+        Ok(())
+    }
+
+    /// Returns the measuring range of the gyroscope.
+    #[inline]
+    pub fn gyro_range(&self) -> GyroRange {
+        self.gyro_range
+    }
+
+    /// Sets the digital low pass filter of both sensors, trading delay for noise.
+    #[inline]
+    pub fn set_dlpf(&mut self, dlpf: Mpu6050Dlpf) -> Result<(), WiringXError> {
+        self.write(CONFIG, dlpf as u8)
+    }
+
+    /// Sets the rate samples are taken at to the gyroscope output rate divided by `divider + 1`.
+    ///
+    /// The gyroscope outputs at 8 kHz with the low pass filter off and at 1 kHz with it on.
+    #[inline]
+    pub fn set_sample_rate_divider(&mut self, divider: u8) -> Result<(), WiringXError> {
+        self.write(SMPLRT_DIV, divider)
+    }
+
+    /// Reads the latest raw values of the accelerometer and gyroscope.
+    pub fn read_raw(&mut self) -> Result<Mpu6050Raw, WiringXError> {
+        let mut data = [0; 14];
+        self.i2c.read_reg_bytes(ACCEL_XOUT_H, &mut data)?;
+
+        // The temperature sits between the accelerometer and the gyroscope.
+        let word = |i: usize| i16::from_be_bytes([data[i], data[i + 1]]);
+
+        Ok(Mpu6050Raw {
+            accel: [word(0), word(2), word(4)],
+            gyro: [word(8), word(10), word(12)],
+        })
+    }
+
+    /// Reads the latest sample of the accelerometer and gyroscope.
+    pub fn read(&mut self) -> Result<Mpu6050Sample, WiringXError> {
+        let raw = self.read_raw()?;
+
+        Ok(self.scale(raw))
+    }
+
+    /// Reads the temperature of the die in degrees Celsius.
+    pub fn read_temperature(&mut self) -> Result<f32, WiringXError> {
+        let mut data = [0; 2];
+        self.i2c.read_reg_bytes(TEMP_OUT_H, &mut data)?;
+
+        Ok(i16::from_be_bytes(data) as f32 / 340.0 + 36.53)
+    }
+
+    /// Enables or disables buffering samples in the 1024 byte FIFO at the sample rate,
+    /// so none get lost between reads. Clears the FIFO.
+    pub fn set_fifo_enabled(&mut self, enabled: bool) -> Result<(), WiringXError> {
+        self.write(USER_CTRL, USER_CTRL_FIFO_RESET)?;
+
+        if enabled {
+            self.write(FIFO_EN, FIFO_EN_ACCEL_GYRO)?;
+            self.write(USER_CTRL, USER_CTRL_FIFO_EN)
+        } else {
+            self.write(FIFO_EN, 0)?;
+            self.write(USER_CTRL, 0)
+        }
+    }
+
+    /// Reads all complete samples buffered in the FIFO in one burst, oldest first.
+    ///
+    /// Returns [`BufferOverflow`](DeviceFault::BufferOverflow) and clears the FIFO if it overflowed, because samples got lost.
+    pub fn read_fifo(&mut self) -> Result<Vec<Mpu6050Sample>, WiringXError> {
+        if self.i2c.read_reg8(INT_STATUS as i32)? & INT_STATUS_FIFO_OVERFLOW != 0 {
+            self.write(USER_CTRL, USER_CTRL_FIFO_EN | USER_CTRL_FIFO_RESET)?;
+
+            return Err(WiringXError::DeviceFault(DeviceFault::BufferOverflow));
+        }
+
+        let mut count = [0; 2];
+        self.i2c.read_reg_bytes(FIFO_COUNT_H, &mut count)?;
+        let len = u16::from_be_bytes(count) as usize / FIFO_SAMPLE_LEN * FIFO_SAMPLE_LEN;
+
+        if len == 0 {
+            return Ok(Vec::new());
+        }
+
+        // Reading the FIFO register repeatedly pops the next bytes.
+        let mut data = vec![0; len];
+        self.i2c.read_reg_bytes(FIFO_R_W, &mut data)?;
+
+        Ok(data
+            .chunks_exact(FIFO_SAMPLE_LEN)
+            .map(|sample| {
+                let word = |i: usize| i16::from_be_bytes([sample[i], sample[i + 1]]);
+
+                self.scale(Mpu6050Raw {
+                    accel: [word(0), word(2), word(4)],
+                    gyro: [word(6), word(8), word(10)],
+                })
+            })
+            .collect())
+    }
+
+    fn scale(&self, raw: Mpu6050Raw) -> Mpu6050Sample {
+        let accel = self.accel_range.sensitivity();
+        let gyro = self.gyro_range.sensitivity();
+
+        Mpu6050Sample {
+            accel: raw.accel.map(|value| value as f32 / accel),
+            gyro: raw.gyro.map(|value| value as f32 / gyro),
+        }
+    }
+
+    fn write(&mut self, register: u8, value: u8) -> Result<(), WiringXError> {
+        self.i2c.write_reg8(register as i32, value)?;
+
+        Ok(())
+    }
+}
+
+/// Raw values of an [`Mpu6050`], in the order X, Y, Z.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mpu6050Raw {
+    /// Raw acceleration.
+    pub accel: [i16; 3],
+    /// Raw angular rate.
+    pub gyro: [i16; 3],
+}
+
+/// A scaled sample of an [`Mpu6050`], in the order X, Y, Z.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mpu6050Sample {
+    /// Acceleration in g.
+    pub accel: [f32; 3],
+    /// Angular rate in degrees per second.
+    pub gyro: [f32; 3],
+}
+
+/// Measuring range of the accelerometer of an [`Mpu6050`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum AccelRange {
+    /// ±2 g
+    #[default]
+    G2 = 0,
+    /// ±4 g
+    G4 = 1,
+    /// ±8 g
+    G8 = 2,
+    /// ±16 g
+    G16 = 3,
+}
+
+impl AccelRange {
+    /// Returns the raw value of 1 g.
+    #[inline]
+    pub fn sensitivity(&self) -> f32 {
+        (16384 >> *self as u32) as f32
+    }
+}
+
+/// Measuring range of the gyroscope of an [`Mpu6050`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum GyroRange {
+    /// ±250 °/s
+    #[default]
+    Dps250 = 0,
+    /// ±500 °/s
+    Dps500 = 1,
+    /// ±1000 °/s
+    Dps1000 = 2,
+    /// ±2000 °/s
+    Dps2000 = 3,
+}
+
+impl GyroRange {
+    /// Returns the raw value of 1 °/s.
+    #[inline]
+    pub fn sensitivity(&self) -> f32 {
+        131.0 / (1 << *self as u32) as f32
+    }
+}
+
+/// Bandwidth of the digital low pass filter of an [`Mpu6050`], for the accelerometer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mpu6050Dlpf {
+    /// 260 Hz, filter off
+    #[default]
+    Hz260 = 0,
+    /// 184 Hz, 2 ms delay
+    Hz184 = 1,
+    /// 94 Hz, 3 ms delay
+    Hz94 = 2,
+    /// 44 Hz, 4.9 ms delay
+    Hz44 = 3,
+    /// 21 Hz, 8.5 ms delay
+    Hz21 = 4,
+    /// 10 Hz, 13.8 ms delay
+    Hz10 = 5,
+    /// 5 Hz, 19 ms delay
+    Hz5 = 6,
+}
+
+/// Orientation in degrees, estimated by a [`ComplementaryFilter`] or a [`MadgwickFilter`].
+///
+/// Yaw is only integrated from the gyroscope, so it drifts over time.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Orientation {
+    /// Rotation around the X axis.
+    pub roll: f32,
+    /// Rotation around the Y axis.
+    pub pitch: f32,
+    /// Rotation around the Z axis.
+    pub yaw: f32,
+}
+
+/// Estimates orientation by integrating the gyroscope, corrected by the tilt the accelerometer measures.
+///
+/// Cheap and sufficient for balancing robots, but inaccurate at large tilts.
+#[derive(Debug, Clone)]
+pub struct ComplementaryFilter {
+    alpha: f32,
+    orientation: Option<Orientation>,
+}
+
+impl ComplementaryFilter {
+    /// Creates a filter trusting the gyroscope with the weight `alpha` from 0.0 to 1.0, usually around `0.98`.
+    #[inline]
+    pub fn new(alpha: f32) -> Self {
+        Self {
+            alpha: alpha.clamp(0.0, 1.0),
+            orientation: None,
+        }
+    }
+
+    /// Updates the estimate with the sample taken `dt` after the previous one.
+    pub fn update(&mut self, sample: &Mpu6050Sample, dt: Duration) -> Orientation {
+        let [ax, ay, az] = sample.accel;
+        let [gx, gy, gz] = sample.gyro;
+        let dt = dt.as_secs_f32();
+
+        let accel_roll = ay.atan2(az).to_degrees();
+        let accel_pitch = (-ax).atan2((ay * ay + az * az).sqrt()).to_degrees();
+
+        let orientation = match self.orientation {
+            None => Orientation {
+                roll: accel_roll,
+                pitch: accel_pitch,
+                yaw: 0.0,
+            },
+            Some(previous) => Orientation {
+                roll: self.alpha * (previous.roll + gx * dt) + (1.0 - self.alpha) * accel_roll,
+                pitch: self.alpha * (previous.pitch + gy * dt) + (1.0 - self.alpha) * accel_pitch,
+                yaw: previous.yaw + gz * dt,
+            },
+        };
+        self.orientation = Some(orientation);
+
+        orientation
+    }
+
+    /// Returns the current estimate.
+    #[inline]
+    pub fn orientation(&self) -> Orientation {
+        self.orientation.unwrap_or_default()
+    }
+}
+
+/// Estimates orientation with Sebastian Madgwick's gradient descent filter on a quaternion.
+///
+/// More accurate than the [`ComplementaryFilter`] at all tilts, for a few more calculations per sample.
+#[derive(Debug, Clone)]
+pub struct MadgwickFilter {
+    beta: f32,
+    q: [f32; 4],
+}
+
+impl MadgwickFilter {
+    /// Creates a filter correcting the gyroscope with the gain `beta`, usually around `0.1`.
+    ///
+    /// Higher gains converge faster, lower gains are less affected by linear acceleration.
+    #[inline]
+    pub fn new(beta: f32) -> Self {
+        Self {
+            beta,
+            q: [1.0, 0.0, 0.0, 0.0],
+        }
+    }
+
+    /// Updates the estimate with the sample taken `dt` after the previous one.
+    pub fn update(&mut self, sample: &Mpu6050Sample, dt: Duration) -> Orientation {
+        let [q0, q1, q2, q3] = self.q;
+        let [gx, gy, gz] = sample.gyro.map(f32::to_radians);
+        let [mut ax, mut ay, mut az] = sample.accel;
+
+        // Rate of change of the quaternion from the gyroscope.
+        let mut dq = [
+            0.5 * (-q1 * gx - q2 * gy - q3 * gz),
+            0.5 * (q0 * gx + q2 * gz - q3 * gy),
+            0.5 * (q0 * gy - q1 * gz + q3 * gx),
+            0.5 * (q0 * gz + q1 * gy - q2 * gx),
+        ];
+
+        // Without acceleration, for example in free fall, there is nothing to correct with.
+        let norm = (ax * ax + ay * ay + az * az).sqrt();
+        if norm > 0.0 {
+            ax /= norm;
+            ay /= norm;
+            az /= norm;
+
+            // Gradient of the error between measured and estimated direction of gravity.
+            let s = [
+                4.0 * q0 * q2 * q2 + 2.0 * q2 * ax + 4.0 * q0 * q1 * q1 - 2.0 * q1 * ay,
+                4.0 * q1 * q3 * q3 - 2.0 * q3 * ax + 4.0 * q0 * q0 * q1 - 2.0 * q0 * ay - 4.0 * q1
+                    + 8.0 * q1 * q1 * q1
+                    + 8.0 * q1 * q2 * q2
+                    + 4.0 * q1 * az,
+                4.0 * q0 * q0 * q2 + 2.0 * q0 * ax + 4.0 * q2 * q3 * q3 - 2.0 * q3 * ay - 4.0 * q2
+                    + 8.0 * q2 * q1 * q1
+                    + 8.0 * q2 * q2 * q2
+                    + 4.0 * q2 * az,
+                4.0 * q1 * q1 * q3 - 2.0 * q1 * ax + 4.0 * q2 * q2 * q3 - 2.0 * q2 * ay,
+            ];
+
+            let norm = s.iter().map(|v| v * v).sum::<f32>().sqrt();
+            if norm > 0.0 {
+                for (dq, s) in dq.iter_mut().zip(s) {
+                    *dq -= self.beta * s / norm;
+                }
+            }
+        }
+
+        let dt = dt.as_secs_f32();
+        let mut q = [q0, q1, q2, q3];
+        for (q, dq) in q.iter_mut().zip(dq) {
+            *q += dq * dt;
+        }
+
+        let norm = q.iter().map(|v| v * v).sum::<f32>().sqrt();
+        self.q = q.map(|v| v / norm);
+
+        self.orientation()
+    }
+
+    /// Returns the current estimate.
+    pub fn orientation(&self) -> Orientation {
+        let [q0, q1, q2, q3] = self.q;
+
+        Orientation {
+            roll: (2.0 * (q0 * q1 + q2 * q3))
+                .atan2(1.0 - 2.0 * (q1 * q1 + q2 * q2))
+                .to_degrees(),
+            pitch: (2.0 * (q0 * q2 - q3 * q1))
+                .clamp(-1.0, 1.0)
+                .asin()
+                .to_degrees(),
+            yaw: (2.0 * (q0 * q3 + q1 * q2))
+                .atan2(1.0 - 2.0 * (q2 * q2 + q3 * q3))
+                .to_degrees(),
+        }
+    }
+
+    /// Returns the current estimate as quaternion in the order w, x, y, z.
+    #[inline]
+    pub fn quaternion(&self) -> [f32; 4] {
+        self.q
+    }
+}
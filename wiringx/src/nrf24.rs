@@ -0,0 +1,538 @@
+//! nRF24L01(+) 2.4 GHz radio transceivers.
+
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use thiserror::Error;
+
+use crate::{BitOrder, Input, IsrMode, Output, Pin, Spi, SpiMode, Value, WiringXError};
+
+const R_REGISTER: u8 = 0x00;
+const W_REGISTER: u8 = 0x20;
+const R_RX_PL_WID: u8 = 0x60;
+const R_RX_PAYLOAD: u8 = 0x61;
+const W_TX_PAYLOAD: u8 = 0xA0;
+const FLUSH_TX: u8 = 0xE1;
+const FLUSH_RX: u8 = 0xE2;
+
+const CONFIG: u8 = 0x00;
+const EN_AA: u8 = 0x01;
+const EN_RXADDR: u8 = 0x02;
+const SETUP_AW: u8 = 0x03;
+const SETUP_RETR: u8 = 0x04;
+const RF_CH: u8 = 0x05;
+const RF_SETUP: u8 = 0x06;
+const STATUS: u8 = 0x07;
+const RX_ADDR_P0: u8 = 0x0A;
+const TX_ADDR: u8 = 0x10;
+const RX_PW_P0: u8 = 0x11;
+const FIFO_STATUS: u8 = 0x17;
+const DYNPD: u8 = 0x1C;
+const FEATURE: u8 = 0x1D;
+
+const CONFIG_EN_CRC: u8 = 0x08;
+const CONFIG_CRC_16: u8 = 0x04;
+const CONFIG_PWR_UP: u8 = 0x02;
+const CONFIG_PRIM_RX: u8 = 0x01;
+const STATUS_RX_DR: u8 = 0x40;
+const STATUS_TX_DS: u8 = 0x20;
+const STATUS_MAX_RT: u8 = 0x10;
+const FIFO_RX_EMPTY: u8 = 0x01;
+const FEATURE_EN_DPL: u8 = 0x04;
+/// Five byte addresses.
+const ADDRESS_WIDTH_5: u8 = 0x03;
+const ALL_PIPES: u8 = 0x3F;
+
+/// Largest payload of one packet in bytes.
+pub const NRF24_MAX_PAYLOAD: usize = 32;
+
+/// Time the oscillator takes to start when powering up.
+const POWER_UP_DELAY: Duration = Duration::from_millis(5);
+/// Time from switching to receive or transmit until the radio is ready.
+const SETTLING_DELAY: Duration = Duration::from_micros(130);
+/// Longest time a transmission including all retries can take.
+const SEND_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// An nRF24L01 or nRF24L01+ radio on the SPI bus, with its CE pin and the optional IRQ pin.
+///
+/// Packets of up to 32 bytes are sent to the address of the writing pipe and received on up to six reading pipes.
+/// Auto acknowledgement with retransmission and dynamic payload lengths are enabled by default,
+/// so both sides only have to agree on channel, data rate and addresses.
+///
+/// Example sending telemetry to a receiver with the address `"base1"`:
+/// ```no_run
+/// use wiringx::{Nrf24, Output, Platform, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+///
+/// let mut radio = Nrf24::new(
+///     wiringx.setup_spi(0, 8_000_000).unwrap(),
+///     wiringx.gpio_pin::<Output>(15).unwrap(),
+///     None,
+/// )
+/// .unwrap();
+///
+/// radio.set_channel(108).unwrap();
+/// radio.open_writing_pipe(*b"base1").unwrap();
+///
+/// match radio.send(b"temperature=21.5") {
+///     Ok(()) => println!("delivered"),
+///     Err(e) => eprintln!("{e}"),
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Nrf24 {
+    spi: Spi,
+    ce: Pin<Output>,
+    irq: Option<Pin<Input>>,
+    config: u8,
+    listening: bool,
+    dynamic_payloads: bool,
+    payload_size: u8,
+    tx_address: [u8; 5],
+    pipe0_address: Option<[u8; 5]>,
+}
+
+impl Nrf24 {
+    /// Initializes the radio on channel `76` at 1 Mbps and full power, in standby.
+    ///
+    /// The controller is reconfigured to [`SpiMode::Mode0`] and [`BitOrder::MsbFirst`], it may run at up to 10 MHz.
+    pub fn new(
+        mut spi: Spi,
+        mut ce: Pin<Output>,
+        irq: Option<Pin<Input>>,
+    ) -> Result<Self, Nrf24Error> {
+        spi.set_mode(SpiMode::Mode0)?;
+        spi.set_bit_order(BitOrder::MsbFirst)?;
+        ce.write(Value::Low);
+
+        if let Some(irq) = &irq {
+            irq.set_isr_mode(IsrMode::Falling)
+                .map_err(WiringXError::from)?;
+        }
+
+        let mut radio = Self {
+            spi,
+            ce,
+            irq,
+            config: CONFIG_EN_CRC | CONFIG_CRC_16,
+            listening: false,
+            dynamic_payloads: true,
+            payload_size: NRF24_MAX_PAYLOAD as u8,
+            tx_address: [0xE7; 5],
+            pipe0_address: None,
+        };
+
+        radio.write_register(CONFIG, radio.config)?;
+        // The register keeps its value only if a radio answers.
+        if radio.read_register(CONFIG)? != radio.config {
+            return Err(WiringXError::Unsupported.into());
+        }
+
+        radio.write_register(SETUP_AW, ADDRESS_WIDTH_5)?;
+        radio.set_retries(Duration::from_micros(1500), 15)?;
+        radio.write_register(RF_SETUP, 0)?;
+        radio.set_data_rate(Nrf24DataRate::Mbps1)?;
+        radio.set_power(Nrf24Power::Max)?;
+        radio.set_channel(76)?;
+        radio.write_register(EN_AA, ALL_PIPES)?;
+        radio.write_register(EN_RXADDR, 0)?;
+        radio.set_dynamic_payloads(true)?;
+
+        radio.command(FLUSH_RX)?;
+        radio.command(FLUSH_TX)?;
+        radio.clear_status()?;
+
+        radio.config |= CONFIG_PWR_UP;
+        radio.write_register(CONFIG, radio.config)?;
+        thread::sleep(POWER_UP_DELAY);
+
+        Ok(radio)
+    }
+
+    /// Sets the RF channel from `0` to `125`, at 2400 MHz plus the channel number.
+    ///
+    /// Channels above `100` are usually clear of WiFi.
+    pub fn set_channel(&mut self, channel: u8) -> Result<(), Nrf24Error> {
+        if channel > 125 {
+            return Err(WiringXError::InvalidArgument.into());
+        }
+
+        self.write_register(RF_CH, channel)
+    }
+
+    /// Sets the air data rate, lower rates reach further. 250 kbps is only supported by the nRF24L01+.
+    pub fn set_data_rate(&mut self, data_rate: Nrf24DataRate) -> Result<(), Nrf24Error> {
+        let bits = match data_rate {
+            Nrf24DataRate::Kbps250 => 0x20,
+            Nrf24DataRate::Mbps1 => 0x00,
+            Nrf24DataRate::Mbps2 => 0x08,
+        };
+
+        let setup = self.read_register(RF_SETUP)? & !0x28;
+        self.write_register(RF_SETUP, setup | bits)
+    }
+
+    /// Sets the transmit power.
+    pub fn set_power(&mut self, power: Nrf24Power) -> Result<(), Nrf24Error> {
+        let setup = self.read_register(RF_SETUP)? & !0x06;
+        self.write_register(RF_SETUP, setup | (power as u8) << 1)
+    }
+
+    /// Sets how long to wait for an acknowledgement, from 250 µs to 4 ms in steps of 250 µs,
+    /// and how often to retransmit without one, up to `15` times.
+    pub fn set_retries(&mut self, delay: Duration, count: u8) -> Result<(), Nrf24Error> {
+        let steps = delay.as_micros().div_ceil(250);
+        if !(1..=16).contains(&steps) || count > 15 {
+            return Err(WiringXError::InvalidArgument.into());
+        }
+
+        self.write_register(SETUP_RETR, ((steps - 1) as u8) << 4 | count)
+    }
+
+    /// Enables or disables acknowledgements on all pipes, without them packets are sent once.
+    #[inline]
+    pub fn set_auto_ack(&mut self, enabled: bool) -> Result<(), Nrf24Error> {
+        self.write_register(EN_AA, if enabled { ALL_PIPES } else { 0 })
+    }
+
+    /// Enables or disables payloads of varying length on all pipes.
+    ///
+    /// Without them, all payloads have the length set with [`set_payload_size`](Self::set_payload_size).
+    pub fn set_dynamic_payloads(&mut self, enabled: bool) -> Result<(), Nrf24Error> {
+        let feature = self.read_register(FEATURE)?;
+
+        if enabled {
+            self.write_register(FEATURE, feature | FEATURE_EN_DPL)?;
+            self.write_register(DYNPD, ALL_PIPES)?;
+        } else {
+            self.write_register(DYNPD, 0)?;
+            self.write_register(FEATURE, feature & !FEATURE_EN_DPL)?;
+        }
+
+        self.dynamic_payloads = enabled;
+
+        Ok(())
+    }
+
+    /// Sets the length of all payloads from `1` to `32` bytes, used if dynamic payloads are disabled.
+    pub fn set_payload_size(&mut self, size: u8) -> Result<(), Nrf24Error> {
+        if size == 0 || size as usize > NRF24_MAX_PAYLOAD {
+            return Err(WiringXError::InvalidArgument.into());
+        }
+
+        for pipe in 0..6 {
+            self.write_register(RX_PW_P0 + pipe, size)?;
+        }
+        self.payload_size = size;
+
+        Ok(())
+    }
+
+    /// Sets the address packets are sent to.
+    ///
+    /// Pipe `0` receives on the same address while sending, for the acknowledgements.
+    pub fn open_writing_pipe(&mut self, address: [u8; 5]) -> Result<(), Nrf24Error> {
+        self.write_registers(TX_ADDR, &address)?;
+        self.write_registers(RX_ADDR_P0, &address)?;
+        self.tx_address = address;
+
+        Ok(())
+    }
+
+    /// Receives packets sent to the address on the pipe from `0` to `5`.
+    ///
+    /// Pipes `2` - `5` share the first four bytes with pipe `1` and only use the last byte of their address,
+    /// given first as the least significant byte is sent first.
+    pub fn open_reading_pipe(&mut self, pipe: u8, address: [u8; 5]) -> Result<(), Nrf24Error> {
+        match pipe {
+            0 => {
+                self.pipe0_address = Some(address);
+                if self.listening {
+                    self.write_registers(RX_ADDR_P0, &address)?;
+                }
+            }
+            1 => self.write_registers(RX_ADDR_P0 + 1, &address)?,
+            2..=5 => self.write_register(RX_ADDR_P0 + pipe, address[0])?,
+            _ => return Err(WiringXError::InvalidArgument.into()),
+        }
+
+        self.write_register(RX_PW_P0 + pipe, self.payload_size)?;
+
+        let enabled = self.read_register(EN_RXADDR)?;
+        self.write_register(EN_RXADDR, enabled | 1 << pipe)
+    }
+
+    /// Stops receiving on the pipe from `0` to `5`.
+    pub fn close_reading_pipe(&mut self, pipe: u8) -> Result<(), Nrf24Error> {
+        if pipe > 5 {
+            return Err(WiringXError::InvalidArgument.into());
+        }
+
+        if pipe == 0 {
+            self.pipe0_address = None;
+        }
+
+        let enabled = self.read_register(EN_RXADDR)?;
+        self.write_register(EN_RXADDR, enabled & !(1 << pipe))
+    }
+
+    /// Switches to receive mode, packets arrive in the receive FIFO until read with [`receive`](Self::receive).
+    pub fn start_listening(&mut self) -> Result<(), Nrf24Error> {
+        if let Some(address) = self.pipe0_address {
+            self.write_registers(RX_ADDR_P0, &address)?;
+        }
+
+        self.config |= CONFIG_PRIM_RX;
+        self.write_register(CONFIG, self.config)?;
+        self.clear_status()?;
+
+        self.ce.write(Value::High);
+        thread::sleep(SETTLING_DELAY);
+
+        self.listening = true;
+
+        Ok(())
+    }
+
+    /// Switches back to standby, ready to send.
+    pub fn stop_listening(&mut self) -> Result<(), Nrf24Error> {
+        self.ce.write(Value::Low);
+        thread::sleep(SETTLING_DELAY);
+
+        self.config &= !CONFIG_PRIM_RX;
+        self.write_register(CONFIG, self.config)?;
+        self.write_registers(RX_ADDR_P0, &self.tx_address.clone())?;
+
+        self.listening = false;
+
+        Ok(())
+    }
+
+    /// Returns whether the radio is in receive mode.
+    #[inline]
+    pub fn is_listening(&self) -> bool {
+        self.listening
+    }
+
+    /// Sends the payload of up to 32 bytes to the address of the writing pipe and waits until it was acknowledged.
+    ///
+    /// Receiving is paused for the transmission if the radio is listening.
+    /// Returns [`NoAck`](Nrf24Error::NoAck) if the receiver did not acknowledge any retransmission.
+    pub fn send(&mut self, payload: &[u8]) -> Result<(), Nrf24Error> {
+        if payload.is_empty() || payload.len() > NRF24_MAX_PAYLOAD {
+            return Err(WiringXError::InvalidArgument.into());
+        }
+
+        let listening = self.listening;
+        if listening {
+            self.stop_listening()?;
+        }
+
+        let result = self.transmit(payload);
+
+        if listening {
+            self.start_listening()?;
+        }
+
+        result
+    }
+
+    /// Returns the next packet from the receive FIFO, none if it is empty.
+    pub fn receive(&mut self) -> Result<Option<Nrf24Packet>, Nrf24Error> {
+        if self.read_register(FIFO_STATUS)? & FIFO_RX_EMPTY != 0 {
+            return Ok(None);
+        }
+
+        let pipe = (self.status()? >> 1) & 0x07;
+
+        let len = if self.dynamic_payloads {
+            let mut message = [R_RX_PL_WID, 0];
+            self.spi.read_write(&mut message)?;
+
+            // Corrupted lengths have to be flushed, the payload can not be read.
+            if message[1] as usize > NRF24_MAX_PAYLOAD {
+                self.command(FLUSH_RX)?;
+                return Ok(None);
+            }
+
+            message[1] as usize
+        } else {
+            self.payload_size as usize
+        };
+
+        let mut message = vec![0; len + 1];
+        message[0] = R_RX_PAYLOAD;
+        self.spi.read_write(&mut message)?;
+        self.write_register(STATUS, STATUS_RX_DR)?;
+
+        message.remove(0);
+
+        Ok(Some(Nrf24Packet {
+            pipe,
+            data: message,
+        }))
+    }
+
+    /// Waits for the next packet while listening.
+    ///
+    /// Waits on the IRQ pin if there is one, otherwise polls the radio every millisecond.
+    /// Returns [`TimedOut`](WiringXError::TimedOut) if none arrived within the timeout.
+    pub fn receive_timeout(&mut self, timeout: Duration) -> Result<Nrf24Packet, Nrf24Error> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            if let Some(packet) = self.receive()? {
+                return Ok(packet);
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(WiringXError::TimedOut.into());
+            }
+
+            match &self.irq {
+                Some(irq) if irq.read() == Value::High => {
+                    let _ = irq.wait_for_interrupt(remaining);
+                }
+                _ => thread::sleep(remaining.min(Duration::from_millis(1))),
+            }
+        }
+    }
+
+    fn transmit(&mut self, payload: &[u8]) -> Result<(), Nrf24Error> {
+        let mut message = Vec::with_capacity(NRF24_MAX_PAYLOAD + 1);
+        message.push(W_TX_PAYLOAD);
+        message.extend_from_slice(payload);
+        if !self.dynamic_payloads {
+            message.resize(self.payload_size as usize + 1, 0);
+        }
+        self.spi.write(&message)?;
+
+        // A pulse of at least 10 µs on CE starts the transmission.
+        self.ce.write(Value::High);
+        thread::sleep(Duration::from_micros(15));
+        self.ce.write(Value::Low);
+
+        let deadline = Instant::now() + SEND_TIMEOUT;
+        loop {
+            let status = self.status()?;
+
+            if status & STATUS_TX_DS != 0 {
+                self.write_register(STATUS, STATUS_TX_DS)?;
+                return Ok(());
+            }
+
+            if status & STATUS_MAX_RT != 0 {
+                self.write_register(STATUS, STATUS_MAX_RT)?;
+                self.command(FLUSH_TX)?;
+                return Err(Nrf24Error::NoAck);
+            }
+
+            if Instant::now() > deadline {
+                self.command(FLUSH_TX)?;
+                return Err(WiringXError::TimedOut.into());
+            }
+
+            match &self.irq {
+                Some(irq) if irq.read() == Value::High => {
+                    let _ = irq.wait_for_interrupt(Duration::from_millis(1));
+                }
+                _ => thread::sleep(Duration::from_micros(100)),
+            }
+        }
+    }
+
+    #[inline]
+    fn status(&mut self) -> Result<u8, Nrf24Error> {
+        self.read_register(STATUS)
+    }
+
+    #[inline]
+    fn clear_status(&mut self) -> Result<(), Nrf24Error> {
+        self.write_register(STATUS, STATUS_RX_DR | STATUS_TX_DS | STATUS_MAX_RT)
+    }
+
+    fn command(&mut self, command: u8) -> Result<(), Nrf24Error> {
+        self.spi.write(&[command])?;
+
+        Ok(())
+    }
+
+    fn read_register(&mut self, register: u8) -> Result<u8, Nrf24Error> {
+        let mut message = [R_REGISTER | register, 0];
+        self.spi.read_write(&mut message)?;
+
+        Ok(message[1])
+    }
+
+    #[inline]
+    fn write_register(&mut self, register: u8, value: u8) -> Result<(), Nrf24Error> {
+        self.write_registers(register, &[value])
+    }
+
+    fn write_registers(&mut self, register: u8, values: &[u8]) -> Result<(), Nrf24Error> {
+        let mut message = Vec::with_capacity(values.len() + 1);
+        message.push(W_REGISTER | register);
+        message.extend_from_slice(values);
+
+        self.spi.write(&message)?;
+
+        Ok(())
+    }
+}
+
+impl Drop for Nrf24 {
+    fn drop(&mut self) {
+        self.ce.write(Value::Low);
+        let _ = self.write_register(CONFIG, self.config & !CONFIG_PWR_UP);
+    }
+}
+
+/// A packet received by an [`Nrf24`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Nrf24Packet {
+    /// Pipe the packet was received on, from `0` to `5`.
+    pub pipe: u8,
+    /// Payload of up to 32 bytes.
+    pub data: Vec<u8>,
+}
+
+/// Air data rate of an [`Nrf24`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Nrf24DataRate {
+    /// 250 kbps, longest range
+    Kbps250,
+    /// 1 Mbps
+    #[default]
+    Mbps1,
+    /// 2 Mbps
+    Mbps2,
+}
+
+/// Transmit power of an [`Nrf24`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Nrf24Power {
+    /// -18 dBm
+    Min = 0,
+    /// -12 dBm
+    Low = 1,
+    /// -6 dBm
+    High = 2,
+    /// 0 dBm
+    #[default]
+    Max = 3,
+}
+
+/// Errors when sending or receiving with an [`Nrf24`].
+#[derive(Error, Debug)]
+pub enum Nrf24Error {
+    /// Gets returned when the SPI bus or a pin fails, or an argument is out of range.
+    #[error("Radio communication failed: {0}")]
+    Spi(#[from] WiringXError),
+    /// Gets returned when the receiver did not acknowledge the packet after all retransmissions.
+    #[error("The packet was not acknowledged.")]
+    NoAck,
+}
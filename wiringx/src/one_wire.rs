@@ -0,0 +1,297 @@
+//! Bit-banged 1-Wire bus master on a single GPIO pin.
+
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use thiserror::Error;
+
+use crate::{gpio::wait_until, Output, Pin, Value};
+
+const SEARCH_ROM: u8 = 0xF0;
+const MATCH_ROM: u8 = 0x55;
+const SKIP_ROM: u8 = 0xCC;
+const ALARM_SEARCH: u8 = 0xEC;
+
+/// A 1-Wire bus master, for boards without the kernel `w1-gpio` overlay.
+///
+/// The pin emulates an open-drain output by switching between driving low and input,
+/// so the bus needs an external pull-up resistor, usually 4.7 kΩ to 3.3 V.
+///
+/// Time slots are timed by busy waiting. The thread getting preempted in the middle of one corrupts it,
+/// which shows up as [`Crc`](OneWireError::Crc) errors, so transactions with checksums should be retried.
+///
+/// Example listing all devices on the bus:
+/// ```no_run
+/// use wiringx::{OneWire, Output, Platform, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+///
+/// let mut bus = OneWire::new(wiringx.gpio_pin::<Output>(15).unwrap());
+///
+/// for rom in bus.search().unwrap() {
+///     println!("{rom}");
+/// }
+/// ```
+#[derive(Debug)]
+pub struct OneWire {
+    pin: Pin<Output>,
+}
+
+impl OneWire {
+    /// Takes ownership of the pin the bus is connected to and releases the line.
+    pub fn new(mut pin: Pin<Output>) -> Self {
+        pin.release();
+
+        Self { pin }
+    }
+
+    /// Returns the pin of the bus.
+    #[inline]
+    pub fn into_inner(self) -> Pin<Output> {
+        self.pin
+    }
+
+    /// Sends a reset pulse, returning whether at least one device answered with a presence pulse.
+    pub fn reset(&mut self) -> Result<bool, OneWireError> {
+        if self.pin.read() == Value::Low {
+            return Err(OneWireError::BusLow);
+        }
+
+        let start = Instant::now();
+        self.pin.drive(Value::Low);
+        wait_until(start + Duration::from_micros(480));
+
+        self.pin.release();
+        wait_until(start + Duration::from_micros(550));
+        let present = self.pin.read() == Value::Low;
+
+        wait_until(start + Duration::from_micros(960));
+
+        Ok(present)
+    }
+
+    /// Writes a single bit in one time slot.
+    pub fn write_bit(&mut self, bit: bool) {
+        let low = if bit { 6 } else { 60 };
+
+        let start = Instant::now();
+        self.pin.drive(Value::Low);
+        wait_until(start + Duration::from_micros(low));
+
+        self.pin.release();
+        wait_until(start + Duration::from_micros(70));
+    }
+
+    /// Reads a single bit in one time slot.
+    pub fn read_bit(&mut self) -> bool {
+        let start = Instant::now();
+        self.pin.drive(Value::Low);
+        wait_until(start + Duration::from_micros(6));
+
+        self.pin.release();
+        wait_until(start + Duration::from_micros(15));
+        let bit = self.pin.read() == Value::High;
+
+        wait_until(start + Duration::from_micros(70));
+
+        bit
+    }
+
+    /// Writes a byte, least significant bit first.
+    pub fn write_byte(&mut self, byte: u8) {
+        for i in 0..8 {
+            self.write_bit(byte >> i & 1 == 1);
+        }
+    }
+
+    /// Reads a byte, least significant bit first.
+    pub fn read_byte(&mut self) -> u8 {
+        (0..8).fold(0, |byte, i| byte | (self.read_bit() as u8) << i)
+    }
+
+    /// Writes all bytes.
+    pub fn write(&mut self, data: &[u8]) {
+        for byte in data {
+            self.write_byte(*byte);
+        }
+    }
+
+    /// Fills the buffer with read bytes.
+    pub fn read(&mut self, data: &mut [u8]) {
+        for byte in data.iter_mut() {
+            *byte = self.read_byte();
+        }
+    }
+
+    /// Resets the bus and addresses the device with the ROM code, the following commands only reach this device.
+    pub fn select(&mut self, rom: &RomCode) -> Result<(), OneWireError> {
+        self.reset_present()?;
+
+        self.write_byte(MATCH_ROM);
+        self.write(&rom.0);
+
+        Ok(())
+    }
+
+    /// Resets the bus and addresses all devices at once, the following commands reach every device.
+    ///
+    /// Reading afterwards only works if there is just one device on the bus.
+    pub fn skip(&mut self) -> Result<(), OneWireError> {
+        self.reset_present()?;
+
+        self.write_byte(SKIP_ROM);
+
+        Ok(())
+    }
+
+    /// Enumerates the ROM codes of all devices on the bus.
+    #[inline]
+    pub fn search(&mut self) -> Result<Vec<RomCode>, OneWireError> {
+        self.search_with(SEARCH_ROM)
+    }
+
+    /// Enumerates the ROM codes of all devices with an alarm condition, like exceeded temperature limits.
+    #[inline]
+    pub fn search_alarms(&mut self) -> Result<Vec<RomCode>, OneWireError> {
+        self.search_with(ALARM_SEARCH)
+    }
+
+    /// Calculates the Dallas/Maxim CRC8 checksum used for ROM codes and scratchpads.
+    pub fn crc8(data: &[u8]) -> u8 {
+        let mut crc = 0;
+
+        for byte in data {
+            let mut byte = *byte;
+            for _ in 0..8 {
+                let mix = (crc ^ byte) & 1;
+                crc >>= 1;
+                if mix == 1 {
+                    crc ^= 0x8C;
+                }
+                byte >>= 1;
+            }
+        }
+
+        crc
+    }
+
+    fn reset_present(&mut self) -> Result<(), OneWireError> {
+        if self.reset()? {
+            Ok(())
+        } else {
+            Err(OneWireError::NoPresence)
+        }
+    }
+
+    /// Walks the binary tree of ROM codes, taking the branch not taken before at the last discrepancy every pass.
+    fn search_with(&mut self, command: u8) -> Result<Vec<RomCode>, OneWireError> {
+        let mut roms = Vec::new();
+        let mut rom = [0; 8];
+        let mut last_discrepancy = 0;
+
+        loop {
+            if !self.reset()? {
+                return Ok(roms);
+            }
+            self.write_byte(command);
+
+            let mut last_zero = 0;
+
+            for bit in 1..=64 {
+                let (byte, mask) = ((bit - 1) / 8, 1 << ((bit - 1) % 8));
+
+                let direction = match (self.read_bit(), self.read_bit()) {
+                    (true, true) if bit == 1 && roms.is_empty() => return Ok(roms),
+                    (true, true) => return Err(OneWireError::Search),
+                    (false, true) => false,
+                    (true, false) => true,
+                    (false, false) => {
+                        let direction = if bit < last_discrepancy {
+                            rom[byte] & mask != 0
+                        } else {
+                            bit == last_discrepancy
+                        };
+
+                        if !direction {
+                            last_zero = bit;
+                        }
+
+                        direction
+                    }
+                };
+
+                if direction {
+                    rom[byte] |= mask;
+                } else {
+                    rom[byte] &= !mask;
+                }
+                self.write_bit(direction);
+            }
+
+            if Self::crc8(&rom[..7]) != rom[7] {
+                return Err(OneWireError::Crc);
+            }
+
+            roms.push(RomCode(rom));
+
+            last_discrepancy = last_zero;
+            if last_discrepancy == 0 {
+                return Ok(roms);
+            }
+        }
+    }
+}
+
+/// The unique 64 bit address of a 1-Wire device, as sent on the bus.
+///
+/// The first byte is the family code, followed by the six byte serial number and the CRC8 checksum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RomCode(pub [u8; 8]);
+
+impl RomCode {
+    /// Returns the family code identifying the device type, for example `0x28` for the DS18B20.
+    #[inline]
+    pub fn family(&self) -> u8 {
+        self.0[0]
+    }
+
+    /// Returns true if the checksum of the ROM code is correct.
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        OneWire::crc8(&self.0[..7]) == self.0[7]
+    }
+}
+
+/// Formats the ROM code like the kernel names its devices, for example `28-0316a2797fff`.
+impl fmt::Display for RomCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02x}-", self.family())?;
+        for byte in self.0[1..7].iter().rev() {
+            write!(f, "{byte:02x}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Errors on the 1-Wire bus.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OneWireError {
+    /// Gets returned when no device answered the reset pulse.
+    #[error("No device is present on the bus.")]
+    NoPresence,
+    /// Gets returned when the line is held low, usually because the pull-up resistor is missing.
+    #[error("The bus line is held low.")]
+    BusLow,
+    /// Gets returned when received data does not match its checksum.
+    #[error("The checksum does not match.")]
+    Crc,
+    /// Gets returned when devices stopped answering in the middle of a search.
+    #[error("The devices stopped responding during the search.")]
+    Search,
+    /// Gets returned when a ROM code belongs to another type of device than the driver supports.
+    #[error("The device family is not supported by this driver.")]
+    WrongFamily,
+}
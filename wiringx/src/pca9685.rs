@@ -0,0 +1,288 @@
+//! PCA9685 16 channel PWM expander.
+
+use std::{sync::Arc, thread, time::Duration};
+
+use parking_lot::Mutex;
+
+use crate::{Polarity, PwmOutput, WiringXError, I2C};
+
+const MODE1: u8 = 0x00;
+const MODE2: u8 = 0x01;
+const LED0_ON_L: u8 = 0x06;
+const PRESCALE: u8 = 0xFE;
+
+const MODE1_RESTART: u8 = 0x80;
+const MODE1_AUTO_INCREMENT: u8 = 0x20;
+const MODE1_SLEEP: u8 = 0x10;
+/// Drives the outputs push-pull instead of open-drain.
+const MODE2_TOTEM_POLE: u8 = 0x04;
+
+/// Bit in the high byte of the on and off times, keeping the output fully on or off.
+const FULL: u8 = 0x10;
+
+/// Frequency of the internal oscillator.
+const OSCILLATOR_HZ: f64 = 25_000_000.0;
+/// Steps of a PWM cycle.
+pub(crate) const STEPS: u16 = 4096;
+
+/// A PCA9685 expander adding 16 PWM channels with a shared period over I2C, like the common servo driver boards.
+///
+/// Its channels are handed out as [`Pca9685Channel`]s implementing [`PwmOutput`] just like native [`PwmPin`](super::PwmPin)s,
+/// with a resolution of 12 bits.
+///
+/// Example moving a servo on channel `0`:
+/// ```no_run
+/// use wiringx::{Pca9685, Platform, Polarity, Servo, WiringX};
+///
+/// use std::time::Duration;
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let i2c = wiringx.setup_i2c("/dev/i2c-1".into(), 0x40).unwrap();
+///
+/// let pca = Pca9685::new(i2c, Duration::from_millis(20)).unwrap();
+///
+/// let mut servo = Servo::new(pca.channel(0, 0.0, Polarity::Normal).unwrap()).unwrap();
+/// servo.set_angle(90.0).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Pca9685 {
+    inner: Arc<Mutex<Pca9685Inner>>,
+}
+
+#[derive(Debug)]
+struct Pca9685Inner {
+    i2c: I2C,
+    period: Duration,
+    /// Channels handed out.
+    used: u16,
+}
+
+impl Pca9685 {
+    /// Initializes the expander with the period shared by all channels, from 0.66 to 41 milliseconds.
+    ///
+    /// The period gets rounded to the closest one the prescaler supports.
+    pub fn new(i2c: I2C, period: Duration) -> Result<Self, WiringXError> {
+        let mut inner = Pca9685Inner {
+            i2c,
+            period,
+            used: 0,
+        };
+
+        inner.write_reg(MODE2, MODE2_TOTEM_POLE)?;
+        inner.set_period(period)?;
+
+        Ok(Self {
+            inner: Arc::new(Mutex::new(inner)),
+        })
+    }
+
+    /// Returns the channel from `0` to `15` as output, starting with the duty cycle and polarity.
+    pub fn channel(
+        &self,
+        channel: u8,
+        duty_cycle: f32,
+        polarity: Polarity,
+    ) -> Result<Pca9685Channel, WiringXError> {
+        if channel > 15 {
+            return Err(WiringXError::InvalidPin);
+        }
+
+        let mut inner = self.inner.lock();
+        if inner.used & 1 << channel != 0 {
+            return Err(WiringXError::PinUsed);
+        }
+
+        let duty_cycle = duty_cycle.clamp(0.0, 1.0);
+        inner.write_duty_cycle(channel, duty_cycle, polarity)?;
+        inner.used |= 1 << channel;
+
+        Ok(Pca9685Channel {
+            expander: self.clone(),
+            channel,
+            duty_cycle,
+            polarity,
+        })
+    }
+
+    /// Sets the period of all channels, keeping their duty cycles.
+    #[inline]
+    pub fn set_period(&self, period: Duration) -> Result<(), WiringXError> {
+        self.inner.lock().set_period(period)
+    }
+
+    /// Returns the period shared by all channels.
+    #[inline]
+    pub fn period(&self) -> Duration {
+        self.inner.lock().period
+    }
+}
+
+impl Pca9685Inner {
+    fn write_reg(&mut self, register: u8, value: u8) -> Result<(), WiringXError> {
+        self.i2c.write_bytes(&[register, value])?;
+
+        Ok(())
+    }
+
+    fn set_period(&mut self, period: Duration) -> Result<(), WiringXError> {
+        let prescale = (OSCILLATOR_HZ * period.as_secs_f64() / STEPS as f64).round() - 1.0;
+        if !(3.0..=255.0).contains(&prescale) {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        // The prescaler can only be written while the oscillator sleeps.
+        self.write_reg(MODE1, MODE1_AUTO_INCREMENT | MODE1_SLEEP)?;
+        self.write_reg(PRESCALE, prescale as u8)?;
+        self.write_reg(MODE1, MODE1_AUTO_INCREMENT)?;
+        thread::sleep(Duration::from_micros(500));
+        self.write_reg(MODE1, MODE1_AUTO_INCREMENT | MODE1_RESTART)?;
+
+        self.period = Duration::from_secs_f64((prescale + 1.0) * STEPS as f64 / OSCILLATOR_HZ);
+
+        Ok(())
+    }
+
+    fn write_duty_cycle(
+        &mut self,
+        channel: u8,
+        duty_cycle: f32,
+        polarity: Polarity,
+    ) -> Result<(), WiringXError> {
+        let duty_cycle = match polarity {
+            Polarity::Normal => duty_cycle,
+            Polarity::Inversed => 1.0 - duty_cycle,
+        };
+
+        let steps = (duty_cycle * STEPS as f32).round() as u16;
+        let (on, off) = match steps {
+            0 => (0, (FULL as u16) << 8),
+            STEPS => ((FULL as u16) << 8, 0),
+            steps => (0, steps),
+        };
+
+        let [on_l, on_h] = on.to_le_bytes();
+        let [off_l, off_h] = off.to_le_bytes();
+        self.i2c
+            .write_bytes(&[LED0_ON_L + 4 * channel, on_l, on_h, off_l, off_h])?;
+
+        Ok(())
+    }
+}
+
+/// A channel of a [`Pca9685`].
+///
+/// Every channel can only be taken once, dropping it switches the output off and releases it.
+#[derive(Debug)]
+pub struct Pca9685Channel {
+    expander: Pca9685,
+    channel: u8,
+    duty_cycle: f32,
+    polarity: Polarity,
+}
+
+impl Pca9685Channel {
+    /// Returns the channel number, from `0` to `15`.
+    #[inline]
+    pub fn number(&self) -> u8 {
+        self.channel
+    }
+
+    /// Sets the period of time a PWM cycle takes.
+    ///
+    /// The period is shared, so this changes it for all channels of the expander.
+    #[inline]
+    pub fn set_period(&mut self, period: Duration) -> Result<(), WiringXError> {
+        self.expander.set_period(period)
+    }
+
+    /// Returns the period shared by all channels of the expander.
+    #[inline]
+    pub fn period(&self) -> Duration {
+        self.expander.period()
+    }
+
+    /// Sets the duty cycle of the channel.
+    ///
+    /// Takes a value from 0.0 - 1.0, automatically clamped to that range.
+    pub fn set_duty_cycle(&mut self, duty_cycle: f32) -> Result<(), WiringXError> {
+        let duty_cycle = duty_cycle.clamp(0.0, 1.0);
+
+        self.expander
+            .inner
+            .lock()
+            .write_duty_cycle(self.channel, duty_cycle, self.polarity)?;
+        self.duty_cycle = duty_cycle;
+
+        Ok(())
+    }
+
+    /// Returns the duty cycle of the channel.
+    #[inline]
+    pub fn duty_cycle(&self) -> f32 {
+        self.duty_cycle
+    }
+
+    /// Returns the duty cycle in form of a duration.
+    #[inline]
+    pub fn duty_cycle_as_dur(&self) -> Duration {
+        self.period().mul_f32(self.duty_cycle)
+    }
+
+    /// Sets the polarity of the channel.
+    pub fn set_polarity(&mut self, polarity: Polarity) -> Result<(), WiringXError> {
+        self.expander
+            .inner
+            .lock()
+            .write_duty_cycle(self.channel, self.duty_cycle, polarity)?;
+        self.polarity = polarity;
+
+        Ok(())
+    }
+
+    /// Returns the polarity of the channel.
+    #[inline]
+    pub fn polarity(&self) -> Polarity {
+        self.polarity
+    }
+}
+
+impl Drop for Pca9685Channel {
+    fn drop(&mut self) {
+        let mut inner = self.expander.inner.lock();
+
+        inner.used &= !(1 << self.channel);
+        let _ = inner.write_duty_cycle(self.channel, 0.0, Polarity::Normal);
+    }
+}
+
+impl PwmOutput for Pca9685Channel {
+    #[inline]
+    fn set_period(&mut self, period: Duration) -> Result<(), WiringXError> {
+        Pca9685Channel::set_period(self, period)
+    }
+
+    #[inline]
+    fn period(&self) -> Duration {
+        Pca9685Channel::period(self)
+    }
+
+    #[inline]
+    fn set_duty_cycle(&mut self, duty_cycle: f32) -> Result<(), WiringXError> {
+        Pca9685Channel::set_duty_cycle(self, duty_cycle)
+    }
+
+    #[inline]
+    fn duty_cycle(&self) -> f32 {
+        Pca9685Channel::duty_cycle(self)
+    }
+
+    #[inline]
+    fn set_polarity(&mut self, polarity: Polarity) -> Result<(), WiringXError> {
+        Pca9685Channel::set_polarity(self, polarity)
+    }
+
+    #[inline]
+    fn polarity(&self) -> Polarity {
+        Pca9685Channel::polarity(self)
+    }
+}
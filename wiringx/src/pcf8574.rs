@@ -0,0 +1,320 @@
+//! PCF8574 8 bit I/O expander.
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+use crate::{DigitalInput, DigitalOutput, Input, IsrMode, Pin, Value, WiringXError, I2C};
+
+/// Time waited on the interrupt pin at once, so an edge missed between checks only delays the wakeup.
+const INTERRUPT_SLICE: Duration = Duration::from_millis(50);
+
+/// A PCF8574 or PCF8574A I/O expander adding 8 quasi-bidirectional GPIO channels over I2C.
+///
+/// The channels have no direction: a channel written low sinks current, a channel written high
+/// is only pulled up weakly and can be pulled low from outside, which is how it is used as input.
+/// All channels start high.
+///
+/// Its channels are handed out as [`Pcf8574Pin`]s implementing both [`DigitalOutput`] and [`DigitalInput`].
+/// Interrupts need the `INT` output connected to a native input pin, see [`with_interrupt`](Self::with_interrupt).
+///
+/// Example switching a relay on `P0` while a button on `P7` is pressed:
+/// ```no_run
+/// use wiringx::{Pcf8574, Platform, Value, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let i2c = wiringx.setup_i2c("/dev/i2c-1".into(), 0x20).unwrap();
+///
+/// let expander = Pcf8574::new(i2c).unwrap();
+///
+/// let mut relay = expander.pin(0).unwrap();
+/// let button = expander.pin(7).unwrap();
+///
+/// loop {
+///     relay.write(button.read().unwrap().opposite()).unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Pcf8574 {
+    inner: Arc<Mutex<Pcf8574Inner>>,
+    interrupt: Option<Arc<Pin<Input>>>,
+}
+
+#[derive(Debug)]
+struct Pcf8574Inner {
+    i2c: I2C,
+    /// Channels handed out as pins.
+    used: u8,
+    /// Values last written to the port.
+    latch: u8,
+    /// Port state at the last interrupt check.
+    last: u8,
+    /// Channels with interrupts enabled on rising and on falling edges.
+    rising: u8,
+    falling: u8,
+    /// Channels with an interrupt not yet picked up by their pin.
+    pending: u8,
+}
+
+impl Pcf8574 {
+    /// Initializes the expander with all channels high, without interrupt support.
+    pub fn new(i2c: I2C) -> Result<Self, WiringXError> {
+        Self::init(i2c, None)
+    }
+
+    /// Initializes the expander with all channels high, with its interrupt output connected to the pin.
+    pub fn with_interrupt(i2c: I2C, interrupt: Pin<Input>) -> Result<Self, WiringXError> {
+        interrupt.set_isr_mode(IsrMode::Falling)?;
+
+        Self::init(i2c, Some(interrupt))
+    }
+
+    fn init(i2c: I2C, interrupt: Option<Pin<Input>>) -> Result<Self, WiringXError> {
+        i2c.write(0xFF)?;
+        let last = i2c.read()?;
+
+        Ok(Self {
+            inner: Arc::new(Mutex::new(Pcf8574Inner {
+                i2c,
+                used: 0,
+                latch: 0xFF,
+                last,
+                rising: 0,
+                falling: 0,
+                pending: 0,
+            })),
+            interrupt: interrupt.map(Arc::new),
+        })
+    }
+
+    /// Returns the channel from `0` to `7` as pin, keeping its current value.
+    pub fn pin(&self, channel: u8) -> Result<Pcf8574Pin, WiringXError> {
+        if channel > 7 {
+            return Err(WiringXError::InvalidPin);
+        }
+
+        let mut inner = self.inner.lock();
+        if inner.used & 1 << channel != 0 {
+            return Err(WiringXError::PinUsed);
+        }
+        inner.used |= 1 << channel;
+
+        Ok(Pcf8574Pin {
+            expander: self.clone(),
+            channel,
+        })
+    }
+
+    /// Reads the levels of all 8 channels at once, `P0` in the least significant bit.
+    pub fn read_port(&self) -> Result<u8, WiringXError> {
+        self.inner.lock().read()
+    }
+
+    /// Writes all 8 channels at once, `P0` in the least significant bit.
+    ///
+    /// Channels used as inputs have to be kept high.
+    pub fn write_port(&self, value: u8) -> Result<(), WiringXError> {
+        self.inner.lock().write(value)
+    }
+}
+
+impl Pcf8574Inner {
+    #[inline]
+    fn read(&self) -> Result<u8, WiringXError> {
+        Ok(self.i2c.read()?)
+    }
+
+    fn write(&mut self, value: u8) -> Result<(), WiringXError> {
+        self.i2c.write(value as i32)?;
+        self.latch = value;
+
+        Ok(())
+    }
+
+    /// Compares the port with its state at the last check and marks the channels
+    /// whose edge matches their interrupt mode as pending.
+    fn collect_interrupts(&mut self) -> Result<(), WiringXError> {
+        // Reading the port also clears the interrupt.
+        let port = self.read()?;
+        let changed = port ^ self.last;
+        self.last = port;
+
+        self.pending |= changed & ((port & self.rising) | (!port & self.falling));
+
+        Ok(())
+    }
+}
+
+/// A channel of a [`Pcf8574`].
+///
+/// Every channel can only be taken once, dropping the pin releases it.
+#[derive(Debug)]
+pub struct Pcf8574Pin {
+    expander: Pcf8574,
+    channel: u8,
+}
+
+impl Pcf8574Pin {
+    /// Returns the channel number of this pin, from `0` to `7`.
+    #[inline]
+    pub fn number(&self) -> u8 {
+        self.channel
+    }
+
+    /// Drives the pin low, or releases it to the weak pull-up for high.
+    pub fn write(&mut self, value: Value) -> Result<(), WiringXError> {
+        let mut inner = self.expander.inner.lock();
+
+        let latch = match value {
+            Value::High => inner.latch | self.mask(),
+            Value::Low => inner.latch & !self.mask(),
+        };
+
+        inner.write(latch)
+    }
+
+    /// Toggles the written value of the pin.
+    pub fn toggle(&mut self) -> Result<(), WiringXError> {
+        let value = self.latched();
+        self.write(value.opposite())
+    }
+
+    /// Reads the current level of the pin, which can be low from outside even if written high.
+    pub fn read(&self) -> Result<Value, WiringXError> {
+        let port = self.expander.inner.lock().read()?;
+
+        Ok(level(port & self.mask() != 0))
+    }
+
+    /// Returns the value last written to the pin.
+    #[inline]
+    pub fn latched(&self) -> Value {
+        level(self.expander.inner.lock().latch & self.mask() != 0)
+    }
+
+    /// Sets on which edges [`wait_for_interrupt`](Self::wait_for_interrupt) returns.
+    ///
+    /// Returns [`Unsupported`](WiringXError::Unsupported) if the expander was created without interrupt pin.
+    pub fn set_isr_mode(&self, mode: IsrMode) -> Result<(), WiringXError> {
+        if self.expander.interrupt.is_none() {
+            return Err(WiringXError::Unsupported);
+        }
+
+        let (rising, falling) = match mode {
+            IsrMode::Rising => (true, false),
+            IsrMode::Falling => (false, true),
+            IsrMode::Both => (true, true),
+            IsrMode::None | IsrMode::Unknown => (false, false),
+        };
+
+        let mask = self.mask();
+        let mut inner = self.expander.inner.lock();
+        inner.collect_interrupts()?;
+
+        inner.rising = if rising {
+            inner.rising | mask
+        } else {
+            inner.rising & !mask
+        };
+        inner.falling = if falling {
+            inner.falling | mask
+        } else {
+            inner.falling & !mask
+        };
+        inner.pending &= !mask;
+
+        Ok(())
+    }
+
+    /// Suspends the thread until an edge selected with [`set_isr_mode`](Self::set_isr_mode) was detected on this pin.
+    ///
+    /// Returns [`InterruptTimedOut`](WiringXError::InterruptTimedOut) if none happened within the timeout.
+    pub fn wait_for_interrupt(&self, timeout: Duration) -> Result<(), WiringXError> {
+        let interrupt = self
+            .expander
+            .interrupt
+            .as_ref()
+            .ok_or(WiringXError::Unsupported)?;
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            {
+                let mut inner = self.expander.inner.lock();
+                inner.collect_interrupts()?;
+
+                if inner.pending & self.mask() != 0 {
+                    inner.pending &= !self.mask();
+                    return Ok(());
+                }
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(WiringXError::InterruptTimedOut);
+            }
+
+            // The interrupt output stays low until the port was read, so only wait while it is high.
+            if interrupt.read() == Value::High {
+                let _ = interrupt.wait_for_interrupt(remaining.min(INTERRUPT_SLICE));
+            }
+        }
+    }
+
+    #[inline]
+    fn mask(&self) -> u8 {
+        1 << self.channel
+    }
+}
+
+impl Drop for Pcf8574Pin {
+    fn drop(&mut self) {
+        let mask = self.mask();
+        let mut inner = self.expander.inner.lock();
+
+        inner.used &= !mask;
+        inner.rising &= !mask;
+        inner.falling &= !mask;
+        inner.pending &= !mask;
+    }
+}
+
+impl DigitalOutput for Pcf8574Pin {
+    #[inline]
+    fn write(&mut self, value: Value) -> Result<(), WiringXError> {
+        Pcf8574Pin::write(self, value)
+    }
+
+    #[inline]
+    fn read(&self) -> Result<Value, WiringXError> {
+        Ok(self.latched())
+    }
+}
+
+impl DigitalInput for Pcf8574Pin {
+    #[inline]
+    fn read(&self) -> Result<Value, WiringXError> {
+        Pcf8574Pin::read(self)
+    }
+
+    #[inline]
+    fn set_isr_mode(&self, mode: IsrMode) -> Result<(), WiringXError> {
+        Pcf8574Pin::set_isr_mode(self, mode)
+    }
+
+    #[inline]
+    fn wait_for_interrupt(&self, timeout: Duration) -> Result<(), WiringXError> {
+        Pcf8574Pin::wait_for_interrupt(self, timeout)
+    }
+}
+
+#[inline]
+fn level(high: bool) -> Value {
+    if high {
+        Value::High
+    } else {
+        Value::Low
+    }
+}
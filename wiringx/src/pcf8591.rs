@@ -0,0 +1,197 @@
+//! PCF8591 4 channel 8 bit ADC with DAC.
+
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::{AnalogInput, WiringXError, I2C};
+
+/// Highest conversion result and output value of the 8 bit converters.
+pub const PCF8591_MAX: u16 = 255;
+
+const OUTPUT_ENABLE: u8 = 0x40;
+const AUTO_INCREMENT: u8 = 0x04;
+
+/// A PCF8591 with 4 analog inputs and 1 analog output on the I2C bus, found on many beginner sensor kits.
+///
+/// The converter can be cloned to share it, for example between threads,
+/// and hands out its inputs as [`AnalogInput`]s with [`channel`](Self::channel).
+///
+/// Example dimming an LED on the output with a potentiometer on input `0` and a 3.3 V reference:
+/// ```no_run
+/// use wiringx::{Pcf8591, Platform, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let i2c = wiringx.setup_i2c("/dev/i2c-1".into(), 0x48).unwrap();
+///
+/// let adc = Pcf8591::new(i2c, 3.3).unwrap();
+///
+/// loop {
+///     let value = adc.read(0).unwrap();
+///     adc.set_output(value).unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Pcf8591 {
+    inner: Arc<Mutex<Pcf8591Inner>>,
+    reference: f32,
+}
+
+#[derive(Debug)]
+struct Pcf8591Inner {
+    i2c: I2C,
+    /// The output has to stay enabled in every control byte, or it switches off.
+    output_enabled: bool,
+    output: u8,
+}
+
+impl Pcf8591 {
+    /// Uses the converter on the I2C bus, with the voltage on its `VREF` pin, and the output disabled.
+    pub fn new(i2c: I2C, reference: f32) -> Result<Self, WiringXError> {
+        if reference <= 0.0 {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        i2c.write_bytes(&[0x00])?;
+
+        Ok(Self {
+            inner: Arc::new(Mutex::new(Pcf8591Inner {
+                i2c,
+                output_enabled: false,
+                output: 0,
+            })),
+            reference,
+        })
+    }
+
+    /// Returns the reference voltage conversions and the output are scaled with.
+    #[inline]
+    pub fn reference(&self) -> f32 {
+        self.reference
+    }
+
+    /// Returns the input from `0` to `3` as analog input measured against ground.
+    pub fn channel(&self, channel: u8) -> Result<Pcf8591Channel, WiringXError> {
+        if channel > 3 {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        Ok(Pcf8591Channel {
+            adc: self.clone(),
+            channel,
+        })
+    }
+
+    /// Converts the voltage of the input from `0` to `3` against ground, from `0` to `255`.
+    pub fn read(&self, channel: u8) -> Result<u8, WiringXError> {
+        if channel > 3 {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        let inner = self.inner.lock();
+        inner.i2c.write_bytes(&[inner.control() | channel])?;
+
+        // The conversion starts with the read, so the first byte is the previous result.
+        let mut data = [0; 2];
+        inner.i2c.read_bytes(&mut data)?;
+
+        Ok(data[1])
+    }
+
+    /// Converts the voltage of the input from `0` to `3` to volts.
+    pub fn read_voltage(&self, channel: u8) -> Result<f32, WiringXError> {
+        Ok(self.read(channel)? as f32 * self.reference / (PCF8591_MAX + 1) as f32)
+    }
+
+    /// Converts all 4 inputs against ground, one after another.
+    pub fn read_all(&self) -> Result<[u8; 4], WiringXError> {
+        let inner = self.inner.lock();
+        inner.i2c.write_bytes(&[inner.control() | AUTO_INCREMENT])?;
+
+        let mut data = [0; 5];
+        inner.i2c.read_bytes(&mut data)?;
+
+        Ok([data[1], data[2], data[3], data[4]])
+    }
+
+    /// Enables the output and sets it from `0` to `255`, `255` being close to the reference voltage.
+    pub fn set_output(&self, value: u8) -> Result<(), WiringXError> {
+        let mut inner = self.inner.lock();
+        inner.i2c.write_bytes(&[OUTPUT_ENABLE, value])?;
+        inner.output_enabled = true;
+        inner.output = value;
+
+        Ok(())
+    }
+
+    /// Enables the output and sets it to the voltage, from 0 V to the reference voltage.
+    pub fn set_output_voltage(&self, voltage: f32) -> Result<(), WiringXError> {
+        if !(0.0..=self.reference).contains(&voltage) {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        let value = (voltage / self.reference * (PCF8591_MAX + 1) as f32).min(PCF8591_MAX as f32);
+
+        self.set_output(value as u8)
+    }
+
+    /// Returns the output value, none if the output is disabled.
+    pub fn output(&self) -> Option<u8> {
+        let inner = self.inner.lock();
+
+        inner.output_enabled.then_some(inner.output)
+    }
+
+    /// Disables the output, leaving it high impedance.
+    pub fn disable_output(&self) -> Result<(), WiringXError> {
+        let mut inner = self.inner.lock();
+        inner.i2c.write_bytes(&[0x00])?;
+        inner.output_enabled = false;
+
+        Ok(())
+    }
+}
+
+impl Pcf8591Inner {
+    /// Returns the control byte for four single-ended inputs.
+    #[inline]
+    fn control(&self) -> u8 {
+        if self.output_enabled {
+            OUTPUT_ENABLE
+        } else {
+            0x00
+        }
+    }
+}
+
+/// An input of a [`Pcf8591`], measured against ground.
+#[derive(Debug, Clone)]
+pub struct Pcf8591Channel {
+    adc: Pcf8591,
+    channel: u8,
+}
+
+impl Pcf8591Channel {
+    /// Returns the input number, from `0` to `3`.
+    #[inline]
+    pub fn number(&self) -> u8 {
+        self.channel
+    }
+}
+
+impl AnalogInput for Pcf8591Channel {
+    #[inline]
+    fn read_raw(&self) -> Result<i32, WiringXError> {
+        Ok(self.adc.read(self.channel)?.into())
+    }
+
+    #[inline]
+    fn max_raw(&self) -> i32 {
+        PCF8591_MAX.into()
+    }
+
+    #[inline]
+    fn read_voltage(&self) -> Result<f32, WiringXError> {
+        self.adc.read_voltage(self.channel)
+    }
+}
@@ -0,0 +1,257 @@
+//! Checking and setting the functions the pads of the SoC are muxed to.
+//!
+//! Most pads of the Milk-V boards can be GPIO, PWM, I2C, SPI or UART, and only work as one at a time.
+//! A pin used as GPIO while its pad is muxed to PWM does not fail, it just does nothing,
+//! so [`Pinmux::check`] turns this into an error naming the command fixing it,
+//! and [`Pinmux::apply`] fixes it right away, through the `duo-pinmux` tool of the Milk-V images.
+//!
+//! Example making sure pin `4` of a Milk-V Duo is muxed to PWM before using it:
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use wiringx::{pinmux::Pinmux, Platform, Polarity, WiringX};
+//!
+//! let wiringx = WiringX::new(Platform::MilkVDuo).unwrap();
+//!
+//! let pinmux = Pinmux::new(wiringx.platform()).unwrap();
+//! pinmux.apply_pwm(&pinmux.pad(4).unwrap(), 4).unwrap();
+//!
+//! let pwm = wiringx
+//!     .pwm_pin(4, Duration::from_millis(1), 0.5, Polarity::Normal)
+//!     .unwrap();
+//! ```
+
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::{Platform, WiringXError};
+
+/// Kind of function a pad can be muxed to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PinFunction {
+    Gpio,
+    Pwm,
+    I2c,
+    Spi,
+    Uart,
+    /// Anything else, like the camera or audio interfaces
+    Other,
+}
+
+impl PinFunction {
+    /// Returns the kind of the function with the name used by the SoC, like `PWM_5` or `IIC0_SCL`.
+    pub fn of(name: &str) -> Self {
+        let name = name.to_uppercase();
+
+        if name.starts_with("GP") || name.starts_with("XGPIO") || name.starts_with("PWR_GPIO") {
+            Self::Gpio
+        } else if name.starts_with("PWM") {
+            Self::Pwm
+        } else if name.starts_with("IIC") || name.starts_with("I2C") {
+            Self::I2c
+        } else if name.starts_with("SPI") {
+            Self::Spi
+        } else if name.starts_with("UART") {
+            Self::Uart
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// The functions of a pad, read with [`Pinmux::read`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PadFunctions {
+    /// Name of the pad, like `GP4` or `B20`.
+    pub pad: String,
+    /// Name of the function the pad is muxed to.
+    pub current: String,
+    /// Names of all functions the pad can be muxed to.
+    pub available: Vec<String>,
+}
+
+impl PadFunctions {
+    /// Returns the kind of the function the pad is muxed to.
+    #[inline]
+    pub fn function(&self) -> PinFunction {
+        PinFunction::of(&self.current)
+    }
+
+    /// Returns the name of the first function of the kind the pad can be muxed to.
+    pub fn find(&self, function: PinFunction) -> Option<&str> {
+        self.available
+            .iter()
+            .find(|name| PinFunction::of(name) == function)
+            .map(String::as_str)
+    }
+}
+
+/// Access to the pad muxing of the SoC.
+#[derive(Debug, Clone)]
+pub struct Pinmux {
+    platform: Platform,
+    tool: PathBuf,
+}
+
+impl Pinmux {
+    /// Uses the `duo-pinmux` tool found in the `PATH`.
+    ///
+    /// Returns [`Unsupported`](WiringXError::Unsupported) for platforms other than the Milk-V Duo family.
+    pub fn new(platform: Platform) -> Result<Self, WiringXError> {
+        match platform {
+            Platform::MilkVDuo | Platform::MilkVDuo256M | Platform::MilkVDuoS => Ok(Self {
+                platform,
+                tool: PathBuf::from("duo-pinmux"),
+            }),
+            _ => Err(WiringXError::Unsupported),
+        }
+    }
+
+    /// Uses the tool at the path instead of the one in the `PATH`.
+    pub fn with_tool(mut self, tool: impl Into<PathBuf>) -> Self {
+        self.tool = tool.into();
+        self
+    }
+
+    /// Returns the path of the tool.
+    #[inline]
+    pub fn tool(&self) -> &Path {
+        &self.tool
+    }
+
+    /// Returns the name of the pad of the wiringX pin number, like `GP4` for pin `4` of the Milk-V Duo.
+    ///
+    /// The Milk-V Duo S names its pads after the SoC instead, like `B20`, so they are to be given directly.
+    pub fn pad(&self, pin: i32) -> Result<String, WiringXError> {
+        match self.platform {
+            Platform::MilkVDuo | Platform::MilkVDuo256M if (0..=28).contains(&pin) => {
+                Ok(format!("GP{pin}"))
+            }
+            Platform::MilkVDuo | Platform::MilkVDuo256M => Err(WiringXError::InvalidPin),
+            _ => Err(WiringXError::Unsupported),
+        }
+    }
+
+    /// Reads the function the pad is muxed to and the ones it could be.
+    pub fn read(&self, pad: &str) -> Result<PadFunctions, WiringXError> {
+        let output = self.run(&["-r", pad])?;
+
+        let mut current = None;
+        let mut available = Vec::new();
+
+        // One line per function, the current one marked like `[v] PWM_5`.
+        for line in output.lines() {
+            let line = line.trim();
+
+            if let Some(name) = line.strip_prefix("[v]") {
+                current = Some(name.trim().to_string());
+                available.push(name.trim().to_string());
+            } else if let Some(name) = line.strip_prefix("[ ]") {
+                available.push(name.trim().to_string());
+            }
+        }
+
+        let current = current.ok_or_else(|| {
+            WiringXError::Other(format!("duo-pinmux does not know the pad {pad}."))
+        })?;
+
+        Ok(PadFunctions {
+            pad: pad.to_string(),
+            current,
+            available,
+        })
+    }
+
+    /// Returns an error naming the command fixing it if the pad is not muxed to a function of the kind.
+    pub fn check(&self, pad: &str, function: PinFunction) -> Result<(), WiringXError> {
+        let functions = self.read(pad)?;
+        if functions.function() == function {
+            return Ok(());
+        }
+
+        Err(match functions.find(function) {
+            Some(name) => WiringXError::Other(format!(
+                "The pad {pad} is muxed to {}, set it to {name} with `{} -w {pad}/{name}`.",
+                functions.current,
+                self.tool.display()
+            )),
+            None => WiringXError::Other(format!(
+                "The pad {pad} is muxed to {} and can not be {function:?}.",
+                functions.current
+            )),
+        })
+    }
+
+    /// Muxes the pad to the first function of the kind, if it is not muxed to one already.
+    ///
+    /// Needs root, and lasts until the next reboot.
+    pub fn apply(&self, pad: &str, function: PinFunction) -> Result<(), WiringXError> {
+        let functions = self.read(pad)?;
+        if functions.function() == function {
+            return Ok(());
+        }
+
+        let name = functions.find(function).ok_or_else(|| {
+            WiringXError::Other(format!(
+                "The pad {pad} is muxed to {} and can not be {function:?}.",
+                functions.current
+            ))
+        })?;
+
+        self.run(&["-w", &format!("{pad}/{name}")])?;
+
+        Ok(())
+    }
+
+    /// Muxes the pad to the PWM channel wiringX drives the pin with, see [`Platform::pwm_channel`].
+    ///
+    /// Pads can often be muxed to more than one PWM channel, and which one wiringX uses differs between
+    /// the boards, like pin `2` being `PWM_10` on the Milk-V Duo but `PWM_7` on the Duo 256M,
+    /// so this is to be preferred over [`apply`](Self::apply) with [`PinFunction::Pwm`].
+    pub fn apply_pwm(&self, pad: &str, pin: i32) -> Result<(), WiringXError> {
+        let channel = self
+            .platform
+            .pwm_channel(pin)
+            .ok_or(WiringXError::Unsupported)?;
+        let name = format!("PWM_{channel}");
+
+        let functions = self.read(pad)?;
+        if functions.current.eq_ignore_ascii_case(&name) {
+            return Ok(());
+        }
+
+        if !functions
+            .available
+            .iter()
+            .any(|function| function.eq_ignore_ascii_case(&name))
+        {
+            return Err(WiringXError::Other(format!(
+                "The pad {pad} can not be muxed to {name}."
+            )));
+        }
+
+        self.run(&["-w", &format!("{pad}/{name}")])?;
+
+        Ok(())
+    }
+
+    fn run(&self, arguments: &[&str]) -> Result<String, WiringXError> {
+        let output = Command::new(&self.tool)
+            .args(arguments)
+            .output()
+            .map_err(WiringXError::Io)?;
+
+        if !output.status.success() {
+            return Err(WiringXError::Other(format!(
+                "{} failed: {}",
+                self.tool.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
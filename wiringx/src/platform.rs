@@ -1,11 +1,13 @@
 //! All supported platforms of this library.
 
-use std::{ffi::CString, os::raw::c_char};
+use std::{collections::BTreeMap, ffi::CString, fmt, fs, iter, str::FromStr, sync::OnceLock};
 
 use thiserror::Error;
+use wiringx_sys::{PlatformSource, PLATFORMS};
 
 /// All supported platforms of WiringX
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Platform {
     Odriodc1,
     Odriodc2,
@@ -17,6 +19,10 @@ pub enum Platform {
     Pcduino1,
 
     MilkVDuo,
+    /// The Milk-V Duo 256M, with the SG2002 instead of the CV1800B of the Duo.
+    ///
+    /// The header is the one of the Duo, but pins `10` and `11` have PWM as well,
+    /// and pins `2` and `3` other PWM channels, see [`pwm_channel`](Self::pwm_channel).
     MilkVDuo256M,
     MilkVDuoS,
 
@@ -42,11 +48,298 @@ pub enum Platform {
 
     OrangePiPC2,
     OrangePiPCPlus,
+    /// The Orange Pi Zero 3, with the H618, numbered like wiringOP from `0` to `16`.
+    ///
+    /// wiringX does not support it, so like with [`Custom`](Self::Custom) platforms GPIO goes through the sysfs
+    /// or gpiochip backend, with the single `/dev/gpiochip0` of the SoC, and PWM through the sysfs PWM interface.
+    /// PWM needs the `ph-pwm12` and `ph-pwm34` overlays, which take over the UART and I2C pins.
+    OrangePiZero3,
+
+    /// A board of the vendored wiringX without its own variant yet, named like in wiringX.
+    ///
+    /// These are found in the wiringX sources when building, so boards added upstream can be used
+    /// before their variant with the data curated for this library, like the buses, is added.
+    /// Created by [`from_string`](Self::from_string) and listed by [`iter`](Self::iter).
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Vendored(&'static str),
+
+    /// Any other board, without wiringX.
+    ///
+    /// Only GPIO is available, through [`GpioBackend::Sysfs`](crate::GpioBackend::Sysfs)
+    /// or [`GpioBackend::Gpiochip`](crate::GpioBackend::Gpiochip),
+    /// or GPIO and PWM are simulated with the `Mock` backend of the `mock` feature.
+    Generic,
+
+    /// A board described at runtime, created with [`Platform::custom`].
+    ///
+    /// Like with [`Generic`](Self::Generic) wiringX is not set up, GPIO goes through the sysfs or gpiochip backend
+    /// with the pin numbers of the [`PlatformSpec`], and PWM through the sysfs PWM interface.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(&'static PlatformSpec),
 }
 
 impl Platform {
-    pub(crate) fn as_c_addr(&self) -> *mut c_char {
-        let string = match self {
+    /// Every platform with its own variant, in the order of [`iter`](Self::iter).
+    const ALL: [Self; 30] = [
+        Self::Odriodc1,
+        Self::Odriodc2,
+        Self::Odriodxu4,
+        Self::BananaPi1,
+        Self::BananaPim2,
+        Self::Pcduino1,
+        Self::MilkVDuo,
+        Self::MilkVDuo256M,
+        Self::MilkVDuoS,
+        Self::Rock4,
+        Self::Rock5b,
+        Self::RaspberryPi1b1,
+        Self::RaspberryPi1b2,
+        Self::RaspberryPi1bPlus,
+        Self::RaspberryPi2,
+        Self::RaspberryPi3,
+        Self::RaspberryPi4,
+        Self::RaspberryPiZero,
+        Self::HummingboardBasedq,
+        Self::HummingboardProdq,
+        Self::HummingboardBasesdl,
+        Self::HummingboardProsdl,
+        Self::HummingboardGatedq,
+        Self::HummingboardEdgedq,
+        Self::HummingboardGatesdl,
+        Self::HummingboardEdgesdl,
+        Self::OrangePiPC2,
+        Self::OrangePiPCPlus,
+        Self::OrangePiZero3,
+        Self::Generic,
+    ];
+
+    /// Returns all built in platforms, for example to list the choices of a command line option.
+    ///
+    /// The [vendored](Self::Vendored) ones without their own variant come right before [`Generic`](Self::Generic).
+    pub fn iter() -> impl Iterator<Item = Self> {
+        Self::all().iter().copied()
+    }
+
+    /// Returns all built in platforms as a slice, in the same order as [`iter`](Self::iter).
+    pub fn all() -> &'static [Self] {
+        static ALL: OnceLock<Vec<Platform>> = OnceLock::new();
+
+        ALL.get_or_init(|| {
+            let vendored = PLATFORMS
+                .iter()
+                .filter(|source| {
+                    !Self::ALL.iter().any(|platform| {
+                        platform.name() == source.name || source.aliases.contains(&platform.name())
+                    })
+                })
+                .map(|source| Self::Vendored(source.name));
+
+            let (generic, variants) = Self::ALL.split_last().unwrap();
+            variants
+                .iter()
+                .copied()
+                .chain(vendored)
+                .chain([*generic])
+                .collect()
+        })
+    }
+
+    /// Returns the platform of the vendored wiringX sources with the name.
+    const fn source(name: &str) -> Option<&'static PlatformSource> {
+        let sources: &'static [PlatformSource] = &PLATFORMS;
+
+        let mut index = 0;
+        while index < sources.len() {
+            let source = &sources[index];
+            if str_eq(source.name, name) {
+                return Some(source);
+            }
+
+            let mut alias = 0;
+            while alias < source.aliases.len() {
+                if str_eq(source.aliases[alias], name) {
+                    return Some(source);
+                }
+                alias += 1;
+            }
+
+            index += 1;
+        }
+
+        None
+    }
+
+    /// Returns the pins of the platform in the vendored wiringX sources, with no buses.
+    fn wiringx_pins(&self) -> PlatformInfo {
+        match Self::source(self.name()) {
+            Some(source) => PlatformInfo {
+                gpio_pins: source.gpio_pins,
+                pwm_pins: source.pwm_pins,
+                isr_pins: source.isr_pins,
+                ..PlatformInfo::default()
+            },
+            None => PlatformInfo::default(),
+        }
+    }
+
+    /// Returns what the platform offers through this library, to validate pins and buses before setting them up.
+    pub fn info(&self) -> PlatformInfo {
+        match self {
+            Self::Odriodc1 => PlatformInfo {
+                i2c_buses: &["/dev/i2c-1", "/dev/i2c-2"],
+                spi_channels: &[],
+                uarts: &["/dev/ttyS2"],
+                ..self.wiringx_pins()
+            },
+            Self::Odriodc2 => PlatformInfo {
+                i2c_buses: &["/dev/i2c-1", "/dev/i2c-2"],
+                spi_channels: &[],
+                uarts: &["/dev/ttyS1"],
+                ..self.wiringx_pins()
+            },
+            Self::Odriodxu4 => PlatformInfo {
+                i2c_buses: &["/dev/i2c-1", "/dev/i2c-5"],
+                spi_channels: &[],
+                uarts: &["/dev/ttySAC0"],
+                ..self.wiringx_pins()
+            },
+            Self::BananaPi1 => PlatformInfo {
+                i2c_buses: &["/dev/i2c-2"],
+                spi_channels: &[0, 1],
+                uarts: &["/dev/ttyS2"],
+                ..self.wiringx_pins()
+            },
+            Self::BananaPim2 => PlatformInfo {
+                i2c_buses: &["/dev/i2c-2"],
+                spi_channels: &[0],
+                uarts: &["/dev/ttyS2"],
+                ..self.wiringx_pins()
+            },
+            Self::Pcduino1 => PlatformInfo {
+                i2c_buses: &["/dev/i2c-2"],
+                spi_channels: &[0],
+                uarts: &["/dev/ttyS1"],
+                ..self.wiringx_pins()
+            },
+            Self::MilkVDuo => PlatformInfo {
+                i2c_buses: &MILKV_DUO_I2C,
+                spi_channels: &[0],
+                uarts: &MILKV_UARTS,
+                ..self.wiringx_pins()
+            },
+            Self::MilkVDuo256M => PlatformInfo {
+                i2c_buses: &MILKV_DUO_I2C,
+                spi_channels: &[0],
+                uarts: &MILKV_UARTS,
+                ..self.wiringx_pins()
+            },
+            Self::MilkVDuoS => PlatformInfo {
+                i2c_buses: &["/dev/i2c-1", "/dev/i2c-2", "/dev/i2c-4"],
+                spi_channels: &[0],
+                uarts: &MILKV_UARTS,
+                ..self.wiringx_pins()
+            },
+            Self::Rock4 => PlatformInfo {
+                i2c_buses: &["/dev/i2c-2", "/dev/i2c-6", "/dev/i2c-7"],
+                spi_channels: &[],
+                uarts: &["/dev/ttyS2", "/dev/ttyS4"],
+                ..self.wiringx_pins()
+            },
+            Self::Rock5b => PlatformInfo {
+                i2c_buses: &["/dev/i2c-3", "/dev/i2c-6", "/dev/i2c-7", "/dev/i2c-8"],
+                spi_channels: &[],
+                uarts: &["/dev/ttyS2", "/dev/ttyS4", "/dev/ttyS7"],
+                ..self.wiringx_pins()
+            },
+            Self::RaspberryPi1b1 => PlatformInfo {
+                i2c_buses: &["/dev/i2c-0"],
+                spi_channels: &[0, 1],
+                uarts: &["/dev/ttyAMA0"],
+                ..self.wiringx_pins()
+            },
+            Self::RaspberryPi1b2 => PlatformInfo {
+                i2c_buses: &["/dev/i2c-1"],
+                spi_channels: &[0, 1],
+                uarts: &["/dev/ttyAMA0"],
+                ..self.wiringx_pins()
+            },
+            Self::RaspberryPi1bPlus | Self::RaspberryPi2 => PlatformInfo {
+                i2c_buses: &["/dev/i2c-1"],
+                spi_channels: &[0, 1],
+                uarts: &["/dev/ttyAMA0"],
+                ..self.wiringx_pins()
+            },
+            Self::RaspberryPi3 | Self::RaspberryPi4 | Self::RaspberryPiZero => PlatformInfo {
+                i2c_buses: &["/dev/i2c-1"],
+                spi_channels: &[0, 1],
+                uarts: &["/dev/serial0"],
+                ..self.wiringx_pins()
+            },
+            Self::HummingboardBasedq
+            | Self::HummingboardProdq
+            | Self::HummingboardBasesdl
+            | Self::HummingboardProsdl => PlatformInfo {
+                i2c_buses: &["/dev/i2c-2"],
+                spi_channels: &[],
+                uarts: &["/dev/ttymxc1"],
+                ..self.wiringx_pins()
+            },
+            Self::HummingboardGatedq | Self::HummingboardEdgedq => PlatformInfo {
+                i2c_buses: &["/dev/i2c-2"],
+                spi_channels: &[],
+                uarts: &["/dev/ttymxc1"],
+                ..self.wiringx_pins()
+            },
+            Self::HummingboardGatesdl | Self::HummingboardEdgesdl => PlatformInfo {
+                i2c_buses: &["/dev/i2c-2"],
+                spi_channels: &[],
+                uarts: &["/dev/ttymxc1"],
+                ..self.wiringx_pins()
+            },
+            Self::OrangePiPC2 => PlatformInfo {
+                i2c_buses: &["/dev/i2c-0", "/dev/i2c-1"],
+                spi_channels: &[0],
+                uarts: &["/dev/ttyS3"],
+                ..self.wiringx_pins()
+            },
+            Self::OrangePiPCPlus => PlatformInfo {
+                i2c_buses: &["/dev/i2c-0", "/dev/i2c-1"],
+                spi_channels: &[0],
+                uarts: &["/dev/ttyS3"],
+                ..self.wiringx_pins()
+            },
+            Self::OrangePiZero3 => PlatformInfo {
+                gpio_pins: &PINS_17,
+                pwm_pins: &[0, 1, 3, 4],
+                isr_pins: &PINS_17,
+                i2c_buses: &["/dev/i2c-3"],
+                // Only SPI1 is on the header, which is not one of the `/dev/spidev0.*` wiringX opens.
+                spi_channels: &[],
+                uarts: &["/dev/ttyS5"],
+            },
+            Self::Vendored(_) => self.wiringx_pins(),
+            Self::Custom(spec) => PlatformInfo {
+                gpio_pins: &spec.gpio_numbers,
+                pwm_pins: &spec.pwm_numbers,
+                isr_pins: &spec.gpio_numbers,
+                i2c_buses: &[],
+                spi_channels: &[],
+                uarts: &[],
+            },
+            Self::Generic => PlatformInfo {
+                gpio_pins: &[],
+                pwm_pins: &[],
+                isr_pins: &[],
+                i2c_buses: &[],
+                spi_channels: &[],
+                uarts: &[],
+            },
+        }
+    }
+
+    /// Returns the name of the platform used by wiringX, like `milkv_duos`, which is also its [`Display`](fmt::Display).
+    pub const fn name(&self) -> &'static str {
+        match self {
             Self::Odriodc1 => "odroidc1",
             Self::Odriodc2 => "odroidc2",
             Self::Odriodxu4 => "odroidxu4",
@@ -75,14 +368,128 @@ impl Platform {
             Self::HummingboardGatesdl => "hummingboard_gate_sdl",
             Self::OrangePiPC2 => "orangepipc2",
             Self::OrangePiPCPlus => "orangepipc+",
-        };
+            Self::OrangePiZero3 => "orangepizero3",
+            Self::Vendored(name) => name,
+            Self::Generic => "generic",
+            Self::Custom(spec) => spec.name.as_str(),
+        }
+    }
+
+    /// Returns the PWM channel of the SoC the pin is muxed to for PWM, like `7` for `PWM_7`.
+    ///
+    /// The Milk-V boards share pin numbers but not channels: on the Duo pins `2` and `3` are `PWM_10` and `PWM_11`,
+    /// while on the Duo 256M they are `PWM_7` and `PWM_6`, and pins `10` and `11` take over `PWM_10` and `PWM_11`.
+    /// The channels of the wiringX platforms are the ones of the tables of their SoC in the vendored sources.
+    /// Returns none for pins without PWM and for platforms without.
+    pub fn pwm_channel(&self, pin: i32) -> Option<u32> {
+        if let Some(spec) = self.spec() {
+            return spec.pwm_channel(pin).map(|(_, channel)| channel);
+        }
+
+        Self::source(self.name())?
+            .pwm_channels
+            .iter()
+            .find(|(channel_pin, _)| *channel_pin == pin)
+            .map(|(_, channel)| *channel)
+    }
+
+    /// Returns the cargo feature compiling in the wiringX support of the platform, like `milkv`.
+    pub const fn feature(&self) -> Option<&'static str> {
+        match self {
+            Self::Odriodc1 | Self::Odriodc2 | Self::Odriodxu4 => Some("hardkernel"),
+            Self::BananaPi1 | Self::BananaPim2 => Some("lemaker"),
+            Self::Pcduino1 => Some("linksprite"),
+            Self::MilkVDuo | Self::MilkVDuo256M | Self::MilkVDuoS => Some("milkv"),
+            Self::Rock4 | Self::Rock5b => Some("radxa"),
+            Self::RaspberryPi1b1
+            | Self::RaspberryPi1b2
+            | Self::RaspberryPi1bPlus
+            | Self::RaspberryPi2
+            | Self::RaspberryPi3
+            | Self::RaspberryPi4
+            | Self::RaspberryPiZero => Some("raspberrypi"),
+            Self::HummingboardBasedq
+            | Self::HummingboardProdq
+            | Self::HummingboardBasesdl
+            | Self::HummingboardProsdl
+            | Self::HummingboardGatedq
+            | Self::HummingboardEdgedq
+            | Self::HummingboardGatesdl
+            | Self::HummingboardEdgesdl => Some("solidrun"),
+            Self::OrangePiPC2 | Self::OrangePiPCPlus => Some("xunlong"),
+            Self::Vendored(name) => match Self::source(name) {
+                Some(source) => Some(source.family),
+                None => None,
+            },
+            Self::OrangePiZero3 | Self::Generic | Self::Custom(_) => None,
+        }
+    }
+
+    /// Returns whether the wiringX support of the platform is compiled in, always true with `dlopen`.
+    pub(crate) fn is_compiled_in(&self) -> bool {
+        if cfg!(feature = "dlopen") {
+            return true;
+        }
 
-        let cstring = CString::new(string).unwrap();
+        const COMPILED_IN: [(&str, bool); 8] = [
+            ("hardkernel", cfg!(feature = "hardkernel")),
+            ("lemaker", cfg!(feature = "lemaker")),
+            ("linksprite", cfg!(feature = "linksprite")),
+            ("milkv", cfg!(feature = "milkv")),
+            ("radxa", cfg!(feature = "radxa")),
+            ("raspberrypi", cfg!(feature = "raspberrypi")),
+            ("solidrun", cfg!(feature = "solidrun")),
+            ("xunlong", cfg!(feature = "xunlong")),
+        ];
 
-        cstring.into_raw() as *mut c_char
+        match self.feature() {
+            Some(feature) => COMPILED_IN.contains(&(feature, true)),
+            None => true,
+        }
     }
 
-    /// Parses a string to the platform type.
+    /// Creates a platform from the description of a board, which is kept until the process exits.
+    pub fn custom(spec: PlatformSpec) -> Self {
+        Self::Custom(Box::leak(Box::new(spec.finish())))
+    }
+
+    /// Returns whether the platform is driven by wiringX, which is not set up for generic and custom platforms
+    /// and the boards wiringX does not support.
+    #[inline]
+    pub(crate) fn uses_wiringx(&self) -> bool {
+        !matches!(self, Self::OrangePiZero3 | Self::Generic | Self::Custom(_))
+    }
+
+    /// Returns the pin mapping of the platforms not driven by wiringX, none for the others and generic.
+    pub(crate) fn spec(&self) -> Option<&'static PlatformSpec> {
+        match self {
+            Self::OrangePiZero3 => {
+                static SPEC: OnceLock<PlatformSpec> = OnceLock::new();
+
+                Some(SPEC.get_or_init(|| {
+                    let spec = ORANGEPI_ZERO3_LINES.iter().zip(0..).fold(
+                        PlatformSpec::new("orangepizero3").gpio_chip(0),
+                        |spec, (&line, pin)| spec.pin(pin, 0, line),
+                    );
+
+                    ORANGEPI_ZERO3_PWM
+                        .iter()
+                        .fold(spec, |spec, &(pin, channel)| spec.pwm_pin(pin, 0, channel))
+                        .finish()
+                }))
+            }
+            Self::Custom(spec) => Some(spec),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn c_name(&self) -> CString {
+        CString::new(self.name()).unwrap()
+    }
+
+    /// Parses a string to the platform type, the same as [`str::parse`].
+    ///
+    /// Takes the names used by wiringX, see [`name`](Self::name), ignoring case.
     pub fn from_string(string: &str) -> Result<Self, PlatformParseError> {
         let platform = match string.to_lowercase().as_str() {
             "odroidc1" => Self::Odriodc1,
@@ -115,13 +522,374 @@ impl Platform {
             "orangepipc2" => Self::OrangePiPC2,
             "orangepipc+" => Self::OrangePiPCPlus,
             "orangepipcplus" => Self::OrangePiPCPlus,
-            _ => return Err(PlatformParseError(string.to_string())),
+            "orangepizero3" => Self::OrangePiZero3,
+            "generic" => Self::Generic,
+            name => PLATFORMS
+                .iter()
+                .flat_map(|source| iter::once(&source.name).chain(source.aliases))
+                .find(|&&vendored| vendored == name)
+                .map(|&name| Self::Vendored(name))
+                .ok_or_else(|| PlatformParseError(string.to_string()))?,
         };
 
         Ok(platform)
     }
 }
 
+impl Platform {
+    /// Detects the board from the model and compatible strings in `/proc/device-tree`.
+    ///
+    /// Returns none for other boards, without a device tree or for the Hummingboards,
+    /// whose variants can not be told apart by their device tree.
+    pub fn detect() -> Option<Self> {
+        let model = fs::read("/proc/device-tree/model").unwrap_or_default();
+        let compatible = fs::read("/proc/device-tree/compatible").unwrap_or_default();
+
+        let platform = device_tree_strings(&model)
+            .chain(device_tree_strings(&compatible))
+            .find_map(|name| Self::from_device_tree(&name));
+
+        platform
+    }
+
+    /// Returns the board of a device tree model or compatible string, like `Milk-V DuoS` or `raspberrypi,4-model-b`.
+    fn from_device_tree(name: &str) -> Option<Self> {
+        // Only letters and digits are compared, as the strings vary in case, spaces and punctuation.
+        let name: String = name
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .map(|char| char.to_ascii_lowercase())
+            .collect();
+        let has = |part: &str| name.contains(part);
+
+        let platform = if has("milkvduos") {
+            Self::MilkVDuoS
+        } else if has("milkvduo256m") {
+            Self::MilkVDuo256M
+        } else if has("milkvduo") {
+            Self::MilkVDuo
+        } else if has("raspberrypizero2") {
+            // The Zero 2 has the SoC of the 3.
+            Self::RaspberryPi3
+        } else if has("raspberrypizero") {
+            Self::RaspberryPiZero
+        } else if has("raspberrypi4") {
+            Self::RaspberryPi4
+        } else if has("raspberrypi3") {
+            Self::RaspberryPi3
+        } else if has("raspberrypi2") {
+            Self::RaspberryPi2
+        } else if has("raspberrypimodelbplus") {
+            Self::RaspberryPi1bPlus
+        } else if has("raspberrypimodelbrev1") {
+            Self::RaspberryPi1b1
+        } else if has("raspberrypimodelb") {
+            Self::RaspberryPi1b2
+        } else if has("odroidc1") {
+            Self::Odriodc1
+        } else if has("odroidc2") {
+            Self::Odriodc2
+        } else if has("odroidxu4") {
+            Self::Odriodxu4
+        } else if has("bananapim2") || has("bpim2") {
+            Self::BananaPim2
+        } else if has("bananapi") {
+            Self::BananaPi1
+        } else if name == "linkspritepcduino" || has("pcduino1") {
+            Self::Pcduino1
+        } else if has("rockpi4") || has("rock4") {
+            Self::Rock4
+        } else if has("rock5b") || has("rock5modelb") {
+            Self::Rock5b
+        } else if has("orangepizero3") {
+            Self::OrangePiZero3
+        } else if has("orangepipc2") {
+            Self::OrangePiPC2
+        } else if has("orangepipcplus") {
+            Self::OrangePiPCPlus
+        } else {
+            return None;
+        };
+
+        Some(platform)
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Platform {
+    type Err = PlatformParseError;
+
+    #[inline]
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        Self::from_string(string)
+    }
+}
+
+/// Description of a board wiringX does not support, for [`Platform::custom`].
+///
+/// The pins get board numbers, mapped to the lines of GPIO chips and the channels of PWM chips.
+/// Example for a board with two GPIO chips of 32 lines each and one PWM chip:
+/// ```no_run
+/// use wiringx::{GpioBackend, Output, Platform, PlatformSpec, WiringX};
+///
+/// let spec = PlatformSpec::new("my_board")
+///     .gpio_chip(0)
+///     .gpio_chip(32)
+///     .pin(1, 0, 12)
+///     .pin(2, 1, 3)
+///     .pwm_pin(3, 0, 1);
+///
+/// let wiringx = WiringX::with_gpio_backend(Platform::custom(spec), GpioBackend::Sysfs).unwrap();
+///
+/// // Line `3` of the second chip, the sysfs GPIO `35`
+/// let mut pin = wiringx.gpio_pin::<Output>(2).unwrap();
+/// pin.toggle();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PlatformSpec {
+    name: String,
+    gpio_chips: Vec<i32>,
+    pins: BTreeMap<i32, (usize, u32)>,
+    pwm_pins: BTreeMap<i32, (u32, u32)>,
+    /// Listed for [`PlatformInfo`] by [`Platform::custom`].
+    gpio_numbers: Vec<i32>,
+    pwm_numbers: Vec<i32>,
+}
+
+impl PlatformSpec {
+    /// Starts the description of a board without pins, with the name returned by [`Platform::name`].
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Adds a GPIO chip with the sysfs number of its first line.
+    ///
+    /// Chips are referred to by the order they are added in, which has to be the order of the paths
+    /// given to [`GpioBackend::Gpiochip`](crate::GpioBackend::Gpiochip), where the base is not used.
+    pub fn gpio_chip(mut self, base: i32) -> Self {
+        self.gpio_chips.push(base);
+        self
+    }
+
+    /// Maps the board pin number to the line of the GPIO chip.
+    pub fn pin(mut self, pin: i32, chip: usize, line: u32) -> Self {
+        self.pins.insert(pin, (chip, line));
+        self
+    }
+
+    /// Maps the board pin number to the channel of `/sys/class/pwm/pwmchipN`.
+    pub fn pwm_pin(mut self, pin: i32, pwm_chip: u32, channel: u32) -> Self {
+        self.pwm_pins.insert(pin, (pwm_chip, channel));
+        self
+    }
+
+    /// Returns the name of the board.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the index of the GPIO chip and the line the board pin is mapped to.
+    #[inline]
+    pub fn line(&self, pin: i32) -> Option<(usize, u32)> {
+        self.pins.get(&pin).copied()
+    }
+
+    /// Returns the board pin mapped to the line of the GPIO chip.
+    pub fn pin_at(&self, chip: usize, line: u32) -> Option<i32> {
+        self.pins
+            .iter()
+            .find(|(_, &mapped)| mapped == (chip, line))
+            .map(|(&pin, _)| pin)
+    }
+
+    /// Returns the PWM chip and channel the board pin is mapped to.
+    #[inline]
+    pub fn pwm_channel(&self, pin: i32) -> Option<(u32, u32)> {
+        self.pwm_pins.get(&pin).copied()
+    }
+
+    /// Lists the pins for [`PlatformInfo`], once all are added.
+    fn finish(mut self) -> Self {
+        self.gpio_numbers = self.pins.keys().copied().collect();
+        self.pwm_numbers = self.pwm_pins.keys().copied().collect();
+        self
+    }
+
+    /// Returns the sysfs GPIO number of the board pin.
+    pub(crate) fn sysfs_number(&self, pin: i32) -> Option<i32> {
+        let (chip, line) = self.line(pin)?;
+        let base = self.gpio_chips.get(chip)?;
+
+        base.checked_add(i32::try_from(line).ok()?)
+    }
+}
+
+/// What a platform offers through this library, returned by [`Platform::info`].
+///
+/// The pins are the wiringX numbers mapped to a GPIO of the SoC, and the PWM and interrupt pins the ones wiringX
+/// implements these for, read from the vendored wiringX sources when building. The buses are the ones on the pin header of the vendor images,
+/// which can differ with other images or device tree overlays.
+/// [`Generic`](Platform::Generic) lists nothing, as its pins and buses are only known on the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PlatformInfo {
+    /// Pins usable for digital I/O.
+    pub gpio_pins: &'static [i32],
+    /// Pins usable with [`WiringX::pwm_pin`](crate::WiringX::pwm_pin).
+    pub pwm_pins: &'static [i32],
+    /// Pins that can wait for edges.
+    pub isr_pins: &'static [i32],
+    /// Device paths of the I2C buses, for [`WiringX::setup_i2c`](crate::WiringX::setup_i2c).
+    pub i2c_buses: &'static [&'static str],
+    /// Channels of `/dev/spidev0.*`, for [`WiringX::setup_spi`](crate::WiringX::setup_spi).
+    pub spi_channels: &'static [i32],
+    /// Device paths of the UARTs, for [`WiringX::setup_uart`](crate::WiringX::setup_uart).
+    pub uarts: &'static [&'static str],
+}
+
+impl PlatformInfo {
+    /// Returns the number of pins usable for digital I/O.
+    #[inline]
+    pub fn gpio_count(&self) -> usize {
+        self.gpio_pins.len()
+    }
+}
+
+/// The board the program runs on, as described by the kernel, returned by [`WiringX::board_info`](crate::WiringX::board_info).
+///
+/// Read from the device tree and `/proc/cpuinfo`, each field is none if the kernel does not tell.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct BoardInfo {
+    /// Model of the board, like `Milk-V DuoS` or `Raspberry Pi 4 Model B Rev 1.4`.
+    pub model: Option<String>,
+    /// Revision of the board, like the `Revision` code of the Raspberry Pis, in hexadecimal.
+    pub revision: Option<String>,
+    /// SoC of the board, like `sophgo,sg2000` or `brcm,bcm2711`.
+    pub soc: Option<String>,
+    /// Platform detected from the model, see [`Platform::detect`].
+    pub platform: Option<Platform>,
+}
+
+impl BoardInfo {
+    /// Reads the description of the board the program runs on.
+    pub fn read() -> Self {
+        let cpuinfo = fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+        let cpuinfo_field = |name: &str| {
+            cpuinfo.lines().find_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                let value = value.trim();
+                (key.trim() == name && !value.is_empty()).then(|| value.to_string())
+            })
+        };
+
+        let model = fs::read("/proc/device-tree/model")
+            .ok()
+            .and_then(|model| device_tree_strings(&model).next())
+            .or_else(|| cpuinfo_field("Model"));
+
+        let revision = cpuinfo_field("Revision").or_else(|| {
+            let revision = fs::read("/proc/device-tree/system/linux,revision").ok()?;
+            let revision = u32::from_be_bytes(revision.get(..4)?.try_into().ok()?);
+            Some(format!("{revision:x}"))
+        });
+
+        // The compatible strings go from the board to the SoC.
+        let soc = fs::read("/proc/device-tree/compatible")
+            .ok()
+            .and_then(|compatible| device_tree_strings(&compatible).last())
+            .or_else(|| cpuinfo_field("Hardware"));
+
+        Self {
+            model,
+            revision,
+            soc,
+            platform: Platform::detect(),
+        }
+    }
+}
+
+/// Returns the non-empty strings of a device tree property, a list of NUL terminated strings.
+fn device_tree_strings(property: &[u8]) -> impl Iterator<Item = String> + '_ {
+    property
+        .split(|&byte| byte == 0)
+        .filter(|string| !string.is_empty())
+        .map(|string| String::from_utf8_lossy(string).into_owned())
+}
+
+/// Returns the pins `0..N`, for the platforms mapping all of them.
+const fn pins<const N: usize>() -> [i32; N] {
+    let mut pins = [0; N];
+    let mut pin = 0;
+    while pin < N {
+        pins[pin] = pin as i32;
+        pin += 1;
+    }
+    pins
+}
+
+const PINS_17: [i32; 17] = pins();
+/// Lines of the pins of the Orange Pi Zero 3, `32 * bank + index` like `229` for `PH5`.
+const ORANGEPI_ZERO3_LINES: [u32; 17] = [
+    229, 228, 73, 226, 227, 70, 75, 69, 72, 79, 78, 231, 232, 71, 230, 233, 74,
+];
+/// Pins of the Orange Pi Zero 3 and their channels of `pwmchip0`, `PWM1` to `PWM4` on `PH3` to `PH5` and `PH2`.
+const ORANGEPI_ZERO3_PWM: [(i32, u32); 4] = [(0, 4), (1, 3), (3, 2), (4, 1)];
+const MILKV_DUO_I2C: [&str; 3] = ["/dev/i2c-0", "/dev/i2c-1", "/dev/i2c-3"];
+const MILKV_UARTS: [&str; 5] = [
+    "/dev/ttyS0",
+    "/dev/ttyS1",
+    "/dev/ttyS2",
+    "/dev/ttyS3",
+    "/dev/ttyS4",
+];
+
+/// Fails the build if a platform with its own variant is missing in the vendored wiringX sources
+/// or is in another family there, as its pins are taken from the sources.
+const _: () = {
+    let mut index = 0;
+    while index < Platform::ALL.len() {
+        let platform = Platform::ALL[index];
+        if let Some(feature) = platform.feature() {
+            match Platform::source(platform.name()) {
+                Some(source) => assert!(
+                    str_eq(source.family, feature),
+                    "a platform is in another family in the vendored wiringX sources"
+                ),
+                None => panic!("a platform is missing in the vendored wiringX sources"),
+            }
+        }
+        index += 1;
+    }
+};
+
+/// Compares the strings in const contexts, where `==` is not available.
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut index = 0;
+    while index < a.len() {
+        if a[index] != b[index] {
+            return false;
+        }
+        index += 1;
+    }
+
+    true
+}
+
 /// Returns when the given platform string is invalid.
 #[derive(Debug, Error)]
 #[error("Can not determine a valid platform from {0}.")]
@@ -0,0 +1,98 @@
+//! Switching the process to an unprivileged user once everything needing root is set up.
+
+use std::{ffi::CString, io, mem::MaybeUninit, ptr};
+
+use crate::WiringXError;
+
+/// Size of the buffer for the strings of the user and group entries.
+const ENTRY_BUFFER: usize = 16 * 1024;
+
+/// Switches the process to the user and group, given by name or number, with no other groups.
+pub(crate) fn drop_to(user: &str, group: &str) -> Result<(), WiringXError> {
+    let uid = uid_of(user)?;
+    let gid = gid_of(group)?;
+
+    // The groups go first, as they can not be changed anymore without root.
+    check(unsafe { libc::setgroups(1, &gid) })?;
+    check(unsafe { libc::setgid(gid) })?;
+    check(unsafe { libc::setuid(uid) })?;
+
+    // Getting root back has to fail now, unless switching to root.
+    if uid != 0 && unsafe { libc::setuid(0) } == 0 {
+        return Err(WiringXError::Other(
+            "The root privileges could be regained after dropping them.".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn uid_of(user: &str) -> Result<libc::uid_t, WiringXError> {
+    if let Ok(uid) = user.parse() {
+        return Ok(uid);
+    }
+
+    let name = CString::new(user).map_err(|_| WiringXError::InvalidArgument)?;
+    let mut entry = MaybeUninit::<libc::passwd>::uninit();
+    let mut buffer = vec![0; ENTRY_BUFFER];
+    let mut result = ptr::null_mut();
+
+    let error = unsafe {
+        libc::getpwnam_r(
+            name.as_ptr(),
+            entry.as_mut_ptr(),
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            &mut result,
+        )
+    };
+    if error != 0 {
+        return Err(WiringXError::Io(io::Error::from_raw_os_error(error)));
+    }
+    if result.is_null() {
+        return Err(WiringXError::Other(format!(
+            "The user {user} does not exist."
+        )));
+    }
+
+    Ok(unsafe { entry.assume_init() }.pw_uid)
+}
+
+fn gid_of(group: &str) -> Result<libc::gid_t, WiringXError> {
+    if let Ok(gid) = group.parse() {
+        return Ok(gid);
+    }
+
+    let name = CString::new(group).map_err(|_| WiringXError::InvalidArgument)?;
+    let mut entry = MaybeUninit::<libc::group>::uninit();
+    let mut buffer = vec![0; ENTRY_BUFFER];
+    let mut result = ptr::null_mut();
+
+    let error = unsafe {
+        libc::getgrnam_r(
+            name.as_ptr(),
+            entry.as_mut_ptr(),
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            &mut result,
+        )
+    };
+    if error != 0 {
+        return Err(WiringXError::Io(io::Error::from_raw_os_error(error)));
+    }
+    if result.is_null() {
+        return Err(WiringXError::Other(format!(
+            "The group {group} does not exist."
+        )));
+    }
+
+    Ok(unsafe { entry.assume_init() }.gr_gid)
+}
+
+fn check(result: libc::c_int) -> Result<(), WiringXError> {
+    if result < 0 {
+        Err(WiringXError::Io(io::Error::last_os_error()))
+    } else {
+        Ok(())
+    }
+}
@@ -1,12 +1,26 @@
 //! Pulse width modulation related objects.
 
-use std::time::Duration;
+use std::{fmt, fs, io, ops::RangeInclusive, path::PathBuf, sync::Arc, time::Duration};
 
 use wiringx_sys::{
     wiringXPWMEnable, wiringXPWMSetDuty, wiringXPWMSetPeriod, wiringXPWMSetPolarity,
 };
 
-use crate::{Hand, WiringXError};
+#[cfg(feature = "events")]
+use crate::events::{self, Event};
+#[cfg(feature = "metrics")]
+use crate::metrics;
+#[cfg(feature = "trace")]
+use crate::trace::{self, TraceKind};
+use crate::{
+    reservation::Reservation,
+    safe_state::{self, SafeState},
+    wiringx_call, Claim, Hand, PinOperation, PlatformSpec, WiringXError,
+};
+
+const SYSFS_PWM: &str = "/sys/class/pwm";
+/// Longest period or duty, as wiringX takes them in ns as a `long`.
+const MAX_TIME: Duration = Duration::from_nanos(i64::MAX as u64);
 
 /// Instance of a pulse-width modulated pin.
 ///
@@ -40,6 +54,9 @@ use crate::{Hand, WiringXError};
 pub struct PwmPin {
     number: i32,
     handles: Hand<i32>,
+    backend: Arc<dyn PwmBackend>,
+    _reservation: Option<Reservation>,
+    safe_state: Option<SafeState>,
 
     period: Duration,
     duty_cycle: f32,
@@ -49,84 +66,112 @@ pub struct PwmPin {
 impl PwmPin {
     pub(super) fn new(
         number: i32,
-        handles: Hand<i32>,
+        claim: Claim<i32>,
+        reservation: Option<Reservation>,
+        backend: Arc<dyn PwmBackend>,
         period: Duration,
         duty_cycle: f32,
         polarity: Polarity,
     ) -> Result<Self, WiringXError> {
-        if handles.lock().contains(&number) {
-            return Err(WiringXError::PinUsed);
-        }
-
-        let result = unsafe { wiringXPWMSetPeriod(number, period.as_nanos() as i64) };
+        let failed = |operation| {
+            move |error: io::Error| WiringXError::Pin {
+                pin: number,
+                operation,
+                errno: error.raw_os_error(),
+            }
+        };
 
-        if result < 0 {
-            let result = unsafe { wiringXPWMSetDuty(number, 0) };
-            if result < 0 {
+        // A duty left from before may exceed the period, then it is cleared first.
+        if apply_period(&*backend, number, period, Duration::ZERO).is_err() {
+            if backend.set_duty(number, 0).is_err() {
                 return Err(WiringXError::Unsupported);
             }
 
-            let result = unsafe { wiringXPWMSetPeriod(number, period.as_nanos() as i64) };
-            if result < 0 {
-                return Err(WiringXError::InvalidArgument);
-            }
+            apply_period(&*backend, number, period, Duration::ZERO)?;
         }
 
         let duty_cycle = duty_cycle.clamp(0.0, 1.0);
 
-        let result =
-            unsafe { wiringXPWMSetDuty(number, period.mul_f32(duty_cycle).as_nanos() as i64) };
-
-        if result < 0 {
-            return Err(WiringXError::InvalidArgument);
-        }
+        apply_duty(&*backend, number, period.mul_f32(duty_cycle), period)?;
+        backend
+            .set_polarity(number, polarity as i32)
+            .map_err(failed(PinOperation::SetPwmPolarity))?;
+        backend
+            .enable(number, 1)
+            .map_err(failed(PinOperation::EnablePwm))?;
 
-        let result = unsafe { wiringXPWMSetPolarity(number, polarity as i32) };
+        let handles = claim.keep();
 
-        if result < 0 {
-            return Err(WiringXError::InvalidArgument);
+        #[cfg(feature = "trace")]
+        {
+            trace::log(number, TraceKind::Period(period));
+            trace::log(number, TraceKind::DutyCycle(duty_cycle));
         }
 
-        let result = unsafe { wiringXPWMEnable(number, 1) };
+        #[cfg(feature = "metrics")]
+        metrics::duty_cycle(number, duty_cycle);
 
-        if result < 0 {
-            return Err(WiringXError::InvalidArgument);
-        }
-
-        handles.lock().insert(number);
+        #[cfg(feature = "events")]
+        events::emit(|| Event::DutyCycle {
+            pin: number,
+            duty_cycle,
+        });
 
         Ok(Self {
             number,
             handles,
+            backend,
+            _reservation: reservation,
+            safe_state: None,
             period,
             duty_cycle,
             polarity,
         })
     }
 
-    /// Sets the period of time a PWM cycle takes.
+    /// Sets whether the output is disabled when the program panics or is interrupted.
+    ///
+    /// Only works after [`safe_state::install`](crate::safe_state::install), and ends when the pin is dropped.
+    pub fn set_safe_state(&mut self, disable: bool) {
+        self.safe_state = disable.then(|| {
+            let (backend, number) = (self.backend.clone(), self.number);
+            safe_state::register(move || {
+                let _ = backend.enable(number, 0);
+            })
+        });
+    }
+
+    /// Sets the period of time a PWM cycle takes, keeping the duty cycle.
+    ///
+    /// If the period or the duty cycle is rejected, the previous ones are set again before returning
+    /// [`PwmTime`](WiringXError::PwmTime) with the time tried.
     pub fn set_period(&mut self, period: Duration) -> Result<(), WiringXError> {
-        // First set duty cycle lower
-        let result = unsafe {
-            wiringXPWMSetDuty(
+        let duty = period.mul_f32(self.duty_cycle);
+
+        // The duty can not exceed the period at any time, so whichever gets shorter goes first.
+        let result = if period >= self.period {
+            apply_period(
+                &*self.backend,
                 self.number,
-                period.mul_f32(self.duty_cycle).as_nanos() as i64,
+                period,
+                self.duty_cycle_as_dur(),
             )
+            .and_then(|_| apply_duty(&*self.backend, self.number, duty, period))
+        } else {
+            apply_duty(&*self.backend, self.number, duty, self.period)
+                .and_then(|_| apply_period(&*self.backend, self.number, period, duty))
         };
 
-        if result < 0 {
-            return Err(WiringXError::InvalidArgument);
-        }
-
-        // Next set period
-        let result = unsafe { wiringXPWMSetPeriod(self.number, period.as_nanos() as i64) };
-
-        if result < 0 {
-            return Err(WiringXError::InvalidArgument);
+        if let Err(error) = result {
+            self.restore();
+            return Err(error);
         }
 
         self.period = period;
 
+        #[cfg(feature = "trace")]
+        trace::log(self.number, TraceKind::Period(period));
+
         Ok(())
     }
 
@@ -143,22 +188,31 @@ impl PwmPin {
     /// Takes a value from 0.0 - 1.0, where 0 represents 0% and 1 represents 100%
     ///
     /// Automatically clamps to a value in range, in case the given value is smaller than 0 or bigger than 1.
+    /// Returns [`PwmTime`](WiringXError::PwmTime) with the time tried if it is rejected.
     pub fn set_duty_cycle(&mut self, duty_cycle: f32) -> Result<(), WiringXError> {
         let duty_cycle = duty_cycle.clamp(0.0, 1.0);
 
-        let result = unsafe {
-            wiringXPWMSetDuty(
-                self.number,
-                self.period.mul_f32(duty_cycle).as_nanos() as i64,
-            )
-        };
-
-        if result < 0 {
-            return Err(WiringXError::InvalidArgument);
-        }
+        apply_duty(
+            &*self.backend,
+            self.number,
+            self.period.mul_f32(duty_cycle),
+            self.period,
+        )?;
 
         self.duty_cycle = duty_cycle;
 
+        #[cfg(feature = "trace")]
+        trace::log(self.number, TraceKind::DutyCycle(duty_cycle));
+
+        #[cfg(feature = "metrics")]
+        metrics::duty_cycle(self.number, duty_cycle);
+
+        #[cfg(feature = "events")]
+        events::emit(|| Event::DutyCycle {
+            pin: self.number,
+            duty_cycle,
+        });
+
         Ok(())
     }
 
@@ -175,11 +229,9 @@ impl PwmPin {
 
     /// Sets the polarity of the PWM pin.
     pub fn set_polarity(&mut self, polarity: Polarity) -> Result<(), WiringXError> {
-        let result = unsafe { wiringXPWMSetPolarity(self.number, polarity as i32) };
-
-        if result < 0 {
-            return Err(WiringXError::InvalidArgument);
-        }
+        self.backend
+            .set_polarity(self.number, polarity as i32)
+            .map_err(|e| self.error(PinOperation::SetPwmPolarity, e))?;
 
         self.polarity = polarity;
 
@@ -191,17 +243,265 @@ impl PwmPin {
     pub fn polarity(&self) -> Polarity {
         self.polarity
     }
+
+    /// Sets the period and duty stored in the struct again, after changing them failed halfway.
+    fn restore(&self) {
+        let period = self.period.as_nanos() as i64;
+        let duty = self.duty_cycle_as_dur().as_nanos() as i64;
+
+        // Setting the period only fails if the new duty exceeds the old period, which setting the old duty first fixes.
+        let restored = self.backend.set_period(self.number, period).is_ok();
+        let _ = self.backend.set_duty(self.number, duty);
+
+        if !restored {
+            let _ = self.backend.set_period(self.number, period);
+        }
+    }
+
+    fn error(&self, operation: PinOperation, error: io::Error) -> WiringXError {
+        WiringXError::Pin {
+            pin: self.number,
+            operation,
+            errno: error.raw_os_error(),
+        }
+    }
+}
+
+/// Sets the period of the pin, which can not be shorter than the duty set at the time.
+fn apply_period(
+    backend: &dyn PwmBackend,
+    number: i32,
+    period: Duration,
+    duty: Duration,
+) -> Result<(), WiringXError> {
+    apply(
+        number,
+        PinOperation::SetPwmPeriod,
+        period,
+        duty..=MAX_TIME,
+        |nanos| backend.set_period(number, nanos),
+    )
+}
+
+/// Sets the duty of the pin, which can not be longer than the period set at the time.
+fn apply_duty(
+    backend: &dyn PwmBackend,
+    number: i32,
+    duty: Duration,
+    period: Duration,
+) -> Result<(), WiringXError> {
+    apply(
+        number,
+        PinOperation::SetPwmDutyCycle,
+        duty,
+        Duration::ZERO..=period,
+        |nanos| backend.set_duty(number, nanos),
+    )
+}
+
+/// Passes the time on in ns if it is within the range, returning the time and range tried otherwise.
+/// The range is left out if the backend rejects the time, as the limits of the controller are not known.
+fn apply(
+    number: i32,
+    operation: PinOperation,
+    time: Duration,
+    range: RangeInclusive<Duration>,
+    set: impl FnOnce(i64) -> io::Result<()>,
+) -> Result<(), WiringXError> {
+    if !range.contains(&time) {
+        return Err(WiringXError::PwmTime {
+            pin: number,
+            operation,
+            time,
+            accepted: Some(range),
+            errno: None,
+        });
+    }
+
+    set(time.as_nanos() as i64).map_err(|e| WiringXError::PwmTime {
+        pin: number,
+        operation,
+        time,
+        accepted: None,
+        errno: e.raw_os_error(),
+    })
+}
+
+/// A pulse-width modulated output, implemented by native [`PwmPin`]s and the channels of PWM expanders,
+/// so drivers like `Servo` can be written once for both.
+pub trait PwmOutput {
+    /// Sets the period of time a PWM cycle takes.
+    fn set_period(&mut self, period: Duration) -> Result<(), WiringXError>;
+
+    /// Returns the period duration of the output.
+    fn period(&self) -> Duration;
+
+    /// Sets the proportion of the period the signal is high, clamped to 0.0 - 1.0.
+    fn set_duty_cycle(&mut self, duty_cycle: f32) -> Result<(), WiringXError>;
+
+    /// Returns the duty cycle of the output.
+    fn duty_cycle(&self) -> f32;
+
+    /// Returns the duty cycle in form of a duration.
+    fn duty_cycle_as_dur(&self) -> Duration {
+        self.period().mul_f32(self.duty_cycle())
+    }
+
+    /// Sets the polarity of the output.
+    fn set_polarity(&mut self, polarity: Polarity) -> Result<(), WiringXError>;
+
+    /// Returns the polarity of the output.
+    fn polarity(&self) -> Polarity;
+}
+
+impl PwmOutput for PwmPin {
+    #[inline]
+    fn set_period(&mut self, period: Duration) -> Result<(), WiringXError> {
+        PwmPin::set_period(self, period)
+    }
+
+    #[inline]
+    fn period(&self) -> Duration {
+        PwmPin::period(self)
+    }
+
+    #[inline]
+    fn set_duty_cycle(&mut self, duty_cycle: f32) -> Result<(), WiringXError> {
+        PwmPin::set_duty_cycle(self, duty_cycle)
+    }
+
+    #[inline]
+    fn duty_cycle(&self) -> f32 {
+        PwmPin::duty_cycle(self)
+    }
+
+    #[inline]
+    fn set_polarity(&mut self, polarity: Polarity) -> Result<(), WiringXError> {
+        PwmPin::set_polarity(self, polarity)
+    }
+
+    #[inline]
+    fn polarity(&self) -> Polarity {
+        PwmPin::polarity(self)
+    }
 }
 
 impl Drop for PwmPin {
     fn drop(&mut self) {
         self.handles.lock().remove(&self.number);
-        unsafe { wiringXPWMEnable(self.number, 0) };
+        let _ = self.backend.enable(self.number, 0);
+    }
+}
+
+/// What drives a [`PwmPin`], implemented for wiringX and the simulation.
+///
+/// Times are in ns and other arguments follow wiringX, errors carry the errno if there was one.
+pub(crate) trait PwmBackend: fmt::Debug + Send + Sync {
+    fn set_period(&self, number: i32, period: i64) -> io::Result<()>;
+
+    fn set_duty(&self, number: i32, duty: i64) -> io::Result<()>;
+
+    fn set_polarity(&self, number: i32, polarity: i32) -> io::Result<()>;
+
+    fn enable(&self, number: i32, enabled: i32) -> io::Result<()>;
+}
+
+/// PWM pins driven by wiringX.
+#[derive(Debug)]
+pub(crate) struct WiringXPwm;
+
+impl PwmBackend for WiringXPwm {
+    #[inline]
+    fn set_period(&self, number: i32, period: i64) -> io::Result<()> {
+        check(wiringx_call(|| unsafe {
+            wiringXPWMSetPeriod(number, period)
+        }))
+    }
+
+    #[inline]
+    fn set_duty(&self, number: i32, duty: i64) -> io::Result<()> {
+        check(wiringx_call(|| unsafe { wiringXPWMSetDuty(number, duty) }))
+    }
+
+    #[inline]
+    fn set_polarity(&self, number: i32, polarity: i32) -> io::Result<()> {
+        check(wiringx_call(|| unsafe {
+            wiringXPWMSetPolarity(number, polarity)
+        }))
+    }
+
+    #[inline]
+    fn enable(&self, number: i32, enabled: i32) -> io::Result<()> {
+        check(wiringx_call(|| unsafe {
+            wiringXPWMEnable(number, enabled)
+        }))
+    }
+}
+
+fn check(result: Result<i32, Option<i32>>) -> io::Result<()> {
+    match result {
+        Ok(_) => Ok(()),
+        Err(Some(errno)) => Err(io::Error::from_raw_os_error(errno)),
+        Err(None) => Err(io::Error::other("wiringX rejected the PWM setting")),
+    }
+}
+
+/// PWM pins of a custom platform, driven through `/sys/class/pwm`.
+#[derive(Debug)]
+pub(crate) struct SysfsPwm {
+    pub(crate) spec: &'static PlatformSpec,
+}
+
+impl SysfsPwm {
+    /// Writes the attribute of the channel the pin is mapped to, exporting the channel first if needed.
+    fn write(&self, number: i32, attribute: &str, value: &str) -> io::Result<()> {
+        let (chip, channel) = self
+            .spec
+            .pwm_channel(number)
+            .ok_or_else(|| io::Error::from_raw_os_error(libc::ENODEV))?;
+
+        let chip = PathBuf::from(format!("{SYSFS_PWM}/pwmchip{chip}"));
+        let path = chip.join(format!("pwm{channel}"));
+
+        if !path.exists() {
+            fs::write(chip.join("export"), channel.to_string())?;
+        }
+
+        fs::write(path.join(attribute), value)
+    }
+}
+
+impl PwmBackend for SysfsPwm {
+    #[inline]
+    fn set_period(&self, number: i32, period: i64) -> io::Result<()> {
+        self.write(number, "period", &period.to_string())
+    }
+
+    #[inline]
+    fn set_duty(&self, number: i32, duty: i64) -> io::Result<()> {
+        self.write(number, "duty_cycle", &duty.to_string())
+    }
+
+    #[inline]
+    fn set_polarity(&self, number: i32, polarity: i32) -> io::Result<()> {
+        let polarity = if polarity == Polarity::Inversed as i32 {
+            "inversed"
+        } else {
+            "normal"
+        };
+
+        self.write(number, "polarity", polarity)
+    }
+
+    #[inline]
+    fn enable(&self, number: i32, enabled: i32) -> io::Result<()> {
+        self.write(number, "enable", &enabled.to_string())
     }
 }
 
 /// PWM polarity of a pin.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum Polarity {
     Normal = 0,
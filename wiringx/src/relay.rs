@@ -0,0 +1,226 @@
+//! Relay boards.
+
+use std::fmt;
+
+use crate::{DigitalOutput, Value, WiringXError};
+
+/// A board of relays on output pins, each with a name and the level switching it on.
+///
+/// The pins can be any [`DigitalOutput`], so native pins and the pins of I/O expanders can be mixed.
+///
+/// Relays in an interlock group can not be on at the same time, like the two directions of a motor
+/// or a heater and a cooler. Switching one on while another one of its group is on fails.
+///
+/// All relays are switched off when the bank is dropped, also while unwinding from a panic.
+///
+/// Example with a shutter motor on a common active low relay module:
+/// ```no_run
+/// use std::{thread, time::Duration};
+///
+/// use wiringx::{Output, Platform, RelayBank, RelayPolarity, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+///
+/// let mut relays = RelayBank::new();
+/// relays
+///     .add("up", wiringx.gpio_pin::<Output>(14).unwrap(), RelayPolarity::ActiveLow)
+///     .unwrap();
+/// relays
+///     .add("down", wiringx.gpio_pin::<Output>(15).unwrap(), RelayPolarity::ActiveLow)
+///     .unwrap();
+/// relays.add_interlock(&["up", "down"]).unwrap();
+///
+/// relays.on("up").unwrap();
+/// thread::sleep(Duration::from_secs(10));
+/// relays.off("up").unwrap();
+/// ```
+#[derive(Debug, Default)]
+pub struct RelayBank {
+    relays: Vec<Relay>,
+    interlocks: Vec<Vec<usize>>,
+}
+
+struct Relay {
+    name: String,
+    pin: Box<dyn DigitalOutput + Send>,
+    polarity: RelayPolarity,
+    on: bool,
+}
+
+impl RelayBank {
+    /// Creates a bank without relays.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes ownership of the pin switching a relay, switches it off and adds it under the name.
+    ///
+    /// Returns [`InvalidArgument`](WiringXError::InvalidArgument) if the name is already used.
+    pub fn add(
+        &mut self,
+        name: impl Into<String>,
+        pin: impl DigitalOutput + Send + 'static,
+        polarity: RelayPolarity,
+    ) -> Result<(), WiringXError> {
+        let name = name.into();
+        if self.relays.iter().any(|relay| relay.name == name) {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        let mut relay = Relay {
+            name,
+            pin: Box::new(pin),
+            polarity,
+            on: false,
+        };
+        relay.write(false)?;
+        self.relays.push(relay);
+
+        Ok(())
+    }
+
+    /// Adds a group of at least two relays of which only one may be on at a time.
+    ///
+    /// Fails if more than one of them is on already.
+    pub fn add_interlock(&mut self, names: &[&str]) -> Result<(), WiringXError> {
+        let mut group = Vec::with_capacity(names.len());
+        for name in names {
+            let index = self.index(name)?;
+            if group.contains(&index) {
+                return Err(WiringXError::InvalidArgument);
+            }
+
+            group.push(index);
+        }
+
+        if group.len() < 2 {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        if group.iter().filter(|&&index| self.relays[index].on).count() > 1 {
+            return Err(WiringXError::Other(
+                "More than one of the interlocked relays is on.".to_string(),
+            ));
+        }
+
+        self.interlocks.push(group);
+
+        Ok(())
+    }
+
+    /// Returns the names of the relays, in the order they were added.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.relays.iter().map(|relay| relay.name.as_str())
+    }
+
+    /// Switches the relay on or off.
+    ///
+    /// Switching on fails if another relay interlocked with it is on, leaving both unchanged.
+    pub fn set(&mut self, name: &str, on: bool) -> Result<(), WiringXError> {
+        let index = self.index(name)?;
+
+        if on {
+            let interlocked = self
+                .interlocks
+                .iter()
+                .filter(|group| group.contains(&index))
+                .flatten()
+                .find(|&&other| other != index && self.relays[other].on);
+
+            if let Some(&other) = interlocked {
+                return Err(WiringXError::Other(format!(
+                    "The relay {name} is interlocked with {}, which is on.",
+                    self.relays[other].name
+                )));
+            }
+        }
+
+        self.relays[index].write(on)
+    }
+
+    /// Switches the relay on, see [`set`](Self::set).
+    #[inline]
+    pub fn on(&mut self, name: &str) -> Result<(), WiringXError> {
+        self.set(name, true)
+    }
+
+    /// Switches the relay off.
+    #[inline]
+    pub fn off(&mut self, name: &str) -> Result<(), WiringXError> {
+        self.set(name, false)
+    }
+
+    /// Switches the relay on if it was off or off if it was on, see [`set`](Self::set).
+    pub fn toggle(&mut self, name: &str) -> Result<(), WiringXError> {
+        let on = self.is_on(name)?;
+
+        self.set(name, !on)
+    }
+
+    /// Returns whether the relay is on.
+    pub fn is_on(&self, name: &str) -> Result<bool, WiringXError> {
+        Ok(self.relays[self.index(name)?].on)
+    }
+
+    /// Switches all relays off, trying all of them even if switching one fails.
+    pub fn all_off(&mut self) -> Result<(), WiringXError> {
+        let mut result = Ok(());
+        for relay in &mut self.relays {
+            if let Err(e) = relay.write(false) {
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+
+        result
+    }
+
+    fn index(&self, name: &str) -> Result<usize, WiringXError> {
+        self.relays
+            .iter()
+            .position(|relay| relay.name == name)
+            .ok_or(WiringXError::InvalidArgument)
+    }
+}
+
+impl Drop for RelayBank {
+    fn drop(&mut self) {
+        let _ = self.all_off();
+    }
+}
+
+impl Relay {
+    fn write(&mut self, on: bool) -> Result<(), WiringXError> {
+        let value = match (self.polarity, on) {
+            (RelayPolarity::ActiveHigh, true) | (RelayPolarity::ActiveLow, false) => Value::High,
+            (RelayPolarity::ActiveHigh, false) | (RelayPolarity::ActiveLow, true) => Value::Low,
+        };
+
+        self.pin.write(value)?;
+        self.on = on;
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Relay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Relay")
+            .field("name", &self.name)
+            .field("polarity", &self.polarity)
+            .field("on", &self.on)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Level of the pin switching a relay of a [`RelayBank`] on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RelayPolarity {
+    /// On at high level
+    ActiveHigh,
+    /// On at low level, like most relay modules with optocouplers
+    ActiveLow,
+}
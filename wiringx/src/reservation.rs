@@ -0,0 +1,49 @@
+//! Advisory locks reserving pins across processes, see [`WiringX::lock_pins_in`](crate::WiringX::lock_pins_in).
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    os::{fd::AsRawFd, unix::fs::OpenOptionsExt},
+    path::Path,
+};
+
+use crate::WiringXError;
+
+/// Directory for the lock files shared by all processes of the board.
+pub const PIN_LOCK_DIR: &str = "/run/wiringx";
+
+/// The lock of a pin, held until dropped, when the file is closed.
+#[derive(Debug)]
+pub(crate) struct Reservation {
+    _file: File,
+}
+
+impl Reservation {
+    /// Locks `pin-N.lock` in the directory, returning [`PinUsed`](WiringXError::PinUsed) if another process holds it.
+    pub(crate) fn acquire(dir: &Path, pin: i32) -> Result<Self, WiringXError> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .mode(0o666)
+            .open(dir.join(format!("pin-{pin}.lock")))
+            .map_err(WiringXError::Io)?;
+
+        // Released by the kernel when the process exits, even when it crashes.
+        if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } < 0 {
+            let error = io::Error::last_os_error();
+            return Err(if error.kind() == io::ErrorKind::WouldBlock {
+                WiringXError::PinUsed
+            } else {
+                WiringXError::Io(error)
+            });
+        }
+
+        // Names the holder for whoever looks into the file, failing to do so does not matter.
+        let _ = file
+            .set_len(0)
+            .and_then(|()| writeln!(file, "{}", std::process::id()));
+
+        Ok(Self { _file: file })
+    }
+}
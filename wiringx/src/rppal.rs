@@ -0,0 +1,487 @@
+//! The GPIO and PWM API of [rppal](https://docs.rs/rppal) on top of wiringX, enabled with the `rppal-compat` feature.
+//!
+//! Code written for the Raspberry Pi with rppal can be ported by replacing `rppal::` with `wiringx::rppal::`.
+//! Pin numbers are the ones of wiringX for the board instead of the BCM numbers,
+//! and PWM is set up on pin numbers instead of channels.
+//!
+//! [`Gpio::new`](gpio::Gpio::new) uses the [`WiringX`](crate::WiringX) set up before,
+//! or sets it up for the platform named in the `WIRINGX_PLATFORM` environment variable, like `milkv_duos`.
+//!
+//! Example blinking pin `0`:
+//! ```no_run
+//! use std::{thread, time::Duration};
+//!
+//! use wiringx::rppal::gpio::Gpio;
+//!
+//! let mut pin = Gpio::new().unwrap().get(0).unwrap().into_output();
+//!
+//! loop {
+//!     pin.toggle();
+//!     thread::sleep(Duration::from_millis(500));
+//! }
+//! ```
+
+/// GPIO pins.
+pub mod gpio {
+    use std::{env, time::Duration};
+
+    use crate::{Input, IsrMode, Output, Platform, Pull, Value, WiringX, WiringXError};
+
+    /// Errors of the rppal compatible API.
+    pub type Error = WiringXError;
+
+    /// Result of the rppal compatible API.
+    pub type Result<T> = std::result::Result<T, Error>;
+
+    /// Logic level of a pin.
+    pub type Level = Value;
+
+    /// Access to the GPIO pins.
+    #[derive(Debug, Clone, Copy)]
+    pub struct Gpio {
+        pub(super) wiringx: &'static WiringX,
+    }
+
+    impl Gpio {
+        /// Uses the [`WiringX`] set up before, or sets it up for the platform in the `WIRINGX_PLATFORM` environment variable.
+        pub fn new() -> Result<Self> {
+            if let Some(wiringx) = WiringX::instance() {
+                return Ok(Self { wiringx });
+            }
+
+            let platform = env::var("WIRINGX_PLATFORM")
+                .map_err(|_| {
+                    WiringXError::InitError(
+                        "WiringX is not set up and WIRINGX_PLATFORM is not set".to_string(),
+                    )
+                })
+                .and_then(|platform| {
+                    Platform::from_string(&platform)
+                        .map_err(|e| WiringXError::InitError(format!("{e}")))
+                })?;
+
+            Ok(Self {
+                wiringx: WiringX::new(platform)?,
+            })
+        }
+
+        /// Returns the pin with the wiringX number, to be configured as input or output.
+        ///
+        /// The pin is reserved as input until it is configured or dropped,
+        /// a pin already in use returns [`PinUsed`](WiringXError::PinUsed) like with rppal.
+        pub fn get(&self, pin: u8) -> Result<Pin> {
+            Ok(Pin {
+                pin: self.wiringx.gpio_pin::<Input>(pin as i32)?,
+            })
+        }
+    }
+
+    /// An unconfigured pin, see [`Gpio::get`].
+    #[derive(Debug)]
+    pub struct Pin {
+        pin: crate::Pin<Input>,
+    }
+
+    impl Pin {
+        /// Returns the number of the pin.
+        #[inline]
+        pub fn pin(&self) -> u8 {
+            self.pin.number() as u8
+        }
+
+        /// Configures the pin as input.
+        #[inline]
+        pub fn into_input(self) -> InputPin {
+            InputPin { pin: self.pin }
+        }
+
+        /// Configures the pin as input with the pull-up resistor enabled,
+        /// if the backend can configure it, see [`Pin::set_pull`](crate::Pin::set_pull).
+        pub fn into_input_pullup(self) -> InputPin {
+            let pin = self.into_input();
+            let _ = pin.pin.set_pull(Pull::Up);
+            pin
+        }
+
+        /// Configures the pin as input with the pull-down resistor enabled,
+        /// if the backend can configure it, see [`Pin::set_pull`](crate::Pin::set_pull).
+        pub fn into_input_pulldown(self) -> InputPin {
+            let pin = self.into_input();
+            let _ = pin.pin.set_pull(Pull::Down);
+            pin
+        }
+
+        /// Configures the pin as output, driving it low.
+        #[inline]
+        pub fn into_output(self) -> OutputPin {
+            OutputPin {
+                pin: self.pin.into_output(Level::Low),
+            }
+        }
+
+        /// Configures the pin as output, driving it low.
+        #[inline]
+        pub fn into_output_low(self) -> OutputPin {
+            self.into_output()
+        }
+
+        /// Configures the pin as output, driving it high.
+        #[inline]
+        pub fn into_output_high(self) -> OutputPin {
+            OutputPin {
+                pin: self.pin.into_output(Level::High),
+            }
+        }
+    }
+
+    /// A pin configured as output.
+    #[derive(Debug)]
+    pub struct OutputPin {
+        pin: crate::Pin<Output>,
+    }
+
+    impl OutputPin {
+        /// Returns the number of the pin.
+        #[inline]
+        pub fn pin(&self) -> u8 {
+            self.pin.number() as u8
+        }
+
+        /// Drives the pin high.
+        #[inline]
+        pub fn set_high(&mut self) {
+            self.pin.write(Level::High);
+        }
+
+        /// Drives the pin low.
+        #[inline]
+        pub fn set_low(&mut self) {
+            self.pin.write(Level::Low);
+        }
+
+        /// Drives the pin to the level.
+        #[inline]
+        pub fn write(&mut self, level: Level) {
+            self.pin.write(level);
+        }
+
+        /// Drives the pin to the opposite level.
+        #[inline]
+        pub fn toggle(&mut self) {
+            self.pin.toggle();
+        }
+
+        /// Returns whether the pin is driven high.
+        #[inline]
+        pub fn is_set_high(&self) -> bool {
+            self.pin.read() == Level::High
+        }
+
+        /// Returns whether the pin is driven low.
+        #[inline]
+        pub fn is_set_low(&self) -> bool {
+            self.pin.read() == Level::Low
+        }
+
+        /// Returns the wiringX pin.
+        #[inline]
+        pub fn into_inner(self) -> crate::Pin<Output> {
+            self.pin
+        }
+    }
+
+    /// A pin configured as input.
+    #[derive(Debug)]
+    pub struct InputPin {
+        pin: crate::Pin<Input>,
+    }
+
+    impl InputPin {
+        /// Returns the number of the pin.
+        #[inline]
+        pub fn pin(&self) -> u8 {
+            self.pin.number() as u8
+        }
+
+        /// Reads the level of the pin.
+        #[inline]
+        pub fn read(&self) -> Level {
+            self.pin.read()
+        }
+
+        /// Returns whether the pin is high.
+        #[inline]
+        pub fn is_high(&self) -> bool {
+            self.pin.read() == Level::High
+        }
+
+        /// Returns whether the pin is low.
+        #[inline]
+        pub fn is_low(&self) -> bool {
+            self.pin.read() == Level::Low
+        }
+
+        /// Sets the edges [`poll_interrupt`](Self::poll_interrupt) waits for.
+        #[inline]
+        pub fn set_interrupt(&mut self, trigger: Trigger) -> Result<()> {
+            Ok(self.pin.set_isr_mode(trigger.into())?)
+        }
+
+        /// Removes the interrupt set with [`set_interrupt`](Self::set_interrupt).
+        #[inline]
+        pub fn clear_interrupt(&mut self) -> Result<()> {
+            Ok(self.pin.set_isr_mode(IsrMode::None)?)
+        }
+
+        /// Waits for an edge, returning the level after it or none on timeout.
+        ///
+        /// With `reset` only edges from the call on count, otherwise an edge queued before is returned right away.
+        /// Waits forever without a timeout.
+        pub fn poll_interrupt(
+            &mut self,
+            reset: bool,
+            timeout: Option<Duration>,
+        ) -> Result<Option<Level>> {
+            if reset {
+                self.pin.clear_interrupt();
+            }
+
+            let timeout = timeout.unwrap_or(Duration::from_millis(i32::MAX as u64));
+
+            match self.pin.wait_for_event(timeout) {
+                Ok(event) => Ok(Some(match event.edge {
+                    crate::Edge::Rising => Level::High,
+                    crate::Edge::Falling => Level::Low,
+                })),
+                Err(WiringXError::InterruptTimedOut) => Ok(None),
+                Err(e) => Err(e),
+            }
+        }
+
+        /// Returns the wiringX pin.
+        #[inline]
+        pub fn into_inner(self) -> crate::Pin<Input> {
+            self.pin
+        }
+    }
+
+    /// Edges an interrupt triggers on.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Trigger {
+        Disabled,
+        RisingEdge,
+        FallingEdge,
+        Both,
+    }
+
+    impl From<Trigger> for IsrMode {
+        fn from(trigger: Trigger) -> Self {
+            match trigger {
+                Trigger::Disabled => IsrMode::None,
+                Trigger::RisingEdge => IsrMode::Rising,
+                Trigger::FallingEdge => IsrMode::Falling,
+                Trigger::Both => IsrMode::Both,
+            }
+        }
+    }
+}
+
+/// Hardware PWM.
+pub mod pwm {
+    use std::time::Duration;
+
+    use super::gpio::{Gpio, Result};
+    use crate::{PwmPin, WiringXError};
+
+    /// Polarity of the PWM signal.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Polarity {
+        Normal,
+        Inverse,
+    }
+
+    impl From<Polarity> for crate::Polarity {
+        fn from(polarity: Polarity) -> Self {
+            match polarity {
+                Polarity::Normal => crate::Polarity::Normal,
+                Polarity::Inverse => crate::Polarity::Inversed,
+            }
+        }
+    }
+
+    /// A PWM pin, set up on the wiringX pin number instead of a channel.
+    ///
+    /// Disabling keeps the signal low by setting the duty cycle to zero, as wiringX keeps the pins enabled.
+    #[derive(Debug)]
+    pub struct Pwm {
+        pwm: PwmPin,
+        polarity: Polarity,
+        duty_cycle: f64,
+        enabled: bool,
+    }
+
+    impl Pwm {
+        /// Sets up the PWM pin with the period and the time of each period the signal is high.
+        pub fn with_period(
+            pin: u8,
+            period: Duration,
+            pulse_width: Duration,
+            polarity: Polarity,
+            enabled: bool,
+        ) -> Result<Self> {
+            if period.is_zero() {
+                return Err(WiringXError::InvalidArgument);
+            }
+
+            let duty_cycle = pulse_width.as_secs_f64() / period.as_secs_f64();
+
+            Self::new(pin, period, duty_cycle, polarity, enabled)
+        }
+
+        /// Sets up the PWM pin with the frequency in Hz and the duty cycle from 0.0 - 1.0.
+        pub fn with_frequency(
+            pin: u8,
+            frequency: f64,
+            duty_cycle: f64,
+            polarity: Polarity,
+            enabled: bool,
+        ) -> Result<Self> {
+            Self::new(pin, period(frequency)?, duty_cycle, polarity, enabled)
+        }
+
+        fn new(
+            pin: u8,
+            period: Duration,
+            duty_cycle: f64,
+            polarity: Polarity,
+            enabled: bool,
+        ) -> Result<Self> {
+            let duty_cycle = duty_cycle.clamp(0.0, 1.0);
+            let pwm = Gpio::new()?.wiringx.pwm_pin(
+                pin as i32,
+                period,
+                if enabled { duty_cycle as f32 } else { 0.0 },
+                polarity.into(),
+            )?;
+
+            Ok(Self {
+                pwm,
+                polarity,
+                duty_cycle,
+                enabled,
+            })
+        }
+
+        /// Returns the period.
+        #[inline]
+        pub fn period(&self) -> Result<Duration> {
+            Ok(self.pwm.period())
+        }
+
+        /// Sets the period, keeping the duty cycle.
+        #[inline]
+        pub fn set_period(&mut self, period: Duration) -> Result<()> {
+            self.pwm.set_period(period)
+        }
+
+        /// Returns the time of each period the signal is high.
+        #[inline]
+        pub fn pulse_width(&self) -> Result<Duration> {
+            Ok(self.pwm.period().mul_f64(self.duty_cycle))
+        }
+
+        /// Sets the time of each period the signal is high.
+        pub fn set_pulse_width(&mut self, pulse_width: Duration) -> Result<()> {
+            let period = self.pwm.period();
+            if period.is_zero() {
+                return Err(WiringXError::InvalidArgument);
+            }
+
+            self.set_duty_cycle(pulse_width.as_secs_f64() / period.as_secs_f64())
+        }
+
+        /// Returns the frequency in Hz.
+        #[inline]
+        pub fn frequency(&self) -> Result<f64> {
+            Ok(1.0 / self.pwm.period().as_secs_f64())
+        }
+
+        /// Sets the frequency in Hz and the duty cycle from 0.0 - 1.0.
+        pub fn set_frequency(&mut self, frequency: f64, duty_cycle: f64) -> Result<()> {
+            let period = period(frequency)?;
+
+            // Lowering the duty cycle first keeps it within the period in between.
+            self.set_duty_cycle(self.duty_cycle.min(duty_cycle))?;
+            self.pwm.set_period(period)?;
+            self.set_duty_cycle(duty_cycle)
+        }
+
+        /// Returns the duty cycle from 0.0 - 1.0.
+        #[inline]
+        pub fn duty_cycle(&self) -> Result<f64> {
+            Ok(self.duty_cycle)
+        }
+
+        /// Sets the duty cycle from 0.0 - 1.0.
+        pub fn set_duty_cycle(&mut self, duty_cycle: f64) -> Result<()> {
+            let duty_cycle = duty_cycle.clamp(0.0, 1.0);
+            if self.enabled {
+                self.pwm.set_duty_cycle(duty_cycle as f32)?;
+            }
+
+            self.duty_cycle = duty_cycle;
+
+            Ok(())
+        }
+
+        /// Returns the polarity.
+        #[inline]
+        pub fn polarity(&self) -> Result<Polarity> {
+            Ok(self.polarity)
+        }
+
+        /// Sets the polarity.
+        pub fn set_polarity(&mut self, polarity: Polarity) -> Result<()> {
+            self.pwm.set_polarity(polarity.into())?;
+            self.polarity = polarity;
+
+            Ok(())
+        }
+
+        /// Returns whether the signal is output.
+        #[inline]
+        pub fn is_enabled(&self) -> Result<bool> {
+            Ok(self.enabled)
+        }
+
+        /// Starts outputting the signal.
+        pub fn enable(&mut self) -> Result<()> {
+            self.pwm.set_duty_cycle(self.duty_cycle as f32)?;
+            self.enabled = true;
+
+            Ok(())
+        }
+
+        /// Keeps the signal low.
+        pub fn disable(&mut self) -> Result<()> {
+            self.pwm.set_duty_cycle(0.0)?;
+            self.enabled = false;
+
+            Ok(())
+        }
+
+        /// Returns the wiringX PWM pin.
+        #[inline]
+        pub fn into_inner(self) -> PwmPin {
+            self.pwm
+        }
+    }
+
+    fn period(frequency: f64) -> Result<Duration> {
+        // Rejects frequencies that are not positive, and ones too low or too high for a period.
+        match Duration::try_from_secs_f64(1.0 / frequency) {
+            Ok(period) if !period.is_zero() => Ok(period),
+            _ => Err(WiringXError::InvalidArgument),
+        }
+    }
+}
@@ -0,0 +1,186 @@
+//! Driving pins to a safe state when the program panics or is interrupted.
+//!
+//! A control loop crashing or being stopped with Ctrl+C would otherwise leave the outputs as they were,
+//! like a heater or motor running. After [`install`], panics and the signals `SIGINT`, `SIGTERM`, `SIGHUP`
+//! and `SIGQUIT` run the registered actions first, then the process panics or exits as it would have.
+//!
+//! Actions are registered with [`register`], or for pins with [`Pin::set_safe_state`](crate::Pin::set_safe_state)
+//! and [`PwmPin::set_safe_state`](crate::PwmPin::set_safe_state), and are removed again when their pin is dropped.
+//! `SIGKILL` and power loss can not be handled, so hardware that must not stay on needs a pull resistor as well.
+//!
+//! Example turning a heater off whatever makes the program end:
+//! ```no_run
+//! use wiringx::{safe_state, Output, Platform, Value, WiringX};
+//!
+//! let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+//! safe_state::install().unwrap();
+//!
+//! let mut heater = wiringx.gpio_pin::<Output>(3).unwrap();
+//! heater.set_safe_state(Some(Value::Low));
+//!
+//! loop {
+//!     heater.write(Value::High);
+//!     // A panic here turns the heater off.
+//! }
+//! ```
+
+use std::{
+    io,
+    os::raw::c_int,
+    panic,
+    sync::{
+        atomic::{AtomicI32, AtomicU64, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+use parking_lot::Mutex;
+
+use crate::WiringXError;
+
+/// The signals ending the process that can be handled.
+const SIGNALS: [c_int; 4] = [libc::SIGINT, libc::SIGTERM, libc::SIGHUP, libc::SIGQUIT];
+
+type Action = Arc<dyn Fn() + Send + Sync>;
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+static ACTIONS: Mutex<Vec<(u64, Action)>> = Mutex::new(Vec::new());
+static INSTALLED: Mutex<bool> = Mutex::new(false);
+/// Write end of the pipe the signal handler passes the signals through, as it can not run the actions itself.
+static SIGNAL_PIPE: AtomicI32 = AtomicI32::new(-1);
+
+/// Hooks into panics and the signals ending the process, to run the actions before.
+///
+/// Calling it again does nothing. The previous panic hook still runs after the actions,
+/// while previous handlers of the signals are replaced.
+pub fn install() -> Result<(), WiringXError> {
+    let mut installed = INSTALLED.lock();
+    if *installed {
+        return Ok(());
+    }
+
+    let mut fds = [0; 2];
+    if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) } < 0 {
+        return Err(WiringXError::Io(io::Error::last_os_error()));
+    }
+    let [read, write] = fds;
+
+    if let Err(error) = thread::Builder::new()
+        .name("wiringx-safe-state".to_string())
+        .spawn(move || watch(read))
+    {
+        unsafe {
+            libc::close(read);
+            libc::close(write);
+        }
+        return Err(WiringXError::Io(error));
+    }
+    SIGNAL_PIPE.store(write, Ordering::Release);
+
+    let mut previous: [libc::sigaction; SIGNALS.len()] = unsafe { std::mem::zeroed() };
+    for (i, signal) in SIGNALS.into_iter().enumerate() {
+        let mut action: libc::sigaction = unsafe { std::mem::zeroed() };
+        action.sa_sigaction = forward as extern "C" fn(c_int) as libc::sighandler_t;
+        action.sa_flags = libc::SA_RESTART;
+
+        if unsafe { libc::sigaction(signal, &action, &mut previous[i]) } < 0 {
+            let error = io::Error::last_os_error();
+
+            // Restores the handlers replaced so far, and closing the pipe ends the watching thread,
+            // so calling this again starts over.
+            for (signal, previous) in SIGNALS.into_iter().zip(&previous).take(i) {
+                unsafe { libc::sigaction(signal, previous, std::ptr::null_mut()) };
+            }
+            SIGNAL_PIPE.store(-1, Ordering::Release);
+            unsafe { libc::close(write) };
+
+            return Err(WiringXError::Io(error));
+        }
+    }
+
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        run();
+        previous(info);
+    }));
+
+    *installed = true;
+
+    Ok(())
+}
+
+/// Registers the action, until the returned [`SafeState`] is dropped.
+///
+/// Actions run on the thread that panicked or on a thread of their own for signals,
+/// in the order they were registered.
+pub fn register(action: impl Fn() + Send + Sync + 'static) -> SafeState {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    ACTIONS.lock().push((id, Arc::new(action)));
+
+    SafeState { id }
+}
+
+/// Runs all registered actions right away, for example before exiting on an error.
+pub fn run() {
+    // Copied first, so actions can register or drop others.
+    let actions: Vec<_> = ACTIONS
+        .lock()
+        .iter()
+        .map(|(_, action)| action.clone())
+        .collect();
+
+    for action in actions {
+        action();
+    }
+}
+
+/// An action registered with [`register`], which is removed when dropped.
+#[derive(Debug)]
+pub struct SafeState {
+    id: u64,
+}
+
+impl Drop for SafeState {
+    fn drop(&mut self) {
+        ACTIONS.lock().retain(|(id, _)| *id != self.id);
+    }
+}
+
+/// Passes the signal on to the watching thread, only doing what is safe in a signal handler.
+extern "C" fn forward(signal: c_int) {
+    let byte = signal as u8;
+    unsafe {
+        libc::write(
+            SIGNAL_PIPE.load(Ordering::Acquire),
+            (&byte as *const u8).cast(),
+            1,
+        );
+    }
+}
+
+/// Runs the actions for each signal, then ends the process with it like without the handler.
+///
+/// Returns once the write end of the pipe is closed, closing the read end.
+fn watch(read: c_int) {
+    loop {
+        let mut byte = 0u8;
+        let result = unsafe { libc::read(read, (&mut byte as *mut u8).cast(), 1) };
+
+        if result == 1 {
+            run();
+
+            let signal = c_int::from(byte);
+            unsafe {
+                libc::signal(signal, libc::SIG_DFL);
+                libc::raise(signal);
+            }
+        } else if result < 0 && io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+            continue;
+        } else {
+            break;
+        }
+    }
+
+    unsafe { libc::close(read) };
+}
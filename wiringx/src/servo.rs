@@ -0,0 +1,124 @@
+//! Hobby servos driven by PWM.
+
+use std::time::Duration;
+
+use crate::{PwmOutput, WiringXError};
+
+/// Period of the servo signal.
+const SERVO_PERIOD: Duration = Duration::from_millis(20);
+
+/// A hobby servo on any [`PwmOutput`], a native [`PwmPin`](super::PwmPin) or a [`Pca9685Channel`](super::Pca9685Channel).
+///
+/// Positions are set as angle, mapped linearly to a pulse width between the minimum and maximum pulse.
+/// Defaults to pulses from 1 to 2 milliseconds for 0 to 180 degrees, the range most servos support.
+///
+/// Example sweeping a servo on the native PWM pin `11`:
+/// ```no_run
+/// use wiringx::{Platform, Polarity, Servo, WiringX};
+///
+/// use std::{thread, time::Duration};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let pwm = wiringx
+///     .pwm_pin(11, Duration::from_millis(20), 0.0, Polarity::Normal)
+///     .unwrap();
+///
+/// let mut servo = Servo::new(pwm).unwrap();
+/// servo
+///     .set_pulse_range(Duration::from_micros(500), Duration::from_micros(2500))
+///     .unwrap();
+///
+/// for angle in (0..=180).step_by(10) {
+///     servo.set_angle(angle as f32).unwrap();
+///     thread::sleep(Duration::from_millis(100));
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Servo<P> {
+    pwm: P,
+    min_pulse: Duration,
+    max_pulse: Duration,
+    max_angle: f32,
+    angle: Option<f32>,
+}
+
+impl<P: PwmOutput> Servo<P> {
+    /// Sets the period of the output to the 20 milliseconds servos expect, without moving the servo yet.
+    pub fn new(mut pwm: P) -> Result<Self, WiringXError> {
+        pwm.set_period(SERVO_PERIOD)?;
+
+        Ok(Self {
+            pwm,
+            min_pulse: Duration::from_millis(1),
+            max_pulse: Duration::from_millis(2),
+            max_angle: 180.0,
+            angle: None,
+        })
+    }
+
+    /// Returns the PWM output.
+    #[inline]
+    pub fn into_inner(self) -> P {
+        self.pwm
+    }
+
+    /// Sets the pulse widths of the minimum and maximum angle, from the datasheet of the servo.
+    pub fn set_pulse_range(
+        &mut self,
+        min_pulse: Duration,
+        max_pulse: Duration,
+    ) -> Result<(), WiringXError> {
+        if min_pulse >= max_pulse || max_pulse > self.pwm.period() {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        self.min_pulse = min_pulse;
+        self.max_pulse = max_pulse;
+
+        Ok(())
+    }
+
+    /// Sets the angle the maximum pulse width moves the servo to, `180` degrees by default.
+    pub fn set_max_angle(&mut self, max_angle: f32) -> Result<(), WiringXError> {
+        if max_angle <= 0.0 {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        self.max_angle = max_angle;
+
+        Ok(())
+    }
+
+    /// Moves the servo to the angle in degrees, clamped to the range of the servo.
+    pub fn set_angle(&mut self, angle: f32) -> Result<(), WiringXError> {
+        let angle = angle.clamp(0.0, self.max_angle);
+
+        let pulse =
+            self.min_pulse + (self.max_pulse - self.min_pulse).mul_f32(angle / self.max_angle);
+        self.set_pulse_width(pulse)?;
+        self.angle = Some(angle);
+
+        Ok(())
+    }
+
+    /// Returns the angle the servo was last moved to, none before it was moved.
+    #[inline]
+    pub fn angle(&self) -> Option<f32> {
+        self.angle
+    }
+
+    /// Sends pulses of the width, clamped to the period of the output.
+    pub fn set_pulse_width(&mut self, pulse: Duration) -> Result<(), WiringXError> {
+        let period = self.pwm.period();
+
+        self.angle = None;
+        self.pwm
+            .set_duty_cycle(pulse.min(period).as_secs_f32() / period.as_secs_f32())
+    }
+
+    /// Stops sending pulses, so the servo no longer holds its position.
+    pub fn detach(&mut self) -> Result<(), WiringXError> {
+        self.angle = None;
+        self.pwm.set_duty_cycle(0.0)
+    }
+}
@@ -0,0 +1,222 @@
+//! Multiplexed 7 segment LED displays without controller.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+use crate::{
+    tm1637::{encode_str, format_number},
+    Output, Pin, Value, WiringXError,
+};
+
+/// Time each digit is lit, refreshing all 4 digits at 100 Hz.
+const DIGIT_TIME: Duration = Duration::from_micros(2500);
+
+/// A 4 digit common cathode 7 segment LED display driven directly from GPIO pins, like the 5641AS.
+///
+/// The display has a pin per segment shared by all digits and a cathode pin per digit,
+/// so only one digit can be lit at a time. A background thread lights them one after another,
+/// fast enough to appear steady. The segment pins need series resistors,
+/// and the cathodes transistors if the pins can not sink the current of 8 segments.
+///
+/// Example showing a counter:
+/// ```no_run
+/// use std::{thread, time::Duration};
+///
+/// use wiringx::{Output, Platform, SevenSegment, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+///
+/// let segments = [2, 3, 4, 5, 6, 7, 8, 9].map(|pin| wiringx.gpio_pin::<Output>(pin).unwrap());
+/// let digits = [10, 11, 12, 13].map(|pin| wiringx.gpio_pin::<Output>(pin).unwrap());
+///
+/// let display = SevenSegment::new(segments, digits);
+///
+/// for number in 0..=9999 {
+///     display.show_number(number, false).unwrap();
+///     thread::sleep(Duration::from_millis(100));
+/// }
+/// ```
+#[derive(Debug)]
+pub struct SevenSegment {
+    segments: Arc<Mutex<[u8; 4]>>,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl SevenSegment {
+    /// Takes ownership of the segment pins in the order `a` to `g` and the decimal point,
+    /// and the cathode pins from the left digit to the right one, and starts refreshing the blank display.
+    pub fn new(segment_pins: [Pin<Output>; 8], digit_pins: [Pin<Output>; 4]) -> Self {
+        Self::start(segment_pins, digit_pins, [0, 1, 2, 3, 4, 5, 6, 7])
+    }
+
+    /// Like [`new`](Self::new), for displays wired in a different order.
+    ///
+    /// The mapping has the segment bit each of the segment pins drives, from `0` for segment `a` to `7` for the decimal point.
+    pub fn with_mapping(
+        segment_pins: [Pin<Output>; 8],
+        digit_pins: [Pin<Output>; 4],
+        mapping: [u8; 8],
+    ) -> Result<Self, WiringXError> {
+        let mut used = 0u8;
+        for bit in mapping {
+            if bit > 7 || used & 1 << bit != 0 {
+                return Err(WiringXError::InvalidArgument);
+            }
+
+            used |= 1 << bit;
+        }
+
+        Ok(Self::start(segment_pins, digit_pins, mapping))
+    }
+
+    fn start(
+        mut segment_pins: [Pin<Output>; 8],
+        mut digit_pins: [Pin<Output>; 4],
+        mapping: [u8; 8],
+    ) -> Self {
+        for pin in &mut segment_pins {
+            pin.write(Value::Low);
+        }
+        for pin in &mut digit_pins {
+            pin.write(Value::High);
+        }
+
+        let segments = Arc::new(Mutex::new([0; 4]));
+        let running = Arc::new(AtomicBool::new(true));
+
+        let thread = {
+            let segments = segments.clone();
+            let running = running.clone();
+
+            thread::spawn(move || {
+                refresh(
+                    &mut segment_pins,
+                    &mut digit_pins,
+                    mapping,
+                    &segments,
+                    &running,
+                )
+            })
+        };
+
+        Self {
+            segments,
+            running,
+            thread: Some(thread),
+        }
+    }
+
+    /// Sets raw segments of the digits from the position on, with segment `a` in the least significant bit
+    /// and the decimal point in the most significant one.
+    pub fn set_segments(&self, position: usize, segments: &[u8]) -> Result<(), WiringXError> {
+        let mut digits = self.segments.lock();
+        digits
+            .get_mut(position..position + segments.len())
+            .ok_or(WiringXError::InvalidArgument)?
+            .copy_from_slice(segments);
+
+        Ok(())
+    }
+
+    /// Returns the raw segments of the digits.
+    #[inline]
+    pub fn segments(&self) -> [u8; 4] {
+        *self.segments.lock()
+    }
+
+    /// Switches all segments off.
+    #[inline]
+    pub fn clear(&self) {
+        *self.segments.lock() = [0; 4];
+    }
+
+    /// Shows the number right-aligned, from `-999` to `9999`.
+    pub fn show_number(&self, number: i32, leading_zeros: bool) -> Result<(), WiringXError> {
+        let text = format_number(number, leading_zeros).ok_or(WiringXError::InvalidArgument)?;
+
+        self.show_str(&text)
+    }
+
+    /// Shows up to four characters from the left, where a `.` lights the decimal point of the previous one.
+    ///
+    /// Digits, spaces, `-`, `_` and the letters 7 segments can show are supported,
+    /// others are shown blank.
+    pub fn show_str(&self, text: &str) -> Result<(), WiringXError> {
+        let segments = encode_str(text, 4).ok_or(WiringXError::InvalidArgument)?;
+
+        self.set_segments(0, &segments)
+    }
+
+    /// Stops refreshing and switches the display off.
+    pub fn stop(mut self) {
+        self.join();
+    }
+
+    fn join(&mut self) {
+        self.running.store(false, Ordering::Release);
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for SevenSegment {
+    fn drop(&mut self) {
+        self.join();
+    }
+}
+
+/// Lights the digits one after another until `running` gets cleared, then switches all of them off.
+fn refresh(
+    segment_pins: &mut [Pin<Output>; 8],
+    digit_pins: &mut [Pin<Output>; 4],
+    mapping: [u8; 8],
+    segments: &Mutex<[u8; 4]>,
+    running: &AtomicBool,
+) {
+    let mut next = Instant::now();
+
+    'refresh: while running.load(Ordering::Acquire) {
+        let digits = *segments.lock();
+
+        for (digit, pin) in digits.iter().zip(digit_pins.iter_mut()) {
+            if !running.load(Ordering::Acquire) {
+                break 'refresh;
+            }
+
+            for (segment, bit) in segment_pins.iter_mut().zip(mapping) {
+                segment.write(if digit >> bit & 1 == 1 {
+                    Value::High
+                } else {
+                    Value::Low
+                });
+            }
+
+            pin.write(Value::Low);
+            next += DIGIT_TIME;
+            thread::sleep(next.saturating_duration_since(Instant::now()));
+            // The cathode is released before the segments change, so they do not ghost on the next digit.
+            pin.write(Value::High);
+        }
+
+        // Fall behind instead of catching up after the thread was not scheduled for a while.
+        next = next.max(Instant::now());
+    }
+
+    for pin in digit_pins {
+        pin.write(Value::High);
+    }
+    for pin in segment_pins {
+        pin.write(Value::Low);
+    }
+}
@@ -0,0 +1,174 @@
+//! SHT3x temperature and humidity sensors.
+
+use std::{thread, time::Duration};
+
+use crate::{WiringXError, I2C};
+
+const SOFT_RESET: u16 = 0x30A2;
+const HEATER_ON: u16 = 0x306D;
+const HEATER_OFF: u16 = 0x3066;
+const READ_STATUS: u16 = 0xF32D;
+const CLEAR_STATUS: u16 = 0x3041;
+
+const STATUS_HEATER: u16 = 0x2000;
+
+/// A Sensirion SHT31 sensor measuring temperature and relative humidity, also working with the SHT30 and SHT35.
+///
+/// Every measurement is verified with its CRC. The built-in heater can dry the sensor after condensation,
+/// it falsifies measurements while it is on.
+///
+/// Example on the I2C bus at address `0x44`:
+/// ```no_run
+/// use wiringx::{Platform, Sht31, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let i2c = wiringx.setup_i2c("/dev/i2c-1".into(), 0x44).unwrap();
+///
+/// let mut sensor = Sht31::new(i2c).unwrap();
+///
+/// let measurement = sensor.measure().unwrap();
+/// println!("{:.1} °C, {:.1} %", measurement.temperature, measurement.humidity);
+/// ```
+#[derive(Debug)]
+pub struct Sht31 {
+    i2c: I2C,
+    repeatability: Sht31Repeatability,
+}
+
+impl Sht31 {
+    /// Resets the sensor, measuring with high repeatability.
+    ///
+    /// The sensor is at address `0x44`, or `0x45` if its `ADDR` pin is high.
+    pub fn new(i2c: I2C) -> Result<Self, WiringXError> {
+        let mut sensor = Self {
+            i2c,
+            repeatability: Sht31Repeatability::High,
+        };
+
+        sensor.command(SOFT_RESET)?;
+        thread::sleep(Duration::from_millis(2));
+
+        Ok(sensor)
+    }
+
+    /// Sets the repeatability of measurements, higher repeatability takes longer.
+    #[inline]
+    pub fn set_repeatability(&mut self, repeatability: Sht31Repeatability) {
+        self.repeatability = repeatability;
+    }
+
+    /// Returns the repeatability of measurements.
+    #[inline]
+    pub fn repeatability(&self) -> Sht31Repeatability {
+        self.repeatability
+    }
+
+    /// Measures temperature and humidity once.
+    ///
+    /// Returns [`Checksum`](WiringXError::Checksum) if a CRC does not match.
+    pub fn measure(&mut self) -> Result<Sht31Measurement, WiringXError> {
+        // Single shot without clock stretching, the sensor does not answer until done.
+        let (command, duration) = match self.repeatability {
+            Sht31Repeatability::High => (0x2400, 16),
+            Sht31Repeatability::Medium => (0x240B, 7),
+            Sht31Repeatability::Low => (0x2416, 5),
+        };
+
+        self.command(command)?;
+        thread::sleep(Duration::from_millis(duration));
+
+        let mut data = [0; 6];
+        self.i2c.read_bytes(&mut data)?;
+
+        let temperature = checked_word(&data[0..3])?;
+        let humidity = checked_word(&data[3..6])?;
+
+        Ok(Sht31Measurement {
+            temperature: -45.0 + 175.0 * temperature as f32 / 65535.0,
+            humidity: 100.0 * humidity as f32 / 65535.0,
+        })
+    }
+
+    /// Switches the heater on or off.
+    #[inline]
+    pub fn set_heater(&mut self, on: bool) -> Result<(), WiringXError> {
+        self.command(if on { HEATER_ON } else { HEATER_OFF })
+    }
+
+    /// Returns whether the heater is on.
+    pub fn heater(&mut self) -> Result<bool, WiringXError> {
+        Ok(self.status()? & STATUS_HEATER != 0)
+    }
+
+    /// Reads the status register, with alerts, the heater state and the reset flag.
+    ///
+    /// Returns [`Checksum`](WiringXError::Checksum) if the CRC does not match.
+    pub fn status(&mut self) -> Result<u16, WiringXError> {
+        self.command(READ_STATUS)?;
+
+        let mut data = [0; 3];
+        self.i2c.read_bytes(&mut data)?;
+
+        checked_word(&data)
+    }
+
+    /// Clears the alert and reset flags of the status register.
+    #[inline]
+    pub fn clear_status(&mut self) -> Result<(), WiringXError> {
+        self.command(CLEAR_STATUS)
+    }
+
+    fn command(&mut self, command: u16) -> Result<(), WiringXError> {
+        self.i2c.write_bytes(&command.to_be_bytes())?;
+
+        Ok(())
+    }
+}
+
+/// A measurement of an [`Sht31`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sht31Measurement {
+    /// Temperature in °C.
+    pub temperature: f32,
+    /// Relative humidity in %.
+    pub humidity: f32,
+}
+
+/// Repeatability of [`Sht31`] measurements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Sht31Repeatability {
+    /// 0.04 % and 0.04 °C, takes up to 15.5 ms
+    #[default]
+    High,
+    /// 0.08 % and 0.08 °C, takes up to 6.5 ms
+    Medium,
+    /// 0.21 % and 0.15 °C, takes up to 4.5 ms
+    Low,
+}
+
+/// Returns the big endian word of the first two bytes, if the third is their CRC.
+fn checked_word(data: &[u8]) -> Result<u16, WiringXError> {
+    if sensirion_crc8(&data[..2]) != data[2] {
+        return Err(WiringXError::Checksum);
+    }
+
+    Ok(u16::from_be_bytes([data[0], data[1]]))
+}
+
+/// Calculates the CRC8 checksum with polynomial `0x31` and initial value `0xFF`, used by Sensirion and Aosong sensors.
+pub(crate) fn sensirion_crc8(data: &[u8]) -> u8 {
+    let mut crc = 0xFF;
+
+    for byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            if crc & 0x80 != 0 {
+                crc = (crc << 1) ^ 0x31;
+            } else {
+                crc <<= 1;
+            }
+        }
+    }
+
+    crc
+}
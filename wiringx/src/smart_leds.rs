@@ -0,0 +1,62 @@
+//! [`smart-leds`](smart_leds_trait) trait implementations, enabled with the `smart-leds-trait` feature.
+
+use smart_leds_trait::{SmartLedsWrite, RGB8};
+
+use crate::{Apa102, Rgb, WiringXError, Ws2812};
+
+impl From<RGB8> for Rgb {
+    #[inline]
+    fn from(color: RGB8) -> Self {
+        Self::new(color.r, color.g, color.b)
+    }
+}
+
+impl From<Rgb> for RGB8 {
+    #[inline]
+    fn from(color: Rgb) -> Self {
+        Self::new(color.r, color.g, color.b)
+    }
+}
+
+/// Copies the colors into the frame buffer, switching off LEDs past their end.
+fn fill_from<I: Into<RGB8>>(pixels: &mut [Rgb], colors: impl IntoIterator<Item = I>) {
+    let mut colors = colors.into_iter();
+
+    for pixel in pixels.iter_mut() {
+        *pixel = colors
+            .next()
+            .map_or(Rgb::BLACK, |color| color.into().into());
+    }
+}
+
+/// Replaces the frame buffer with the colors and shows it.
+impl SmartLedsWrite for Ws2812 {
+    type Error = WiringXError;
+    type Color = RGB8;
+
+    fn write<T, I>(&mut self, iterator: T) -> Result<(), Self::Error>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<Self::Color>,
+    {
+        fill_from(self.pixels_mut(), iterator);
+
+        self.show()
+    }
+}
+
+/// Replaces the frame buffer with the colors and shows it.
+impl SmartLedsWrite for Apa102 {
+    type Error = WiringXError;
+    type Color = RGB8;
+
+    fn write<T, I>(&mut self, iterator: T) -> Result<(), Self::Error>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<Self::Color>,
+    {
+        fill_from(self.pixels_mut(), iterator);
+
+        self.show()
+    }
+}
@@ -0,0 +1,277 @@
+//! Bit-banged serial peripheral interface over GPIO pins.
+
+use parking_lot::Mutex;
+use wiringx_sys::delayMicroseconds;
+
+use crate::{BitOrder, Input, Output, Pin, SpiMode, Value, WiringXError};
+
+/// A Serial Peripheral Interface bus driven in software using GPIO pins.
+///
+/// Offers the same interface as the hardware [`Spi`](super::Spi), for boards where the SPI
+/// controller pins are used otherwise or an additional low-speed bus is needed.
+///
+/// The clock is generated with busy waits, so speeds above a few hundred kilohertz are
+/// limited by how fast the GPIO pins can be toggled.
+#[derive(Debug)]
+pub struct SoftSpi {
+    pins: Mutex<SoftSpiPins>,
+
+    speed: u32,
+    mode: SpiMode,
+    bit_order: BitOrder,
+}
+
+#[derive(Debug)]
+struct SoftSpiPins {
+    sclk: Pin<Output>,
+    mosi: Pin<Output>,
+    miso: Pin<Input>,
+    cs: Option<Pin<Output>>,
+}
+
+impl SoftSpi {
+    /// Creates a software SPI bus from the clock, data out and data in pins, with an optional chip-select pin.
+    ///
+    /// The chip-select pin is driven low for the duration of every transfer.
+    ///
+    /// Speed is measured in Hertz here. The bus starts in [`SpiMode::Mode0`] with [`BitOrder::MsbFirst`].
+    pub fn new(
+        mut sclk: Pin<Output>,
+        mut mosi: Pin<Output>,
+        miso: Pin<Input>,
+        mut cs: Option<Pin<Output>>,
+        speed: u32,
+    ) -> Result<Self, WiringXError> {
+        if speed == 0 {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        sclk.write(Value::Low);
+        mosi.write(Value::Low);
+        if let Some(cs) = cs.as_mut() {
+            cs.write(Value::High);
+        }
+
+        Ok(Self {
+            pins: Mutex::new(SoftSpiPins {
+                sclk,
+                mosi,
+                miso,
+                cs,
+            }),
+            speed,
+            mode: SpiMode::Mode0,
+            bit_order: BitOrder::MsbFirst,
+        })
+    }
+
+    /// Sets the clock polarity and phase used by this bus.
+    pub fn set_mode(&mut self, mode: SpiMode) -> Result<(), WiringXError> {
+        self.pins.get_mut().sclk.write(idle_level(mode));
+
+        self.mode = mode;
+
+        Ok(())
+    }
+
+    /// Returns the SPI mode of this bus.
+    #[inline]
+    pub fn mode(&self) -> SpiMode {
+        self.mode
+    }
+
+    /// Sets whether words are shifted out most or least significant bit first.
+    pub fn set_bit_order(&mut self, bit_order: BitOrder) -> Result<(), WiringXError> {
+        self.bit_order = bit_order;
+
+        Ok(())
+    }
+
+    /// Returns the bit order of this bus.
+    #[inline]
+    pub fn bit_order(&self) -> BitOrder {
+        self.bit_order
+    }
+
+    /// Sets the clock speed of this bus in Hertz.
+    pub fn set_speed(&mut self, speed: u32) -> Result<(), WiringXError> {
+        if speed == 0 {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        self.speed = speed;
+
+        Ok(())
+    }
+
+    /// Returns the clock speed of this bus in Hertz.
+    #[inline]
+    pub fn speed(&self) -> u32 {
+        self.speed
+    }
+
+    /// Writes the data to the SPI device and overwrites the provided data with the read data from the device.
+    #[inline]
+    pub fn read_write(&self, data: &mut [u8]) -> Result<(), WiringXError> {
+        self.read_write_with_speed(data, self.speed)
+    }
+
+    /// Same as [`read_write`](Self::read_write), but clocks this single transfer with the given speed in Hertz.
+    pub fn read_write_with_speed(&self, data: &mut [u8], speed: u32) -> Result<(), WiringXError> {
+        if speed == 0 {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        self.selected(speed, |shifter| shifter.read_write(data));
+
+        Ok(())
+    }
+
+    /// Reads from the SPI device into the buffer, writing zeros.
+    pub fn read(&self, data: &mut [u8]) -> Result<(), WiringXError> {
+        self.selected(self.speed, |shifter| shifter.read(data));
+
+        Ok(())
+    }
+
+    /// Writes the data to the SPI device, discarding everything read.
+    pub fn write(&self, data: &[u8]) -> Result<(), WiringXError> {
+        self.selected(self.speed, |shifter| shifter.write(data));
+
+        Ok(())
+    }
+
+    /// Writes `write` to the SPI device while reading into `read` in one transfer.
+    ///
+    /// If the buffers differ in length, zeros are written past the end of `write`
+    /// and data read past the end of `read` is discarded.
+    pub fn transfer(&self, read: &mut [u8], write: &[u8]) -> Result<(), WiringXError> {
+        self.selected(self.speed, |shifter| shifter.transfer(read, write));
+
+        Ok(())
+    }
+
+    /// Locks the bus and runs `f` with chip-select asserted for its whole duration.
+    pub(crate) fn selected<R>(&self, speed: u32, f: impl FnOnce(&mut Shifter) -> R) -> R {
+        let idle = idle_level(self.mode);
+
+        let mut pins = self.pins.lock();
+
+        pins.sclk.write(idle);
+        if let Some(cs) = pins.cs.as_mut() {
+            cs.write(Value::Low);
+        }
+
+        let mut shifter = Shifter {
+            pins: &mut pins,
+            mode: self.mode,
+            bit_order: self.bit_order,
+            idle,
+            half_period: 500_000 / speed,
+        };
+        let result = f(&mut shifter);
+
+        if let Some(cs) = pins.cs.as_mut() {
+            cs.write(Value::High);
+        }
+
+        result
+    }
+}
+
+/// Shifts bytes over a locked [`SoftSpi`] bus.
+pub(crate) struct Shifter<'a> {
+    pins: &'a mut SoftSpiPins,
+    mode: SpiMode,
+    bit_order: BitOrder,
+    idle: Value,
+    half_period: u32,
+}
+
+impl Shifter<'_> {
+    pub(crate) fn read_write(&mut self, data: &mut [u8]) {
+        for byte in data.iter_mut() {
+            *byte = self.byte(*byte);
+        }
+    }
+
+    pub(crate) fn read(&mut self, data: &mut [u8]) {
+        for byte in data.iter_mut() {
+            *byte = self.byte(0);
+        }
+    }
+
+    pub(crate) fn write(&mut self, data: &[u8]) {
+        for byte in data {
+            self.byte(*byte);
+        }
+    }
+
+    pub(crate) fn transfer(&mut self, read: &mut [u8], write: &[u8]) {
+        for i in 0..read.len().max(write.len()) {
+            let received = self.byte(write.get(i).copied().unwrap_or(0));
+            if let Some(byte) = read.get_mut(i) {
+                *byte = received;
+            }
+        }
+    }
+
+    /// Shifts out one byte while shifting in another.
+    fn byte(&mut self, byte: u8) -> u8 {
+        let mut received = 0;
+
+        for i in 0..8 {
+            let bit = match self.bit_order {
+                BitOrder::MsbFirst => 7 - i,
+                BitOrder::LsbFirst => i,
+            };
+            let out = if byte >> bit & 1 == 1 {
+                Value::High
+            } else {
+                Value::Low
+            };
+
+            let pins = &mut *self.pins;
+            let sampled = if self.mode.cpha() {
+                pins.sclk.write(self.idle.opposite());
+                pins.mosi.write(out);
+                delay(self.half_period);
+                pins.sclk.write(self.idle);
+                let value = pins.miso.read();
+                delay(self.half_period);
+                value
+            } else {
+                pins.mosi.write(out);
+                delay(self.half_period);
+                pins.sclk.write(self.idle.opposite());
+                let value = pins.miso.read();
+                delay(self.half_period);
+                pins.sclk.write(self.idle);
+                value
+            };
+
+            if sampled == Value::High {
+                received |= 1 << bit;
+            }
+        }
+
+        received
+    }
+}
+
+/// Returns the level the clock line rests at between transfers.
+#[inline]
+fn idle_level(mode: SpiMode) -> Value {
+    if mode.cpol() {
+        Value::High
+    } else {
+        Value::Low
+    }
+}
+
+#[inline]
+pub(crate) fn delay(micros: u32) {
+    if micros > 0 {
+        unsafe { delayMicroseconds(micros) }
+    }
+}
@@ -1,36 +1,167 @@
 //! Serial peripheral interface communication related objects.
 
-use std::{ffi::c_uchar, os::fd::RawFd};
+use std::{
+    io,
+    mem::{size_of, size_of_val},
+    os::fd::RawFd,
+};
 
-use wiringx_sys::{wiringXSPIDataRW, wiringXSPIGetFd, wiringXSPISetup};
+use wiringx_sys::{wiringXSPIGetFd, wiringXSPISetup};
 
-use crate::{Hand, WiringXError};
+use crate::{Claim, Hand, WiringXError};
+
+const SPI_IOC_MAGIC: u8 = b'k';
+
+/// Linux `_IOW` request number for the spidev driver.
+const fn spi_iow(nr: u8, size: usize) -> u32 {
+    (1 << 30) | ((size as u32) << 16) | ((SPI_IOC_MAGIC as u32) << 8) | nr as u32
+}
+
+/// Linux `_IOR` request number for the spidev driver.
+const fn spi_ior(nr: u8, size: usize) -> u32 {
+    (2 << 30) | ((size as u32) << 16) | ((SPI_IOC_MAGIC as u32) << 8) | nr as u32
+}
+
+const SPI_IOC_WR_MODE: u32 = spi_iow(1, size_of::<u8>());
+const SPI_IOC_RD_MODE: u32 = spi_ior(1, size_of::<u8>());
+const SPI_IOC_WR_LSB_FIRST: u32 = spi_iow(2, size_of::<u8>());
+const SPI_IOC_RD_LSB_FIRST: u32 = spi_ior(2, size_of::<u8>());
+const SPI_IOC_WR_MAX_SPEED_HZ: u32 = spi_iow(4, size_of::<u32>());
+
+/// Mirror of the kernel's `struct spi_ioc_transfer`.
+///
+/// Only holds raw pointers, the buffers have to outlive the [`Spi::message`] call using them.
+#[repr(C)]
+#[derive(Default)]
+pub(crate) struct SpiIocTransfer {
+    tx_buf: u64,
+    rx_buf: u64,
+    len: u32,
+    speed_hz: u32,
+    delay_usecs: u16,
+    bits_per_word: u8,
+    cs_change: u8,
+    tx_nbits: u8,
+    rx_nbits: u8,
+    word_delay_usecs: u8,
+    pad: u8,
+}
+
+impl SpiIocTransfer {
+    /// Clocks out zeros while reading into `buf`.
+    pub(crate) fn read(buf: &mut [u8], speed: u32) -> Self {
+        Self {
+            rx_buf: buf.as_mut_ptr() as u64,
+            len: buf.len() as u32,
+            speed_hz: speed,
+            bits_per_word: 8,
+            ..Default::default()
+        }
+    }
+
+    /// Clocks out `buf` while discarding the read data.
+    pub(crate) fn write(buf: &[u8], speed: u32) -> Self {
+        Self {
+            tx_buf: buf.as_ptr() as u64,
+            len: buf.len() as u32,
+            speed_hz: speed,
+            bits_per_word: 8,
+            ..Default::default()
+        }
+    }
+
+    /// Clocks out `buf` and overwrites it with the read data.
+    pub(crate) fn read_write(buf: &mut [u8], speed: u32) -> Self {
+        Self {
+            tx_buf: buf.as_ptr() as u64,
+            rx_buf: buf.as_mut_ptr() as u64,
+            len: buf.len() as u32,
+            speed_hz: speed,
+            bits_per_word: 8,
+            ..Default::default()
+        }
+    }
+
+    /// Clocks out `write` while reading into `read`, both of the same length.
+    pub(crate) fn transfer(read: &mut [u8], write: &[u8], speed: u32) -> Self {
+        debug_assert_eq!(read.len(), write.len());
+
+        Self {
+            tx_buf: write.as_ptr() as u64,
+            rx_buf: read.as_mut_ptr() as u64,
+            len: read.len() as u32,
+            speed_hz: speed,
+            bits_per_word: 8,
+            ..Default::default()
+        }
+    }
+
+    /// Pauses with chip-select still asserted.
+    #[cfg(feature = "embedded-hal")]
+    pub(crate) fn delay(micros: u16) -> Self {
+        Self {
+            delay_usecs: micros,
+            ..Default::default()
+        }
+    }
+
+    /// Appends the transfers for `read` and `write` to `transfers`.
+    ///
+    /// Splits the transfer in two when the buffers differ in length, as the kernel only takes equally sized ones.
+    pub(crate) fn push_transfer(
+        transfers: &mut Vec<Self>,
+        read: &mut [u8],
+        write: &[u8],
+        speed: u32,
+    ) {
+        let common = read.len().min(write.len());
+        let (read, read_rest) = read.split_at_mut(common);
+        let (write, write_rest) = write.split_at(common);
+
+        transfers.push(Self::transfer(read, write, speed));
+
+        if !read_rest.is_empty() {
+            transfers.push(Self::read(read_rest, speed));
+        }
+        if !write_rest.is_empty() {
+            transfers.push(Self::write(write_rest, speed));
+        }
+    }
+}
 
 /// A Serial Peripheral Interface communication instance.
 ///
 /// You receive this struct from the [`WiringX::setup_spi`](super::WiringX::setup_spi)
 /// method of the [`WiringX`](super::WiringX) struct.
+///
+/// New instances start in [`SpiMode::Mode0`] with [`BitOrder::MsbFirst`].
 #[derive(Debug)]
 pub struct Spi {
     channel: i32,
     handle: Hand<i32>,
+
+    speed: u32,
+    mode: SpiMode,
+    bit_order: BitOrder,
 }
 
 impl Spi {
-    pub(super) fn new(channel: i32, speed: i32, handle: Hand<i32>) -> Result<Self, WiringXError> {
-        if handle.lock().contains(&channel) {
-            return Err(WiringXError::PinUsed);
-        }
+    pub(super) fn new(channel: i32, speed: u32, claim: Claim<i32>) -> Result<Self, WiringXError> {
+        check_speed(speed)?;
 
-        let result = unsafe { wiringXSPISetup(channel, speed) };
+        let result = unsafe { wiringXSPISetup(channel, speed as i32) };
 
         if result < 0 {
             return Err(WiringXError::Unsupported);
         }
 
-        handle.lock().insert(channel);
-
-        Ok(Self { channel, handle })
+        Ok(Self {
+            channel,
+            handle: claim.keep(),
+            speed,
+            mode: SpiMode::Mode0,
+            bit_order: BitOrder::MsbFirst,
+        })
     }
 
     /// Returns the raw file descriptor of this spi instance.
@@ -39,17 +170,142 @@ impl Spi {
         unsafe { wiringXSPIGetFd(self.channel) }
     }
 
+    /// Sets the clock polarity and phase used by this device.
+    ///
+    /// The mode is read back from the driver afterwards, returning
+    /// [`Unsupported`](WiringXError::Unsupported) if the controller did not accept it.
+    pub fn set_mode(&mut self, mode: SpiMode) -> Result<(), WiringXError> {
+        // Keep the LSB-first flag of the current mode byte intact.
+        let mut raw = self.read_u8(SPI_IOC_RD_MODE)?;
+        raw = (raw & !0b11) | mode as u8;
+        self.write_u8(SPI_IOC_WR_MODE, raw)?;
+
+        let applied = self.read_u8(SPI_IOC_RD_MODE)?;
+        if applied & 0b11 != mode as u8 {
+            return Err(WiringXError::Unsupported);
+        }
+
+        self.mode = mode;
+
+        Ok(())
+    }
+
+    /// Returns the SPI mode of this instance.
+    #[inline]
+    pub fn mode(&self) -> SpiMode {
+        self.mode
+    }
+
+    /// Sets whether words are shifted out most or least significant bit first.
+    ///
+    /// Returns [`Unsupported`](WiringXError::Unsupported) if the controller can not shift in the given order.
+    pub fn set_bit_order(&mut self, bit_order: BitOrder) -> Result<(), WiringXError> {
+        let lsb_first = (bit_order == BitOrder::LsbFirst) as u8;
+
+        self.write_u8(SPI_IOC_WR_LSB_FIRST, lsb_first)?;
+
+        if self.read_u8(SPI_IOC_RD_LSB_FIRST)? != lsb_first {
+            return Err(WiringXError::Unsupported);
+        }
+
+        self.bit_order = bit_order;
+
+        Ok(())
+    }
+
+    /// Returns the bit order of this instance.
+    #[inline]
+    pub fn bit_order(&self) -> BitOrder {
+        self.bit_order
+    }
+
+    /// Sets the clock speed of this device in Hertz.
+    ///
+    /// Used for every following transfer that does not specify its own speed.
+    pub fn set_speed(&mut self, speed: u32) -> Result<(), WiringXError> {
+        check_speed(speed)?;
+
+        let result = unsafe { libc::ioctl(self.get_fd(), SPI_IOC_WR_MAX_SPEED_HZ as _, &speed) };
+
+        if result < 0 {
+            return Err(WiringXError::Io(io::Error::last_os_error()));
+        }
+
+        self.speed = speed;
+
+        Ok(())
+    }
+
+    /// Returns the clock speed of this instance in Hertz.
+    #[inline]
+    pub fn speed(&self) -> u32 {
+        self.speed
+    }
+
     /// Writes the data to the SPI device and overwrites the provided data with the read data from the device.
+    #[inline]
     pub fn read_write(&self, data: &mut [u8]) -> Result<(), WiringXError> {
-        let len = data.len();
-        let result = unsafe {
-            wiringXSPIDataRW(self.channel, data.as_mut_ptr() as *mut c_uchar, len as i32)
-        };
+        self.read_write_with_speed(data, self.speed)
+    }
+
+    /// Same as [`read_write`](Self::read_write), but clocks this single transfer with the given speed in Hertz.
+    pub fn read_write_with_speed(&self, data: &mut [u8], speed: u32) -> Result<(), WiringXError> {
+        check_speed(speed)?;
+        check_len(data.len())?;
+
+        self.message(&[SpiIocTransfer::read_write(data, speed)])
+    }
+
+    /// Reads from the SPI device into the buffer, writing zeros.
+    pub fn read(&self, data: &mut [u8]) -> Result<(), WiringXError> {
+        check_len(data.len())?;
+
+        self.message(&[SpiIocTransfer::read(data, self.speed)])
+    }
+
+    /// Writes the data to the SPI device, discarding everything read.
+    pub fn write(&self, data: &[u8]) -> Result<(), WiringXError> {
+        check_len(data.len())?;
+
+        self.message(&[SpiIocTransfer::write(data, self.speed)])
+    }
+
+    /// Writes `write` to the SPI device while reading into `read` in one transfer.
+    ///
+    /// If the buffers differ in length, zeros are written past the end of `write`
+    /// and data read past the end of `read` is discarded.
+    pub fn transfer(&self, read: &mut [u8], write: &[u8]) -> Result<(), WiringXError> {
+        check_len(read.len().max(write.len()))?;
+
+        let mut transfers = Vec::with_capacity(2);
+        SpiIocTransfer::push_transfer(&mut transfers, read, write, self.speed);
+
+        self.message(&transfers)
+    }
+
+    /// Executes all transfers in one message, keeping chip-select asserted in between.
+    #[inline]
+    pub(crate) fn message(&self, transfers: &[SpiIocTransfer]) -> Result<(), WiringXError> {
+        message(self.get_fd(), transfers)
+    }
+
+    fn read_u8(&self, request: u32) -> Result<u8, WiringXError> {
+        let mut value = 0u8;
+
+        let result = unsafe { libc::ioctl(self.get_fd(), request as _, &mut value) };
+
+        if result < 0 {
+            Err(WiringXError::Io(io::Error::last_os_error()))
+        } else {
+            Ok(value)
+        }
+    }
+
+    fn write_u8(&self, request: u32, value: u8) -> Result<(), WiringXError> {
+        let result = unsafe { libc::ioctl(self.get_fd(), request as _, &value) };
 
         if result < 0 {
-            Err(WiringXError::Other(
-                "Failed to read and write to SPI device.".to_string(),
-            ))
+            Err(WiringXError::Io(io::Error::last_os_error()))
         } else {
             Ok(())
         }
@@ -61,3 +317,100 @@ impl Drop for Spi {
         self.handle.lock().remove(&self.channel);
     }
 }
+
+/// Executes all transfers in one message on the spidev file descriptor.
+pub(crate) fn message(fd: RawFd, transfers: &[SpiIocTransfer]) -> Result<(), WiringXError> {
+    if transfers.is_empty() {
+        return Ok(());
+    }
+
+    // The size field of the request number is only 14 bits wide.
+    if size_of_val(transfers) >= 1 << 14 {
+        return Err(WiringXError::InvalidArgument);
+    }
+
+    let request = spi_iow(0, size_of_val(transfers));
+
+    let result = unsafe { libc::ioctl(fd, request as _, transfers.as_ptr()) };
+
+    if result < 0 {
+        Err(WiringXError::Io(io::Error::last_os_error()))
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns [`InvalidArgument`](WiringXError::InvalidArgument) for transfers too long for one ioctl.
+pub(crate) fn check_len(len: usize) -> Result<(), WiringXError> {
+    if len > u32::MAX as usize {
+        Err(WiringXError::InvalidArgument)
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns [`InvalidArgument`](WiringXError::InvalidArgument) for clock speeds the spidev driver can not take.
+fn check_speed(speed: u32) -> Result<(), WiringXError> {
+    if speed == 0 || speed > i32::MAX as u32 {
+        Err(WiringXError::InvalidArgument)
+    } else {
+        Ok(())
+    }
+}
+
+/// Clock polarity (CPOL) and clock phase (CPHA) combination of an SPI device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum SpiMode {
+    /// Clock idles low, data is sampled on the rising edge.
+    #[default]
+    Mode0 = 0,
+    /// Clock idles low, data is sampled on the falling edge.
+    Mode1 = 1,
+    /// Clock idles high, data is sampled on the falling edge.
+    Mode2 = 2,
+    /// Clock idles high, data is sampled on the rising edge.
+    Mode3 = 3,
+}
+
+impl SpiMode {
+    /// Returns true if the clock line idles high.
+    #[inline]
+    pub fn cpol(&self) -> bool {
+        *self as u8 & 0b10 != 0
+    }
+
+    /// Returns true if data is sampled on the second clock edge.
+    #[inline]
+    pub fn cpha(&self) -> bool {
+        *self as u8 & 0b01 != 0
+    }
+}
+
+impl TryFrom<u8> for SpiMode {
+    type Error = WiringXError;
+
+    /// Converts the mode number `0` - `3` into a mode, returning
+    /// [`InvalidArgument`](WiringXError::InvalidArgument) for anything else.
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Mode0),
+            1 => Ok(Self::Mode1),
+            2 => Ok(Self::Mode2),
+            3 => Ok(Self::Mode3),
+            _ => Err(WiringXError::InvalidArgument),
+        }
+    }
+}
+
+/// Order in which the bits of a word are shifted out on the SPI bus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BitOrder {
+    /// Most significant bit first, the default for nearly all devices.
+    #[default]
+    MsbFirst,
+    /// Least significant bit first.
+    LsbFirst,
+}
@@ -0,0 +1,175 @@
+//! Sharing one SPI controller between several devices with GPIO chip-select pins.
+
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::{BitOrder, Output, Pin, Spi, SpiMode, Value, WiringXError};
+
+/// Shares one [`Spi`] controller between several devices, each selected by its own GPIO pin.
+///
+/// Access from multiple threads is serialized, so every [`SpiBusDevice`] can be moved to its own thread.
+///
+/// The chip-select line of the controller itself is still toggled by the kernel on every transfer,
+/// so it should be left unconnected.
+///
+/// Example with two devices on channel `0`:
+/// ```no_run
+/// use wiringx::{Output, Platform, SpiBusManager, SpiMode, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+///
+/// let manager = SpiBusManager::new(wiringx.setup_spi(0, 1_000_000).unwrap());
+///
+/// let mut thermocouple = manager.device(wiringx.gpio_pin::<Output>(20).unwrap());
+/// thermocouple.set_mode(SpiMode::Mode1);
+/// let mut display = manager.device(wiringx.gpio_pin::<Output>(21).unwrap());
+///
+/// let mut reading = [0; 4];
+/// thermocouple.read(&mut reading).unwrap();
+/// display.write(&[0xAE]).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct SpiBusManager {
+    bus: Arc<Mutex<Spi>>,
+}
+
+impl SpiBusManager {
+    /// Takes ownership of the given SPI controller to share it.
+    pub fn new(bus: Spi) -> Self {
+        Self {
+            bus: Arc::new(Mutex::new(bus)),
+        }
+    }
+
+    /// Registers a device selected by driving the given pin low.
+    ///
+    /// The device starts with the mode, bit order and speed the controller currently has.
+    pub fn device(&self, mut cs: Pin<Output>) -> SpiBusDevice {
+        cs.write(Value::High);
+
+        let bus = self.bus.lock();
+        let (mode, bit_order, speed) = (bus.mode(), bus.bit_order(), bus.speed());
+        drop(bus);
+
+        SpiBusDevice {
+            bus: self.bus.clone(),
+            cs,
+            mode,
+            bit_order,
+            speed,
+        }
+    }
+}
+
+/// A device on a shared SPI controller, received from [`SpiBusManager::device`].
+///
+/// Keeps its own mode, bit order and speed, which get applied to the controller before every transaction.
+#[derive(Debug)]
+pub struct SpiBusDevice {
+    bus: Arc<Mutex<Spi>>,
+    cs: Pin<Output>,
+
+    mode: SpiMode,
+    bit_order: BitOrder,
+    speed: u32,
+}
+
+impl SpiBusDevice {
+    /// Sets the clock polarity and phase used for this device.
+    #[inline]
+    pub fn set_mode(&mut self, mode: SpiMode) {
+        self.mode = mode;
+    }
+
+    /// Returns the SPI mode of this device.
+    #[inline]
+    pub fn mode(&self) -> SpiMode {
+        self.mode
+    }
+
+    /// Sets the bit order used for this device.
+    #[inline]
+    pub fn set_bit_order(&mut self, bit_order: BitOrder) {
+        self.bit_order = bit_order;
+    }
+
+    /// Returns the bit order of this device.
+    #[inline]
+    pub fn bit_order(&self) -> BitOrder {
+        self.bit_order
+    }
+
+    /// Sets the clock speed used for this device in Hertz.
+    pub fn set_speed(&mut self, speed: u32) -> Result<(), WiringXError> {
+        if speed == 0 || speed > i32::MAX as u32 {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        self.speed = speed;
+
+        Ok(())
+    }
+
+    /// Returns the clock speed of this device in Hertz.
+    #[inline]
+    pub fn speed(&self) -> u32 {
+        self.speed
+    }
+
+    /// Returns the number of the chip-select pin of this device.
+    #[inline]
+    pub fn cs_number(&self) -> i32 {
+        self.cs.number()
+    }
+
+    /// Writes the data to the device and overwrites the provided data with the read data from the device.
+    #[inline]
+    pub fn read_write(&mut self, data: &mut [u8]) -> Result<(), WiringXError> {
+        self.transaction(|bus| bus.read_write(data))
+    }
+
+    /// Reads from the device into the buffer, writing zeros.
+    #[inline]
+    pub fn read(&mut self, data: &mut [u8]) -> Result<(), WiringXError> {
+        self.transaction(|bus| bus.read(data))
+    }
+
+    /// Writes the data to the device, discarding everything read.
+    #[inline]
+    pub fn write(&mut self, data: &[u8]) -> Result<(), WiringXError> {
+        self.transaction(|bus| bus.write(data))
+    }
+
+    /// Writes `write` to the device while reading into `read`.
+    #[inline]
+    pub fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), WiringXError> {
+        self.transaction(|bus| bus.transfer(read, write))
+    }
+
+    /// Locks the controller and runs `f` with this device selected for its whole duration.
+    ///
+    /// Other devices on the same controller wait until the transaction finished.
+    pub fn transaction<R>(
+        &mut self,
+        f: impl FnOnce(&Spi) -> Result<R, WiringXError>,
+    ) -> Result<R, WiringXError> {
+        let mut bus = self.bus.lock();
+
+        if bus.mode() != self.mode {
+            bus.set_mode(self.mode)?;
+        }
+        if bus.bit_order() != self.bit_order {
+            bus.set_bit_order(self.bit_order)?;
+        }
+        if bus.speed() != self.speed {
+            bus.set_speed(self.speed)?;
+        }
+
+        self.cs.write(Value::Low);
+        let result = f(&bus);
+        self.cs.write(Value::High);
+
+        result
+    }
+}
@@ -0,0 +1,296 @@
+//! SSD1306 monochrome OLED displays.
+
+use std::{thread, time::Duration};
+
+use crate::{Output, Pin, Spi, Value, WiringXError, I2C};
+
+const SET_CONTRAST: u8 = 0x81;
+const DISPLAY_RAM: u8 = 0xA4;
+const NORMAL_DISPLAY: u8 = 0xA6;
+const INVERT_DISPLAY: u8 = 0xA7;
+const DISPLAY_OFF: u8 = 0xAE;
+const DISPLAY_ON: u8 = 0xAF;
+const SET_DISPLAY_OFFSET: u8 = 0xD3;
+const SET_COM_PINS: u8 = 0xDA;
+const SET_VCOM_DETECT: u8 = 0xDB;
+const SET_CLOCK_DIVIDE: u8 = 0xD5;
+const SET_PRECHARGE: u8 = 0xD9;
+const SET_MULTIPLEX: u8 = 0xA8;
+const SET_START_LINE: u8 = 0x40;
+const MEMORY_MODE: u8 = 0x20;
+const COLUMN_ADDRESS: u8 = 0x21;
+const PAGE_ADDRESS: u8 = 0x22;
+const SEGMENT_REMAP: u8 = 0xA1;
+const COM_SCAN_DECREMENT: u8 = 0xC8;
+const CHARGE_PUMP: u8 = 0x8D;
+
+/// Control bytes prefixing I2C writes.
+const I2C_COMMAND: u8 = 0x00;
+const I2C_DATA: u8 = 0x40;
+
+/// A monochrome OLED display with an SSD1306 controller, like the common 0.96" 128x64 modules.
+///
+/// Drawing happens in a local framebuffer, which gets sent to the display with [`flush`](Self::flush).
+///
+/// Example drawing a frame around the display:
+/// ```no_run
+/// use wiringx::{Platform, Ssd1306, Ssd1306Size, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let i2c = wiringx.setup_i2c("/dev/i2c-1".into(), 0x3C).unwrap();
+///
+/// let mut display = Ssd1306::new_i2c(i2c, Ssd1306Size::Size128x64).unwrap();
+///
+/// let (width, height) = (display.width(), display.height());
+/// for x in 0..width {
+///     display.set_pixel(x, 0, true);
+///     display.set_pixel(x, height - 1, true);
+/// }
+/// for y in 0..height {
+///     display.set_pixel(0, y, true);
+///     display.set_pixel(width - 1, y, true);
+/// }
+///
+/// display.flush().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct Ssd1306 {
+    interface: Ssd1306Interface,
+    size: Ssd1306Size,
+    buffer: Vec<u8>,
+}
+
+/// The bus the display is connected with.
+#[derive(Debug)]
+enum Ssd1306Interface {
+    I2c(I2C),
+    Spi { spi: Spi, dc: Pin<Output> },
+}
+
+impl Ssd1306 {
+    /// Initializes a display on the I2C bus, usually at address `0x3C` or `0x3D`.
+    pub fn new_i2c(i2c: I2C, size: Ssd1306Size) -> Result<Self, WiringXError> {
+        Self::init(Ssd1306Interface::I2c(i2c), size)
+    }
+
+    /// Initializes a display on the SPI bus, with the data/command pin and the optional reset pin.
+    pub fn new_spi(
+        spi: Spi,
+        dc: Pin<Output>,
+        reset: Option<Pin<Output>>,
+        size: Ssd1306Size,
+    ) -> Result<Self, WiringXError> {
+        if let Some(mut reset) = reset {
+            reset.write(Value::High);
+            thread::sleep(Duration::from_millis(1));
+            reset.write(Value::Low);
+            thread::sleep(Duration::from_millis(10));
+            reset.write(Value::High);
+        }
+
+        Self::init(Ssd1306Interface::Spi { spi, dc }, size)
+    }
+
+    fn init(interface: Ssd1306Interface, size: Ssd1306Size) -> Result<Self, WiringXError> {
+        let mut display = Self {
+            interface,
+            size,
+            buffer: vec![0; size.width() as usize * size.height() as usize / 8],
+        };
+
+        let com_pins = match size {
+            Ssd1306Size::Size128x64 => 0x12,
+            Ssd1306Size::Size128x32 => 0x02,
+        };
+
+        display.commands(&[
+            DISPLAY_OFF,
+            SET_CLOCK_DIVIDE,
+            0x80,
+            SET_MULTIPLEX,
+            size.height() - 1,
+            SET_DISPLAY_OFFSET,
+            0x00,
+            SET_START_LINE,
+            CHARGE_PUMP,
+            0x14,
+            MEMORY_MODE,
+            0x00,
+            SEGMENT_REMAP,
+            COM_SCAN_DECREMENT,
+            SET_COM_PINS,
+            com_pins,
+            SET_CONTRAST,
+            0xCF,
+            SET_PRECHARGE,
+            0xF1,
+            SET_VCOM_DETECT,
+            0x40,
+            DISPLAY_RAM,
+            NORMAL_DISPLAY,
+        ])?;
+
+        display.flush()?;
+        display.commands(&[DISPLAY_ON])?;
+
+        Ok(display)
+    }
+
+    /// Returns the width of the display in pixels.
+    #[inline]
+    pub fn width(&self) -> u8 {
+        self.size.width()
+    }
+
+    /// Returns the height of the display in pixels.
+    #[inline]
+    pub fn height(&self) -> u8 {
+        self.size.height()
+    }
+
+    /// Switches all pixels in the framebuffer off.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.buffer.fill(0);
+    }
+
+    /// Switches the pixel in the framebuffer on or off, ignoring coordinates outside the display.
+    pub fn set_pixel(&mut self, x: u8, y: u8, on: bool) {
+        let Some((index, mask)) = self.position(x, y) else {
+            return;
+        };
+
+        if on {
+            self.buffer[index] |= mask;
+        } else {
+            self.buffer[index] &= !mask;
+        }
+    }
+
+    /// Returns whether the pixel in the framebuffer is on, false for coordinates outside the display.
+    pub fn pixel(&self, x: u8, y: u8) -> bool {
+        self.position(x, y)
+            .is_some_and(|(index, mask)| self.buffer[index] & mask != 0)
+    }
+
+    /// Returns the framebuffer, in pages of eight rows with the least significant bit at the top.
+    #[inline]
+    pub fn buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Returns the framebuffer for modification.
+    #[inline]
+    pub fn buffer_mut(&mut self) -> &mut [u8] {
+        &mut self.buffer
+    }
+
+    /// Sends the framebuffer to the display.
+    pub fn flush(&mut self) -> Result<(), WiringXError> {
+        self.commands(&[
+            COLUMN_ADDRESS,
+            0,
+            self.width() - 1,
+            PAGE_ADDRESS,
+            0,
+            self.height() / 8 - 1,
+        ])?;
+
+        match &mut self.interface {
+            Ssd1306Interface::I2c(i2c) => {
+                for chunk in self.buffer.chunks(128) {
+                    let mut message = Vec::with_capacity(chunk.len() + 1);
+                    message.push(I2C_DATA);
+                    message.extend_from_slice(chunk);
+
+                    i2c.write_bytes(&message)?;
+                }
+
+                Ok(())
+            }
+            Ssd1306Interface::Spi { spi, dc } => {
+                dc.write(Value::High);
+                spi.write(&self.buffer)
+            }
+        }
+    }
+
+    /// Sets the brightness of the display, from `0` to `255`.
+    #[inline]
+    pub fn set_contrast(&mut self, contrast: u8) -> Result<(), WiringXError> {
+        self.commands(&[SET_CONTRAST, contrast])
+    }
+
+    /// Switches the display on or off, keeping its content.
+    #[inline]
+    pub fn set_display_on(&mut self, on: bool) -> Result<(), WiringXError> {
+        self.commands(&[if on { DISPLAY_ON } else { DISPLAY_OFF }])
+    }
+
+    /// Shows switched off pixels lit and vice-versa.
+    #[inline]
+    pub fn set_inverted(&mut self, inverted: bool) -> Result<(), WiringXError> {
+        self.commands(&[if inverted {
+            INVERT_DISPLAY
+        } else {
+            NORMAL_DISPLAY
+        }])
+    }
+
+    /// Returns the index into the framebuffer and the bit of the pixel.
+    #[inline]
+    fn position(&self, x: u8, y: u8) -> Option<(usize, u8)> {
+        if x >= self.width() || y >= self.height() {
+            return None;
+        }
+
+        Some((
+            (y / 8) as usize * self.width() as usize + x as usize,
+            1 << (y % 8),
+        ))
+    }
+
+    fn commands(&mut self, commands: &[u8]) -> Result<(), WiringXError> {
+        match &mut self.interface {
+            Ssd1306Interface::I2c(i2c) => {
+                let mut message = Vec::with_capacity(commands.len() + 1);
+                message.push(I2C_COMMAND);
+                message.extend_from_slice(commands);
+
+                i2c.write_bytes(&message)?;
+
+                Ok(())
+            }
+            Ssd1306Interface::Spi { spi, dc } => {
+                dc.write(Value::Low);
+                spi.write(commands)
+            }
+        }
+    }
+}
+
+/// Resolution of an [`Ssd1306`] display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ssd1306Size {
+    /// 128 by 64 pixels
+    Size128x64,
+    /// 128 by 32 pixels
+    Size128x32,
+}
+
+impl Ssd1306Size {
+    /// Returns the width in pixels.
+    #[inline]
+    pub fn width(&self) -> u8 {
+        128
+    }
+
+    /// Returns the height in pixels.
+    #[inline]
+    pub fn height(&self) -> u8 {
+        match self {
+            Ssd1306Size::Size128x64 => 64,
+            Ssd1306Size::Size128x32 => 32,
+        }
+    }
+}
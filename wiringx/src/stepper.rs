@@ -0,0 +1,581 @@
+//! Stepper motors, unipolar on four driver inputs or on STEP/DIR drivers.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicI64, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+use crate::{gpio::wait_until, Output, Pin, Value, WiringXError};
+
+/// Energized coils of every half step, in the order of the driver inputs `IN1` - `IN4`.
+/// Full steps use the odd entries, with two coils on for more torque.
+const HALF_STEPS: [[bool; 4]; 8] = [
+    [true, false, false, false],
+    [true, true, false, false],
+    [false, true, false, false],
+    [false, true, true, false],
+    [false, false, true, false],
+    [false, false, true, true],
+    [false, false, false, true],
+    [true, false, false, true],
+];
+
+/// Full steps per output shaft revolution of the 28BYJ-48 with its 1:64 gearbox.
+const BYJ48_FULL_STEPS: u32 = 2048;
+
+/// A unipolar stepper motor like the 28BYJ-48, on a ULN2003 or similar driver board with four inputs.
+///
+/// Steps block the calling thread with [`step`](Self::step) or run on a background thread
+/// with [`step_in_background`](Self::step_in_background), while the position is tracked in steps.
+/// The coils are switched off when the stepper is dropped, see [`release`](Self::release).
+///
+/// Example turning the shaft once forward at 10 RPM and back in the background:
+/// ```no_run
+/// use wiringx::{Output, Platform, StepMode, Stepper4Wire, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let pins = [12, 13, 14, 15].map(|pin| wiringx.gpio_pin::<Output>(pin).unwrap());
+///
+/// let mut stepper = Stepper4Wire::new(pins, StepMode::Half);
+/// stepper.set_rpm(10.0).unwrap();
+///
+/// stepper.step(4096);
+/// stepper.step_in_background(-4096);
+/// while stepper.is_moving() {
+///     println!("at {}", stepper.position());
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Stepper4Wire {
+    motor: Arc<Mutex<Motor>>,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+    mode: StepMode,
+    interval: Duration,
+    steps_per_revolution: u32,
+}
+
+#[derive(Debug)]
+struct Motor {
+    pins: [Pin<Output>; 4],
+    /// Index into [`HALF_STEPS`] of the energized coils.
+    phase: usize,
+    position: i64,
+}
+
+impl Stepper4Wire {
+    /// Takes ownership of the pins connected to `IN1` - `IN4`, with the coils off.
+    ///
+    /// Starts at 100 steps per second and 2048 full steps per revolution, the values of the 28BYJ-48.
+    pub fn new(mut pins: [Pin<Output>; 4], mode: StepMode) -> Self {
+        for pin in &mut pins {
+            pin.write(Value::Low);
+        }
+
+        let (phase, steps_per_revolution) = match mode {
+            StepMode::Full => (1, BYJ48_FULL_STEPS),
+            StepMode::Half => (0, BYJ48_FULL_STEPS * 2),
+        };
+
+        Self {
+            motor: Arc::new(Mutex::new(Motor {
+                pins,
+                phase,
+                position: 0,
+            })),
+            running: Arc::new(AtomicBool::new(false)),
+            thread: None,
+            mode,
+            interval: Duration::from_millis(10),
+            steps_per_revolution,
+        }
+    }
+
+    /// Returns the step sequence of the stepper.
+    #[inline]
+    pub fn mode(&self) -> StepMode {
+        self.mode
+    }
+
+    /// Sets the speed in steps per second, taking effect with the next move.
+    pub fn set_speed(&mut self, steps_per_second: f32) -> Result<(), WiringXError> {
+        if steps_per_second <= 0.0 || !steps_per_second.is_finite() {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        self.interval = Duration::from_secs_f32(1.0 / steps_per_second);
+
+        Ok(())
+    }
+
+    /// Returns the speed in steps per second.
+    #[inline]
+    pub fn speed(&self) -> f32 {
+        1.0 / self.interval.as_secs_f32()
+    }
+
+    /// Sets the speed in revolutions per minute of the output shaft, taking effect with the next move.
+    #[inline]
+    pub fn set_rpm(&mut self, rpm: f32) -> Result<(), WiringXError> {
+        self.set_speed(rpm * self.steps_per_revolution as f32 / 60.0)
+    }
+
+    /// Sets the steps of the current mode per output shaft revolution, for motors with other gearboxes.
+    pub fn set_steps_per_revolution(&mut self, steps: u32) -> Result<(), WiringXError> {
+        if steps == 0 {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        self.steps_per_revolution = steps;
+
+        Ok(())
+    }
+
+    /// Returns the steps per output shaft revolution.
+    #[inline]
+    pub fn steps_per_revolution(&self) -> u32 {
+        self.steps_per_revolution
+    }
+
+    /// Returns the position in steps, counted from the start or the last [`set_position`](Self::set_position).
+    #[inline]
+    pub fn position(&self) -> i64 {
+        self.motor.lock().position
+    }
+
+    /// Sets the current position, for example to zero it at a home switch.
+    #[inline]
+    pub fn set_position(&mut self, position: i64) {
+        self.motor.lock().position = position;
+    }
+
+    /// Moves the number of steps, backwards for negative ones, and returns when done.
+    ///
+    /// Stops a move running in the background first.
+    pub fn step(&mut self, steps: i64) {
+        self.stop();
+
+        self.running.store(true, Ordering::Release);
+        run(&self.motor, &self.running, self.mode, self.interval, steps);
+    }
+
+    /// Moves the number of steps on a background thread, backwards for negative ones.
+    ///
+    /// Stops a move running in the background first.
+    pub fn step_in_background(&mut self, steps: i64) {
+        self.stop();
+
+        self.running.store(true, Ordering::Release);
+
+        let motor = self.motor.clone();
+        let running = self.running.clone();
+        let mode = self.mode;
+        let interval = self.interval;
+
+        self.thread = Some(thread::spawn(move || {
+            run(&motor, &running, mode, interval, steps)
+        }));
+    }
+
+    /// Returns whether a move is still running in the background.
+    #[inline]
+    pub fn is_moving(&self) -> bool {
+        self.running.load(Ordering::Acquire)
+    }
+
+    /// Waits until the move running in the background is done.
+    pub fn wait(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+
+    /// Stops the move running in the background after the current step, keeping the coils energized.
+    pub fn stop(&mut self) {
+        self.running.store(false, Ordering::Release);
+        self.wait();
+    }
+
+    /// Stops moving and switches all coils off, so the motor stops drawing current and heating up.
+    ///
+    /// The motor no longer holds its position and may lose it if turned by hand.
+    pub fn release(&mut self) {
+        self.stop();
+
+        for pin in &mut self.motor.lock().pins {
+            pin.write(Value::Low);
+        }
+    }
+}
+
+impl Drop for Stepper4Wire {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+/// Steps until done or `running` gets cleared, each step scheduled from the start so delays do not add up.
+fn run(motor: &Mutex<Motor>, running: &AtomicBool, mode: StepMode, interval: Duration, steps: i64) {
+    let stride = match mode {
+        StepMode::Full => 2,
+        StepMode::Half => 1,
+    };
+    let direction = steps.signum();
+    let mut next = Instant::now();
+
+    for _ in 0..steps.unsigned_abs() {
+        if !running.load(Ordering::Acquire) {
+            break;
+        }
+
+        {
+            let mut motor = motor.lock();
+
+            motor.phase = if direction > 0 {
+                (motor.phase + stride) % HALF_STEPS.len()
+            } else {
+                (motor.phase + HALF_STEPS.len() - stride) % HALF_STEPS.len()
+            };
+            motor.position += direction;
+
+            let coils = HALF_STEPS[motor.phase];
+            for (pin, on) in motor.pins.iter_mut().zip(coils) {
+                pin.write(if on { Value::High } else { Value::Low });
+            }
+        }
+
+        next += interval;
+        let now = Instant::now();
+        if next > now {
+            thread::sleep(next - now);
+        }
+    }
+
+    running.store(false, Ordering::Release);
+}
+
+/// A stepper motor on a driver with STEP and DIR inputs, like the A4988, DRV8825 or TMC2209.
+///
+/// Moves accelerate up to the maximum speed and decelerate again before the target, in a trapezoidal profile.
+/// They block the calling thread with [`move_to`](Self::move_to) or run on a background thread
+/// with [`move_to_in_background`](Self::move_to_in_background), while the position is tracked in steps,
+/// or microsteps if configured on the driver.
+///
+/// Steps are timed by busy waiting for the last millisecond before each, which limits the speed
+/// to a few thousand steps per second depending on the board.
+///
+/// Example moving 10 revolutions of a 200 step motor with 16 microsteps and back:
+/// ```no_run
+/// use wiringx::{Output, Platform, StepDirStepper, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+///
+/// let mut stepper = StepDirStepper::new(
+///     wiringx.gpio_pin::<Output>(12).unwrap(),
+///     wiringx.gpio_pin::<Output>(13).unwrap(),
+///     Some(wiringx.gpio_pin::<Output>(14).unwrap()),
+/// );
+/// stepper.set_max_speed(3200.0).unwrap();
+/// stepper.set_acceleration(6400.0).unwrap();
+/// stepper.set_enabled(true).unwrap();
+///
+/// stepper.move_by(10 * 200 * 16);
+/// stepper.move_to(0);
+/// ```
+#[derive(Debug)]
+pub struct StepDirStepper {
+    pins: Arc<Mutex<StepDirPins>>,
+    enable: Option<Pin<Output>>,
+    position: Arc<AtomicI64>,
+    running: Arc<AtomicBool>,
+    stopping: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+    profile: Profile,
+}
+
+#[derive(Debug)]
+struct StepDirPins {
+    step: Pin<Output>,
+    dir: Pin<Output>,
+    inverted: bool,
+}
+
+/// Speed limits of a move.
+#[derive(Debug, Clone, Copy)]
+struct Profile {
+    max_speed: f32,
+    acceleration: f32,
+    pulse_width: Duration,
+}
+
+impl StepDirStepper {
+    /// Takes ownership of the STEP and DIR pins and the optional, active low ENABLE pin, leaving the driver disabled.
+    ///
+    /// Starts at a maximum speed of 200 steps per second with an acceleration of 400 steps per second squared.
+    pub fn new(mut step: Pin<Output>, mut dir: Pin<Output>, enable: Option<Pin<Output>>) -> Self {
+        step.write(Value::Low);
+        dir.write(Value::Low);
+
+        let mut stepper = Self {
+            pins: Arc::new(Mutex::new(StepDirPins {
+                step,
+                dir,
+                inverted: false,
+            })),
+            enable,
+            position: Arc::new(AtomicI64::new(0)),
+            running: Arc::new(AtomicBool::new(false)),
+            stopping: Arc::new(AtomicBool::new(false)),
+            thread: None,
+            profile: Profile {
+                max_speed: 200.0,
+                acceleration: 400.0,
+                pulse_width: Duration::from_micros(2),
+            },
+        };
+
+        let _ = stepper.set_enabled(false);
+
+        stepper
+    }
+
+    /// Enables or disables the driver outputs with the ENABLE pin.
+    ///
+    /// Returns [`Unsupported`](WiringXError::Unsupported) without ENABLE pin, these drivers are always enabled.
+    pub fn set_enabled(&mut self, enabled: bool) -> Result<(), WiringXError> {
+        let enable = self.enable.as_mut().ok_or(WiringXError::Unsupported)?;
+        enable.write(if enabled { Value::Low } else { Value::High });
+
+        Ok(())
+    }
+
+    /// Sets the highest speed in steps per second, taking effect with the next move.
+    pub fn set_max_speed(&mut self, steps_per_second: f32) -> Result<(), WiringXError> {
+        if steps_per_second <= 0.0 || !steps_per_second.is_finite() {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        self.profile.max_speed = steps_per_second;
+
+        Ok(())
+    }
+
+    /// Returns the highest speed in steps per second.
+    #[inline]
+    pub fn max_speed(&self) -> f32 {
+        self.profile.max_speed
+    }
+
+    /// Sets the acceleration and deceleration in steps per second squared, taking effect with the next move.
+    pub fn set_acceleration(&mut self, acceleration: f32) -> Result<(), WiringXError> {
+        if acceleration <= 0.0 || !acceleration.is_finite() {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        self.profile.acceleration = acceleration;
+
+        Ok(())
+    }
+
+    /// Returns the acceleration in steps per second squared.
+    #[inline]
+    pub fn acceleration(&self) -> f32 {
+        self.profile.acceleration
+    }
+
+    /// Sets how long STEP is held high per step, 2 µs by default, enough for the A4988 and DRV8825.
+    #[inline]
+    pub fn set_pulse_width(&mut self, pulse_width: Duration) {
+        self.profile.pulse_width = pulse_width;
+    }
+
+    /// Inverts the level of DIR, for motors wired to turn the other way.
+    pub fn set_direction_inverted(&mut self, inverted: bool) {
+        self.halt();
+
+        self.pins.lock().inverted = inverted;
+    }
+
+    /// Returns the position in steps, counted from the start or the last [`set_position`](Self::set_position).
+    #[inline]
+    pub fn position(&self) -> i64 {
+        self.position.load(Ordering::Acquire)
+    }
+
+    /// Sets the current position, for example to zero it at a home switch. Halts a running move.
+    pub fn set_position(&mut self, position: i64) {
+        self.halt();
+
+        self.position.store(position, Ordering::Release);
+    }
+
+    /// Moves to the absolute position and returns when done.
+    ///
+    /// Halts a move running in the background first.
+    pub fn move_to(&mut self, target: i64) {
+        self.halt();
+
+        self.running.store(true, Ordering::Release);
+        run_profile(
+            &self.pins,
+            &self.position,
+            &self.running,
+            &self.stopping,
+            self.profile,
+            target,
+        );
+    }
+
+    /// Moves the number of steps relative to the current position and returns when done.
+    #[inline]
+    pub fn move_by(&mut self, steps: i64) {
+        self.move_to(self.position() + steps);
+    }
+
+    /// Moves to the absolute position on a background thread.
+    ///
+    /// Halts a move running in the background first.
+    pub fn move_to_in_background(&mut self, target: i64) {
+        self.halt();
+
+        self.running.store(true, Ordering::Release);
+
+        let pins = self.pins.clone();
+        let position = self.position.clone();
+        let running = self.running.clone();
+        let stopping = self.stopping.clone();
+        let profile = self.profile;
+
+        self.thread = Some(thread::spawn(move || {
+            run_profile(&pins, &position, &running, &stopping, profile, target)
+        }));
+    }
+
+    /// Moves the number of steps relative to the current position on a background thread.
+    #[inline]
+    pub fn move_by_in_background(&mut self, steps: i64) {
+        self.move_to_in_background(self.position() + steps);
+    }
+
+    /// Returns whether a move is still running in the background.
+    #[inline]
+    pub fn is_moving(&self) -> bool {
+        self.running.load(Ordering::Acquire)
+    }
+
+    /// Waits until the move running in the background is done.
+    pub fn wait(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+
+    /// Decelerates the move running in the background to a stop and waits for it.
+    pub fn stop(&mut self) {
+        self.stopping.store(true, Ordering::Release);
+        self.wait();
+        self.stopping.store(false, Ordering::Release);
+    }
+
+    /// Stops the move running in the background after the current step, without decelerating.
+    ///
+    /// Stopping abruptly at speed may make the motor skip steps, so the position may be off afterwards.
+    pub fn halt(&mut self) {
+        self.running.store(false, Ordering::Release);
+        self.wait();
+    }
+}
+
+impl Drop for StepDirStepper {
+    fn drop(&mut self) {
+        self.halt();
+        let _ = self.set_enabled(false);
+    }
+}
+
+/// Steps to the target in a trapezoidal profile, until done or `running` gets cleared.
+///
+/// The speed is updated every step from `v² = v₀² ± 2a`, as every step covers a distance of one.
+fn run_profile(
+    pins: &Mutex<StepDirPins>,
+    position: &AtomicI64,
+    running: &AtomicBool,
+    stopping: &AtomicBool,
+    profile: Profile,
+    mut target: i64,
+) {
+    let mut pins = pins.lock();
+    let min_speed = (2.0 * profile.acceleration).sqrt().min(profile.max_speed);
+    let mut speed = 0.0f32;
+    let mut direction = 0;
+    let mut next = Instant::now();
+
+    while running.load(Ordering::Acquire) {
+        let current = position.load(Ordering::Acquire);
+
+        if stopping.swap(false, Ordering::AcqRel) && direction != 0 {
+            let stopping_steps = (speed * speed / (2.0 * profile.acceleration)).ceil() as i64;
+            target = current + direction * stopping_steps;
+        }
+
+        let remaining = target - current;
+        if remaining == 0 {
+            break;
+        }
+
+        if remaining.signum() != direction {
+            direction = remaining.signum();
+            let forward = (direction > 0) != pins.inverted;
+            pins.dir
+                .write(if forward { Value::High } else { Value::Low });
+
+            // The drivers need DIR stable for at least 200 ns before the step.
+            next = next.max(Instant::now() + Duration::from_micros(1));
+        }
+
+        let stopping_distance = speed * speed / (2.0 * profile.acceleration);
+        speed = if remaining.unsigned_abs() as f32 <= stopping_distance {
+            (speed * speed - 2.0 * profile.acceleration).max(0.0).sqrt()
+        } else {
+            (speed * speed + 2.0 * profile.acceleration).sqrt()
+        }
+        .clamp(min_speed, profile.max_speed);
+
+        sleep_until(next);
+        let start = Instant::now();
+        pins.step.write(Value::High);
+        wait_until(start + profile.pulse_width);
+        pins.step.write(Value::Low);
+
+        position.fetch_add(direction, Ordering::AcqRel);
+        next = start + Duration::from_secs_f32(1.0 / speed);
+    }
+
+    running.store(false, Ordering::Release);
+}
+
+/// Sleeps until shortly before the deadline and busy waits the rest, for timing below the scheduler resolution.
+fn sleep_until(deadline: Instant) {
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    if remaining > Duration::from_millis(2) {
+        thread::sleep(remaining - Duration::from_millis(1));
+    }
+
+    wait_until(deadline);
+}
+
+/// Step sequence of a [`Stepper4Wire`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StepMode {
+    /// Two coils on at a time, full torque
+    Full,
+    /// Alternating one and two coils, double resolution and smoother at less torque
+    #[default]
+    Half,
+}
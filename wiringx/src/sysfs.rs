@@ -0,0 +1,212 @@
+//! GPIO through the deprecated but widely available sysfs interface `/sys/class/gpio`.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io,
+    os::{
+        fd::{AsRawFd, RawFd},
+        unix::fs::FileExt,
+    },
+    path::PathBuf,
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    gpio::{monotonic_now, LineBackend},
+    Edge, EdgeEvent, IsrMode, Value, WiringXError,
+};
+
+const SYSFS_GPIO: &str = "/sys/class/gpio";
+
+/// How long udev may take to make the files of an exported GPIO accessible.
+const EXPORT_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Returns the base and number of lines of the GPIO chips, ordered by their base.
+pub(crate) fn chips() -> Vec<(i32, u32)> {
+    let Ok(entries) = fs::read_dir(SYSFS_GPIO) else {
+        return Vec::new();
+    };
+
+    let mut chips: Vec<(i32, u32)> = entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("gpiochip"))
+        .filter_map(|entry| {
+            let base = fs::read_to_string(entry.path().join("base")).ok()?;
+            let lines = fs::read_to_string(entry.path().join("ngpio")).ok()?;
+
+            Some((base.trim().parse().ok()?, lines.trim().parse().ok()?))
+        })
+        .collect();
+    chips.sort_unstable();
+
+    chips
+}
+
+/// A GPIO exported in sysfs, unexported when dropped.
+#[derive(Debug)]
+pub(crate) struct SysfsLine {
+    number: u32,
+    value: File,
+}
+
+impl SysfsLine {
+    /// Exports the GPIO with the kernel's global number as input.
+    pub(crate) fn input(number: i32) -> Result<Self, WiringXError> {
+        Self::export(number, "in")
+    }
+
+    /// Exports the GPIO with the kernel's global number as output, driving it low.
+    pub(crate) fn output(number: i32) -> Result<Self, WiringXError> {
+        Self::export(number, "low")
+    }
+
+    fn export(number: i32, direction: &str) -> Result<Self, WiringXError> {
+        let number = u32::try_from(number).map_err(|_| WiringXError::InvalidPin)?;
+        let path = PathBuf::from(format!("{SYSFS_GPIO}/gpio{number}"));
+
+        if !path.exists() {
+            fs::write(format!("{SYSFS_GPIO}/export"), number.to_string()).map_err(|e| {
+                if e.kind() == io::ErrorKind::InvalidInput {
+                    WiringXError::InvalidPin
+                } else {
+                    WiringXError::Io(e)
+                }
+            })?;
+        }
+
+        // The files appear right away, but udev may still be changing their permissions.
+        let deadline = Instant::now() + EXPORT_TIMEOUT;
+        loop {
+            match fs::write(path.join("direction"), direction) {
+                Ok(()) => break,
+                Err(e)
+                    if e.kind() == io::ErrorKind::PermissionDenied && Instant::now() < deadline =>
+                {
+                    thread::sleep(Duration::from_millis(10));
+                }
+                Err(e) => return Err(WiringXError::Io(e)),
+            }
+        }
+
+        let value = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path.join("value"))
+            .map_err(WiringXError::Io)?;
+
+        Ok(Self { number, value })
+    }
+
+    fn write_attribute(&self, attribute: &str, value: &str) -> Result<(), WiringXError> {
+        fs::write(
+            format!("{SYSFS_GPIO}/gpio{}/{attribute}", self.number),
+            value,
+        )
+        .map_err(WiringXError::Io)
+    }
+}
+
+impl LineBackend for SysfsLine {
+    /// Reads the level of the GPIO, also of outputs.
+    fn read(&self) -> Result<Value, WiringXError> {
+        let mut value = [0; 1];
+        self.value
+            .read_exact_at(&mut value, 0)
+            .map_err(WiringXError::Io)?;
+
+        Ok(if value[0] == b'1' {
+            Value::High
+        } else {
+            Value::Low
+        })
+    }
+
+    /// Drives the output to the value.
+    fn write(&self, value: Value) -> Result<(), WiringXError> {
+        let value = match value {
+            Value::High => b"1",
+            Value::Low => b"0",
+        };
+
+        self.value.write_all_at(value, 0).map_err(WiringXError::Io)
+    }
+
+    /// Switches the GPIO to input, detecting the edges of the mode.
+    fn set_input(&self, mode: IsrMode) -> Result<(), WiringXError> {
+        let edge = match mode {
+            IsrMode::Rising => "rising",
+            IsrMode::Falling => "falling",
+            IsrMode::Both => "both",
+            IsrMode::None => "none",
+            IsrMode::Unknown => return Err(WiringXError::InvalidArgument),
+        };
+
+        self.write_attribute("direction", "in")?;
+        self.write_attribute("edge", edge)
+    }
+
+    /// Switches the GPIO to output, driving the value.
+    fn set_output(&self, value: Value) -> Result<(), WiringXError> {
+        let direction = match value {
+            Value::High => "high",
+            Value::Low => "low",
+        };
+
+        self.write_attribute("direction", direction)
+    }
+
+    /// Discards the edges detected so far, reading the value clears them.
+    #[inline]
+    fn clear_events(&self) -> Result<(), WiringXError> {
+        self.read().map(|_| ())
+    }
+
+    /// Waits for the next detected edge, none if there was none within the timeout.
+    ///
+    /// The time is taken when waking up and the edge is derived from the level at that time.
+    fn read_event(&self, timeout: Duration) -> Result<Option<EdgeEvent>, WiringXError> {
+        let mut poll = libc::pollfd {
+            fd: self.value.as_raw_fd(),
+            events: libc::POLLPRI | libc::POLLERR,
+            revents: 0,
+        };
+
+        let timeout = timeout.as_millis().min(i32::MAX as u128) as i32;
+        let result = unsafe { libc::poll(&mut poll, 1, timeout) };
+        if result < 0 {
+            let error = io::Error::last_os_error();
+            return if error.kind() == io::ErrorKind::Interrupted {
+                Ok(None)
+            } else {
+                Err(WiringXError::Io(error))
+            };
+        }
+
+        if result == 0 {
+            return Ok(None);
+        }
+
+        let timestamp = monotonic_now();
+
+        Ok(Some(EdgeEvent {
+            edge: match self.read()? {
+                Value::High => Edge::Rising,
+                Value::Low => Edge::Falling,
+            },
+            timestamp,
+        }))
+    }
+
+    fn interrupt_fd(&self, mode: IsrMode) -> Result<(RawFd, bool), WiringXError> {
+        self.set_input(mode)?;
+
+        Ok((self.value.as_raw_fd(), true))
+    }
+}
+
+impl Drop for SysfsLine {
+    fn drop(&mut self) {
+        let _ = fs::write(format!("{SYSFS_GPIO}/unexport"), self.number.to_string());
+    }
+}
@@ -0,0 +1,258 @@
+//! TCS34725 color sensors.
+
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{DeviceFault, WiringXError, I2C};
+
+/// Selects the register, `| 0x20` increments it automatically.
+const COMMAND: u8 = 0x80;
+const AUTO_INCREMENT: u8 = 0x20;
+
+const ENABLE: u8 = 0x00;
+const ATIME: u8 = 0x01;
+const CONTROL: u8 = 0x0F;
+const ID: u8 = 0x12;
+const STATUS: u8 = 0x13;
+const CDATAL: u8 = 0x14;
+
+const ENABLE_PON: u8 = 0x01;
+const ENABLE_AEN: u8 = 0x02;
+const STATUS_AVALID: u8 = 0x01;
+
+/// Time of one integration cycle.
+const CYCLE: Duration = Duration::from_micros(2400);
+/// Device and glass factor of the lux calculation, for the open sensor without glass in front.
+const DEVICE_FACTOR: f32 = 310.0;
+
+/// An AMS TCS34725 sensor measuring red, green, blue and unfiltered (clear) light, with an IR blocking filter.
+///
+/// Illuminance and correlated color temperature are calculated as in the DN40 application note.
+///
+/// Example on the I2C bus at address `0x29`:
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use wiringx::{Platform, Tcs34725, Tcs34725Gain, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let i2c = wiringx.setup_i2c("/dev/i2c-1".into(), 0x29).unwrap();
+///
+/// let mut sensor = Tcs34725::new(i2c).unwrap();
+/// sensor.set_gain(Tcs34725Gain::X16).unwrap();
+/// sensor.set_integration_time(Duration::from_millis(100)).unwrap();
+///
+/// let measurement = sensor.read().unwrap();
+/// println!("{:?}, {:.0} lx", measurement.color, measurement.lux);
+/// ```
+#[derive(Debug)]
+pub struct Tcs34725 {
+    i2c: I2C,
+    cycles: u16,
+    gain: Tcs34725Gain,
+}
+
+impl Tcs34725 {
+    /// Powers on the sensor with a gain of 4x and an integration time of 50 ms.
+    pub fn new(i2c: I2C) -> Result<Self, WiringXError> {
+        let mut sensor = Self {
+            i2c,
+            cycles: 0,
+            gain: Tcs34725Gain::X4,
+        };
+
+        // The TCS34727 variant has a different id.
+        let id = sensor.read_register(ID)?;
+        if id != 0x44 && id != 0x4D {
+            return Err(WiringXError::Unsupported);
+        }
+
+        sensor.set_integration_time(Duration::from_millis(50))?;
+        sensor.set_gain(Tcs34725Gain::X4)?;
+
+        sensor.write_register(ENABLE, ENABLE_PON)?;
+        thread::sleep(Duration::from_millis(3));
+        sensor.write_register(ENABLE, ENABLE_PON | ENABLE_AEN)?;
+
+        Ok(sensor)
+    }
+
+    /// Sets the integration time from 2.4 ms to 614.4 ms in steps of 2.4 ms.
+    ///
+    /// Longer times are more sensitive and reach higher counts.
+    pub fn set_integration_time(&mut self, time: Duration) -> Result<(), WiringXError> {
+        let cycles = (time.as_secs_f32() / CYCLE.as_secs_f32()).round();
+        if !(1.0..=256.0).contains(&cycles) {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        self.write_register(ATIME, (256 - cycles as u16) as u8)?;
+        self.cycles = cycles as u16;
+
+        Ok(())
+    }
+
+    /// Returns the integration time.
+    #[inline]
+    pub fn integration_time(&self) -> Duration {
+        CYCLE * self.cycles as u32
+    }
+
+    /// Sets the gain of the analog front end.
+    pub fn set_gain(&mut self, gain: Tcs34725Gain) -> Result<(), WiringXError> {
+        self.write_register(CONTROL, gain as u8)?;
+        self.gain = gain;
+
+        Ok(())
+    }
+
+    /// Returns the gain of the analog front end.
+    #[inline]
+    pub fn gain(&self) -> Tcs34725Gain {
+        self.gain
+    }
+
+    /// Returns the highest count of the channels at the integration time.
+    #[inline]
+    pub fn max_count(&self) -> u16 {
+        (self.cycles as u32 * 1024).min(u16::MAX as u32) as u16
+    }
+
+    /// Waits for the next integration to complete and reads the counts of all channels.
+    pub fn read_raw(&mut self) -> Result<Tcs34725Color, WiringXError> {
+        let deadline = Instant::now() + self.integration_time() * 2 + Duration::from_millis(10);
+        while self.read_register(STATUS)? & STATUS_AVALID == 0 {
+            if Instant::now() > deadline {
+                return Err(WiringXError::TimedOut);
+            }
+
+            thread::sleep(CYCLE);
+        }
+
+        // Reading the clear channel latches the other ones.
+        let mut data = [0; 8];
+        self.i2c
+            .read_reg_bytes(COMMAND | AUTO_INCREMENT | CDATAL, &mut data)?;
+
+        Ok(Tcs34725Color {
+            clear: u16::from_le_bytes([data[0], data[1]]),
+            red: u16::from_le_bytes([data[2], data[3]]),
+            green: u16::from_le_bytes([data[4], data[5]]),
+            blue: u16::from_le_bytes([data[6], data[7]]),
+        })
+    }
+
+    /// Reads the counts of all channels, and calculates illuminance and color temperature from them.
+    ///
+    /// Returns [`OutOfRange`](DeviceFault::OutOfRange) if the sensor is saturated, then gain or integration time need to be lowered.
+    pub fn read(&mut self) -> Result<Tcs34725Measurement, WiringXError> {
+        let color = self.read_raw()?;
+
+        // Above 75 % of the counts of short integrations, ripple of the light may saturate the sensor.
+        let max_count = self.max_count() as u32;
+        let limit = if self.cycles > 63 {
+            max_count
+        } else {
+            max_count * 3 / 4
+        };
+        if color.clear as u32 >= limit {
+            return Err(WiringXError::DeviceFault(DeviceFault::OutOfRange));
+        }
+
+        let (red, green, blue, clear) = (
+            color.red as f32,
+            color.green as f32,
+            color.blue as f32,
+            color.clear as f32,
+        );
+
+        // The IR part of the light is in all channels.
+        let ir = ((red + green + blue - clear) / 2.0).max(0.0);
+        let (red, green, blue) = (red - ir, green - ir, blue - ir);
+
+        let counts_per_lux =
+            self.integration_time().as_secs_f32() * 1000.0 * self.gain.factor() / DEVICE_FACTOR;
+        let lux = ((0.136 * red + green - 0.444 * blue) / counts_per_lux).max(0.0);
+
+        let color_temperature = (red > 0.0).then(|| 3810.0 * blue / red + 1391.0);
+
+        Ok(Tcs34725Measurement {
+            color,
+            lux,
+            color_temperature,
+        })
+    }
+
+    /// Powers the sensor down or up again.
+    pub fn set_enabled(&mut self, enabled: bool) -> Result<(), WiringXError> {
+        if enabled {
+            self.write_register(ENABLE, ENABLE_PON)?;
+            thread::sleep(Duration::from_millis(3));
+            self.write_register(ENABLE, ENABLE_PON | ENABLE_AEN)
+        } else {
+            self.write_register(ENABLE, 0)
+        }
+    }
+
+    fn read_register(&mut self, register: u8) -> Result<u8, WiringXError> {
+        Ok(self.i2c.read_reg8((COMMAND | register) as i32)?)
+    }
+
+    fn write_register(&mut self, register: u8, value: u8) -> Result<(), WiringXError> {
+        self.i2c.write_bytes(&[COMMAND | register, value])?;
+
+        Ok(())
+    }
+}
+
+/// Raw counts of the channels of a [`Tcs34725`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tcs34725Color {
+    /// Unfiltered light.
+    pub clear: u16,
+    /// Red light.
+    pub red: u16,
+    /// Green light.
+    pub green: u16,
+    /// Blue light.
+    pub blue: u16,
+}
+
+/// A measurement of a [`Tcs34725`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tcs34725Measurement {
+    /// Raw counts of the channels.
+    pub color: Tcs34725Color,
+    /// Illuminance in lx.
+    pub lux: f32,
+    /// Correlated color temperature in K, none if there is no red light.
+    pub color_temperature: Option<f32>,
+}
+
+/// Gain of a [`Tcs34725`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tcs34725Gain {
+    /// 1x
+    X1 = 0,
+    /// 4x
+    #[default]
+    X4 = 1,
+    /// 16x
+    X16 = 2,
+    /// 60x
+    X60 = 3,
+}
+
+impl Tcs34725Gain {
+    #[inline]
+    fn factor(&self) -> f32 {
+        match self {
+            Tcs34725Gain::X1 => 1.0,
+            Tcs34725Gain::X4 => 4.0,
+            Tcs34725Gain::X16 => 16.0,
+            Tcs34725Gain::X60 => 60.0,
+        }
+    }
+}
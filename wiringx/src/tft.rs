@@ -0,0 +1,486 @@
+//! ST7735 and ILI9341 color TFT displays.
+
+use std::{thread, time::Duration};
+
+use crate::{BitOrder, Output, Pin, Spi, SpiMode, Value, WiringXError};
+
+const SOFTWARE_RESET: u8 = 0x01;
+const SLEEP_OUT: u8 = 0x11;
+const NORMAL_MODE: u8 = 0x13;
+const INVERSION_OFF: u8 = 0x20;
+const INVERSION_ON: u8 = 0x21;
+const DISPLAY_OFF: u8 = 0x28;
+const DISPLAY_ON: u8 = 0x29;
+const COLUMN_ADDRESS: u8 = 0x2A;
+const ROW_ADDRESS: u8 = 0x2B;
+const MEMORY_WRITE: u8 = 0x2C;
+const MEMORY_ACCESS: u8 = 0x36;
+const PIXEL_FORMAT: u8 = 0x3A;
+
+/// 16 bits per pixel in the RGB565 format.
+const PIXEL_FORMAT_16: u8 = 0x55;
+
+const MADCTL_MY: u8 = 0x80;
+const MADCTL_MX: u8 = 0x40;
+const MADCTL_MV: u8 = 0x20;
+const MADCTL_BGR: u8 = 0x08;
+
+/// Largest transfer the spidev driver takes by default.
+const MAX_TRANSFER: usize = 4096;
+
+/// Commands with their parameters and the time to wait afterwards, following the datasheet recommendations.
+const ST7735_INIT: &[(u8, &[u8], u64)] = &[
+    (0xB1, &[0x01, 0x2C, 0x2D], 0),
+    (0xB2, &[0x01, 0x2C, 0x2D], 0),
+    (0xB3, &[0x01, 0x2C, 0x2D, 0x01, 0x2C, 0x2D], 0),
+    (0xB4, &[0x07], 0),
+    (0xC0, &[0xA2, 0x02, 0x84], 0),
+    (0xC1, &[0xC5], 0),
+    (0xC2, &[0x0A, 0x00], 0),
+    (0xC3, &[0x8A, 0x2A], 0),
+    (0xC4, &[0x8A, 0xEE], 0),
+    (0xC5, &[0x0E], 0),
+    (
+        0xE0,
+        &[
+            0x02, 0x1C, 0x07, 0x12, 0x37, 0x32, 0x29, 0x2D, 0x29, 0x25, 0x2B, 0x39, 0x00, 0x01,
+            0x03, 0x10,
+        ],
+        0,
+    ),
+    (
+        0xE1,
+        &[
+            0x03, 0x1D, 0x07, 0x06, 0x2E, 0x2C, 0x29, 0x2D, 0x2E, 0x2E, 0x37, 0x3F, 0x00, 0x00,
+            0x02, 0x10,
+        ],
+        0,
+    ),
+    (NORMAL_MODE, &[], 10),
+];
+
+const ILI9341_INIT: &[(u8, &[u8], u64)] = &[
+    (0xEF, &[0x03, 0x80, 0x02], 0),
+    (0xCF, &[0x00, 0xC1, 0x30], 0),
+    (0xED, &[0x64, 0x03, 0x12, 0x81], 0),
+    (0xE8, &[0x85, 0x00, 0x78], 0),
+    (0xCB, &[0x39, 0x2C, 0x00, 0x34, 0x02], 0),
+    (0xF7, &[0x20], 0),
+    (0xEA, &[0x00, 0x00], 0),
+    (0xC0, &[0x23], 0),
+    (0xC1, &[0x10], 0),
+    (0xC5, &[0x3E, 0x28], 0),
+    (0xC7, &[0x86], 0),
+    (0x37, &[0x00], 0),
+    (0xB1, &[0x00, 0x18], 0),
+    (0xB6, &[0x08, 0x82, 0x27], 0),
+    (0xF2, &[0x00], 0),
+    (0x26, &[0x01], 0),
+    (
+        0xE0,
+        &[
+            0x0F, 0x31, 0x2B, 0x0C, 0x0E, 0x08, 0x4E, 0xF1, 0x37, 0x07, 0x10, 0x03, 0x0E, 0x09,
+            0x00,
+        ],
+        0,
+    ),
+    (
+        0xE1,
+        &[
+            0x00, 0x0E, 0x14, 0x03, 0x11, 0x07, 0x31, 0xC1, 0x48, 0x08, 0x0F, 0x0C, 0x31, 0x36,
+            0x0F,
+        ],
+        0,
+    ),
+];
+
+/// A color TFT display with an ST7735 or ILI9341 controller on the SPI bus, with its data/command pin.
+///
+/// Drawing happens in a local RGB565 framebuffer, [`flush`](Self::flush) only sends the rectangle
+/// around the pixels changed since the last flush, which keeps small updates fast.
+///
+/// Example drawing a red square in the middle of the display:
+/// ```no_run
+/// use wiringx::{rgb565, Output, Platform, Tft, TftModel, TftRotation, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+///
+/// let mut display = Tft::new(
+///     wiringx.setup_spi(0, 32_000_000).unwrap(),
+///     wiringx.gpio_pin::<Output>(14).unwrap(),
+///     Some(wiringx.gpio_pin::<Output>(15).unwrap()),
+///     TftModel::Ili9341,
+/// )
+/// .unwrap();
+///
+/// display.set_rotation(TftRotation::Deg90).unwrap();
+///
+/// let (x, y) = (display.width() / 2 - 20, display.height() / 2 - 20);
+/// display.fill_rect(x, y, 40, 40, rgb565(255, 0, 0));
+///
+/// display.flush().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct Tft {
+    spi: Spi,
+    dc: Pin<Output>,
+    model: TftModel,
+    rotation: TftRotation,
+    bgr: bool,
+    width: u16,
+    height: u16,
+    buffer: Vec<u16>,
+    dirty: Option<Rect>,
+}
+
+/// Inclusive bounds of the changed pixels.
+#[derive(Debug, Clone, Copy)]
+struct Rect {
+    x0: u16,
+    y0: u16,
+    x1: u16,
+    y1: u16,
+}
+
+impl Tft {
+    /// Initializes a display on the SPI bus, with the data/command pin and the optional reset pin, and clears it black.
+    ///
+    /// The SPI controller is reconfigured to [`SpiMode::Mode0`] and [`BitOrder::MsbFirst`].
+    /// ST7735 controllers take up to 15 MHz, ILI9341 ones usually work at 32 MHz and more.
+    pub fn new(
+        mut spi: Spi,
+        dc: Pin<Output>,
+        reset: Option<Pin<Output>>,
+        model: TftModel,
+    ) -> Result<Self, WiringXError> {
+        spi.set_mode(SpiMode::Mode0)?;
+        spi.set_bit_order(BitOrder::MsbFirst)?;
+
+        if let Some(mut reset) = reset {
+            reset.write(Value::High);
+            thread::sleep(Duration::from_millis(5));
+            reset.write(Value::Low);
+            thread::sleep(Duration::from_millis(20));
+            reset.write(Value::High);
+            thread::sleep(Duration::from_millis(150));
+        }
+
+        let (width, height) = model.size();
+        let mut display = Self {
+            spi,
+            dc,
+            model,
+            rotation: TftRotation::Deg0,
+            bgr: model.bgr(),
+            width,
+            height,
+            buffer: vec![0; width as usize * height as usize],
+            dirty: None,
+        };
+
+        display.command(SOFTWARE_RESET, &[])?;
+        thread::sleep(Duration::from_millis(150));
+        display.command(SLEEP_OUT, &[])?;
+        thread::sleep(Duration::from_millis(150));
+
+        let init = match model {
+            TftModel::Ili9341 => ILI9341_INIT,
+            _ => ST7735_INIT,
+        };
+        for (command, parameters, delay) in init {
+            display.command(*command, parameters)?;
+            thread::sleep(Duration::from_millis(*delay));
+        }
+
+        display.command(PIXEL_FORMAT, &[PIXEL_FORMAT_16])?;
+        display.set_inverted(false)?;
+        display.send_rotation()?;
+
+        display.invalidate();
+        display.flush()?;
+        display.command(DISPLAY_ON, &[])?;
+        thread::sleep(Duration::from_millis(10));
+
+        Ok(display)
+    }
+
+    /// Returns the model of the display.
+    #[inline]
+    pub fn model(&self) -> TftModel {
+        self.model
+    }
+
+    /// Returns the width of the display in pixels, in its current rotation.
+    #[inline]
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Returns the height of the display in pixels, in its current rotation.
+    #[inline]
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Rotates the display clockwise from its portrait orientation.
+    ///
+    /// Width and height swap for quarter turns, which clears the framebuffer.
+    /// Otherwise the framebuffer is kept and shown rotated with the next flush.
+    pub fn set_rotation(&mut self, rotation: TftRotation) -> Result<(), WiringXError> {
+        let (width, height) = match rotation {
+            TftRotation::Deg0 | TftRotation::Deg180 => self.model.size(),
+            TftRotation::Deg90 | TftRotation::Deg270 => {
+                let (width, height) = self.model.size();
+                (height, width)
+            }
+        };
+
+        if (width, height) != (self.width, self.height) {
+            self.width = width;
+            self.height = height;
+            self.buffer.fill(0);
+        }
+
+        self.rotation = rotation;
+        self.send_rotation()?;
+        self.invalidate();
+
+        Ok(())
+    }
+
+    /// Returns the rotation.
+    #[inline]
+    pub fn rotation(&self) -> TftRotation {
+        self.rotation
+    }
+
+    /// Swaps the red and blue channels, for panels wired the other way than the model usually is.
+    pub fn set_bgr(&mut self, bgr: bool) -> Result<(), WiringXError> {
+        self.bgr = bgr;
+
+        self.send_rotation()
+    }
+
+    /// Inverts all colors, some panels need this to show the colors right.
+    pub fn set_inverted(&mut self, inverted: bool) -> Result<(), WiringXError> {
+        // Some ST7735 panels are inverted by design.
+        let invert = inverted != self.model.inverted();
+
+        self.command(if invert { INVERSION_ON } else { INVERSION_OFF }, &[])
+    }
+
+    /// Switches the display on or off, keeping its content.
+    #[inline]
+    pub fn set_display_on(&mut self, on: bool) -> Result<(), WiringXError> {
+        self.command(if on { DISPLAY_ON } else { DISPLAY_OFF }, &[])
+    }
+
+    /// Sets the pixel in the framebuffer to the RGB565 color, ignoring coordinates outside the display.
+    pub fn set_pixel(&mut self, x: u16, y: u16, color: u16) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+
+        let index = y as usize * self.width as usize + x as usize;
+        if self.buffer[index] != color {
+            self.buffer[index] = color;
+            self.mark_dirty(x, y, x, y);
+        }
+    }
+
+    /// Returns the RGB565 color of the pixel in the framebuffer, none for coordinates outside the display.
+    pub fn pixel(&self, x: u16, y: u16) -> Option<u16> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        Some(self.buffer[y as usize * self.width as usize + x as usize])
+    }
+
+    /// Fills the rectangle in the framebuffer with the RGB565 color, clipped to the display.
+    pub fn fill_rect(&mut self, x: u16, y: u16, width: u16, height: u16, color: u16) {
+        let x1 = x.saturating_add(width).min(self.width);
+        let y1 = y.saturating_add(height).min(self.height);
+        if x >= x1 || y >= y1 {
+            return;
+        }
+
+        for row in y..y1 {
+            let start = row as usize * self.width as usize;
+            self.buffer[start + x as usize..start + x1 as usize].fill(color);
+        }
+
+        self.mark_dirty(x, y, x1 - 1, y1 - 1);
+    }
+
+    /// Fills the whole framebuffer with the RGB565 color.
+    pub fn fill(&mut self, color: u16) {
+        self.buffer.fill(color);
+        self.invalidate();
+    }
+
+    /// Fills the framebuffer black.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.fill(0);
+    }
+
+    /// Marks the whole framebuffer as changed, so the next flush sends all of it.
+    pub fn invalidate(&mut self) {
+        self.dirty = Some(Rect {
+            x0: 0,
+            y0: 0,
+            x1: self.width - 1,
+            y1: self.height - 1,
+        });
+    }
+
+    /// Sends the pixels changed since the last flush to the display.
+    pub fn flush(&mut self) -> Result<(), WiringXError> {
+        let Some(rect) = self.dirty else {
+            return Ok(());
+        };
+
+        let (offset_x, offset_y) = match self.rotation {
+            TftRotation::Deg0 | TftRotation::Deg180 => self.model.offset(),
+            TftRotation::Deg90 | TftRotation::Deg270 => {
+                let (x, y) = self.model.offset();
+                (y, x)
+            }
+        };
+        let (x0, x1) = (rect.x0 + offset_x, rect.x1 + offset_x);
+        let (y0, y1) = (rect.y0 + offset_y, rect.y1 + offset_y);
+
+        let [x0_high, x0_low] = x0.to_be_bytes();
+        let [x1_high, x1_low] = x1.to_be_bytes();
+        let [y0_high, y0_low] = y0.to_be_bytes();
+        let [y1_high, y1_low] = y1.to_be_bytes();
+        self.command(COLUMN_ADDRESS, &[x0_high, x0_low, x1_high, x1_low])?;
+        self.command(ROW_ADDRESS, &[y0_high, y0_low, y1_high, y1_low])?;
+        self.command(MEMORY_WRITE, &[])?;
+
+        let row_len = (rect.x1 - rect.x0 + 1) as usize;
+        let mut data = Vec::with_capacity(row_len * 2 * (rect.y1 - rect.y0 + 1) as usize);
+        for row in rect.y0..=rect.y1 {
+            let start = row as usize * self.width as usize + rect.x0 as usize;
+            for pixel in &self.buffer[start..start + row_len] {
+                data.extend_from_slice(&pixel.to_be_bytes());
+            }
+        }
+
+        self.dc.write(Value::High);
+        for chunk in data.chunks(MAX_TRANSFER) {
+            self.spi.write(chunk)?;
+        }
+
+        self.dirty = None;
+
+        Ok(())
+    }
+
+    fn mark_dirty(&mut self, x0: u16, y0: u16, x1: u16, y1: u16) {
+        self.dirty = Some(match self.dirty {
+            Some(rect) => Rect {
+                x0: rect.x0.min(x0),
+                y0: rect.y0.min(y0),
+                x1: rect.x1.max(x1),
+                y1: rect.y1.max(y1),
+            },
+            None => Rect { x0, y0, x1, y1 },
+        });
+    }
+
+    fn send_rotation(&mut self) -> Result<(), WiringXError> {
+        let rotation = match (self.model, self.rotation) {
+            (TftModel::Ili9341, TftRotation::Deg0) => MADCTL_MX,
+            (TftModel::Ili9341, TftRotation::Deg90) => MADCTL_MV,
+            (TftModel::Ili9341, TftRotation::Deg180) => MADCTL_MY,
+            (TftModel::Ili9341, TftRotation::Deg270) => MADCTL_MX | MADCTL_MY | MADCTL_MV,
+            (_, TftRotation::Deg0) => MADCTL_MX | MADCTL_MY,
+            (_, TftRotation::Deg90) => MADCTL_MY | MADCTL_MV,
+            (_, TftRotation::Deg180) => 0,
+            (_, TftRotation::Deg270) => MADCTL_MX | MADCTL_MV,
+        };
+        let bgr = if self.bgr { MADCTL_BGR } else { 0 };
+
+        self.command(MEMORY_ACCESS, &[rotation | bgr])
+    }
+
+    fn command(&mut self, command: u8, parameters: &[u8]) -> Result<(), WiringXError> {
+        self.dc.write(Value::Low);
+        self.spi.write(&[command])?;
+
+        if !parameters.is_empty() {
+            self.dc.write(Value::High);
+            self.spi.write(parameters)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Model of a [`Tft`] display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TftModel {
+    /// ST7735 with 128 by 160 pixels, the common 1.8" modules
+    St7735,
+    /// ST7735 with 128 by 128 pixels, the common 1.44" modules
+    St7735Square,
+    /// ST7735 with 80 by 160 pixels, the common 0.96" modules
+    St7735Mini,
+    /// ILI9341 with 240 by 320 pixels, the common 2.2" to 2.8" modules
+    Ili9341,
+}
+
+impl TftModel {
+    /// Returns the width and height in pixels in portrait orientation.
+    #[inline]
+    pub fn size(&self) -> (u16, u16) {
+        match self {
+            TftModel::St7735 => (128, 160),
+            TftModel::St7735Square => (128, 128),
+            TftModel::St7735Mini => (80, 160),
+            TftModel::Ili9341 => (240, 320),
+        }
+    }
+
+    /// Returns where the panel starts in the larger memory of the ST7735.
+    #[inline]
+    fn offset(&self) -> (u16, u16) {
+        match self {
+            TftModel::St7735Square => (2, 3),
+            TftModel::St7735Mini => (26, 1),
+            _ => (0, 0),
+        }
+    }
+
+    #[inline]
+    fn bgr(&self) -> bool {
+        !matches!(self, TftModel::St7735)
+    }
+
+    #[inline]
+    fn inverted(&self) -> bool {
+        matches!(self, TftModel::St7735Mini)
+    }
+}
+
+/// Clockwise rotation of a [`Tft`] display from its portrait orientation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TftRotation {
+    /// Portrait
+    #[default]
+    Deg0,
+    /// Landscape
+    Deg90,
+    /// Portrait upside down
+    Deg180,
+    /// Landscape upside down
+    Deg270,
+}
+
+/// Converts 8 bit red, green and blue to a RGB565 color for [`Tft`] displays.
+#[inline]
+pub const fn rgb565(red: u8, green: u8, blue: u8) -> u16 {
+    (red as u16 & 0xF8) << 8 | (green as u16 & 0xFC) << 3 | blue as u16 >> 3
+}
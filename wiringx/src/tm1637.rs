@@ -0,0 +1,303 @@
+//! TM1637 LED display drivers.
+
+use std::time::{Duration, Instant};
+
+use crate::{gpio::wait_until, DeviceFault, Output, Pin, Value, WiringXError};
+
+const DATA_AUTO_INCREMENT: u8 = 0x40;
+const ADDRESS: u8 = 0xC0;
+const DISPLAY_CONTROL: u8 = 0x80;
+const DISPLAY_ON: u8 = 0x08;
+
+/// Segment bit of the decimal point.
+const DECIMAL_POINT: u8 = 0x80;
+/// The colon of clock displays is wired as decimal point of the second digit.
+const COLON: u8 = DECIMAL_POINT;
+
+/// Half of a clock period, the chip takes up to 250 kHz.
+const HALF_PERIOD: Duration = Duration::from_micros(5);
+
+/// A 4 digit 7 segment LED display with a TM1637 driver, like the common clock displays with a colon.
+///
+/// The chip is controlled with a clock and a data line, in a protocol resembling I2C without addresses.
+/// The data line is driven open-drain, so it needs the pull-up resistor most modules have.
+///
+/// Example showing a time:
+/// ```no_run
+/// use wiringx::{Output, Platform, Tm1637, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+///
+/// let mut display = Tm1637::new(
+///     wiringx.gpio_pin::<Output>(14).unwrap(),
+///     wiringx.gpio_pin::<Output>(15).unwrap(),
+/// )
+/// .unwrap();
+///
+/// display.set_brightness(3).unwrap();
+/// display.show_str("1234").unwrap();
+/// display.set_colon(true).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct Tm1637 {
+    clock: Pin<Output>,
+    data: Pin<Output>,
+    segments: [u8; 4],
+    colon: bool,
+    brightness: u8,
+    display_on: bool,
+}
+
+impl Tm1637 {
+    /// Takes ownership of the clock and data pins and clears the display at full brightness.
+    pub fn new(mut clock: Pin<Output>, mut data: Pin<Output>) -> Result<Self, WiringXError> {
+        clock.write(Value::High);
+        data.release();
+
+        let mut display = Self {
+            clock,
+            data,
+            segments: [0; 4],
+            colon: false,
+            brightness: 7,
+            display_on: true,
+        };
+
+        display.clear()?;
+        display.send_control()?;
+
+        Ok(display)
+    }
+
+    /// Sets the brightness from `0` to `7`.
+    pub fn set_brightness(&mut self, brightness: u8) -> Result<(), WiringXError> {
+        if brightness > 7 {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        self.brightness = brightness;
+
+        self.send_control()
+    }
+
+    /// Returns the brightness.
+    #[inline]
+    pub fn brightness(&self) -> u8 {
+        self.brightness
+    }
+
+    /// Switches the display on or off, keeping its content.
+    pub fn set_display_on(&mut self, on: bool) -> Result<(), WiringXError> {
+        self.display_on = on;
+
+        self.send_control()
+    }
+
+    /// Switches the colon between the second and third digit on or off.
+    pub fn set_colon(&mut self, colon: bool) -> Result<(), WiringXError> {
+        self.colon = colon;
+
+        self.send_segments()
+    }
+
+    /// Switches all segments off, except the colon.
+    pub fn clear(&mut self) -> Result<(), WiringXError> {
+        self.set_segments(0, &[0; 4])
+    }
+
+    /// Sets raw segments of the digits from the position on, with segment `a` in the least significant bit
+    /// and the decimal point in the most significant one.
+    pub fn set_segments(&mut self, position: usize, segments: &[u8]) -> Result<(), WiringXError> {
+        let digits = self
+            .segments
+            .get_mut(position..position + segments.len())
+            .ok_or(WiringXError::InvalidArgument)?;
+        digits.copy_from_slice(segments);
+
+        self.send_segments()
+    }
+
+    /// Shows the number right-aligned, from `-999` to `9999`.
+    pub fn show_number(&mut self, number: i32, leading_zeros: bool) -> Result<(), WiringXError> {
+        let text = format_number(number, leading_zeros).ok_or(WiringXError::InvalidArgument)?;
+
+        self.show_str(&text)
+    }
+
+    /// Shows up to four characters from the left, where a `.` lights the decimal point of the previous one.
+    ///
+    /// Digits, spaces, `-`, `_` and the letters 7 segments can show are supported,
+    /// others are shown blank.
+    pub fn show_str(&mut self, text: &str) -> Result<(), WiringXError> {
+        let segments = encode_str(text, 4).ok_or(WiringXError::InvalidArgument)?;
+
+        self.set_segments(0, &segments)
+    }
+
+    fn send_segments(&mut self) -> Result<(), WiringXError> {
+        let mut segments = self.segments;
+        if self.colon {
+            segments[1] |= COLON;
+        }
+
+        self.start();
+        self.write_byte(DATA_AUTO_INCREMENT)?;
+        self.stop();
+
+        self.start();
+        self.write_byte(ADDRESS)?;
+        for byte in segments {
+            self.write_byte(byte)?;
+        }
+        self.stop();
+
+        Ok(())
+    }
+
+    fn send_control(&mut self) -> Result<(), WiringXError> {
+        let on = if self.display_on { DISPLAY_ON } else { 0 };
+
+        self.start();
+        self.write_byte(DISPLAY_CONTROL | on | self.brightness)?;
+        self.stop();
+
+        Ok(())
+    }
+
+    /// Pulls the data line low while the clock is high.
+    fn start(&mut self) {
+        let start = Instant::now();
+
+        self.data.drive(Value::Low);
+        wait_until(start + HALF_PERIOD);
+    }
+
+    /// Releases the data line while the clock is high.
+    fn stop(&mut self) {
+        let start = Instant::now();
+
+        self.clock.write(Value::Low);
+        self.data.drive(Value::Low);
+        wait_until(start + HALF_PERIOD);
+        self.clock.write(Value::High);
+        wait_until(start + HALF_PERIOD * 2);
+        self.data.release();
+        wait_until(start + HALF_PERIOD * 3);
+    }
+
+    /// Shifts the byte out least significant bit first, then checks the acknowledgement.
+    fn write_byte(&mut self, byte: u8) -> Result<(), WiringXError> {
+        let mut edge = Instant::now();
+
+        for i in 0..8 {
+            self.clock.write(Value::Low);
+            if byte >> i & 1 == 1 {
+                self.data.release();
+            } else {
+                self.data.drive(Value::Low);
+            }
+            edge += HALF_PERIOD;
+            wait_until(edge);
+
+            self.clock.write(Value::High);
+            edge += HALF_PERIOD;
+            wait_until(edge);
+        }
+
+        // The chip pulls the data line low during the ninth clock pulse.
+        self.clock.write(Value::Low);
+        self.data.release();
+        edge += HALF_PERIOD;
+        wait_until(edge);
+
+        self.clock.write(Value::High);
+        edge += HALF_PERIOD;
+        wait_until(edge);
+        let ack = self.data.read() == Value::Low;
+
+        self.clock.write(Value::Low);
+        edge += HALF_PERIOD;
+        wait_until(edge);
+        self.clock.write(Value::High);
+
+        if ack {
+            Ok(())
+        } else {
+            Err(WiringXError::DeviceFault(DeviceFault::NoAcknowledge))
+        }
+    }
+}
+
+/// Returns the segments of the character, with segment `a` in the least significant bit, none if unsupported.
+pub(crate) fn encode_char(character: char) -> Option<u8> {
+    Some(match character.to_ascii_uppercase() {
+        '0' | 'O' => 0x3F,
+        '1' | 'I' => 0x06,
+        '2' | 'Z' => 0x5B,
+        '3' => 0x4F,
+        '4' => 0x66,
+        '5' | 'S' => 0x6D,
+        '6' => 0x7D,
+        '7' => 0x07,
+        '8' => 0x7F,
+        '9' => 0x6F,
+        'A' => 0x77,
+        'B' => 0x7C,
+        'C' => 0x39,
+        'D' => 0x5E,
+        'E' => 0x79,
+        'F' => 0x71,
+        'G' => 0x3D,
+        'H' => 0x76,
+        'J' => 0x1E,
+        'L' => 0x38,
+        'N' => 0x54,
+        'P' => 0x73,
+        'R' => 0x50,
+        'T' => 0x78,
+        'U' => 0x3E,
+        'Y' => 0x6E,
+        '-' => 0x40,
+        '_' => 0x08,
+        ' ' => 0x00,
+        _ => return None,
+    })
+}
+
+/// Formats the number right-aligned for 4 digits, none if it is outside of `-999` to `9999`.
+pub(crate) fn format_number(number: i32, leading_zeros: bool) -> Option<String> {
+    if !(-999..=9999).contains(&number) {
+        return None;
+    }
+
+    Some(if leading_zeros && number >= 0 {
+        format!("{number:04}")
+    } else if leading_zeros {
+        format!("-{:03}", -number)
+    } else {
+        format!("{number:>4}")
+    })
+}
+
+/// Encodes the text into the segments of the number of digits, a `.` lighting the decimal point of the previous digit.
+///
+/// Unsupported characters are blank, none is returned if the text does not fit.
+pub(crate) fn encode_str(text: &str, digits: usize) -> Option<Vec<u8>> {
+    let mut segments: Vec<u8> = Vec::with_capacity(digits);
+
+    for character in text.chars() {
+        match (character, segments.last_mut()) {
+            ('.', Some(last)) if *last & DECIMAL_POINT == 0 => *last |= DECIMAL_POINT,
+            ('.', _) => segments.push(DECIMAL_POINT),
+            (character, _) => segments.push(encode_char(character).unwrap_or(0)),
+        }
+    }
+
+    if segments.len() > digits {
+        return None;
+    }
+
+    segments.resize(digits, 0);
+
+    Some(segments)
+}
@@ -0,0 +1,429 @@
+//! Recording the operations on pins to a file and replaying them, enabled with the `trace` feature.
+//!
+//! While a [`Recording`] is active, every write and read of a GPIO [`Pin`](crate::Pin), every detected edge
+//! and every change of the period and duty cycle of a [`PwmPin`](crate::PwmPin) is appended to the file,
+//! one line each with the time since the start of the recording in µs, for example `1520 read 3 high`.
+//! Pins read in tight loops, like by bit-banged protocols, produce a lot of lines.
+//!
+//! With the `mock` feature, the inputs of a recorded [`Trace`] can be fed back into the `MockBoard`,
+//! to run the application against what happened in the field.
+//! For timing analysis a trace can be exported as VCD file with [`Trace::write_vcd`],
+//! to be opened in waveform viewers like GTKWave or PulseView.
+//!
+//! Example recording a run on the board:
+//! ```no_run
+//! use wiringx::{trace, Input, Platform, WiringX};
+//!
+//! let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+//! let _recording = trace::record("button.trace").unwrap();
+//!
+//! let button = wiringx.gpio_pin::<Input>(3).unwrap();
+//! println!("{:?}", button.read());
+//! ```
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+    str::FromStr,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+use crate::{Edge, Value, WiringXError};
+#[cfg(feature = "mock")]
+use std::thread;
+
+#[cfg(feature = "mock")]
+use crate::MockBoard;
+
+static RECORDING: AtomicBool = AtomicBool::new(false);
+static RECORDER: Mutex<Option<Recorder>> = Mutex::new(None);
+
+#[derive(Debug)]
+struct Recorder {
+    start: Instant,
+    file: BufWriter<File>,
+}
+
+/// Starts recording the operations on pins to the file, until the returned [`Recording`] is dropped.
+///
+/// Returns [`Other`](WiringXError::Other) if a recording is already active.
+pub fn record(path: impl AsRef<Path>) -> Result<Recording, WiringXError> {
+    let mut recorder = RECORDER.lock();
+    if recorder.is_some() {
+        return Err(WiringXError::Other(
+            "A trace is already being recorded.".to_string(),
+        ));
+    }
+
+    let file = File::create(path).map_err(WiringXError::Io)?;
+    *recorder = Some(Recorder {
+        start: Instant::now(),
+        file: BufWriter::new(file),
+    });
+    RECORDING.store(true, Ordering::Release);
+
+    Ok(Recording { _private: () })
+}
+
+/// An active recording started with [`record`], which stops and flushes the file when dropped.
+#[derive(Debug)]
+pub struct Recording {
+    _private: (),
+}
+
+impl Recording {
+    /// Stops the recording, returning an error if the file could not be written.
+    pub fn stop(self) -> Result<(), WiringXError> {
+        let recorder = RECORDER.lock().take();
+        RECORDING.store(false, Ordering::Release);
+        std::mem::forget(self);
+
+        match recorder {
+            Some(mut recorder) => recorder.file.flush().map_err(WiringXError::Io),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for Recording {
+    fn drop(&mut self) {
+        RECORDING.store(false, Ordering::Release);
+        if let Some(mut recorder) = RECORDER.lock().take() {
+            let _ = recorder.file.flush();
+        }
+    }
+}
+
+/// Returns whether a recording is active, so callers can skip gathering what only gets recorded.
+#[inline]
+pub(crate) fn is_recording() -> bool {
+    RECORDING.load(Ordering::Acquire)
+}
+
+/// Appends the operation on the pin to the active recording, if there is one.
+#[inline]
+pub(crate) fn log(pin: i32, kind: TraceKind) {
+    if !is_recording() {
+        return;
+    }
+
+    if let Some(recorder) = RECORDER.lock().as_mut() {
+        let event = TraceEvent {
+            time: recorder.start.elapsed(),
+            pin,
+            kind,
+        };
+        let _ = writeln!(recorder.file, "{event}");
+    }
+}
+
+/// A recorded operation on a pin, one line of a trace.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TraceEvent {
+    /// Time since the start of the recording.
+    pub time: Duration,
+    /// Number of the GPIO or PWM pin.
+    pub pin: i32,
+    /// The operation.
+    pub kind: TraceKind,
+}
+
+/// Kind of a [`TraceEvent`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TraceKind {
+    /// An output was driven to the value
+    Write(Value),
+    /// An input was read with the value
+    Read(Value),
+    /// An edge was detected on an input
+    Edge(Edge),
+    /// The period of a PWM pin was set
+    Period(Duration),
+    /// The duty cycle of a PWM pin was set, from 0.0 - 1.0
+    DutyCycle(f32),
+}
+
+impl fmt::Display for TraceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ", self.time.as_micros())?;
+
+        match self.kind {
+            TraceKind::Write(value) => write!(f, "write {} {}", self.pin, value_name(value)),
+            TraceKind::Read(value) => write!(f, "read {} {}", self.pin, value_name(value)),
+            TraceKind::Edge(Edge::Rising) => write!(f, "edge {} rising", self.pin),
+            TraceKind::Edge(Edge::Falling) => write!(f, "edge {} falling", self.pin),
+            TraceKind::Period(period) => write!(f, "period {} {}", self.pin, period.as_nanos()),
+            TraceKind::DutyCycle(duty_cycle) => write!(f, "duty {} {duty_cycle}", self.pin),
+        }
+    }
+}
+
+impl FromStr for TraceEvent {
+    type Err = WiringXError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let invalid = || WiringXError::Other(format!("Invalid trace line: {line}"));
+
+        let mut fields = line.split_whitespace();
+        let (Some(time), Some(kind), Some(pin), Some(argument), None) = (
+            fields.next(),
+            fields.next(),
+            fields.next(),
+            fields.next(),
+            fields.next(),
+        ) else {
+            return Err(invalid());
+        };
+
+        let time = Duration::from_micros(time.parse().map_err(|_| invalid())?);
+        let pin = pin.parse().map_err(|_| invalid())?;
+        let value = || match argument {
+            "high" => Ok(Value::High),
+            "low" => Ok(Value::Low),
+            _ => Err(invalid()),
+        };
+
+        let kind = match kind {
+            "write" => TraceKind::Write(value()?),
+            "read" => TraceKind::Read(value()?),
+            "edge" => TraceKind::Edge(match argument {
+                "rising" => Edge::Rising,
+                "falling" => Edge::Falling,
+                _ => return Err(invalid()),
+            }),
+            "period" => TraceKind::Period(Duration::from_nanos(
+                argument.parse().map_err(|_| invalid())?,
+            )),
+            "duty" => TraceKind::DutyCycle(argument.parse().map_err(|_| invalid())?),
+            _ => return Err(invalid()),
+        };
+
+        Ok(Self { time, pin, kind })
+    }
+}
+
+fn value_name(value: Value) -> &'static str {
+    match value {
+        Value::High => "high",
+        Value::Low => "low",
+    }
+}
+
+/// A recorded trace, loaded from the file written by a [`Recording`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Trace {
+    events: Vec<TraceEvent>,
+}
+
+impl Trace {
+    /// Loads the trace from the file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, WiringXError> {
+        fs::read_to_string(path).map_err(WiringXError::Io)?.parse()
+    }
+
+    /// Returns the recorded events, in the order they happened.
+    #[inline]
+    pub fn events(&self) -> &[TraceEvent] {
+        &self.events
+    }
+
+    /// Writes the trace as Value Change Dump, with a wire for the level of each GPIO pin
+    /// and real variables for the period in ns and duty cycle of each PWM pin.
+    ///
+    /// Only changes are written, so repeated reads of the same level do not add anything.
+    ///
+    /// Example converting a recording:
+    /// ```no_run
+    /// use wiringx::trace::Trace;
+    ///
+    /// let trace = Trace::load("button.trace").unwrap();
+    /// trace.write_vcd(std::fs::File::create("button.vcd").unwrap()).unwrap();
+    /// ```
+    pub fn write_vcd(&self, writer: impl Write) -> Result<(), WiringXError> {
+        let mut writer = BufWriter::new(writer);
+        self.vcd(&mut writer)
+            .and_then(|_| writer.flush())
+            .map_err(WiringXError::Io)
+    }
+
+    fn vcd(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        // Each signal gets the next short identifier of printable characters.
+        let mut signals = BTreeMap::new();
+        for event in &self.events {
+            let signal = VcdSignal::of(event.kind);
+            let next = signals.len();
+            signals
+                .entry((event.pin, signal))
+                .or_insert_with(|| vcd_identifier(next));
+        }
+
+        writeln!(
+            writer,
+            "$version wiringx {} $end",
+            env!("CARGO_PKG_VERSION")
+        )?;
+        writeln!(writer, "$timescale 1us $end")?;
+        writeln!(writer, "$scope module wiringx $end")?;
+        for ((pin, signal), identifier) in &signals {
+            match signal {
+                VcdSignal::Level => writeln!(writer, "$var wire 1 {identifier} gpio{pin} $end")?,
+                VcdSignal::Period => {
+                    writeln!(writer, "$var real 64 {identifier} pwm{pin}_period $end")?
+                }
+                VcdSignal::DutyCycle => {
+                    writeln!(writer, "$var real 64 {identifier} pwm{pin}_duty $end")?
+                }
+            }
+        }
+        writeln!(writer, "$upscope $end")?;
+        writeln!(writer, "$enddefinitions $end")?;
+
+        writeln!(writer, "#0")?;
+        writeln!(writer, "$dumpvars")?;
+        for ((_, signal), identifier) in &signals {
+            match signal {
+                VcdSignal::Level => writeln!(writer, "x{identifier}")?,
+                VcdSignal::Period | VcdSignal::DutyCycle => writeln!(writer, "r0 {identifier}")?,
+            }
+        }
+        writeln!(writer, "$end")?;
+
+        let mut values = BTreeMap::new();
+        let mut time = 0;
+        for event in &self.events {
+            let signal = VcdSignal::of(event.kind);
+            let value = match event.kind {
+                TraceKind::Write(value) | TraceKind::Read(value) => value_bit(value).to_string(),
+                TraceKind::Edge(Edge::Rising) => "1".to_string(),
+                TraceKind::Edge(Edge::Falling) => "0".to_string(),
+                TraceKind::Period(period) => format!("r{} ", period.as_nanos()),
+                TraceKind::DutyCycle(duty_cycle) => format!("r{duty_cycle} "),
+            };
+
+            if values.get(&(event.pin, signal)) == Some(&value) {
+                continue;
+            }
+
+            let micros = event.time.as_micros();
+            if micros != time {
+                writeln!(writer, "#{micros}")?;
+                time = micros;
+            }
+
+            writeln!(writer, "{value}{}", signals[&(event.pin, signal)])?;
+            values.insert((event.pin, signal), value);
+        }
+
+        Ok(())
+    }
+
+    /// Feeds the levels read and edges detected on inputs into the board at the times they were recorded,
+    /// blocking until the last one.
+    ///
+    /// Writes and PWM changes are not replayed, they are what the application is expected to do again.
+    ///
+    /// Example reproducing a recorded run:
+    /// ```no_run
+    /// use std::thread;
+    ///
+    /// use wiringx::{trace::Trace, GpioBackend, Platform, WiringX};
+    ///
+    /// let wiringx = WiringX::with_gpio_backend(Platform::Generic, GpioBackend::Mock).unwrap();
+    /// let trace = Trace::load("button.trace").unwrap();
+    ///
+    /// let replay = thread::spawn(move || trace.replay(wiringx.mock_board().unwrap()));
+    ///
+    /// // Run the application logic on the pins of `wiringx` here
+    ///
+    /// replay.join().unwrap();
+    /// ```
+    #[cfg(feature = "mock")]
+    pub fn replay(&self, board: &MockBoard) {
+        let start = Instant::now();
+
+        for event in &self.events {
+            let level = match event.kind {
+                TraceKind::Read(value) => value,
+                TraceKind::Edge(Edge::Rising) => Value::High,
+                TraceKind::Edge(Edge::Falling) => Value::Low,
+                TraceKind::Write(_) | TraceKind::Period(_) | TraceKind::DutyCycle(_) => continue,
+            };
+
+            if let Some(remaining) = (start + event.time).checked_duration_since(Instant::now()) {
+                thread::sleep(remaining);
+            }
+
+            board.set_level(event.pin, level);
+        }
+    }
+}
+
+/// A variable of the VCD export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum VcdSignal {
+    Level,
+    Period,
+    DutyCycle,
+}
+
+impl VcdSignal {
+    fn of(kind: TraceKind) -> Self {
+        match kind {
+            TraceKind::Write(_) | TraceKind::Read(_) | TraceKind::Edge(_) => Self::Level,
+            TraceKind::Period(_) => Self::Period,
+            TraceKind::DutyCycle(_) => Self::DutyCycle,
+        }
+    }
+}
+
+/// Returns the identifier of the signal with the index, from the 94 printable ASCII characters.
+fn vcd_identifier(mut index: usize) -> String {
+    let mut identifier = String::new();
+
+    loop {
+        identifier.push((b'!' + (index % 94) as u8) as char);
+        index /= 94;
+
+        if index == 0 {
+            return identifier;
+        }
+
+        index -= 1;
+    }
+}
+
+fn value_bit(value: Value) -> char {
+    match value {
+        Value::High => '1',
+        Value::Low => '0',
+    }
+}
+
+impl FromStr for Trace {
+    type Err = WiringXError;
+
+    fn from_str(trace: &str) -> Result<Self, Self::Err> {
+        let events = trace
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(str::parse)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { events })
+    }
+}
+
+impl From<Vec<TraceEvent>> for Trace {
+    #[inline]
+    fn from(events: Vec<TraceEvent>) -> Self {
+        Self { events }
+    }
+}
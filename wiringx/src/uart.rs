@@ -2,34 +2,56 @@
 
 use std::{
     ffi::{c_uchar, c_uint, CString},
-    os::fd::RawFd,
+    io::{self, BufRead, Read, Write},
+    os::fd::{AsRawFd, RawFd},
     path::PathBuf,
+    thread,
+    time::{Duration, Instant},
 };
 
+use parking_lot::Mutex;
 use thiserror::Error;
 use wiringx_sys::{
     wiringXSerialClose, wiringXSerialDataAvail, wiringXSerialFlush, wiringXSerialGetChar,
     wiringXSerialOpen, wiringXSerialPutChar, wiringXSerialPuts, wiringXSerial_t,
 };
 
-use crate::{Hand, WiringXError};
+use crate::{Claim, Hand, WiringXError};
 
 /// Configuration of the serial connection.
-#[derive(Clone, Copy, Debug)]
+///
+/// Example of an 8E2 configuration, eight data bits, even parity and two stop bits:
+/// ```
+/// use wiringx::{Parity, SerialConfig};
+///
+/// let config = SerialConfig {
+///     parity: Parity::Even,
+///     stop_bits: 2,
+///     ..SerialConfig::new(19200)
+/// };
+///
+/// assert!(config.check().is_ok());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SerialConfig {
     /// The baud rate for the serial communication, specified in bits per second (bps).
     ///
-    /// Only allowed values are
+    /// Standard values are
     /// `50`, `75`, `110`, `134`, `150`, `200`, `300`, `600`, `1200`, `1800`, `2400`, `4800`,
-    /// `9600`, `19200`, `38400`, `57600`, `115200`, or `230400`.
+    /// `9600`, `19200`, `38400`, `57600`, `115200`, `230400`, `460800`, `500000`, `576000`,
+    /// `921600`, `1000000`, `1152000`, `1500000`, `2000000`, `2500000`, `3000000`, `3500000` or `4000000`.
+    ///
+    /// Other rates, like `31250` for MIDI, are requested from the UART driver directly
+    /// and only work if its clock can be divided down to them.
     pub baud_rate: u32,
 
     /// The number of data bits in each character frame.
-    /// Allowed values are `7`, `8`.
+    /// Allowed values are `5`, `6`, `7` or `8`.
     pub data_bits: u32,
 
     /// The parity setting for error detection in the communication.
-    /// This can be `None`, `Even`, or `Odd`.
+    /// This can be `None`, `Even`, `Odd`, `Mark` or `Space`.
     pub parity: Parity,
 
     /// The number of stop bits used to mark the end of a character frame.
@@ -37,36 +59,32 @@ pub struct SerialConfig {
     pub stop_bits: u32,
 
     /// The flow control setting to manage data flow between devices.
-    /// Options include `None`, or `Software` (XON/XOFF).
+    /// Options include `None`, `XOnOff` for software or `RtsCts` for hardware flow control.
     pub flow_control: FlowControl,
 }
 
 impl SerialConfig {
+    /// Returns an 8N1 configuration, eight data bits, no parity and one stop bit,
+    /// without flow control at the given baud rate.
+    pub fn new(baud_rate: u32) -> Self {
+        Self {
+            baud_rate,
+            data_bits: 8,
+            parity: Parity::None,
+            stop_bits: 1,
+            flow_control: FlowControl::None,
+        }
+    }
+
     /// Checks if the configuration provided in this struct is valid and usable in wiringX.
     pub fn check(&self) -> Result<(), InvalidUARTConfig> {
-        match self.baud_rate {
-            50 => (),
-            75 => (),
-            110 => (),
-            134 => (),
-            150 => (),
-            200 => (),
-            300 => (),
-            600 => (),
-            1200 => (),
-            1800 => (),
-            2400 => (),
-            4800 => (),
-            9600 => (),
-            19200 => (),
-            38400 => (),
-            57600 => (),
-            115200 => (),
-            230400 => (),
-            _ => return Err(InvalidUARTConfig::BaudRate),
-        };
+        if self.baud_rate == 0 {
+            return Err(InvalidUARTConfig::BaudRate);
+        }
 
         match self.data_bits {
+            5 => (),
+            6 => (),
             7 => (),
             8 => (),
             _ => return Err(InvalidUARTConfig::DataBits),
@@ -80,22 +98,120 @@ impl SerialConfig {
 
         Ok(())
     }
+
+    /// Writes this configuration into the terminal attributes.
+    ///
+    /// Expects the configuration to be [checked](Self::check).
+    fn apply(&self, options: &mut libc::termios) {
+        // Custom rates are set afterwards using `termios2`.
+        let speed = baud_constant(self.baud_rate).unwrap_or(libc::B38400);
+        unsafe {
+            libc::cfsetispeed(options, speed);
+            libc::cfsetospeed(options, speed);
+        }
+
+        options.c_cflag &= !libc::CSIZE;
+        options.c_cflag |= match self.data_bits {
+            5 => libc::CS5,
+            6 => libc::CS6,
+            7 => libc::CS7,
+            _ => libc::CS8,
+        };
+
+        options.c_cflag &= !(libc::PARENB | libc::PARODD | libc::CMSPAR);
+        options.c_iflag &= !libc::INPCK;
+        options.c_cflag |= match self.parity {
+            Parity::None => 0,
+            Parity::Even => libc::PARENB,
+            Parity::Odd => libc::PARENB | libc::PARODD,
+            Parity::Mark => libc::PARENB | libc::PARODD | libc::CMSPAR,
+            Parity::Space => libc::PARENB | libc::CMSPAR,
+        };
+        if self.parity != Parity::None {
+            options.c_iflag |= libc::INPCK;
+        }
+
+        if self.stop_bits == 2 {
+            options.c_cflag |= libc::CSTOPB;
+        } else {
+            options.c_cflag &= !libc::CSTOPB;
+        }
+
+        options.c_iflag &= !(libc::IXON | libc::IXOFF | libc::IXANY);
+        options.c_cflag &= !libc::CRTSCTS;
+        match self.flow_control {
+            FlowControl::None => (),
+            FlowControl::XOnOff => options.c_iflag |= libc::IXON | libc::IXOFF | libc::IXANY,
+            FlowControl::RtsCts => options.c_cflag |= libc::CRTSCTS,
+        }
+    }
 }
 
+/// Returns the terminal speed constant of the given baud rate, if supported.
+fn baud_constant(baud_rate: u32) -> Option<libc::speed_t> {
+    let speed = match baud_rate {
+        50 => libc::B50,
+        75 => libc::B75,
+        110 => libc::B110,
+        134 => libc::B134,
+        150 => libc::B150,
+        200 => libc::B200,
+        300 => libc::B300,
+        600 => libc::B600,
+        1200 => libc::B1200,
+        1800 => libc::B1800,
+        2400 => libc::B2400,
+        4800 => libc::B4800,
+        9600 => libc::B9600,
+        19200 => libc::B19200,
+        38400 => libc::B38400,
+        57600 => libc::B57600,
+        115200 => libc::B115200,
+        230400 => libc::B230400,
+        460800 => libc::B460800,
+        500000 => libc::B500000,
+        576000 => libc::B576000,
+        921600 => libc::B921600,
+        1000000 => libc::B1000000,
+        1152000 => libc::B1152000,
+        1500000 => libc::B1500000,
+        2000000 => libc::B2000000,
+        2500000 => libc::B2500000,
+        3000000 => libc::B3000000,
+        3500000 => libc::B3500000,
+        4000000 => libc::B4000000,
+        _ => return None,
+    };
+
+    Some(speed)
+}
+
+/// Converts the configuration for `wiringXSerialOpen`.
+///
+/// Settings wiringX does not know are replaced by its closest defaults,
+/// [`SerialPort`] applies the exact configuration afterwards.
 impl From<SerialConfig> for wiringXSerial_t {
     fn from(rh: SerialConfig) -> Self {
+        let baud = match rh.baud_rate {
+            0..=230400 if baud_constant(rh.baud_rate).is_some() => rh.baud_rate,
+            _ => 9600,
+        };
+        let databits = match rh.data_bits {
+            7 => 7,
+            _ => 8,
+        };
         let parity = match rh.parity {
             Parity::Odd => 'o' as c_uint,
             Parity::Even => 'e' as c_uint,
-            Parity::None => 'n' as c_uint,
+            Parity::None | Parity::Mark | Parity::Space => 'n' as c_uint,
         };
         let flow_control = match rh.flow_control {
             FlowControl::XOnOff => 'x' as c_uint,
-            FlowControl::None => 'n' as c_uint,
+            FlowControl::None | FlowControl::RtsCts => 'n' as c_uint,
         };
         wiringXSerial_t {
-            baud: rh.baud_rate,
-            databits: rh.data_bits,
+            baud,
+            databits,
             parity,
             stopbits: rh.stop_bits,
             flowcontrol: flow_control,
@@ -104,25 +220,118 @@ impl From<SerialConfig> for wiringXSerial_t {
 }
 
 /// Universal asynchronous receiver/transmitter serial communication instance.
+///
+/// You receive this struct from the [`WiringX::serial`](super::WiringX::serial) or
+/// [`WiringX::setup_uart`](super::WiringX::setup_uart) methods of the [`WiringX`](super::WiringX) struct.
+///
+/// Reads block for up to 15 seconds while no data arrives, returning `0` bytes read afterwards.
+///
+/// Implements [`Read`], [`BufRead`] and [`Write`], also for shared references, to be used with
+/// the standard I/O ecosystem. Those report a read timeout as [`TimedOut`](io::ErrorKind::TimedOut) instead.
+///
+/// Echo example on `/dev/ttyS1`:
+/// ```no_run
+/// use wiringx::{Platform, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let serial = wiringx.serial("/dev/ttyS1", 115200).unwrap();
+///
+/// let mut buffer = [0; 64];
+/// loop {
+///     let len = serial.read(&mut buffer).unwrap();
+///     serial.write(&buffer[..len]).unwrap();
+/// }
+/// ```
 #[derive(Debug)]
-pub struct Uart {
+pub struct SerialPort {
     fd: RawFd,
     dev: PathBuf,
     handles: Hand<PathBuf>,
+    config: SerialConfig,
+    buffer: Mutex<ReadBuffer>,
+}
+
+/// The name of [`SerialPort`] before it gained reading and writing.
+///
+/// Beware that [`SerialPort::flush`] waits for the written bytes to be transmitted,
+/// while `Uart::flush` discarded the buffers, which is [`SerialPort::clear`] now.
+#[deprecated(
+    note = "renamed to `SerialPort`, whose `flush` waits for the output to be sent, use `clear` to discard the buffers"
+)]
+pub type Uart = SerialPort;
+
+/// Bytes read ahead from the device, filled by [`BufRead::fill_buf`] and the delimiter reading methods.
+#[derive(Debug, Default)]
+struct ReadBuffer {
+    data: Vec<u8>,
+    pos: usize,
 }
 
-impl Uart {
+impl ReadBuffer {
+    const CHUNK: usize = 256;
+
+    #[inline]
+    fn buffered(&self) -> &[u8] {
+        &self.data[self.pos..]
+    }
+
+    /// Moves as many buffered bytes as fit into `buf`.
+    fn take(&mut self, buf: &mut [u8]) -> usize {
+        let len = buf.len().min(self.data.len() - self.pos);
+        buf[..len].copy_from_slice(&self.data[self.pos..self.pos + len]);
+        self.consume(len);
+        len
+    }
+
+    /// Removes the first `amt` buffered bytes.
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.data.len());
+
+        if self.pos == self.data.len() {
+            self.data.clear();
+            self.pos = 0;
+        }
+    }
+
+    /// Reads once from the file descriptor, appending to the buffered bytes.
+    ///
+    /// Returns the number of bytes read, `0` when the read timed out.
+    fn fill(&mut self, fd: RawFd) -> io::Result<usize> {
+        if self.pos > 0 {
+            self.data.drain(..self.pos);
+            self.pos = 0;
+        }
+
+        let len = self.data.len();
+        self.data.resize(len + Self::CHUNK, 0);
+
+        let result = unsafe {
+            libc::read(
+                fd,
+                self.data[len..].as_mut_ptr() as *mut libc::c_void,
+                Self::CHUNK,
+            )
+        };
+
+        let read = if result < 0 { 0 } else { result as usize };
+        self.data.truncate(len + read);
+
+        if result < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(read)
+        }
+    }
+}
+
+impl SerialPort {
     pub(super) fn new(
         dev: PathBuf,
         config: SerialConfig,
-        handles: Hand<PathBuf>,
+        claim: Claim<PathBuf>,
     ) -> Result<Self, WiringXError> {
         config.check().map_err(WiringXError::InvalidUARTConfig)?;
 
-        if handles.lock().contains(&dev.clone()) {
-            return Err(WiringXError::PinUsed);
-        }
-
         let path_string = CString::new(dev.to_str().ok_or(WiringXError::Other(
             "Path contains illegal symbols.".to_string(),
         ))?)
@@ -134,21 +343,307 @@ impl Uart {
             return Err(WiringXError::Unsupported);
         }
 
-        handles.lock().insert(dev.clone());
-
-        Ok(Self {
+        let mut serial = Self {
             fd: fd_result,
             dev,
-            handles,
+            handles: claim.keep(),
+            config,
+            buffer: Mutex::new(ReadBuffer::default()),
+        };
+
+        serial.configure(config)?;
+
+        Ok(serial)
+    }
+
+    /// Changes the line settings of the open serial port.
+    ///
+    /// Bytes still waiting to be transmitted are sent with the previous settings first.
+    pub fn configure(&mut self, config: SerialConfig) -> Result<(), WiringXError> {
+        config.check().map_err(WiringXError::InvalidUARTConfig)?;
+
+        let mut options = unsafe { std::mem::zeroed::<libc::termios>() };
+
+        if unsafe { libc::tcgetattr(self.fd, &mut options) } < 0 {
+            return Err(WiringXError::Io(io::Error::last_os_error()));
+        }
+
+        config.apply(&mut options);
+
+        if unsafe { libc::tcsetattr(self.fd, libc::TCSADRAIN, &options) } < 0 {
+            return Err(WiringXError::Io(io::Error::last_os_error()));
+        }
+
+        if baud_constant(config.baud_rate).is_none() {
+            self.set_custom_baud_rate(config.baud_rate)?;
+        }
+
+        self.config = config;
+
+        Ok(())
+    }
+
+    /// Sets a baud rate without terminal speed constant using the `BOTHER` flag of `termios2`.
+    fn set_custom_baud_rate(&self, baud_rate: u32) -> Result<(), WiringXError> {
+        let mut options = unsafe { std::mem::zeroed::<libc::termios2>() };
+
+        if unsafe { libc::ioctl(self.fd, libc::TCGETS2 as _, &mut options) } < 0 {
+            return Err(WiringXError::Io(io::Error::last_os_error()));
+        }
+
+        options.c_cflag &= !libc::CBAUD;
+        options.c_cflag |= libc::BOTHER;
+        options.c_ispeed = baud_rate;
+        options.c_ospeed = baud_rate;
+
+        if unsafe { libc::ioctl(self.fd, libc::TCSETS2 as _, &options) } < 0 {
+            return Err(WiringXError::Io(io::Error::last_os_error()));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the line settings of this serial port.
+    #[inline]
+    pub fn config(&self) -> SerialConfig {
+        self.config
+    }
+
+    /// Switches the baud rate of the open serial port, keeping all other line settings.
+    #[inline]
+    pub fn set_baud_rate(&mut self, baud_rate: u32) -> Result<(), WiringXError> {
+        self.configure(SerialConfig {
+            baud_rate,
+            ..self.config
         })
     }
 
-    /// Flushes the buffer.
+    /// Returns the baud rate of this serial port.
     #[inline]
-    pub fn flush(&self) {
+    pub fn baud_rate(&self) -> u32 {
+        self.config.baud_rate
+    }
+
+    /// Returns the device path of this serial port.
+    #[inline]
+    pub fn dev(&self) -> &PathBuf {
+        &self.dev
+    }
+
+    /// Returns the raw file descriptor of this serial port.
+    #[inline]
+    pub fn get_fd(&self) -> RawFd {
+        self.fd
+    }
+
+    /// Reads the received bytes into the buffer, returning how many were read.
+    ///
+    /// Waits until at least one byte arrived or the read timed out, in which case `0` is returned.
+    pub fn read(&self, buf: &mut [u8]) -> Result<usize, WiringXError> {
+        let mut buffer = self.buffer.lock();
+        if !buffer.buffered().is_empty() {
+            return Ok(buffer.take(buf));
+        }
+
+        self.read_raw(buf)
+    }
+
+    /// Reads until the delimiter byte was received, returning everything read including the delimiter.
+    ///
+    /// Returns [`TimedOut`](WiringXError::TimedOut) if the delimiter did not arrive within the timeout.
+    /// The bytes received until then are kept and returned by the next read.
+    pub fn read_until(&self, delimiter: u8, timeout: Duration) -> Result<Vec<u8>, WiringXError> {
+        let deadline = Instant::now() + timeout;
+        let mut buffer = self.buffer.lock();
+        let mut searched = 0;
+
+        loop {
+            if let Some(index) = buffer.buffered()[searched..]
+                .iter()
+                .position(|byte| *byte == delimiter)
+            {
+                let line = buffer.buffered()[..searched + index + 1].to_vec();
+                buffer.consume(line.len());
+                return Ok(line);
+            }
+            searched = buffer.buffered().len();
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() || !self.poll_readable(remaining)? {
+                return Err(WiringXError::TimedOut);
+            }
+
+            buffer.fill(self.fd).map_err(WiringXError::Io)?;
+        }
+    }
+
+    /// Fills the whole buffer with received bytes.
+    ///
+    /// Returns [`TimedOut`](WiringXError::TimedOut) if not enough bytes arrived within the timeout.
+    /// The bytes received until then are kept and returned by the next read.
+    pub fn read_exact_timeout(
+        &self,
+        buf: &mut [u8],
+        timeout: Duration,
+    ) -> Result<(), WiringXError> {
+        let deadline = Instant::now() + timeout;
+        let mut buffer = self.buffer.lock();
+
+        while buffer.buffered().len() < buf.len() {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() || !self.poll_readable(remaining)? {
+                return Err(WiringXError::TimedOut);
+            }
+
+            buffer.fill(self.fd).map_err(WiringXError::Io)?;
+        }
+
+        buffer.take(buf);
+
+        Ok(())
+    }
+
+    /// Reads one line terminated by `\n`, returning it without the line ending.
+    ///
+    /// A `\r\n` line ending is removed as well.
+    /// Returns [`TimedOut`](WiringXError::TimedOut) if no complete line arrived within the timeout.
+    pub fn read_line_timeout(&self, timeout: Duration) -> Result<String, WiringXError> {
+        let mut line = self.read_until(b'\n', timeout)?;
+
+        line.pop();
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+
+        String::from_utf8(line)
+            .map_err(|e| WiringXError::Io(io::Error::new(io::ErrorKind::InvalidData, e)))
+    }
+
+    /// Waits until data can be read, returning false if nothing arrived within the timeout.
+    fn poll_readable(&self, timeout: Duration) -> Result<bool, WiringXError> {
+        let mut poll_fd = libc::pollfd {
+            fd: self.fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let millis = timeout.as_millis().clamp(1, i32::MAX as u128) as i32;
+
+        let result = unsafe { libc::poll(&mut poll_fd, 1, millis) };
+
+        if result < 0 {
+            Err(WiringXError::Io(io::Error::last_os_error()))
+        } else {
+            Ok(result > 0)
+        }
+    }
+
+    /// Reads directly from the device, bypassing the read buffer.
+    fn read_raw(&self, buf: &mut [u8]) -> Result<usize, WiringXError> {
+        let result =
+            unsafe { libc::read(self.fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len()) };
+
+        if result < 0 {
+            Err(WiringXError::Io(io::Error::last_os_error()))
+        } else {
+            Ok(result as usize)
+        }
+    }
+
+    /// Writes the bytes to the serial port, returning how many were written.
+    pub fn write(&self, buf: &[u8]) -> Result<usize, WiringXError> {
+        let result =
+            unsafe { libc::write(self.fd, buf.as_ptr() as *const libc::c_void, buf.len()) };
+
+        if result < 0 {
+            Err(WiringXError::Io(io::Error::last_os_error()))
+        } else {
+            Ok(result as usize)
+        }
+    }
+
+    /// Switches the file descriptor between blocking and non-blocking reads and writes.
+    ///
+    /// Non-blocking calls return [`WouldBlock`](io::ErrorKind::WouldBlock) errors instead of waiting.
+    pub fn set_nonblocking(&self, nonblocking: bool) -> Result<(), WiringXError> {
+        let flags = unsafe { libc::fcntl(self.fd, libc::F_GETFL) };
+        if flags < 0 {
+            return Err(WiringXError::Io(io::Error::last_os_error()));
+        }
+
+        let flags = if nonblocking {
+            flags | libc::O_NONBLOCK
+        } else {
+            flags & !libc::O_NONBLOCK
+        };
+
+        let result = unsafe { libc::fcntl(self.fd, libc::F_SETFL, flags) };
+
+        if result < 0 {
+            Err(WiringXError::Io(io::Error::last_os_error()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Waits until all written bytes have been transmitted, like [`Write::flush`](io::Write::flush).
+    ///
+    /// This changed with the rename from `Uart`, whose `flush` discarded the buffers instead,
+    /// see [`clear`](Self::clear) for that.
+    pub fn flush(&self) -> Result<(), WiringXError> {
+        let result = unsafe { libc::tcdrain(self.fd) };
+
+        if result < 0 {
+            Err(WiringXError::Io(io::Error::last_os_error()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Holds the transmit line low for the given duration, after all written bytes have been transmitted.
+    ///
+    /// The duration is a minimum, the scheduler may keep the break going a little longer.
+    pub fn send_break(&self, duration: Duration) -> Result<(), WiringXError> {
+        self.flush()?;
+
+        if unsafe { libc::ioctl(self.fd, libc::TIOCSBRK as _) } < 0 {
+            return Err(WiringXError::Io(io::Error::last_os_error()));
+        }
+
+        thread::sleep(duration);
+
+        if unsafe { libc::ioctl(self.fd, libc::TIOCCBRK as _) } < 0 {
+            return Err(WiringXError::Io(io::Error::last_os_error()));
+        }
+
+        Ok(())
+    }
+
+    /// Discards all bytes in the receiving and transmitting buffers, what `flush` did before the rename from `Uart`.
+    pub fn clear(&self) {
+        let mut buffer = self.buffer.lock();
+        buffer.consume(usize::MAX);
+
         unsafe { wiringXSerialFlush(self.fd) }
     }
 
+    /// Returns the number of bytes present in the receiving buffer.
+    pub fn available(&self) -> Result<usize, WiringXError> {
+        let result = unsafe { wiringXSerialDataAvail(self.fd) };
+
+        if result < 0 {
+            Err(WiringXError::Io(io::Error::last_os_error()))
+        } else {
+            Ok(result as usize + self.buffer.lock().buffered().len())
+        }
+    }
+
+    /// Returns the number of bytes present in the receiving buffer, `0` if it can not be queried.
+    #[deprecated(note = "use available")]
+    #[inline]
+    pub fn data_available(&self) -> usize {
+        self.available().unwrap_or(0)
+    }
+
     /// Outputs a character.
     #[inline]
     pub fn put_char(&self, character: char) {
@@ -162,20 +657,103 @@ impl Uart {
         unsafe { wiringXSerialPuts(self.fd, c_string.as_ptr()) }
     }
 
-    /// Returns the number of bytes present in the receiving buffer.
-    #[inline]
-    pub fn data_available(&self) -> usize {
-        unsafe { wiringXSerialDataAvail(self.fd) as usize }
+    /// Returns a byte from the receiving buffer as character, none if no byte arrived before the read timed out.
+    pub fn try_read_char(&self) -> Option<char> {
+        let mut buffer = self.buffer.lock();
+        let mut byte = [0];
+        if buffer.take(&mut byte) == 1 {
+            return Some(byte[0] as char);
+        }
+
+        // wiringX returns `-1` if the read failed or timed out.
+        u8::try_from(unsafe { wiringXSerialGetChar(self.fd) })
+            .ok()
+            .map(char::from)
     }
 
-    /// Returns a character from the receiving buffer.
+    /// Returns a byte from the receiving buffer as character, `'\0'` if no byte arrived before the read timed out.
+    #[deprecated(note = "use try_read_char, which tells a timeout apart from a received zero")]
     #[inline]
     pub fn read_char(&self) -> char {
-        unsafe { char::from_u32_unchecked(wiringXSerialGetChar(self.fd) as u32) }
+        self.try_read_char().unwrap_or('\0')
+    }
+}
+
+impl AsRawFd for SerialPort {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+/// Maps a read of zero bytes into a non-empty buffer to a timeout, as a serial port has no end.
+fn timed_out(len: usize, requested: usize) -> io::Result<usize> {
+    if len == 0 && requested > 0 {
+        Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            "No data received from the serial port.",
+        ))
+    } else {
+        Ok(len)
+    }
+}
+
+impl Read for &SerialPort {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        timed_out(SerialPort::read(self, buf)?, buf.len())
+    }
+}
+
+impl Read for SerialPort {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(&mut &*self, buf)
+    }
+}
+
+impl BufRead for SerialPort {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let fd = self.fd;
+        let buffer = self.buffer.get_mut();
+
+        if buffer.buffered().is_empty() {
+            timed_out(buffer.fill(fd)?, ReadBuffer::CHUNK)?;
+        }
+
+        Ok(buffer.buffered())
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.buffer.get_mut().consume(amt);
+    }
+}
+
+impl Write for &SerialPort {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Ok(SerialPort::write(self, buf)?)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(SerialPort::flush(self)?)
+    }
+}
+
+impl Write for SerialPort {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Write::write(&mut &*self, buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Write::flush(&mut &*self)
     }
 }
 
-impl Drop for Uart {
+impl Drop for SerialPort {
     fn drop(&mut self) {
         unsafe { wiringXSerialClose(self.fd) }
         self.handles.lock().remove(&self.dev);
@@ -184,6 +762,7 @@ impl Drop for Uart {
 
 /// UART error correction parity.
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Parity {
     /// No parity at all
     None,
@@ -191,15 +770,22 @@ pub enum Parity {
     Even,
     /// Odd parity
     Odd,
+    /// Parity bit always set
+    Mark,
+    /// Parity bit always cleared
+    Space,
 }
 
 /// UART flow control
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FlowControl {
     /// No flow control
     None,
     /// Software flow control using special control characters
     XOnOff,
+    /// Hardware flow control using the RTS and CTS lines
+    RtsCts,
 }
 
 /// When a setting in the config is not supported, this error gets returned.
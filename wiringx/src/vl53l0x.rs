@@ -0,0 +1,604 @@
+//! VL53L0X time-of-flight distance sensors.
+
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{WiringXError, I2C};
+
+const SYSRANGE_START: u8 = 0x00;
+const SYSTEM_SEQUENCE_CONFIG: u8 = 0x01;
+const SYSTEM_INTERMEASUREMENT_PERIOD: u8 = 0x04;
+const SYSTEM_INTERRUPT_CONFIG_GPIO: u8 = 0x0A;
+const SYSTEM_INTERRUPT_CLEAR: u8 = 0x0B;
+const RESULT_INTERRUPT_STATUS: u8 = 0x13;
+const RESULT_RANGE_STATUS: u8 = 0x14;
+const FINAL_RANGE_CONFIG_MIN_COUNT_RATE_RTN_LIMIT: u8 = 0x44;
+const MSRC_CONFIG_TIMEOUT_MACROP: u8 = 0x46;
+const PRE_RANGE_CONFIG_VCSEL_PERIOD: u8 = 0x50;
+const PRE_RANGE_CONFIG_TIMEOUT_MACROP_HI: u8 = 0x51;
+const MSRC_CONFIG_CONTROL: u8 = 0x60;
+const FINAL_RANGE_CONFIG_VCSEL_PERIOD: u8 = 0x70;
+const FINAL_RANGE_CONFIG_TIMEOUT_MACROP_HI: u8 = 0x71;
+const GPIO_HV_MUX_ACTIVE_HIGH: u8 = 0x84;
+const VHV_CONFIG_PAD_SCL_SDA_EXTSUP_HV: u8 = 0x89;
+const DYNAMIC_SPAD_NUM_REQUESTED_REF_SPAD: u8 = 0x4E;
+const DYNAMIC_SPAD_REF_EN_START_OFFSET: u8 = 0x4F;
+const GLOBAL_CONFIG_SPAD_ENABLES_REF_0: u8 = 0xB0;
+const GLOBAL_CONFIG_REF_EN_START_SELECT: u8 = 0xB6;
+const IDENTIFICATION_MODEL_ID: u8 = 0xC0;
+const OSC_CALIBRATE_VAL: u8 = 0xF8;
+
+const VL53L0X_MODEL_ID: u8 = 0xEE;
+/// Distances at and above this mean no target was found.
+const OUT_OF_RANGE: u16 = 8190;
+
+/// Time to wait for a measurement beyond the timing budget.
+const MEASUREMENT_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Overheads of the sequence steps in µs, from the ST API.
+const START_OVERHEAD: u32 = 1910;
+const END_OVERHEAD: u32 = 960;
+const MSRC_OVERHEAD: u32 = 660;
+const TCC_OVERHEAD: u32 = 590;
+const DSS_OVERHEAD: u32 = 690;
+const PRE_RANGE_OVERHEAD: u32 = 660;
+const FINAL_RANGE_OVERHEAD: u32 = 550;
+
+/// Register writes tuning the sensor, from the ST API which does not document them.
+const TUNING_SETTINGS: &[(u8, u8)] = &[
+    (0xFF, 0x01),
+    (0x00, 0x00),
+    (0xFF, 0x00),
+    (0x09, 0x00),
+    (0x10, 0x00),
+    (0x11, 0x00),
+    (0x24, 0x01),
+    (0x25, 0xFF),
+    (0x75, 0x00),
+    (0xFF, 0x01),
+    (0x4E, 0x2C),
+    (0x48, 0x00),
+    (0x30, 0x20),
+    (0xFF, 0x00),
+    (0x30, 0x09),
+    (0x54, 0x00),
+    (0x31, 0x04),
+    (0x32, 0x03),
+    (0x40, 0x83),
+    (0x46, 0x25),
+    (0x60, 0x00),
+    (0x27, 0x00),
+    (0x50, 0x06),
+    (0x51, 0x00),
+    (0x52, 0x96),
+    (0x56, 0x08),
+    (0x57, 0x30),
+    (0x61, 0x00),
+    (0x62, 0x00),
+    (0x64, 0x00),
+    (0x65, 0x00),
+    (0x66, 0xA0),
+    (0xFF, 0x01),
+    (0x22, 0x32),
+    (0x47, 0x14),
+    (0x49, 0xFF),
+    (0x4A, 0x00),
+    (0xFF, 0x00),
+    (0x7A, 0x0A),
+    (0x7B, 0x00),
+    (0x78, 0x21),
+    (0xFF, 0x01),
+    (0x23, 0x34),
+    (0x42, 0x00),
+    (0x44, 0xFF),
+    (0x45, 0x26),
+    (0x46, 0x05),
+    (0x40, 0x40),
+    (0x0E, 0x06),
+    (0x20, 0x1A),
+    (0x43, 0x40),
+    (0xFF, 0x00),
+    (0x34, 0x03),
+    (0x35, 0x44),
+    (0xFF, 0x01),
+    (0x31, 0x04),
+    (0x4B, 0x09),
+    (0x4C, 0x05),
+    (0x4D, 0x04),
+    (0xFF, 0x00),
+    (0x44, 0x00),
+    (0x45, 0x20),
+    (0x47, 0x08),
+    (0x48, 0x28),
+    (0x67, 0x00),
+    (0x70, 0x04),
+    (0x71, 0x01),
+    (0x72, 0xFE),
+    (0x76, 0x00),
+    (0x77, 0x00),
+    (0xFF, 0x01),
+    (0x0D, 0x01),
+    (0xFF, 0x00),
+    (0x80, 0x01),
+    (0x01, 0xF8),
+    (0xFF, 0x01),
+    (0x8E, 0x01),
+    (0x00, 0x01),
+    (0xFF, 0x00),
+    (0x80, 0x00),
+];
+
+/// An ST VL53L0X time-of-flight sensor measuring distances up to about 2 m with an infrared laser.
+///
+/// Unlike ultrasonic sensors it has a narrow field of view and works on soft surfaces.
+/// The initialization follows the ST API, including the calibration of the reference SPADs,
+/// which the sensor has to do in the dark or at least without a target closer than 10 cm.
+///
+/// Example measuring continuously on the I2C bus at address `0x29`:
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use wiringx::{Platform, Vl53l0x, WiringX};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+/// let i2c = wiringx.setup_i2c("/dev/i2c-1".into(), 0x29).unwrap();
+///
+/// let mut sensor = Vl53l0x::new(i2c).unwrap();
+/// sensor.set_timing_budget(Duration::from_millis(50)).unwrap();
+/// sensor.start_continuous(Duration::ZERO).unwrap();
+///
+/// loop {
+///     match sensor.read_continuous().unwrap() {
+///         Some(distance) => println!("{distance} mm"),
+///         None => println!("out of range"),
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Vl53l0x {
+    i2c: I2C,
+    stop_variable: u8,
+    timing_budget: u32,
+}
+
+/// Enabled steps of the measurement sequence.
+#[derive(Debug, Clone, Copy)]
+struct SequenceSteps {
+    tcc: bool,
+    dss: bool,
+    msrc: bool,
+    pre_range: bool,
+    final_range: bool,
+}
+
+/// Timeouts of the steps of the measurement sequence.
+#[derive(Debug, Clone, Copy)]
+struct SequenceTimeouts {
+    final_range_vcsel_period: u8,
+    msrc_dss_tcc_us: u32,
+    pre_range_mclks: u32,
+    pre_range_us: u32,
+    final_range_us: u32,
+}
+
+impl Vl53l0x {
+    /// Initializes and calibrates the sensor, for 2.8 V I/O as on the common modules, with a timing budget of about 33 ms.
+    pub fn new(i2c: I2C) -> Result<Self, WiringXError> {
+        let mut sensor = Self {
+            i2c,
+            stop_variable: 0,
+            timing_budget: 0,
+        };
+
+        if sensor.read_register(IDENTIFICATION_MODEL_ID)? != VL53L0X_MODEL_ID {
+            return Err(WiringXError::Unsupported);
+        }
+
+        let extsup = sensor.read_register(VHV_CONFIG_PAD_SCL_SDA_EXTSUP_HV)?;
+        sensor.write_register(VHV_CONFIG_PAD_SCL_SDA_EXTSUP_HV, extsup | 0x01)?;
+
+        // Standard I2C mode.
+        sensor.write_register(0x88, 0x00)?;
+
+        sensor.write_register(0x80, 0x01)?;
+        sensor.write_register(0xFF, 0x01)?;
+        sensor.write_register(0x00, 0x00)?;
+        sensor.stop_variable = sensor.read_register(0x91)?;
+        sensor.write_register(0x00, 0x01)?;
+        sensor.write_register(0xFF, 0x00)?;
+        sensor.write_register(0x80, 0x00)?;
+
+        // Disables the signal rate checks of the MSRC and pre-range steps.
+        let msrc_control = sensor.read_register(MSRC_CONFIG_CONTROL)?;
+        sensor.write_register(MSRC_CONFIG_CONTROL, msrc_control | 0x12)?;
+
+        sensor.set_signal_rate_limit(0.25)?;
+        sensor.write_register(SYSTEM_SEQUENCE_CONFIG, 0xFF)?;
+
+        sensor.setup_reference_spads()?;
+
+        for &(register, value) in TUNING_SETTINGS {
+            sensor.write_register(register, value)?;
+        }
+
+        // Signals new samples on the GPIO1 pin, active low.
+        sensor.write_register(SYSTEM_INTERRUPT_CONFIG_GPIO, 0x04)?;
+        let mux = sensor.read_register(GPIO_HV_MUX_ACTIVE_HIGH)?;
+        sensor.write_register(GPIO_HV_MUX_ACTIVE_HIGH, mux & !0x10)?;
+        sensor.write_register(SYSTEM_INTERRUPT_CLEAR, 0x01)?;
+
+        sensor.timing_budget = sensor.measure_timing_budget()?;
+
+        // Disables the MSRC and TCC steps by default.
+        sensor.write_register(SYSTEM_SEQUENCE_CONFIG, 0xE8)?;
+        sensor.set_timing_budget(Duration::from_micros(sensor.timing_budget as u64))?;
+
+        sensor.write_register(SYSTEM_SEQUENCE_CONFIG, 0x01)?;
+        sensor.single_reference_calibration(0x40)?;
+        sensor.write_register(SYSTEM_SEQUENCE_CONFIG, 0x02)?;
+        sensor.single_reference_calibration(0x00)?;
+
+        sensor.write_register(SYSTEM_SEQUENCE_CONFIG, 0xE8)?;
+
+        Ok(sensor)
+    }
+
+    /// Sets the smallest signal rate of returned light in MCPS counted as valid, `0.25` by default.
+    ///
+    /// Lower limits reach further but measure more noise.
+    pub fn set_signal_rate_limit(&mut self, limit: f32) -> Result<(), WiringXError> {
+        if !(0.0..512.0).contains(&limit) {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        // Fixed point with 7 fractional bits.
+        self.write_register16(
+            FINAL_RANGE_CONFIG_MIN_COUNT_RATE_RTN_LIMIT,
+            (limit * (1 << 7) as f32) as u16,
+        )
+    }
+
+    /// Sets how long one measurement may take, at least 20 ms.
+    ///
+    /// Longer budgets measure more precisely, 200 ms suit high accuracy and 20 ms high speed.
+    pub fn set_timing_budget(&mut self, budget: Duration) -> Result<(), WiringXError> {
+        let budget =
+            u32::try_from(budget.as_micros()).map_err(|_| WiringXError::InvalidArgument)?;
+        if budget < 20_000 {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        let steps = self.sequence_steps()?;
+        let timeouts = self.sequence_timeouts(steps)?;
+
+        if steps.final_range {
+            let used = fixed_overhead(steps, timeouts) + FINAL_RANGE_OVERHEAD;
+            if used > budget {
+                return Err(WiringXError::InvalidArgument);
+            }
+
+            // The final range timeout includes the pre-range one.
+            let mut final_range_mclks =
+                microseconds_to_mclks(budget - used, timeouts.final_range_vcsel_period);
+            if steps.pre_range {
+                final_range_mclks += timeouts.pre_range_mclks;
+            }
+
+            self.write_register16(
+                FINAL_RANGE_CONFIG_TIMEOUT_MACROP_HI,
+                encode_timeout(final_range_mclks),
+            )?;
+        }
+
+        self.timing_budget = budget;
+
+        Ok(())
+    }
+
+    /// Returns the timing budget of one measurement.
+    #[inline]
+    pub fn timing_budget(&self) -> Duration {
+        Duration::from_micros(self.timing_budget as u64)
+    }
+
+    /// Measures the distance once, in mm, none if no target is in range.
+    pub fn read_single(&mut self) -> Result<Option<u16>, WiringXError> {
+        self.restore_stop_variable()?;
+        self.write_register(SYSRANGE_START, 0x01)?;
+
+        // The start bit clears once the measurement started.
+        let deadline = Instant::now() + MEASUREMENT_TIMEOUT;
+        while self.read_register(SYSRANGE_START)? & 0x01 != 0 {
+            if Instant::now() > deadline {
+                return Err(WiringXError::TimedOut);
+            }
+
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        self.read_continuous()
+    }
+
+    /// Starts measuring continuously, every period or back-to-back as fast as the timing budget allows for a zero period.
+    pub fn start_continuous(&mut self, period: Duration) -> Result<(), WiringXError> {
+        self.restore_stop_variable()?;
+
+        if period.is_zero() {
+            return self.write_register(SYSRANGE_START, 0x02);
+        }
+
+        let mut period =
+            u32::try_from(period.as_millis()).map_err(|_| WiringXError::InvalidArgument)?;
+        // The period is counted in cycles of the oscillator.
+        let oscillator = self.read_register16(OSC_CALIBRATE_VAL)?;
+        if oscillator != 0 {
+            period = period.saturating_mul(oscillator as u32);
+        }
+
+        let mut message = [SYSTEM_INTERMEASUREMENT_PERIOD, 0, 0, 0, 0];
+        message[1..].copy_from_slice(&period.to_be_bytes());
+        self.i2c.write_bytes(&message)?;
+
+        self.write_register(SYSRANGE_START, 0x04)
+    }
+
+    /// Stops measuring continuously.
+    pub fn stop_continuous(&mut self) -> Result<(), WiringXError> {
+        self.write_register(SYSRANGE_START, 0x01)?;
+
+        self.write_register(0xFF, 0x01)?;
+        self.write_register(0x00, 0x00)?;
+        self.write_register(0x91, 0x00)?;
+        self.write_register(0x00, 0x01)?;
+        self.write_register(0xFF, 0x00)
+    }
+
+    /// Waits for the next continuous measurement and returns the distance in mm, none if no target is in range.
+    pub fn read_continuous(&mut self) -> Result<Option<u16>, WiringXError> {
+        self.wait_for_interrupt()?;
+
+        // The distance follows the status and the signal rates.
+        let distance = self.read_register16(RESULT_RANGE_STATUS + 10)?;
+        self.write_register(SYSTEM_INTERRUPT_CLEAR, 0x01)?;
+
+        Ok((distance < OUT_OF_RANGE).then_some(distance))
+    }
+
+    fn restore_stop_variable(&mut self) -> Result<(), WiringXError> {
+        self.write_register(0x80, 0x01)?;
+        self.write_register(0xFF, 0x01)?;
+        self.write_register(0x00, 0x00)?;
+        self.write_register(0x91, self.stop_variable)?;
+        self.write_register(0x00, 0x01)?;
+        self.write_register(0xFF, 0x00)?;
+        self.write_register(0x80, 0x00)
+    }
+
+    fn wait_for_interrupt(&mut self) -> Result<(), WiringXError> {
+        let deadline =
+            Instant::now() + Duration::from_micros(self.timing_budget as u64) + MEASUREMENT_TIMEOUT;
+
+        while self.read_register(RESULT_INTERRUPT_STATUS)? & 0x07 == 0 {
+            if Instant::now() > deadline {
+                return Err(WiringXError::TimedOut);
+            }
+
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        Ok(())
+    }
+
+    fn single_reference_calibration(&mut self, vhv_init: u8) -> Result<(), WiringXError> {
+        self.write_register(SYSRANGE_START, 0x01 | vhv_init)?;
+        self.wait_for_interrupt()?;
+        self.write_register(SYSTEM_INTERRUPT_CLEAR, 0x01)?;
+
+        self.write_register(SYSRANGE_START, 0x00)
+    }
+
+    /// Enables the number and type of reference SPADs stored in the sensor at production.
+    fn setup_reference_spads(&mut self) -> Result<(), WiringXError> {
+        self.write_register(0x80, 0x01)?;
+        self.write_register(0xFF, 0x01)?;
+        self.write_register(0x00, 0x00)?;
+        self.write_register(0xFF, 0x06)?;
+        let value = self.read_register(0x83)?;
+        self.write_register(0x83, value | 0x04)?;
+        self.write_register(0xFF, 0x07)?;
+        self.write_register(0x81, 0x01)?;
+        self.write_register(0x80, 0x01)?;
+        self.write_register(0x94, 0x6B)?;
+        self.write_register(0x83, 0x00)?;
+
+        let deadline = Instant::now() + MEASUREMENT_TIMEOUT;
+        while self.read_register(0x83)? == 0x00 {
+            if Instant::now() > deadline {
+                return Err(WiringXError::TimedOut);
+            }
+
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        self.write_register(0x83, 0x01)?;
+        let info = self.read_register(0x92)?;
+        let count = info & 0x7F;
+        let aperture = info & 0x80 != 0;
+
+        self.write_register(0x81, 0x00)?;
+        self.write_register(0xFF, 0x06)?;
+        let value = self.read_register(0x83)?;
+        self.write_register(0x83, value & !0x04)?;
+        self.write_register(0xFF, 0x01)?;
+        self.write_register(0x00, 0x01)?;
+        self.write_register(0xFF, 0x00)?;
+        self.write_register(0x80, 0x00)?;
+
+        let mut map = [0; 6];
+        self.i2c
+            .read_reg_bytes(GLOBAL_CONFIG_SPAD_ENABLES_REF_0, &mut map)?;
+
+        self.write_register(0xFF, 0x01)?;
+        self.write_register(DYNAMIC_SPAD_REF_EN_START_OFFSET, 0x00)?;
+        self.write_register(DYNAMIC_SPAD_NUM_REQUESTED_REF_SPAD, 0x2C)?;
+        self.write_register(0xFF, 0x00)?;
+        self.write_register(GLOBAL_CONFIG_REF_EN_START_SELECT, 0xB4)?;
+
+        // Aperture SPADs start at 12, only the stored number of them stays enabled.
+        let first = if aperture { 12 } else { 0 };
+        let mut enabled = 0;
+        for spad in 0..48 {
+            let bit = 1 << (spad % 8);
+            if spad < first || enabled == count {
+                map[spad / 8] &= !bit;
+            } else if map[spad / 8] & bit != 0 {
+                enabled += 1;
+            }
+        }
+
+        let mut message = [0; 7];
+        message[0] = GLOBAL_CONFIG_SPAD_ENABLES_REF_0;
+        message[1..].copy_from_slice(&map);
+        self.i2c.write_bytes(&message)?;
+
+        Ok(())
+    }
+
+    fn measure_timing_budget(&mut self) -> Result<u32, WiringXError> {
+        let steps = self.sequence_steps()?;
+        let timeouts = self.sequence_timeouts(steps)?;
+
+        let mut budget = fixed_overhead(steps, timeouts);
+        if steps.final_range {
+            budget += timeouts.final_range_us + FINAL_RANGE_OVERHEAD;
+        }
+
+        Ok(budget)
+    }
+
+    fn sequence_steps(&mut self) -> Result<SequenceSteps, WiringXError> {
+        let config = self.read_register(SYSTEM_SEQUENCE_CONFIG)?;
+
+        Ok(SequenceSteps {
+            tcc: config & 0x10 != 0,
+            dss: config & 0x08 != 0,
+            msrc: config & 0x04 != 0,
+            pre_range: config & 0x40 != 0,
+            final_range: config & 0x80 != 0,
+        })
+    }
+
+    fn sequence_timeouts(
+        &mut self,
+        steps: SequenceSteps,
+    ) -> Result<SequenceTimeouts, WiringXError> {
+        let pre_range_vcsel_period = (self.read_register(PRE_RANGE_CONFIG_VCSEL_PERIOD)? + 1) << 1;
+        let final_range_vcsel_period =
+            (self.read_register(FINAL_RANGE_CONFIG_VCSEL_PERIOD)? + 1) << 1;
+
+        let msrc_dss_tcc_mclks = self.read_register(MSRC_CONFIG_TIMEOUT_MACROP)? as u32 + 1;
+        let pre_range_mclks =
+            decode_timeout(self.read_register16(PRE_RANGE_CONFIG_TIMEOUT_MACROP_HI)?);
+        let mut final_range_mclks =
+            decode_timeout(self.read_register16(FINAL_RANGE_CONFIG_TIMEOUT_MACROP_HI)?);
+        if steps.pre_range {
+            final_range_mclks = final_range_mclks.saturating_sub(pre_range_mclks);
+        }
+
+        Ok(SequenceTimeouts {
+            final_range_vcsel_period,
+            msrc_dss_tcc_us: mclks_to_microseconds(msrc_dss_tcc_mclks, pre_range_vcsel_period),
+            pre_range_mclks,
+            pre_range_us: mclks_to_microseconds(pre_range_mclks, pre_range_vcsel_period),
+            final_range_us: mclks_to_microseconds(final_range_mclks, final_range_vcsel_period),
+        })
+    }
+
+    #[inline]
+    fn read_register(&mut self, register: u8) -> Result<u8, WiringXError> {
+        Ok(self.i2c.read_reg8(register as i32)?)
+    }
+
+    fn read_register16(&mut self, register: u8) -> Result<u16, WiringXError> {
+        let mut data = [0; 2];
+        self.i2c.read_reg_bytes(register, &mut data)?;
+
+        Ok(u16::from_be_bytes(data))
+    }
+
+    #[inline]
+    fn write_register(&mut self, register: u8, value: u8) -> Result<(), WiringXError> {
+        self.i2c.write_bytes(&[register, value])?;
+
+        Ok(())
+    }
+
+    fn write_register16(&mut self, register: u8, value: u16) -> Result<(), WiringXError> {
+        let [high, low] = value.to_be_bytes();
+        self.i2c.write_bytes(&[register, high, low])?;
+
+        Ok(())
+    }
+}
+
+/// Returns the time in µs the sequence takes without the final range step.
+fn fixed_overhead(steps: SequenceSteps, timeouts: SequenceTimeouts) -> u32 {
+    let mut overhead = START_OVERHEAD + END_OVERHEAD;
+
+    if steps.tcc {
+        overhead += timeouts.msrc_dss_tcc_us + TCC_OVERHEAD;
+    }
+    if steps.dss {
+        overhead += 2 * (timeouts.msrc_dss_tcc_us + DSS_OVERHEAD);
+    } else if steps.msrc {
+        overhead += timeouts.msrc_dss_tcc_us + MSRC_OVERHEAD;
+    }
+    if steps.pre_range {
+        overhead += timeouts.pre_range_us + PRE_RANGE_OVERHEAD;
+    }
+
+    overhead
+}
+
+/// Returns the macro period in ns for the VCSEL period in PCLKs.
+#[inline]
+fn macro_period(vcsel_period: u8) -> u32 {
+    (2304 * vcsel_period as u32 * 1655 + 500) / 1000
+}
+
+#[inline]
+fn mclks_to_microseconds(mclks: u32, vcsel_period: u8) -> u32 {
+    let macro_period = macro_period(vcsel_period);
+
+    (mclks * macro_period + macro_period / 2) / 1000
+}
+
+#[inline]
+fn microseconds_to_mclks(microseconds: u32, vcsel_period: u8) -> u32 {
+    let macro_period = macro_period(vcsel_period);
+
+    ((microseconds as u64 * 1000 + macro_period as u64 / 2) / macro_period as u64) as u32
+}
+
+/// Decodes a timeout register, `(LSB * 2 ^ MSB) + 1`.
+#[inline]
+fn decode_timeout(register: u16) -> u32 {
+    ((register as u32 & 0xFF) << (register >> 8)) + 1
+}
+
+fn encode_timeout(mclks: u32) -> u16 {
+    if mclks == 0 {
+        return 0;
+    }
+
+    let mut lsb = mclks - 1;
+    let mut msb = 0;
+    while lsb > 0xFF {
+        lsb >>= 1;
+        msb += 1;
+    }
+
+    (msb << 8 | lsb) as u16
+}
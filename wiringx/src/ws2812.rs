@@ -0,0 +1,235 @@
+//! WS2812 / NeoPixel LED strips.
+
+use std::{
+    fs,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    gpio::wait_until, BitOrder, ColorOrder, Output, Pin, Rgb, Spi, SpiMode, Value, WiringXError,
+};
+
+/// SPI clock encoding every bit of the strip in three bits of 417 nanoseconds.
+const SPI_SPEED: u32 = 2_400_000;
+/// Zero bytes sent after the pixels, holding the line low for the 300 µs latch of newer strips.
+const SPI_RESET_BYTES: usize = 90;
+/// Largest message of the spidev driver, from its `bufsiz` module parameter.
+const SPIDEV_BUFSIZ: &str = "/sys/module/spidev/parameters/bufsiz";
+/// Default of the `bufsiz` module parameter.
+const DEFAULT_BUFSIZ: usize = 4096;
+
+/// Bit timings of the strip, used by the GPIO backend.
+const T0H: Duration = Duration::from_nanos(400);
+const T1H: Duration = Duration::from_nanos(800);
+const PERIOD: Duration = Duration::from_nanos(1250);
+const RESET: Duration = Duration::from_micros(300);
+
+/// A strip of WS2812, WS2812B or SK6812 RGB LEDs, also known as NeoPixels.
+///
+/// Colors are kept in a frame buffer and sent to the strip with [`show`](Self::show).
+///
+/// The recommended backend is the MOSI pin of an [`Spi`] controller, which generates the
+/// timing in hardware. Driving the strip from a GPIO pin only works on boards fast enough to
+/// toggle it within the 150 nanosecond tolerance of the strip.
+///
+/// Example lighting up a strip of eight LEDs on SPI channel `0`:
+/// ```no_run
+/// use wiringx::{Platform, Rgb, WiringX, Ws2812};
+///
+/// let wiringx = WiringX::new(Platform::MilkVDuoS).unwrap();
+///
+/// let mut strip = Ws2812::new(wiringx.setup_spi(0, 2_400_000).unwrap(), 8).unwrap();
+/// strip.set_brightness(64);
+///
+/// strip.fill(Rgb::new(255, 0, 0));
+/// strip.set_pixel(0, Rgb::new(0, 0, 255)).unwrap();
+/// strip.show().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct Ws2812 {
+    output: Ws2812Output,
+    pixels: Vec<Rgb>,
+    brightness: u8,
+    color_order: ColorOrder,
+}
+
+/// The line the pixel data is sent on.
+#[derive(Debug)]
+enum Ws2812Output {
+    Spi(Spi),
+    Gpio(Pin<Output>),
+}
+
+impl Ws2812 {
+    /// Drives a strip of `len` LEDs from the MOSI pin of the SPI controller.
+    ///
+    /// The controller is reconfigured to 2.4 MHz, [`SpiMode::Mode0`] and [`BitOrder::MsbFirst`].
+    ///
+    /// The whole strip is sent in one message, taking 9 bytes per LED and 91 more, as splitting it
+    /// would leave gaps the strip could take as the end of the frame. spidev limits messages to its `bufsiz`
+    /// module parameter, by default 4096 bytes, which allows up to 445 LEDs. Longer strips return
+    /// [`InvalidArgument`](WiringXError::InvalidArgument), unless spidev is loaded with a larger buffer,
+    /// like `spidev.bufsiz=65536` on the kernel command line.
+    pub fn new(mut spi: Spi, len: usize) -> Result<Self, WiringXError> {
+        if len.saturating_mul(9).saturating_add(1 + SPI_RESET_BYTES) > spidev_bufsiz() {
+            return Err(WiringXError::InvalidArgument);
+        }
+
+        spi.set_speed(SPI_SPEED)?;
+        spi.set_mode(SpiMode::Mode0)?;
+        spi.set_bit_order(BitOrder::MsbFirst)?;
+
+        Ok(Self::with_output(Ws2812Output::Spi(spi), len))
+    }
+
+    /// Drives a strip of `len` LEDs by toggling the GPIO pin, timed by busy waiting.
+    pub fn with_gpio(mut pin: Pin<Output>, len: usize) -> Self {
+        pin.write(Value::Low);
+
+        Self::with_output(Ws2812Output::Gpio(pin), len)
+    }
+
+    fn with_output(output: Ws2812Output, len: usize) -> Self {
+        Self {
+            output,
+            pixels: vec![Rgb::BLACK; len],
+            brightness: u8::MAX,
+            color_order: ColorOrder::Grb,
+        }
+    }
+
+    /// Returns the number of LEDs of the strip.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.pixels.len()
+    }
+
+    /// Returns true if the strip has no LEDs.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pixels.is_empty()
+    }
+
+    /// Returns the frame buffer.
+    #[inline]
+    pub fn pixels(&self) -> &[Rgb] {
+        &self.pixels
+    }
+
+    /// Returns the frame buffer for modification, the changes get visible with the next [`show`](Self::show).
+    #[inline]
+    pub fn pixels_mut(&mut self) -> &mut [Rgb] {
+        &mut self.pixels
+    }
+
+    /// Sets the color of the LED at the index in the frame buffer.
+    pub fn set_pixel(&mut self, index: usize, color: Rgb) -> Result<(), WiringXError> {
+        let pixel = self
+            .pixels
+            .get_mut(index)
+            .ok_or(WiringXError::InvalidArgument)?;
+        *pixel = color;
+
+        Ok(())
+    }
+
+    /// Sets all LEDs in the frame buffer to the color.
+    #[inline]
+    pub fn fill(&mut self, color: Rgb) {
+        self.pixels.fill(color);
+    }
+
+    /// Switches all LEDs in the frame buffer off.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.fill(Rgb::BLACK);
+    }
+
+    /// Sets the brightness all colors are scaled with when sent, from `0` for off to `255` for full brightness.
+    #[inline]
+    pub fn set_brightness(&mut self, brightness: u8) {
+        self.brightness = brightness;
+    }
+
+    /// Returns the brightness all colors are scaled with.
+    #[inline]
+    pub fn brightness(&self) -> u8 {
+        self.brightness
+    }
+
+    /// Sets the order the strip expects the color components in, [`ColorOrder::Grb`] by default.
+    #[inline]
+    pub fn set_color_order(&mut self, color_order: ColorOrder) {
+        self.color_order = color_order;
+    }
+
+    /// Returns the order the color components are sent in.
+    #[inline]
+    pub fn color_order(&self) -> ColorOrder {
+        self.color_order
+    }
+
+    /// Sends the frame buffer to the strip.
+    pub fn show(&mut self) -> Result<(), WiringXError> {
+        let data: Vec<u8> = self
+            .pixels
+            .iter()
+            .flat_map(|pixel| self.color_order.arrange(pixel.scale(self.brightness)))
+            .collect();
+
+        match &mut self.output {
+            Ws2812Output::Spi(spi) => spi.write(&encode_spi(&data)),
+            Ws2812Output::Gpio(pin) => {
+                send_gpio(pin, &data);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Returns the largest message spidev takes.
+fn spidev_bufsiz() -> usize {
+    fs::read_to_string(SPIDEV_BUFSIZ)
+        .ok()
+        .and_then(|bufsiz| bufsiz.trim().parse().ok())
+        .unwrap_or(DEFAULT_BUFSIZ)
+}
+
+/// Encodes every bit as three SPI bits, `100` for `0` and `110` for `1`.
+fn encode_spi(data: &[u8]) -> Vec<u8> {
+    // A leading zero byte makes sure the line starts low, whatever level MOSI idles at.
+    let mut encoded = Vec::with_capacity(1 + data.len() * 3 + SPI_RESET_BYTES);
+    encoded.push(0);
+
+    for byte in data {
+        let bits = (0..8).rev().fold(0u32, |bits, i| {
+            bits << 3 | if byte >> i & 1 == 1 { 0b110 } else { 0b100 }
+        });
+
+        encoded.extend_from_slice(&bits.to_be_bytes()[1..]);
+    }
+
+    encoded.resize(encoded.len() + SPI_RESET_BYTES, 0);
+
+    encoded
+}
+
+/// Sends the bits with every edge timed from the start of the frame, so delays do not add up.
+fn send_gpio(pin: &mut Pin<Output>, data: &[u8]) {
+    let mut start = Instant::now();
+
+    for byte in data {
+        for i in (0..8).rev() {
+            let high = if byte >> i & 1 == 1 { T1H } else { T0H };
+
+            pin.write(Value::High);
+            wait_until(start + high);
+            pin.write(Value::Low);
+
+            start += PERIOD;
+            wait_until(start);
+        }
+    }
+
+    wait_until(start + RESET);
+}